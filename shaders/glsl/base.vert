@@ -1,20 +1,22 @@
 #version 450
 
-layout(location = 0) out vec3 fragColor;
+//Vertex attributes, as described by VERTEX_ATTRIBUTE_DESCRIPTIONS
+layout(location = 0) in vec3 inPosition;
+layout(location = 1) in vec3 inNormal;
+layout(location = 2) in vec2 inUv;
+layout(location = 3) in vec4 inTangent;
 
-vec2 positions[3] = vec2[](
-    vec2(0.0, -0.5),
-    vec2(0.5, 0.5),
-    vec2(-0.5, 0.5)
-);
+layout(location = 0) out vec3 fragColor;
 
-vec3 colors[3] = vec3[](
-    vec3(1.0, 0.0, 0.0),
-    vec3(0.0, 1.0, 0.0),
-    vec3(0.0, 0.0, 1.0)
-);
+layout(push_constant) uniform PushConstants {
+    mat4 modelViewProjection;
+    vec4 colorTint;
+    uint outputColorSpace;
+} pushConstants;
 
 void main() {
-    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
-    fragColor = colors[gl_VertexIndex];
-}
\ No newline at end of file
+    gl_Position = pushConstants.modelViewProjection * vec4(inPosition, 1.0);
+
+    //The normal in the model space, mapped from [-1, 1] to [0, 1]
+    fragColor = inNormal * 0.5 + 0.5;
+}
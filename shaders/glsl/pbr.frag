@@ -0,0 +1,372 @@
+#version 450
+
+layout(location = 0) in vec3 fragWorldPosition;
+layout(location = 1) in vec3 fragWorldNormal;
+layout(location = 2) in vec2 fragUv;
+layout(location = 3) in vec4 fragWorldTangent;
+
+layout(location = 0) out vec4 outColor;
+
+//Camera of the frame, as declared by CameraUniforms
+layout(set = 0, binding = 0) uniform FrameUniforms {
+    mat4 view;
+    mat4 projection;
+    mat4 viewProjection;
+    vec4 cameraPosition;
+} frame;
+
+//Light in the world space, as declared by GpuLight
+struct Light {
+    vec3 position;
+    float range;
+    vec3 direction;
+    uint lightType;
+    vec3 color;
+    float innerConeCos;
+    float outerConeCos;
+    uint firstShadowIndex;
+    uint shadowCount;
+};
+
+//Lights of the frame, as written by VulkanLightBuffers
+layout(std430, set = 0, binding = 1) readonly buffer LightBuffer {
+    uint lightCount;
+    Light lights[];
+};
+
+//Shadow view of a light in a tile of the shadow atlas, as declared by GpuShadow
+struct Shadow {
+    mat4 viewProjection;
+    vec4 atlasRect;
+    float splitDistance;
+    float texelSize;
+};
+
+//Shadows of the lights of the frame, as written by VulkanLightBuffers
+layout(std430, set = 0, binding = 2) readonly buffer ShadowBuffer {
+    Shadow shadows[];
+};
+
+layout(set = 0, binding = 3) uniform texture2D shadowAtlas;
+layout(set = 0, binding = 4) uniform sampler shadowSampler;
+
+//Image-based lighting of the environment, as created by VulkanEnvironmentImages
+layout(set = 0, binding = 6) uniform textureCube irradianceTexture;
+layout(set = 0, binding = 7) uniform textureCube prefilteredTexture;
+layout(set = 0, binding = 8) uniform texture2D brdfLut;
+layout(set = 0, binding = 9) uniform sampler environmentSampler;
+
+//Parameters of the material, as declared by MaterialUniforms
+layout(set = 1, binding = 0) uniform MaterialUniforms {
+    vec4 baseColorFactor;
+    vec4 emissiveFactor;
+    float metallicFactor;
+    float roughnessFactor;
+    float normalScale;
+    float occlusionStrength;
+    float alphaCutoff;
+    uint alphaMode;
+} material;
+
+layout(set = 1, binding = 1) uniform texture2D baseColorTexture;
+layout(set = 1, binding = 2) uniform texture2D metallicRoughnessTexture;
+layout(set = 1, binding = 3) uniform texture2D normalTexture;
+layout(set = 1, binding = 4) uniform texture2D occlusionTexture;
+layout(set = 1, binding = 5) uniform texture2D emissiveTexture;
+layout(set = 1, binding = 6) uniform sampler baseColorSampler;
+layout(set = 1, binding = 7) uniform sampler metallicRoughnessSampler;
+layout(set = 1, binding = 8) uniform sampler normalSampler;
+layout(set = 1, binding = 9) uniform sampler occlusionSampler;
+layout(set = 1, binding = 10) uniform sampler emissiveSampler;
+
+layout(push_constant) uniform PushConstants {
+    mat4 model;
+    uint outputColorSpace;
+} pushConstants;
+
+//Alpha modes, as declared by MaterialUniforms
+const uint ALPHA_MODE_OPAQUE = 0;
+const uint ALPHA_MODE_MASK = 1;
+const uint ALPHA_MODE_BLEND = 2;
+
+//Light types, as declared by GpuLight
+const uint LIGHT_TYPE_DIRECTIONAL = 0;
+const uint LIGHT_TYPE_POINT = 1;
+const uint LIGHT_TYPE_SPOT = 2;
+
+const float PI = 3.14159265359;
+
+//Reflectance at normal incidence of the dielectrics
+const float DIELECTRIC_F0 = 0.04;
+
+//Output color spaces, as declared by OutputColorSpace
+const uint OUTPUT_COLOR_SPACE_SRGB = 0;
+const uint OUTPUT_COLOR_SPACE_HDR10 = 1;
+const uint OUTPUT_COLOR_SPACE_SCRGB = 2;
+
+//Luminance (in nits) of the SDR white in HDR, as recommended by BT.2408
+const float SDR_WHITE_NITS = 203.0;
+
+//Luminance (in nits) of 1.0 in scRGB
+const float SCRGB_WHITE_NITS = 80.0;
+
+//Convert linear colors from the BT.709 primaries to the BT.2020 primaries (column major)
+const mat3 BT709_TO_BT2020 = mat3(
+    0.6274, 0.0691, 0.0164,
+    0.3293, 0.9195, 0.0880,
+    0.0433, 0.0114, 0.8956
+);
+
+//Encode linear colors (in nits) with the ST 2084 (PQ) curve
+vec3 encodePq(vec3 nits) {
+    const float m1 = 0.1593017578125;
+    const float m2 = 78.84375;
+    const float c1 = 0.8359375;
+    const float c2 = 18.8515625;
+    const float c3 = 18.6875;
+
+    vec3 y = pow(clamp(nits / 10000.0, 0.0, 1.0), vec3(m1));
+    return pow((c1 + c2 * y) / (1.0 + c3 * y), vec3(m2));
+}
+
+//Tonemap and encode linear colors in BT.709, where 1.0 is the SDR white, for the output color space
+vec3 toOutputColorSpace(vec3 color) {
+    if (pushConstants.outputColorSpace == OUTPUT_COLOR_SPACE_HDR10) {
+        return encodePq(BT709_TO_BT2020 * color * SDR_WHITE_NITS);
+    }
+    if (pushConstants.outputColorSpace == OUTPUT_COLOR_SPACE_SCRGB) {
+        return color * (SDR_WHITE_NITS / SCRGB_WHITE_NITS);
+    }
+    //The sRGB encoding is done by the swapchain format
+    return clamp(color, 0.0, 1.0);
+}
+
+//Return the world space normal of the fragment, perturbed by the normal map
+vec3 getNormal() {
+    vec3 normal = normalize(fragWorldNormal);
+    //The back faces of the double sided materials are lit as front faces
+    if (!gl_FrontFacing) {
+        normal = -normal;
+    }
+
+    vec3 tangent = normalize(fragWorldTangent.xyz - dot(fragWorldTangent.xyz, normal) * normal);
+    vec3 bitangent = cross(normal, tangent) * fragWorldTangent.w;
+
+    vec3 tangentNormal = texture(sampler2D(normalTexture, normalSampler), fragUv).xyz * 2.0 - 1.0;
+    tangentNormal.xy *= material.normalScale;
+    return normalize(mat3(tangent, bitangent, normal) * tangentNormal);
+}
+
+//GGX (Trowbridge-Reitz) distribution of the microfacet normals
+float distributionGgx(float normalDotHalf, float alpha) {
+    float alpha2 = alpha * alpha;
+    float denominator = normalDotHalf * normalDotHalf * (alpha2 - 1.0) + 1.0;
+    return alpha2 / (PI * denominator * denominator);
+}
+
+//Smith height-correlated visibility, the geometric shadowing divided by the denominator of the specular BRDF
+float visibilitySmithGgx(float normalDotLight, float normalDotView, float alpha) {
+    float alpha2 = alpha * alpha;
+    float lightTerm = normalDotView * sqrt(normalDotLight * normalDotLight * (1.0 - alpha2) + alpha2);
+    float viewTerm = normalDotLight * sqrt(normalDotView * normalDotView * (1.0 - alpha2) + alpha2);
+    return 0.5 / max(lightTerm + viewTerm, 1e-5);
+}
+
+vec3 fresnelSchlick(float viewDotHalf, vec3 f0) {
+    return f0 + (1.0 - f0) * pow(1.0 - viewDotHalf, 5.0);
+}
+
+//Return the light reflected toward `viewDirection` from the light coming from `lightDirection` with `lightColor`
+vec3 getReflectedLight(
+    vec3 normal,
+    vec3 viewDirection,
+    vec3 lightDirection,
+    vec3 lightColor,
+    vec3 baseColor,
+    float metallic,
+    float roughness
+) {
+    float normalDotLight = dot(normal, lightDirection);
+    if (normalDotLight <= 0.0) {
+        return vec3(0.0);
+    }
+
+    vec3 halfDirection = normalize(viewDirection + lightDirection);
+    float normalDotView = max(dot(normal, viewDirection), 1e-4);
+    float normalDotHalf = max(dot(normal, halfDirection), 0.0);
+    float viewDotHalf = max(dot(viewDirection, halfDirection), 0.0);
+
+    //The roughness is perceptual, squared to the alpha of the microfacet distribution
+    float alpha = roughness * roughness;
+    vec3 f0 = mix(vec3(DIELECTRIC_F0), baseColor, metallic);
+    vec3 fresnel = fresnelSchlick(viewDotHalf, f0);
+
+    vec3 specular = fresnel * distributionGgx(normalDotHalf, alpha)
+        * visibilitySmithGgx(normalDotLight, normalDotView, alpha);
+    vec3 diffuse = (1.0 - fresnel) * (1.0 - metallic) * baseColor / PI;
+    return (diffuse + specular) * lightColor * normalDotLight;
+}
+
+//Number of texels around the center of the PCF of the shadows, sampling (2 * PCF_RADIUS + 1)^2 texels
+const int PCF_RADIUS = 1;
+
+//Return the fraction of the fragment lit by `light`, from 0 in the shadow to 1 outside of it
+float getShadowFactor(Light light) {
+    if (light.shadowCount == 0) {
+        return 1.0;
+    }
+
+    //The cascade of the directional lights is the first one ending after the fragment
+    uint shadowIndex = light.firstShadowIndex;
+    if (light.lightType == LIGHT_TYPE_DIRECTIONAL) {
+        float viewDistance = -(frame.view * vec4(fragWorldPosition, 1.0)).z;
+        uint cascade = 0;
+        while (cascade < light.shadowCount && viewDistance > shadows[shadowIndex + cascade].splitDistance) {
+            cascade++;
+        }
+        if (cascade == light.shadowCount) {
+            return 1.0;
+        }
+        shadowIndex += cascade;
+    }
+    Shadow shadow = shadows[shadowIndex];
+
+    vec4 lightClipPosition = shadow.viewProjection * vec4(fragWorldPosition, 1.0);
+    if (lightClipPosition.w <= 0.0) {
+        return 1.0;
+    }
+    vec3 lightPosition = lightClipPosition.xyz / lightClipPosition.w;
+    if (any(greaterThan(abs(lightPosition.xy), vec2(1.0))) || lightPosition.z > 1.0) {
+        return 1.0;
+    }
+
+    //The samples are clamped to the tile, to not read the shadows of the neighbouring tiles
+    vec2 atlasUv = shadow.atlasRect.xy + (lightPosition.xy * 0.5 + 0.5) * shadow.atlasRect.zw;
+    vec2 minUv = shadow.atlasRect.xy + shadow.texelSize * 0.5;
+    vec2 maxUv = shadow.atlasRect.xy + shadow.atlasRect.zw - shadow.texelSize * 0.5;
+
+    float litSampleCount = 0.0;
+    for (int y = -PCF_RADIUS; y <= PCF_RADIUS; y++) {
+        for (int x = -PCF_RADIUS; x <= PCF_RADIUS; x++) {
+            vec2 sampleUv = clamp(atlasUv + vec2(x, y) * shadow.texelSize, minUv, maxUv);
+            float shadowDepth = texture(sampler2D(shadowAtlas, shadowSampler), sampleUv).r;
+            if (lightPosition.z <= shadowDepth) {
+                litSampleCount += 1.0;
+            }
+        }
+    }
+
+    float pcfWidth = float(2 * PCF_RADIUS + 1);
+    return litSampleCount / (pcfWidth * pcfWidth);
+}
+
+//Attenuation of the point lights and spotlights at `distance`, by the inverse square law smoothly reaching 0 at `range`
+float getRangeAttenuation(float distance, float range) {
+    float inverseSquare = 1.0 / max(distance * distance, 1e-4);
+    //A range of 0 is infinite
+    if (range <= 0.0) {
+        return inverseSquare;
+    }
+    float rangeRatio = distance / range;
+    return clamp(1.0 - rangeRatio * rangeRatio * rangeRatio * rangeRatio, 0.0, 1.0) * inverseSquare;
+}
+
+//Attenuation of the spotlights, from 1 inside the inner cone to 0 outside the outer cone
+float getSpotAttenuation(Light light, vec3 lightToFragment) {
+    float cosAngle = dot(light.direction, lightToFragment);
+    float attenuation = clamp(
+        (cosAngle - light.outerConeCos) / max(light.innerConeCos - light.outerConeCos, 1e-4),
+        0.0,
+        1.0
+    );
+    return attenuation * attenuation;
+}
+
+//Return the light reflected toward `viewDirection` from all the lights of the frame
+vec3 getReflectedLights(vec3 normal, vec3 viewDirection, vec3 baseColor, float metallic, float roughness) {
+    vec3 color = vec3(0.0);
+
+    //The light count is limited by the host to the size of the buffer
+    for (uint lightIndex = 0; lightIndex < lightCount; lightIndex++) {
+        Light light = lights[lightIndex];
+
+        vec3 lightDirection = -light.direction;
+        vec3 lightColor = light.color;
+        if (light.lightType != LIGHT_TYPE_DIRECTIONAL) {
+            vec3 lightToFragment = fragWorldPosition - light.position;
+            float distance = length(lightToFragment);
+            lightDirection = -lightToFragment / max(distance, 1e-4);
+            lightColor *= getRangeAttenuation(distance, light.range);
+            if (light.lightType == LIGHT_TYPE_SPOT) {
+                lightColor *= getSpotAttenuation(light, -lightDirection);
+            }
+        }
+        lightColor *= getShadowFactor(light);
+
+        color += getReflectedLight(
+            normal,
+            viewDirection,
+            lightDirection,
+            lightColor,
+            baseColor,
+            metallic,
+            roughness
+        );
+    }
+
+    return color;
+}
+
+//Return the light of the environment reflected toward `viewDirection`, with the split sum approximation:
+//the irradiance, and the environment prefiltered by roughness scaled and biased by the BRDF LUT
+vec3 getEnvironmentLight(vec3 normal, vec3 viewDirection, vec3 baseColor, float metallic, float roughness) {
+    float normalDotView = max(dot(normal, viewDirection), 1e-4);
+    vec3 f0 = mix(vec3(DIELECTRIC_F0), baseColor, metallic);
+    //The rough surfaces reflect less at grazing angles than the smooth ones
+    vec3 fresnel = f0 + (max(vec3(1.0 - roughness), f0) - f0) * pow(1.0 - normalDotView, 5.0);
+
+    vec3 irradiance = texture(samplerCube(irradianceTexture, environmentSampler), normal).rgb;
+    vec3 diffuse = (1.0 - fresnel) * (1.0 - metallic) * baseColor * irradiance;
+
+    //The mip levels of the prefiltered environment are evenly spaced in roughness
+    vec3 reflection = reflect(-viewDirection, normal);
+    float maxLod = float(textureQueryLevels(samplerCube(prefilteredTexture, environmentSampler)) - 1);
+    vec3 prefiltered = textureLod(
+        samplerCube(prefilteredTexture, environmentSampler),
+        reflection,
+        roughness * maxLod
+    ).rgb;
+    vec2 scaleBias = texture(sampler2D(brdfLut, environmentSampler), vec2(normalDotView, roughness)).rg;
+    vec3 specular = prefiltered * (f0 * scaleBias.x + scaleBias.y);
+
+    return diffuse + specular;
+}
+
+void main() {
+    vec4 baseColor = material.baseColorFactor
+        * texture(sampler2D(baseColorTexture, baseColorSampler), fragUv);
+    if (material.alphaMode == ALPHA_MODE_MASK && baseColor.a < material.alphaCutoff) {
+        discard;
+    }
+    if (material.alphaMode != ALPHA_MODE_BLEND) {
+        baseColor.a = 1.0;
+    }
+
+    //The metalness is in B and the roughness in G
+    vec4 metallicRoughness = texture(sampler2D(metallicRoughnessTexture, metallicRoughnessSampler), fragUv);
+    float metallic = clamp(material.metallicFactor * metallicRoughness.b, 0.0, 1.0);
+    float roughness = clamp(material.roughnessFactor * metallicRoughness.g, 0.04, 1.0);
+
+    float occlusion = texture(sampler2D(occlusionTexture, occlusionSampler), fragUv).r;
+    occlusion = mix(1.0, occlusion, material.occlusionStrength);
+
+    vec3 normal = getNormal();
+    vec3 viewDirection = normalize(frame.cameraPosition.xyz - fragWorldPosition);
+
+    vec3 color = getReflectedLights(normal, viewDirection, baseColor.rgb, metallic, roughness);
+    color += getEnvironmentLight(normal, viewDirection, baseColor.rgb, metallic, roughness) * occlusion;
+    color += material.emissiveFactor.rgb
+        * texture(sampler2D(emissiveTexture, emissiveSampler), fragUv).rgb;
+
+    outColor = vec4(toOutputColorSpace(color), baseColor.a);
+}
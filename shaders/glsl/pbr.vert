@@ -0,0 +1,37 @@
+#version 450
+
+//Vertex attributes, as described by VERTEX_ATTRIBUTE_DESCRIPTIONS
+layout(location = 0) in vec3 inPosition;
+layout(location = 1) in vec3 inNormal;
+layout(location = 2) in vec2 inUv;
+layout(location = 3) in vec4 inTangent;
+
+layout(location = 0) out vec3 fragWorldPosition;
+layout(location = 1) out vec3 fragWorldNormal;
+layout(location = 2) out vec2 fragUv;
+layout(location = 3) out vec4 fragWorldTangent;
+
+//Camera of the frame, as declared by CameraUniforms
+layout(set = 0, binding = 0) uniform FrameUniforms {
+    mat4 view;
+    mat4 projection;
+    mat4 viewProjection;
+    vec4 cameraPosition;
+} frame;
+
+layout(push_constant) uniform PushConstants {
+    mat4 model;
+    uint outputColorSpace;
+} pushConstants;
+
+void main() {
+    vec4 worldPosition = pushConstants.model * vec4(inPosition, 1.0);
+    gl_Position = frame.viewProjection * worldPosition;
+
+    //The normals are transformed by the inverse transpose, to stay perpendicular to the non-uniformly scaled surfaces
+    mat3 modelMatrix = mat3(pushConstants.model);
+    fragWorldPosition = worldPosition.xyz;
+    fragWorldNormal = transpose(inverse(modelMatrix)) * inNormal;
+    fragUv = inUv;
+    fragWorldTangent = vec4(modelMatrix * inTangent.xyz, inTangent.w);
+}
@@ -0,0 +1,13 @@
+#version 450
+
+//Vertex attributes, as described by VERTEX_ATTRIBUTE_DESCRIPTIONS, only the positions are rendered in the shadow maps
+layout(location = 0) in vec3 inPosition;
+
+layout(push_constant) uniform PushConstants {
+    //Transforms the model space of the drawn mesh to the clip space of the light
+    mat4 modelViewProjection;
+} pushConstants;
+
+void main() {
+    gl_Position = pushConstants.modelViewProjection * vec4(inPosition, 1.0);
+}
@@ -0,0 +1,63 @@
+#version 450
+
+layout(location = 0) in vec4 fragDirection;
+
+layout(location = 0) out vec4 outColor;
+
+//Environment cube map, as created by VulkanEnvironmentImages
+layout(set = 0, binding = 5) uniform textureCube environmentTexture;
+layout(set = 0, binding = 9) uniform sampler environmentSampler;
+
+layout(push_constant) uniform PushConstants {
+    mat4 inverseViewProjection;
+    uint outputColorSpace;
+} pushConstants;
+
+//Output color spaces, as declared by OutputColorSpace
+const uint OUTPUT_COLOR_SPACE_SRGB = 0;
+const uint OUTPUT_COLOR_SPACE_HDR10 = 1;
+const uint OUTPUT_COLOR_SPACE_SCRGB = 2;
+
+//Luminance (in nits) of the SDR white in HDR, as recommended by BT.2408
+const float SDR_WHITE_NITS = 203.0;
+
+//Luminance (in nits) of 1.0 in scRGB
+const float SCRGB_WHITE_NITS = 80.0;
+
+//Convert linear colors from the BT.709 primaries to the BT.2020 primaries (column major)
+const mat3 BT709_TO_BT2020 = mat3(
+    0.6274, 0.0691, 0.0164,
+    0.3293, 0.9195, 0.0880,
+    0.0433, 0.0114, 0.8956
+);
+
+//Encode linear colors (in nits) with the ST 2084 (PQ) curve
+vec3 encodePq(vec3 nits) {
+    const float m1 = 0.1593017578125;
+    const float m2 = 78.84375;
+    const float c1 = 0.8359375;
+    const float c2 = 18.8515625;
+    const float c3 = 18.6875;
+
+    vec3 y = pow(clamp(nits / 10000.0, 0.0, 1.0), vec3(m1));
+    return pow((c1 + c2 * y) / (1.0 + c3 * y), vec3(m2));
+}
+
+//Tonemap and encode linear colors in BT.709, where 1.0 is the SDR white, for the output color space
+vec3 toOutputColorSpace(vec3 color) {
+    if (pushConstants.outputColorSpace == OUTPUT_COLOR_SPACE_HDR10) {
+        return encodePq(BT709_TO_BT2020 * color * SDR_WHITE_NITS);
+    }
+    if (pushConstants.outputColorSpace == OUTPUT_COLOR_SPACE_SCRGB) {
+        return color * (SDR_WHITE_NITS / SCRGB_WHITE_NITS);
+    }
+    //The sRGB encoding is done by the swapchain format
+    return clamp(color, 0.0, 1.0);
+}
+
+void main() {
+    vec3 direction = normalize(fragDirection.xyz / fragDirection.w);
+    vec3 color = textureLod(samplerCube(environmentTexture, environmentSampler), direction, 0.0).rgb;
+
+    outColor = vec4(toOutputColorSpace(color), 1.0);
+}
@@ -0,0 +1,18 @@
+#version 450
+
+//Position of the fragment unprojected in the world space around the camera, divided by W in the fragment shader
+layout(location = 0) out vec4 fragDirection;
+
+layout(push_constant) uniform PushConstants {
+    mat4 inverseViewProjection;
+    uint outputColorSpace;
+} pushConstants;
+
+void main() {
+    //A triangle covering the screen, on the far plane
+    vec2 position = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2) * 2.0 - 1.0;
+    gl_Position = vec4(position, 1.0, 1.0);
+
+    //The homogeneous position is interpolated linearly, unlike the direction
+    fragDirection = pushConstants.inverseViewProjection * vec4(position, 1.0, 1.0);
+}
@@ -0,0 +1,186 @@
+use crate::{
+    math::{matrix::Mat4, quaternion::Quat, vector::Vec4},
+    scene::{NodeId, Scene},
+};
+
+///Joints of a skeleton, deforming the vertices of the skinned meshes attached to them
+#[derive(Clone, Debug, PartialEq)]
+pub struct Skin {
+    pub name: String,
+
+    ///The nodes of the joints, indexed by the joint indices of the vertices
+    pub all_joint_ids: Vec<NodeId>,
+
+    ///The matrices transforming the model space of the skinned meshes to the local space of each joint
+    pub all_inverse_bind_matrices: Vec<Mat4>,
+}
+
+impl Skin {
+    ///Return the matrix of each joint, transforming the model space of the skinned meshes to the world space
+    ///
+    ///update_world_transforms must be called before, after the transforms of the joints changed
+    pub fn get_all_joint_matrices(&self, scene: &Scene) -> Vec<Mat4> {
+        self.all_joint_ids
+            .iter()
+            .zip(&self.all_inverse_bind_matrices)
+            .map(|(&joint_id, inverse_bind_matrix)| {
+                scene
+                    .get_node(joint_id)
+                    .map_or(Mat4::IDENTITY, |joint| *joint.get_world_matrix())
+                    * *inverse_bind_matrix
+            })
+            .collect()
+    }
+}
+
+///How the values of an AnimationSampler are interpolated between two keyframes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    ///The value of the previous keyframe is kept until the next one
+    Step,
+
+    ///The values are interpolated linearly, and the rotations spherically
+    #[default]
+    Linear,
+
+    ///The values are interpolated by a cubic Hermite spline,
+    ///each keyframe has an in-tangent, a value and an out-tangent
+    CubicSpline,
+}
+
+///Property of a node animated by an AnimationChannel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationProperty {
+    Translation,
+    Rotation,
+    Scale,
+}
+
+///Keyframes of an animated property
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnimationSampler {
+    pub interpolation: Interpolation,
+
+    ///The time (in seconds) of each keyframe, in increasing order
+    pub all_times: Vec<f32>,
+
+    ///The values of the keyframes, 3 by keyframe for a cubic spline
+    ///
+    ///The translations and scales are in XYZ, the rotations are quaternions in XYZW
+    pub all_values: Vec<Vec4>,
+}
+
+impl AnimationSampler {
+    ///Return the value at `time`, clamped to the first and the last keyframes
+    ///
+    ///The rotations interpolated by a cubic spline aren't normalized
+    pub fn sample(&self, time: f32, is_rotation: bool) -> Option<Vec4> {
+        let value_count_by_keyframe = match self.interpolation {
+            Interpolation::CubicSpline => 3,
+            _ => 1,
+        };
+        //The value of a keyframe is after its in-tangent for a cubic spline
+        let get_value = |keyframe: usize| {
+            self.all_values
+                .get(keyframe * value_count_by_keyframe + value_count_by_keyframe / 2)
+                .copied()
+        };
+
+        let next_keyframe = self
+            .all_times
+            .partition_point(|&keyframe_time| keyframe_time <= time);
+        if next_keyframe == 0 {
+            return get_value(0);
+        }
+        if next_keyframe == self.all_times.len() {
+            return get_value(next_keyframe - 1);
+        }
+
+        let previous_keyframe = next_keyframe - 1;
+        let previous_time = self.all_times[previous_keyframe];
+        let keyframe_duration = self.all_times[next_keyframe] - previous_time;
+        let t = (time - previous_time) / keyframe_duration;
+        let previous_value = get_value(previous_keyframe)?;
+        let next_value = get_value(next_keyframe)?;
+
+        match self.interpolation {
+            Interpolation::Step => Some(previous_value),
+            Interpolation::Linear if is_rotation => {
+                let previous_rotation = Quat::new(
+                    previous_value.x,
+                    previous_value.y,
+                    previous_value.z,
+                    previous_value.w,
+                );
+                let next_rotation =
+                    Quat::new(next_value.x, next_value.y, next_value.z, next_value.w);
+                let rotation = previous_rotation.slerp(next_rotation, t);
+                Some(Vec4::new(rotation.x, rotation.y, rotation.z, rotation.w))
+            }
+            Interpolation::Linear => Some(previous_value.lerp(next_value, t)),
+            Interpolation::CubicSpline => {
+                let previous_out_tangent =
+                    self.all_values.get(previous_keyframe * 3 + 2).copied()?;
+                let next_in_tangent = self.all_values.get(next_keyframe * 3).copied()?;
+
+                let t_2 = t * t;
+                let t_3 = t_2 * t;
+                Some(
+                    previous_value * (2f32 * t_3 - 3f32 * t_2 + 1f32)
+                        + previous_out_tangent * ((t_3 - 2f32 * t_2 + t) * keyframe_duration)
+                        + next_value * (-2f32 * t_3 + 3f32 * t_2)
+                        + next_in_tangent * ((t_3 - t_2) * keyframe_duration),
+                )
+            }
+        }
+    }
+}
+
+///Animation of a property of a node
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationChannel {
+    pub target_id: NodeId,
+    pub property: AnimationProperty,
+    pub sampler: AnimationSampler,
+}
+
+///Keyframed animation of the transforms of nodes of a Scene
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Animation {
+    pub name: String,
+    pub all_channels: Vec<AnimationChannel>,
+}
+
+impl Animation {
+    ///Return the time (in seconds) of the last keyframe of all the channels
+    pub fn get_duration(&self) -> f32 {
+        self.all_channels
+            .iter()
+            .filter_map(|channel| channel.sampler.all_times.last().copied())
+            .fold(0f32, f32::max)
+    }
+
+    ///Set the animated properties of the nodes of `scene` to their values at `time` (in seconds)
+    ///
+    ///The channels targeting nodes missing from `scene` are ignored
+    pub fn apply(&self, scene: &mut Scene, time: f32) {
+        for channel in &self.all_channels {
+            let is_rotation = channel.property == AnimationProperty::Rotation;
+            let Some(value) = channel.sampler.sample(time, is_rotation) else {
+                continue;
+            };
+            let Some(node) = scene.get_node_mut(channel.target_id) else {
+                continue;
+            };
+
+            let transform = node.get_transform_mut();
+            match channel.property {
+                AnimationProperty::Translation => transform.translation = value.truncate(),
+                AnimationProperty::Rotation => {
+                    transform.rotation = Quat::new(value.x, value.y, value.z, value.w).normalize();
+                }
+                AnimationProperty::Scale => transform.scale = value.truncate(),
+            }
+        }
+    }
+}
@@ -1,82 +1,1185 @@
-use std::{thread::sleep, time::Duration};
+use std::{
+    collections::BTreeSet,
+    f32::consts::{FRAC_PI_3, FRAC_PI_6},
+    ops::Range,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use ash::vk::{self, Extent2D, Format};
-use glfw::{GlfwReceiver, PWindow, WindowEvent, WindowMode};
+use ash::vk;
+use glfw::{GlfwReceiver, Key, PWindow, WindowEvent};
 
 use crate::{
+    animation::Animation,
+    camera::{Camera, CameraUniforms},
+    camera_controller::{CameraController, FlyCameraController, bind_default_camera_actions},
+    ecs::{
+        components::ActiveCamera,
+        schedule::{FrameTime, Schedule},
+        systems::{get_all_lights, sync_camera_transforms},
+        world::World,
+    },
+    environment::{EnvironmentConfig, EnvironmentImage, load_environment_image_from_file},
     glfw::GlfwEntry,
+    gltf::load_gltf_from_file,
+    input::{InputBinding, InputState},
+    material::{Material, MaterialPipelineKey},
+    math::{quaternion::Quat, vector::Vec3},
+    mesh::MeshData,
+    obj::load_obj_from_file,
+    render::{PbrPushConstants, RenderSystem, ShadowPushConstants, SkyboxPushConstants},
+    scene::{Light, MeshId, Scene, Transform},
+    shadow::ShadowConfig,
     vulkan::{
-        device::VulkanDevice, entry::VulkanEntry, graphics_pipeline::VulkanGraphicsPipeline,
-        instance::VulkanInstance, shaders::VulkanShaderModule, surface::VulkanSurface,
-        swapchain::VulkanSwapchain,
+        buffer::VulkanBuffer,
+        capture::VulkanImageCapture,
+        command_buffer::VulkanCommandPool,
+        descriptor::{VulkanDescriptorPool, VulkanDescriptorSetLayout},
+        device::VulkanDevice,
+        entry::VulkanEntry,
+        environment::{VulkanEnvironmentImages, get_environment_sampler_parameters},
+        framebuffer::VulkanFramebuffer,
+        frames::{MAX_FRAMES_IN_FLIGHT, VulkanFramesInFlight},
+        graphics_pipeline::{GraphicsPipelineConfig, VulkanGraphicsPipeline, push_constant_range},
+        image::{VulkanImage, VulkanImageView, VulkanSampler},
+        instance::VulkanInstance,
+        light::{DEFAULT_MAX_LIGHT_COUNT, VulkanLightBuffers},
+        material::{
+            VulkanMaterialInstance, VulkanMaterialTextures, create_material_descriptor_pool,
+            create_material_descriptor_set_layout,
+        },
+        mesh::{VERTEX_ATTRIBUTE_DESCRIPTIONS, VERTEX_BINDING_DESCRIPTIONS, VulkanMesh},
+        shader_watcher::ShaderWatcher,
+        shaders::{
+            DEFAULT_SHADER_ENTRYPOINT, ShaderModuleError, VulkanShaderModule, VulkanShaderStage,
+        },
+        shadow::{create_shadow_atlas_image, get_shadow_sampler_parameters},
+        surface::VulkanSurface,
+        swapchain::{SwapchainConfig, VulkanSwapchain},
     },
+    window::{self, WindowConfig, WindowPlacement},
 };
 
-pub struct Application {
+///Minimum time (in seconds) between two checks of the modified shaders
+const SHADER_WATCH_INTERVAL: f64 = 0.25;
+
+///Action capturing the next rendered frame in a PNG file
+pub const SCREENSHOT_ACTION: &str = "screenshot";
+
+///Action enabling or disabling the vertical synchronization
+pub const VSYNC_ACTION: &str = "toggle_vsync";
+
+///Action switching the window between windowed and fullscreen
+pub const FULLSCREEN_ACTION: &str = "toggle_fullscreen";
+
+///Action switching between the fly and the orbit camera controllers
+pub const CAMERA_CONTROLLER_ACTION: &str = "toggle_camera_controller";
+
+///Action capturing or releasing the cursor, to look around with the fly camera
+pub const CURSOR_CAPTURE_ACTION: &str = "toggle_cursor_capture";
+
+///Key bound by default to SCREENSHOT_ACTION
+const SCREENSHOT_KEY: Key = Key::F12;
+
+///Key bound by default to VSYNC_ACTION
+const VSYNC_KEY: Key = Key::V;
+
+///Key bound by default to FULLSCREEN_ACTION
+const FULLSCREEN_KEY: Key = Key::F11;
+
+///Key bound by default to CAMERA_CONTROLLER_ACTION
+const CAMERA_CONTROLLER_KEY: Key = Key::C;
+
+///Key bound by default to CURSOR_CAPTURE_ACTION
+const CURSOR_CAPTURE_KEY: Key = Key::Tab;
+
+///Initial position of the camera, looking toward -Z
+const CAMERA_START_POSITION: Vec3 = Vec3::new(0f32, 0f32, 3f32);
+
+///Distance in front of the camera of the point the orbit controller orbits around
+const CAMERA_ORBIT_DISTANCE: f32 = 3f32;
+
+///OBJ or glTF file of the model drawn by default
+pub const DEFAULT_MODEL_FILE_PATH: &str = "assets/models/cube.obj";
+
+///The first mesh of the loaded model, the only one of an OBJ model
+pub const MODEL_MESH: MeshId = MeshId(0);
+
+///Linear color of the directional light added to the scenes without lights
+const DEFAULT_LIGHT_COLOR: Vec3 = Vec3::new(1f32, 1f32, 1f32);
+
+///Illuminance of the directional light added to the scenes without lights
+const DEFAULT_LIGHT_INTENSITY: f32 = 3f32;
+
+///Linear color of the environment lighting the scenes without environment image, with no skybox drawn
+const DEFAULT_ENVIRONMENT_COLOR: Vec3 = Vec3::new(0.03f32, 0.03f32, 0.03f32);
+
+///Speed (in radians per second) at which the nodes of the scene turn around the Y axis
+const SCENE_ROTATION_SPEED: f32 = 0.5f32;
+
+///Binding of the CameraUniforms buffer in the descriptor set of a frame
+const CAMERA_UNIFORMS_BINDING: u32 = 0;
+
+///Binding of the light buffer in the descriptor set of a frame
+const LIGHTS_BINDING: u32 = 1;
+
+///Binding of the shadow buffer in the descriptor set of a frame
+const SHADOWS_BINDING: u32 = 2;
+
+///Binding of the sampled image of the shadow atlas in the descriptor set of a frame
+const SHADOW_ATLAS_BINDING: u32 = 3;
+
+///Binding of the sampler of the shadow atlas in the descriptor set of a frame
+const SHADOW_SAMPLER_BINDING: u32 = 4;
+
+///Binding of the sampled image of the environment cube map in the descriptor set of a frame
+const ENVIRONMENT_BINDING: u32 = 5;
+
+///Binding of the sampled image of the irradiance cube map in the descriptor set of a frame
+const IRRADIANCE_BINDING: u32 = 6;
+
+///Binding of the sampled image of the prefiltered cube map in the descriptor set of a frame
+const PREFILTERED_BINDING: u32 = 7;
+
+///Binding of the sampled image of the BRDF LUT in the descriptor set of a frame
+const BRDF_LUT_BINDING: u32 = 8;
+
+///Binding of the sampler of the environment images in the descriptor set of a frame
+const ENVIRONMENT_SAMPLER_BINDING: u32 = 9;
+
+///Shader modules of the pipelines drawing the materials, in the shader modules of the app
+const PBR_SHADER_MODULES: Range<usize> = 0..2;
+
+///Shader modules of the pipeline rendering the shadow atlas, in the shader modules of the app
+const SHADOW_SHADER_MODULES: Range<usize> = 2..3;
+
+///Shader modules of the pipeline drawing the skybox, in the shader modules of the app
+const SKYBOX_SHADER_MODULES: Range<usize> = 3..5;
+
+pub struct Application<'app, 'glfw_lifetime> {
+    glfw_entry: &'app mut GlfwEntry<'glfw_lifetime>,
+    vulkan_entry: &'app VulkanEntry,
     glfw_window: PWindow,
     glfw_events: GlfwReceiver<(f64, WindowEvent)>,
+
+    ///The config the window was created from
+    window_config: WindowConfig,
+
+    ///The placement of the window before it was switched to fullscreen, restored when it's windowed again
+    windowed_placement: Option<WindowPlacement>,
+
+    ///The input of the current frame, built from `glfw_events`
+    input_state: InputState,
+
+    ///If the swapchain presents in HDR when the surface supports it
+    is_hdr_requested: bool,
+
+    ///The entities updated by `schedule` and drawn each frame
+    world: World,
+
+    ///The systems run on `world` each frame
+    schedule: Schedule,
+
+    ///The OBJ or glTF file of the model drawn in the scene
+    model_file_path: PathBuf,
+
+    ///The maximum number of lights shading the scene, the other lights are ignored
+    max_light_count: usize,
+
+    ///The shadow atlas and the cascades of the directional lights
+    shadow_config: ShadowConfig,
+
+    ///The Radiance HDR or OpenEXR equirectangular image drawn by the skybox and lighting the scene,
+    ///None for a uniform ambient light without skybox
+    environment_file_path_opt: Option<PathBuf>,
 }
 
-impl Application {
-    pub fn new(glfw_entry: &mut GlfwEntry, vulkan_entry: &VulkanEntry, title: &str) -> Self {
-        glfw_entry.window_hint(glfw::WindowHint::ClientApi(glfw::ClientApiHint::NoApi));
-        glfw_entry.window_hint(glfw::WindowHint::CenterCursor(false));
-        glfw_entry.window_hint(glfw::WindowHint::AutoIconify(false));
-
-        let (window, events) = glfw_entry
-            .with_connected_monitors(|glfw_entry, monitors| {
-                if monitors.len() > 1 {
-                    let second_monitor = &monitors[1];
-                    let video_mode_opt = second_monitor.get_video_mode();
-                    if let Some(video_mode) = video_mode_opt {
-                        return glfw_entry.create_window(
-                            video_mode.width,
-                            video_mode.height,
-                            title,
-                            WindowMode::FullScreen(second_monitor),
-                        );
-                    }
-                }
-                glfw_entry.create_window(700, 700, title, WindowMode::Windowed)
-            })
-            .expect("failed to create window");
+///Meshes and materials of the model drawn by the app
+struct LoadedModel {
+    ///The meshes indexed by MeshId
+    all_meshes: Vec<MeshData>,
 
-        let vulkan_instance = VulkanInstance::new_from_glfw(vulkan_entry, glfw_entry);
+    ///The materials indexed by the submeshes of all the meshes
+    all_materials: Vec<Material>,
+
+    ///The nodes of a glTF model, None for an OBJ model whose mesh is drawn by the entities of the world
+    scene_opt: Option<Scene>,
+
+    ///The animation of the nodes of `scene_opt` played in a loop
+    animation_opt: Option<Animation>,
+}
 
-        let vulkan_surface = VulkanSurface::new_from_glfw_window(&vulkan_instance, &window);
+impl<'app, 'glfw_lifetime> Application<'app, 'glfw_lifetime> {
+    pub fn new(
+        glfw_entry: &'app mut GlfwEntry<'glfw_lifetime>,
+        vulkan_entry: &'app VulkanEntry,
+        window_config: WindowConfig,
+    ) -> Self {
+        let (mut window, events) = window::create_window(glfw_entry, &window_config);
+
+        InputState::enable_event_polling(&mut window);
+        window.set_framebuffer_size_polling(true);
+
+        let mut input_state = InputState::new();
+        input_state.bind_action(SCREENSHOT_ACTION, InputBinding::Key(SCREENSHOT_KEY));
+        input_state.bind_action(VSYNC_ACTION, InputBinding::Key(VSYNC_KEY));
+        input_state.bind_action(FULLSCREEN_ACTION, InputBinding::Key(FULLSCREEN_KEY));
+        input_state.bind_action(
+            CAMERA_CONTROLLER_ACTION,
+            InputBinding::Key(CAMERA_CONTROLLER_KEY),
+        );
+        input_state.bind_action(CURSOR_CAPTURE_ACTION, InputBinding::Key(CURSOR_CAPTURE_KEY));
+        bind_default_camera_actions(&mut input_state);
+
+        Application {
+            glfw_entry,
+            vulkan_entry,
+            glfw_window: window,
+            glfw_events: events,
+            window_config,
+            windowed_placement: None,
+            input_state,
+            is_hdr_requested: false,
+            world: World::new(),
+            schedule: Schedule::new(),
+            model_file_path: PathBuf::from(DEFAULT_MODEL_FILE_PATH),
+            max_light_count: DEFAULT_MAX_LIGHT_COUNT,
+            shadow_config: ShadowConfig::default(),
+            environment_file_path_opt: None,
+        }
+    }
+
+    ///Return the input state, to bind actions or capture the cursor before running
+    pub fn get_input_state_mut(&mut self) -> &mut InputState {
+        &mut self.input_state
+    }
+
+    ///Return the ECS world, to spawn the entities drawn by the app before running
+    pub fn get_world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    ///Return the system schedule, to add the systems updating the world before running
+    ///
+    ///The systems run before the camera transforms are synchronized
+    pub fn get_schedule_mut(&mut self) -> &mut Schedule {
+        &mut self.schedule
+    }
+
+    ///Return if the model drawn in the scene is a glTF model, drawn by its own nodes
+    ///
+    ///Else it's an OBJ model, only drawn by the entities of the world with a MeshRenderer of MODEL_MESH
+    pub fn has_gltf_model(&self) -> bool {
+        is_gltf_file(&self.model_file_path)
+    }
+
+    ///Request the swapchain to present in HDR10 or scRGB, if the surface supports it
+    pub fn request_hdr(&mut self) {
+        self.is_hdr_requested = true;
+    }
+
+    ///Set the OBJ, glTF or GLB file of the model drawn in the scene, DEFAULT_MODEL_FILE_PATH by default
+    pub fn set_model_file_path(&mut self, model_file_path: impl Into<PathBuf>) {
+        self.model_file_path = model_file_path.into();
+    }
+
+    ///Set the maximum number of lights shading the scene, DEFAULT_MAX_LIGHT_COUNT by default
+    pub fn set_max_light_count(&mut self, max_light_count: usize) {
+        self.max_light_count = max_light_count;
+    }
+
+    ///Set the config of the shadow atlas and of the cascades of the directional lights, ShadowConfig::default() by default
+    pub fn set_shadow_config(&mut self, shadow_config: ShadowConfig) {
+        self.shadow_config = shadow_config;
+    }
+
+    ///Set the Radiance HDR or OpenEXR equirectangular image drawn by the skybox and lighting the scene
+    ///
+    ///Without it, the scene is lit by a uniform ambient light and no skybox is drawn
+    pub fn set_environment_file_path(&mut self, environment_file_path: impl Into<PathBuf>) {
+        self.environment_file_path_opt = Some(environment_file_path.into());
+    }
+
+    ///Switch the window between windowed and the fullscreen display mode of its config
+    ///
+    ///The swapchain must be recreated for the new size of the framebuffer
+    fn toggle_fullscreen(&mut self) {
+        if window::is_fullscreen(&self.glfw_window) {
+            window::set_windowed(
+                self.glfw_entry,
+                &mut self.glfw_window,
+                &self.window_config,
+                self.windowed_placement.take(),
+            );
+        } else {
+            self.windowed_placement =
+                window::set_fullscreen(self.glfw_entry, &mut self.glfw_window, &self.window_config);
+        }
+    }
+
+    pub fn run(mut self) {
+        let vulkan_instance = VulkanInstance::new_from_glfw(self.vulkan_entry, self.glfw_entry);
+
+        let vulkan_surface =
+            VulkanSurface::new_from_glfw_window(&vulkan_instance, &self.glfw_window);
 
         let vulkan_device =
             VulkanDevice::select_suitable_device_for_surface(&vulkan_instance, &vulkan_surface)
                 .expect("failed to select suitable device for surface");
 
-        let vulkan_swapchain =
-            VulkanSwapchain::new_from_device_and_surface(&vulkan_device, &vulkan_surface);
+        let mut is_vsync_enabled = self.window_config.is_vsync_enabled;
+        let mut swapchain_config = SwapchainConfig::default();
+        //The default present modes are kept until the vsync is toggled
+        if is_vsync_enabled {
+            swapchain_config.set_vsync(true);
+        }
+        swapchain_config.set_hdr(self.is_hdr_requested);
+        let mut vulkan_swapchain = VulkanSwapchain::new_from_device_and_surface(
+            &vulkan_device,
+            &vulkan_surface,
+            swapchain_config.clone(),
+            get_framebuffer_extent(&self.glfw_window),
+        );
 
         let vulkan_shader_1 = VulkanShaderModule::from_file(
             &vulkan_device,
-            "shaders/spir-v/base.vert.spv",
+            "shaders/spir-v/pbr.vert.spv",
             vk::ShaderStageFlags::VERTEX,
-        );
+        )
+        .expect("failed to load vertex shader");
         let vulkan_shader_2 = VulkanShaderModule::from_file(
             &vulkan_device,
-            "shaders/spir-v/base.frag.spv",
+            "shaders/spir-v/pbr.frag.spv",
+            vk::ShaderStageFlags::FRAGMENT,
+        )
+        .expect("failed to load fragment shader");
+        let vulkan_shader_3 = VulkanShaderModule::from_file(
+            &vulkan_device,
+            "shaders/spir-v/shadow.vert.spv",
+            vk::ShaderStageFlags::VERTEX,
+        )
+        .expect("failed to load shadow vertex shader");
+        let vulkan_shader_4 = VulkanShaderModule::from_file(
+            &vulkan_device,
+            "shaders/spir-v/skybox.vert.spv",
+            vk::ShaderStageFlags::VERTEX,
+        )
+        .expect("failed to load skybox vertex shader");
+        let vulkan_shader_5 = VulkanShaderModule::from_file(
+            &vulkan_device,
+            "shaders/spir-v/skybox.frag.spv",
             vk::ShaderStageFlags::FRAGMENT,
+        )
+        .expect("failed to load skybox fragment shader");
+        let mut all_shader_modules = [
+            vulkan_shader_1,
+            vulkan_shader_2,
+            vulkan_shader_3,
+            vulkan_shader_4,
+            vulkan_shader_5,
+        ];
+
+        let mut shader_watcher = ShaderWatcher::new();
+        for shader_module in &all_shader_modules {
+            shader_watcher.watch(shader_module);
+        }
+
+        let depth_format = vulkan_device
+            .get_supported_depth_format()
+            .expect("none depth format supported");
+        let mut depth_image = create_depth_image(&vulkan_device, depth_format, &vulkan_swapchain);
+        let mut depth_image_view = VulkanImageView::new_depth(&depth_image);
+
+        let vulkan_command_pool = VulkanCommandPool::new_for_graphics_queue(&vulkan_device);
+        //The present queue acquires the ownership of the swapchain images if they aren't shared concurrently
+        let vulkan_present_command_pool = vulkan_device
+            .has_separate_present_queue()
+            .then(|| VulkanCommandPool::new_for_present_queue(&vulkan_device));
+        let mut vulkan_frames_in_flight = VulkanFramesInFlight::new(
+            &vulkan_device,
+            &vulkan_command_pool,
+            vulkan_present_command_pool.as_ref(),
         );
 
-        let vulkan_graphics_pipeline = VulkanGraphicsPipeline::new(
+        let model = load_model(&self.model_file_path);
+        //The glTF meshes made of points or lines have no triangle to draw
+        let all_meshes: Vec<Option<VulkanMesh>> = model
+            .all_meshes
+            .iter()
+            .map(|mesh| {
+                (mesh.get_triangle_count() > 0)
+                    .then(|| VulkanMesh::new(&vulkan_device, &vulkan_command_pool, mesh))
+            })
+            .collect();
+        //The submeshes without a valid material are drawn with the default one, after the materials of the model
+        let mut all_materials = model.all_materials;
+        all_materials.push(Material::default());
+
+        let material_textures =
+            VulkanMaterialTextures::load(&vulkan_device, &vulkan_command_pool, &all_materials);
+        let all_material_image_views: Vec<VulkanImageView> = material_textures
+            .get_all_images()
+            .iter()
+            .map(VulkanImageView::new_color)
+            .collect();
+        let material_descriptor_set_layout = create_material_descriptor_set_layout(&vulkan_device);
+        let material_descriptor_pool =
+            create_material_descriptor_pool(&vulkan_device, all_materials.len());
+        let all_material_instances = VulkanMaterialInstance::new_all(
             &vulkan_device,
-            Extent2D::default().width(1920).height(1080),
-            Format::R8G8B8A8_SNORM,
-            &[vulkan_shader_1, vulkan_shader_2],
+            &vulkan_command_pool,
+            &material_descriptor_pool,
+            &material_descriptor_set_layout,
+            &all_materials,
+            &material_textures,
+            &all_material_image_views,
         );
 
-        Application {
-            glfw_window: window,
-            glfw_events: events,
+        //Each frame in flight has its own camera uniforms and lights, written while the other frames are rendered
+        let frame_descriptor_set_layout = create_frame_descriptor_set_layout(&vulkan_device);
+        let frame_descriptor_pool = VulkanDescriptorPool::new(
+            &vulkan_device,
+            MAX_FRAMES_IN_FLIGHT as u32,
+            &[
+                vk::DescriptorPoolSize::default()
+                    .ty(vk::DescriptorType::UNIFORM_BUFFER)
+                    .descriptor_count(MAX_FRAMES_IN_FLIGHT as u32),
+                vk::DescriptorPoolSize::default()
+                    .ty(vk::DescriptorType::STORAGE_BUFFER)
+                    .descriptor_count(2 * MAX_FRAMES_IN_FLIGHT as u32),
+                vk::DescriptorPoolSize::default()
+                    .ty(vk::DescriptorType::SAMPLED_IMAGE)
+                    .descriptor_count(5 * MAX_FRAMES_IN_FLIGHT as u32),
+                vk::DescriptorPoolSize::default()
+                    .ty(vk::DescriptorType::SAMPLER)
+                    .descriptor_count(2 * MAX_FRAMES_IN_FLIGHT as u32),
+            ],
+        );
+        let all_frame_descriptor_sets = frame_descriptor_pool.allocate_descriptor_sets(
+            &[frame_descriptor_set_layout.get_descriptor_set_layout(); MAX_FRAMES_IN_FLIGHT],
+        );
+        let all_camera_uniform_buffers: Vec<VulkanBuffer> = all_frame_descriptor_sets
+            .iter()
+            .map(|descriptor_set| {
+                let uniform_buffer = VulkanBuffer::new(
+                    &vulkan_device,
+                    size_of::<CameraUniforms>() as vk::DeviceSize,
+                    vk::BufferUsageFlags::UNIFORM_BUFFER,
+                    vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+                );
+                descriptor_set.write_uniform_buffer(CAMERA_UNIFORMS_BINDING, &uniform_buffer);
+                uniform_buffer
+            })
+            .collect();
+
+        //The shadow atlas is shared by the frames in flight, rendered once the previous frame sampled it
+        let shadow_depth_format = vulkan_device
+            .get_supported_sampled_depth_format()
+            .expect("none sampled depth format supported");
+        let shadow_atlas_image =
+            create_shadow_atlas_image(&vulkan_device, shadow_depth_format, &self.shadow_config);
+        let shadow_atlas_image_view = VulkanImageView::new_depth(&shadow_atlas_image);
+        let shadow_sampler = VulkanSampler::new(&vulkan_device, &get_shadow_sampler_parameters());
+        let mut shadow_graphics_pipeline = VulkanGraphicsPipeline::new_depth_only(
+            &vulkan_device,
+            shadow_atlas_image.get_extent(),
+            &create_all_shader_stages(&all_shader_modules[SHADOW_SHADER_MODULES])
+                .expect("failed to create shadow shader stages"),
+            &[push_constant_range::<ShadowPushConstants>(
+                vk::ShaderStageFlags::VERTEX,
+                0,
+            )],
+            &[],
+            &GraphicsPipelineConfig {
+                all_vertex_binding_descriptions: &VERTEX_BINDING_DESCRIPTIONS,
+                all_vertex_attribute_descriptions: &VERTEX_ATTRIBUTE_DESCRIPTIONS,
+                depth_format: Some(shadow_depth_format),
+                //The back faces also cast shadows, for the meshes which aren't closed
+                cull_mode: vk::CullModeFlags::NONE,
+                depth_bias: Some(self.shadow_config.depth_bias),
+                ..GraphicsPipelineConfig::default()
+            },
+        );
+        let shadow_framebuffer_opt = shadow_graphics_pipeline.get_render_pass().map(|_| {
+            VulkanFramebuffer::new_for_depth_image_view(
+                &vulkan_device,
+                &shadow_graphics_pipeline,
+                shadow_atlas_image_view.get_image_view(),
+                shadow_atlas_image.get_extent(),
+            )
+        });
+
+        let light_buffers = VulkanLightBuffers::new(
+            &vulkan_device,
+            self.max_light_count,
+            self.shadow_config.get_tile_count(),
+        );
+        for (frame_index, descriptor_set) in all_frame_descriptor_sets.iter().enumerate() {
+            light_buffers.write_descriptor_set(
+                frame_index,
+                descriptor_set,
+                LIGHTS_BINDING,
+                SHADOWS_BINDING,
+            );
+            descriptor_set.write_sampled_image(SHADOW_ATLAS_BINDING, &shadow_atlas_image_view);
+            descriptor_set.write_sampler(SHADOW_SAMPLER_BINDING, &shadow_sampler);
+        }
+
+        //The scenes without environment image are lit by the environment of a solid color, without skybox
+        let environment_image_opt =
+            self.environment_file_path_opt
+                .as_deref()
+                .and_then(|environment_file_path| {
+                    load_environment_image_from_file(environment_file_path)
+                        .inspect_err(|error| {
+                            eprintln!(
+                                "failed to load environment {}, a uniform ambient light is used: {error}",
+                                environment_file_path.display()
+                            )
+                        })
+                        .ok()
+                });
+        let is_skybox_drawn = environment_image_opt.is_some();
+        let environment_images = VulkanEnvironmentImages::new(
+            &vulkan_device,
+            &vulkan_command_pool,
+            &environment_image_opt
+                .unwrap_or_else(|| EnvironmentImage::new_solid_color(DEFAULT_ENVIRONMENT_COLOR)),
+            &EnvironmentConfig::default(),
+        )
+        .expect("failed to create environment images");
+        let environment_image_view =
+            VulkanImageView::new_color(environment_images.get_environment_image());
+        let irradiance_image_view =
+            VulkanImageView::new_color(environment_images.get_irradiance_image());
+        let prefiltered_image_view =
+            VulkanImageView::new_color(environment_images.get_prefiltered_image());
+        let brdf_lut_image_view =
+            VulkanImageView::new_color(environment_images.get_brdf_lut_image());
+        let environment_sampler =
+            VulkanSampler::new(&vulkan_device, &get_environment_sampler_parameters());
+        for descriptor_set in &all_frame_descriptor_sets {
+            descriptor_set.write_sampled_image(ENVIRONMENT_BINDING, &environment_image_view);
+            descriptor_set.write_sampled_image(IRRADIANCE_BINDING, &irradiance_image_view);
+            descriptor_set.write_sampled_image(PREFILTERED_BINDING, &prefiltered_image_view);
+            descriptor_set.write_sampled_image(BRDF_LUT_BINDING, &brdf_lut_image_view);
+            descriptor_set.write_sampler(ENVIRONMENT_SAMPLER_BINDING, &environment_sampler);
+        }
+
+        //The materials with the same pipeline key share their pipeline
+        let all_pipeline_keys: BTreeSet<MaterialPipelineKey> = all_material_instances
+            .iter()
+            .map(VulkanMaterialInstance::get_pipeline_key)
+            .collect();
+        let all_shader_stages = create_all_shader_stages(&all_shader_modules[PBR_SHADER_MODULES])
+            .expect("failed to create shader stages");
+        let mut all_graphics_pipelines: Vec<(MaterialPipelineKey, VulkanGraphicsPipeline)> =
+            all_pipeline_keys
+                .into_iter()
+                .map(|pipeline_key| {
+                    let graphics_pipeline = VulkanGraphicsPipeline::new_for_swapchain(
+                        &vulkan_device,
+                        &vulkan_swapchain,
+                        &all_shader_stages,
+                        &[push_constant_range::<PbrPushConstants>(
+                            vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
+                            0,
+                        )],
+                        &[
+                            frame_descriptor_set_layout.get_descriptor_set_layout(),
+                            material_descriptor_set_layout.get_descriptor_set_layout(),
+                        ],
+                        &get_material_pipeline_config(pipeline_key, depth_format),
+                    );
+                    (pipeline_key, graphics_pipeline)
+                })
+                .collect();
+        drop(all_shader_stages);
+
+        //The skybox is drawn behind the opaque draws, where the depth attachment is still cleared
+        let mut skybox_graphics_pipeline = VulkanGraphicsPipeline::new_for_swapchain(
+            &vulkan_device,
+            &vulkan_swapchain,
+            &create_all_shader_stages(&all_shader_modules[SKYBOX_SHADER_MODULES])
+                .expect("failed to create skybox shader stages"),
+            &[push_constant_range::<SkyboxPushConstants>(
+                vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
+                0,
+            )],
+            &[frame_descriptor_set_layout.get_descriptor_set_layout()],
+            &GraphicsPipelineConfig {
+                depth_format: Some(depth_format),
+                cull_mode: vk::CullModeFlags::NONE,
+                depth_compare_op: vk::CompareOp::LESS_OR_EQUAL,
+                ..GraphicsPipelineConfig::default()
+            },
+        );
+
+        //The render passes of the pipelines are compatible, the framebuffers are created for the first one
+        let mut all_framebuffers = create_all_framebuffers(
+            &vulkan_device,
+            &all_graphics_pipelines[0].1,
+            &vulkan_swapchain,
+            depth_image_view.get_image_view(),
+        );
+
+        self.schedule
+            .add_system("sync_camera_transforms", sync_camera_transforms);
+
+        let mut camera = Camera::new_perspective(FRAC_PI_3, 0.1f32, 1000f32);
+        camera.position = CAMERA_START_POSITION;
+        camera.set_aspect_ratio_from_swapchain(&vulkan_swapchain);
+        let mut camera_controller = CameraController::Fly(FlyCameraController::new(&camera));
+        let camera_entity = self.world.spawn();
+        self.world.insert(camera_entity, camera);
+        self.world.insert(camera_entity, ActiveCamera);
+
+        //The nodes of a glTF model are attached to the root turning the scene, the entities of the world are drawn with them
+        let mut scene = model.scene_opt.unwrap_or_default();
+        let all_model_root_ids = scene.get_all_root_ids().to_vec();
+        let scene_root_id = scene.add_node("root", Transform::IDENTITY, None);
+        for model_root_id in all_model_root_ids {
+            scene.set_parent(model_root_id, Some(scene_root_id));
+        }
+
+        //The scenes without lights are lit from above, in front of the camera, unless the world has its own lights
+        if scene.get_all_lights().is_empty() && get_all_lights(&self.world).is_empty() {
+            let light_entity = self.world.spawn();
+            self.world.insert(
+                light_entity,
+                Transform::from_rotation(
+                    Quat::from_rotation_y(FRAC_PI_6) * Quat::from_rotation_x(-FRAC_PI_3),
+                ),
+            );
+            self.world.insert(
+                light_entity,
+                Light::Directional {
+                    color: DEFAULT_LIGHT_COLOR,
+                    intensity: DEFAULT_LIGHT_INTENSITY,
+                },
+            );
+        }
+
+        let mut last_shader_watch_time = self.glfw_entry.get_time();
+        let mut last_frame_time = last_shader_watch_time;
+        let mut is_screenshot_requested = false;
+
+        //If the swapchain must be recreated before the next frame, because it's suboptimal or its config changed
+        let mut is_swapchain_outdated = false;
+
+        while !self.glfw_window.should_close() {
+            self.input_state.begin_frame();
+            self.glfw_entry.poll_events();
+            for (_, event) in glfw::flush_messages(&self.glfw_events) {
+                if let WindowEvent::FramebufferSize(..) = event {
+                    is_swapchain_outdated = true;
+                }
+                self.input_state.handle_event(&event);
+            }
+            self.input_state.update_gamepads(self.glfw_entry);
+
+            if self.input_state.is_action_pressed(SCREENSHOT_ACTION) {
+                is_screenshot_requested = true;
+            }
+            if self.input_state.is_action_pressed(CAMERA_CONTROLLER_ACTION)
+                && let Some(camera) = self.world.get::<Camera>(camera_entity)
+            {
+                camera_controller.toggle(camera, CAMERA_ORBIT_DISTANCE);
+            }
+            if self.input_state.is_action_pressed(CURSOR_CAPTURE_ACTION) {
+                let is_cursor_captured = !self.input_state.is_cursor_captured();
+                self.input_state
+                    .set_cursor_captured(&mut self.glfw_window, is_cursor_captured);
+            }
+            if self.input_state.is_action_pressed(VSYNC_ACTION) {
+                is_vsync_enabled = !is_vsync_enabled;
+                swapchain_config.set_vsync(is_vsync_enabled);
+                is_swapchain_outdated = true;
+            }
+            if self.input_state.is_action_pressed(FULLSCREEN_ACTION) {
+                self.toggle_fullscreen();
+                is_swapchain_outdated = true;
+            }
+
+            if is_swapchain_outdated {
+                let framebuffer_extent = get_framebuffer_extent(&self.glfw_window);
+
+                //The framebuffer of a minimized window is empty, no swapchain can present to it until it's restored
+                if framebuffer_extent.width == 0 || framebuffer_extent.height == 0 {
+                    self.glfw_entry.wait_events();
+                    continue;
+                }
+
+                //The framebuffers use the image views of the old swapchain and the render pass of the pipeline
+                drop(std::mem::take(&mut all_framebuffers));
+                vulkan_swapchain.recreate(swapchain_config.clone(), framebuffer_extent);
+                if let Some(camera) = self.world.get_mut::<Camera>(camera_entity) {
+                    camera.set_aspect_ratio_from_swapchain(&vulkan_swapchain);
+                }
+                //The depth image has the size of the swapchain images
+                drop(depth_image_view);
+                depth_image = create_depth_image(&vulkan_device, depth_format, &vulkan_swapchain);
+                depth_image_view = VulkanImageView::new_depth(&depth_image);
+                //Each pipeline is updated, even after one of them failed
+                let mut are_all_pipelines_updated = true;
+                for (_, graphics_pipeline) in &mut all_graphics_pipelines {
+                    are_all_pipelines_updated &= update_graphics_pipeline_for_swapchain(
+                        graphics_pipeline,
+                        &vulkan_swapchain,
+                        &all_shader_modules[PBR_SHADER_MODULES],
+                    );
+                }
+                are_all_pipelines_updated &= update_graphics_pipeline_for_swapchain(
+                    &mut skybox_graphics_pipeline,
+                    &vulkan_swapchain,
+                    &all_shader_modules[SKYBOX_SHADER_MODULES],
+                );
+                if are_all_pipelines_updated {
+                    all_framebuffers = create_all_framebuffers(
+                        &vulkan_device,
+                        &all_graphics_pipelines[0].1,
+                        &vulkan_swapchain,
+                        depth_image_view.get_image_view(),
+                    );
+                    is_swapchain_outdated = false;
+                } else {
+                    //The frames are skipped until the pipelines are updated, retried once the shaders may have been reloaded
+                    self.glfw_entry.wait_events_timeout(SHADER_WATCH_INTERVAL);
+                }
+            }
+
+            let time = self.glfw_entry.get_time();
+            let frame_time = FrameTime {
+                time,
+                delta_time: (time - last_frame_time) as f32,
+            };
+            last_frame_time = time;
+
+            if let Some(camera) = self.world.get_mut::<Camera>(camera_entity) {
+                camera_controller.update(camera, &self.input_state, frame_time.delta_time);
+            }
+            self.schedule.run(&mut self.world, &frame_time);
+
+            if let Some(animation) = &model.animation_opt {
+                let duration = animation.get_duration();
+                if duration > 0f32 {
+                    animation.apply(&mut scene, time as f32 % duration);
+                }
+            }
+            scene
+                .get_node_mut(scene_root_id)
+                .expect("failed to find the scene root")
+                .set_transform(Transform::from_rotation(Quat::from_rotation_y(
+                    time as f32 * SCENE_ROTATION_SPEED,
+                )));
+            scene.update_world_transforms();
+
+            let image_index_opt = if is_swapchain_outdated {
+                None
+            } else {
+                vulkan_frames_in_flight.begin_frame(&vulkan_swapchain)
+            };
+            if image_index_opt.is_none() {
+                is_swapchain_outdated = true;
+            }
+            if let Some(image_index) = image_index_opt {
+                let render_system = RenderSystem {
+                    all_meshes: &all_meshes,
+                    all_material_instances: &all_material_instances,
+                    all_graphics_pipelines: &all_graphics_pipelines,
+                    skybox_graphics_pipeline_opt: is_skybox_drawn
+                        .then_some(&skybox_graphics_pipeline),
+                    all_frame_descriptor_sets: &all_frame_descriptor_sets,
+                    all_camera_uniform_buffers: &all_camera_uniform_buffers,
+                    light_buffers: &light_buffers,
+                    all_framebuffers: &all_framebuffers,
+                    depth_image: &depth_image,
+                    depth_image_view: &depth_image_view,
+                    shadow_graphics_pipeline: &shadow_graphics_pipeline,
+                    shadow_atlas_image: &shadow_atlas_image,
+                    shadow_atlas_image_view: &shadow_atlas_image_view,
+                    shadow_framebuffer_opt: shadow_framebuffer_opt.as_ref(),
+                    shadow_config: &self.shadow_config,
+                };
+                render_system.record_frame(
+                    &vulkan_frames_in_flight,
+                    &vulkan_swapchain,
+                    image_index,
+                    &self.world,
+                    &scene,
+                );
+                let command_buffer = vulkan_frames_in_flight.get_command_buffer();
+                let image = vulkan_swapchain.get_all_images()[image_index as usize];
+
+                let screenshot_capture_opt = if is_screenshot_requested {
+                    is_screenshot_requested = false;
+                    create_screenshot_capture(&vulkan_device, &vulkan_swapchain)
+                } else {
+                    None
+                };
+                if let Some(screenshot_capture) = &screenshot_capture_opt {
+                    screenshot_capture.record_copy_from_image(
+                        command_buffer,
+                        image,
+                        vk::ImageLayout::PRESENT_SRC_KHR,
+                    );
+                }
+
+                if vulkan_frames_in_flight.end_frame(&vulkan_swapchain, image_index) {
+                    is_swapchain_outdated = true;
+                }
+
+                if let Some(screenshot_capture) = screenshot_capture_opt {
+                    vulkan_device.wait_idle();
+                    save_screenshot(&screenshot_capture);
+                }
+            }
+
+            if time - last_shader_watch_time < SHADER_WATCH_INTERVAL {
+                continue;
+            }
+            last_shader_watch_time = time;
+
+            let all_reloaded_file_paths =
+                shader_watcher.reload_modified_shaders(&mut all_shader_modules);
+            //The old pipelines are destroyed once the frames in flight recording them are rendered,
+            //every frame drawing with all the pipelines
+            if all_reloaded_file_paths.iter().any(|file_path| {
+                all_graphics_pipelines
+                    .iter()
+                    .map(|(_, graphics_pipeline)| graphics_pipeline)
+                    .chain([&shadow_graphics_pipeline, &skybox_graphics_pipeline])
+                    .any(|graphics_pipeline| graphics_pipeline.is_using_shader_file(file_path))
+            }) {
+                vulkan_frames_in_flight.wait_for_all_frames();
+            }
+            if all_reloaded_file_paths.iter().any(|file_path| {
+                all_graphics_pipelines
+                    .iter()
+                    .any(|(_, graphics_pipeline)| graphics_pipeline.is_using_shader_file(file_path))
+            }) {
+                match create_all_shader_stages(&all_shader_modules[PBR_SHADER_MODULES]) {
+                    Ok(all_shader_stages) => {
+                        for (_, graphics_pipeline) in &mut all_graphics_pipelines {
+                            if let Err(error) = graphics_pipeline.rebuild(&all_shader_stages) {
+                                eprintln!(
+                                    "failed to rebuild graphics pipeline, the old one is kept: {error}"
+                                );
+                            }
+                        }
+                    }
+                    Err(error) => eprintln!(
+                        "failed to create shader stages, the old graphics pipeline is kept: {error}"
+                    ),
+                }
+            }
+            if all_reloaded_file_paths
+                .iter()
+                .any(|file_path| shadow_graphics_pipeline.is_using_shader_file(file_path))
+            {
+                match create_all_shader_stages(&all_shader_modules[SHADOW_SHADER_MODULES]) {
+                    Ok(all_shader_stages) => {
+                        if let Err(error) = shadow_graphics_pipeline.rebuild(&all_shader_stages) {
+                            eprintln!(
+                                "failed to rebuild shadow graphics pipeline, the old one is kept: {error}"
+                            );
+                        }
+                    }
+                    Err(error) => eprintln!(
+                        "failed to create shader stages, the old shadow graphics pipeline is kept: {error}"
+                    ),
+                }
+            }
+            if all_reloaded_file_paths
+                .iter()
+                .any(|file_path| skybox_graphics_pipeline.is_using_shader_file(file_path))
+            {
+                match create_all_shader_stages(&all_shader_modules[SKYBOX_SHADER_MODULES]) {
+                    Ok(all_shader_stages) => {
+                        if let Err(error) = skybox_graphics_pipeline.rebuild(&all_shader_stages) {
+                            eprintln!(
+                                "failed to rebuild skybox graphics pipeline, the old one is kept: {error}"
+                            );
+                        }
+                    }
+                    Err(error) => eprintln!(
+                        "failed to create shader stages, the old skybox graphics pipeline is kept: {error}"
+                    ),
+                }
+            }
         }
+
+        vulkan_device.wait_idle();
     }
+}
+
+///Load the model at `model_file_path`, from a glTF or GLB file according to its extension, else from an OBJ file
+///
+/// # Panics
+///
+/// If the model cannot be loaded
+fn load_model(model_file_path: &Path) -> LoadedModel {
+    if is_gltf_file(model_file_path) {
+        let model = load_gltf_from_file(model_file_path).unwrap_or_else(|error| {
+            panic!(
+                "failed to load model {}: {error}",
+                model_file_path.display()
+            )
+        });
+        LoadedModel {
+            all_meshes: model
+                .all_meshes
+                .into_iter()
+                .map(|gltf_mesh| gltf_mesh.mesh)
+                .collect(),
+            all_materials: model.all_materials,
+            scene_opt: Some(model.scene),
+            animation_opt: model.all_animations.into_iter().next(),
+        }
+    } else {
+        let model = load_obj_from_file(model_file_path).unwrap_or_else(|error| {
+            panic!(
+                "failed to load model {}: {error}",
+                model_file_path.display()
+            )
+        });
+        LoadedModel {
+            all_meshes: vec![model.mesh],
+            all_materials: model.all_materials,
+            scene_opt: None,
+            animation_opt: None,
+        }
+    }
+}
 
-    pub fn run(&self) {
-        sleep(Duration::from_secs(60));
+///Return if `file_path` is a glTF or GLB file from its extension, else it's loaded as an OBJ file
+fn is_gltf_file(file_path: &Path) -> bool {
+    file_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("gltf") || extension.eq_ignore_ascii_case("glb")
+        })
+}
+
+///Return the config of the pipeline drawing the materials of `pipeline_key`, tested with `depth_format`
+fn get_material_pipeline_config(
+    pipeline_key: MaterialPipelineKey,
+    depth_format: vk::Format,
+) -> GraphicsPipelineConfig {
+    GraphicsPipelineConfig {
+        all_vertex_binding_descriptions: &VERTEX_BINDING_DESCRIPTIONS,
+        all_vertex_attribute_descriptions: &VERTEX_ATTRIBUTE_DESCRIPTIONS,
+        depth_format: Some(depth_format),
+        cull_mode: if pipeline_key.is_double_sided {
+            vk::CullModeFlags::NONE
+        } else {
+            vk::CullModeFlags::BACK
+        },
+        is_alpha_blended: pipeline_key.is_alpha_blended,
+        depth_bias: None,
+        depth_compare_op: vk::CompareOp::LESS,
     }
 }
+
+///Create the layout of the descriptor set of a frame, with the CameraUniforms buffer, the light and shadow buffers,
+///the shadow atlas and the environment images
+fn create_frame_descriptor_set_layout(
+    vulkan_device: &VulkanDevice,
+) -> VulkanDescriptorSetLayout<'_> {
+    VulkanDescriptorSetLayout::new(
+        vulkan_device,
+        &[
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(CAMERA_UNIFORMS_BINDING)
+                .descriptor_type(vk::DescriptorType::UNIFORM_BUFFER)
+                .descriptor_count(1)
+                .stage_flags(vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT),
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(LIGHTS_BINDING)
+                .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+                .descriptor_count(1)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(SHADOWS_BINDING)
+                .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+                .descriptor_count(1)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(SHADOW_ATLAS_BINDING)
+                .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+                .descriptor_count(1)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(SHADOW_SAMPLER_BINDING)
+                .descriptor_type(vk::DescriptorType::SAMPLER)
+                .descriptor_count(1)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(ENVIRONMENT_BINDING)
+                .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+                .descriptor_count(1)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(IRRADIANCE_BINDING)
+                .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+                .descriptor_count(1)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(PREFILTERED_BINDING)
+                .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+                .descriptor_count(1)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(BRDF_LUT_BINDING)
+                .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+                .descriptor_count(1)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(ENVIRONMENT_SAMPLER_BINDING)
+                .descriptor_type(vk::DescriptorType::SAMPLER)
+                .descriptor_count(1)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+        ],
+    )
+}
+
+///Return the size (in pixels) of the framebuffer of `glfw_window`
+fn get_framebuffer_extent(glfw_window: &PWindow) -> vk::Extent2D {
+    let (width, height) = glfw_window.get_framebuffer_size();
+
+    vk::Extent2D {
+        width: width.max(0) as u32,
+        height: height.max(0) as u32,
+    }
+}
+
+///Create the depth image tested with the images of `vulkan_swapchain`
+fn create_depth_image<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    depth_format: vk::Format,
+    vulkan_swapchain: &VulkanSwapchain,
+) -> VulkanImage<'vulkan_device> {
+    VulkanImage::new_2d(
+        vulkan_device,
+        vulkan_swapchain.get_image_extent(),
+        depth_format,
+        1,
+        1,
+        false,
+        vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+    )
+}
+
+///Create a framebuffer for each image of `vulkan_swapchain` with `depth_image_view`,
+///used by the render pass of `vulkan_graphics_pipeline`
+///
+/// # Returns
+///
+/// None framebuffer if `vulkan_graphics_pipeline` is used with the dynamic rendering
+fn create_all_framebuffers<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    vulkan_graphics_pipeline: &VulkanGraphicsPipeline,
+    vulkan_swapchain: &VulkanSwapchain,
+    depth_image_view: vk::ImageView,
+) -> Box<[VulkanFramebuffer<'vulkan_device>]> {
+    if vulkan_graphics_pipeline.get_render_pass().is_none() {
+        return Box::default();
+    }
+
+    vulkan_swapchain
+        .get_all_image_views()
+        .iter()
+        .map(|image_view| {
+            VulkanFramebuffer::new_for_image_views(
+                vulkan_device,
+                vulkan_graphics_pipeline,
+                *image_view,
+                Some(depth_image_view),
+                vulkan_swapchain.get_image_extent(),
+            )
+        })
+        .collect()
+}
+
+///Create the capture of a swapchain image
+///
+/// # Returns
+///
+/// None if the swapchain images cannot be captured, the reason is printed
+fn create_screenshot_capture<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    vulkan_swapchain: &VulkanSwapchain,
+) -> Option<VulkanImageCapture<'vulkan_device>> {
+    if !vulkan_swapchain
+        .get_image_usage()
+        .contains(vk::ImageUsageFlags::TRANSFER_SRC)
+    {
+        eprintln!("failed to take screenshot: swapchain images cannot be copied");
+        return None;
+    }
+
+    VulkanImageCapture::new(
+        vulkan_device,
+        vulkan_swapchain.get_image_format(),
+        vulkan_swapchain.get_image_extent(),
+    )
+    .inspect_err(|error| eprintln!("failed to take screenshot: {error}"))
+    .ok()
+}
+
+///Save `screenshot_capture` in a PNG file named from the current time
+fn save_screenshot(screenshot_capture: &VulkanImageCapture) {
+    //In milliseconds, so that the screenshots taken in the same second don't overwrite each other
+    let milliseconds_since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let file_path = format!("screenshot-{milliseconds_since_epoch}.png");
+
+    match screenshot_capture.save_png(&file_path) {
+        Ok(()) => eprintln!("screenshot saved in {file_path}"),
+        Err(error) => eprintln!("failed to save screenshot: {error}"),
+    }
+}
+
+///Update `graphics_pipeline` for `swapchain` after it was recreated, with the default stages of `all_shader_modules`
+///
+///If it cannot be updated, the error is printed and the old pipeline is kept, as when the shaders are reloaded
+///
+/// # Returns
+///
+/// If `graphics_pipeline` can render in the images of `swapchain`, false if their format changed and it wasn't updated
+fn update_graphics_pipeline_for_swapchain(
+    graphics_pipeline: &mut VulkanGraphicsPipeline,
+    swapchain: &VulkanSwapchain,
+    all_shader_modules: &[VulkanShaderModule],
+) -> bool {
+    match create_all_shader_stages(all_shader_modules) {
+        Ok(all_shader_stages) => {
+            if let Err(error) =
+                graphics_pipeline.update_for_swapchain(swapchain, &all_shader_stages)
+            {
+                eprintln!(
+                    "failed to update graphics pipeline for swapchain, the old one is kept: {error}"
+                );
+            }
+        }
+        Err(error) => {
+            graphics_pipeline.set_extent(swapchain.get_image_extent());
+            eprintln!("failed to create shader stages, the old graphics pipeline is kept: {error}");
+        }
+    }
+
+    graphics_pipeline.is_compatible_with_swapchain(swapchain)
+}
+
+///Create a stage running the default entry point of each shader module of `all_shader_modules`
+fn create_all_shader_stages<'shader_module, 'vulkan_device>(
+    all_shader_modules: &'shader_module [VulkanShaderModule<'vulkan_device>],
+) -> Result<Box<[VulkanShaderStage<'shader_module, 'vulkan_device>]>, ShaderModuleError> {
+    all_shader_modules
+        .iter()
+        .map(|shader_module| VulkanShaderStage::new(shader_module, DEFAULT_SHADER_ENTRYPOINT))
+        .collect()
+}
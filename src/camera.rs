@@ -0,0 +1,203 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    math::{
+        matrix::Mat4,
+        quaternion::Quat,
+        vector::{Vec3, Vec4},
+    },
+    vulkan::swapchain::VulkanSwapchain,
+};
+
+///Projection of the view space of a camera to the clip space
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    Perspective {
+        ///The vertical field of view (in radians)
+        fov_y: f32,
+        z_near: f32,
+        z_far: f32,
+    },
+    Orthographic {
+        ///The height of the visible box (in view space units), whose width follows the aspect ratio
+        height: f32,
+        z_near: f32,
+        z_far: f32,
+    },
+}
+
+///Matrices of a camera, laid out as a std140 uniform block of the shaders
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct CameraUniforms {
+    pub view: Mat4,
+    pub projection: Mat4,
+
+    ///The projection multiplied by the view
+    pub view_projection: Mat4,
+
+    ///The position of the camera in the world space, with a W of 1
+    pub position: Vec4,
+}
+
+///Camera looking toward -Z with +Y up when its rotation is the identity, as expected by the projections of Mat4
+#[derive(Clone, Debug)]
+pub struct Camera {
+    ///The position of the camera in the world space
+    pub position: Vec3,
+
+    ///The orientation of the camera in the world space
+    pub rotation: Quat,
+
+    pub projection: Projection,
+
+    ///If the near plane is projected to a depth of 1 and the far plane to 0, which must then be tested with GREATER
+    pub is_reverse_z: bool,
+
+    ///The width of the rendered images divided by their height
+    aspect_ratio: f32,
+}
+
+impl Camera {
+    ///Create a camera at the origin of the world, looking toward -Z
+    pub fn new(projection: Projection) -> Self {
+        Self {
+            position: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            projection,
+            is_reverse_z: false,
+            aspect_ratio: 1f32,
+        }
+    }
+
+    ///Create a camera with a perspective projection of a vertical field of view of `fov_y` (in radians)
+    pub fn new_perspective(fov_y: f32, z_near: f32, z_far: f32) -> Self {
+        Self::new(Projection::Perspective {
+            fov_y,
+            z_near,
+            z_far,
+        })
+    }
+
+    ///Create a camera with an orthographic projection of a box of `height` (in view space units)
+    pub fn new_orthographic(height: f32, z_near: f32, z_far: f32) -> Self {
+        Self::new(Projection::Orthographic {
+            height,
+            z_near,
+            z_far,
+        })
+    }
+
+    pub fn get_aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    ///Set the width of the rendered images divided by their height
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.aspect_ratio = aspect_ratio;
+    }
+
+    ///Set the aspect ratio to the one of the images of `swapchain`
+    ///
+    ///Must be called each time `swapchain` is recreated.
+    ///The aspect ratio is kept if the images are empty
+    pub fn set_aspect_ratio_from_swapchain(&mut self, swapchain: &VulkanSwapchain) {
+        let image_extent = swapchain.get_image_extent();
+        if image_extent.width == 0 || image_extent.height == 0 {
+            return;
+        }
+
+        self.aspect_ratio = image_extent.width as f32 / image_extent.height as f32;
+    }
+
+    ///Return the direction the camera looks toward, in the world space
+    pub fn get_forward(&self) -> Vec3 {
+        self.rotation * -Vec3::Z
+    }
+
+    ///Return the direction of the right of the screen, in the world space
+    pub fn get_right(&self) -> Vec3 {
+        self.rotation * Vec3::X
+    }
+
+    ///Return the direction of the top of the screen, in the world space
+    pub fn get_up(&self) -> Vec3 {
+        self.rotation * Vec3::Y
+    }
+
+    ///Rotate the camera to look at `target`, with `up` pointing up on the screen
+    ///
+    ///`up` must not be parallel to the direction from the camera to `target`
+    pub fn look_at(&mut self, target: Vec3, up: Vec3) {
+        let view_matrix = Mat4::look_at(self.position, target, up);
+
+        //The rotation of the camera is the inverse of the rotation of its view matrix
+        self.rotation = view_matrix
+            .to_scale_rotation_translation()
+            .map_or(self.rotation, |(_, view_rotation, _)| {
+                view_rotation.conjugate()
+            });
+    }
+
+    ///Return the matrix transforming the world space to the view space of the camera
+    pub fn get_view_matrix(&self) -> Mat4 {
+        Mat4::look_to(self.position, self.get_forward(), self.get_up())
+    }
+
+    ///Return the matrix transforming the view space of the camera to the clip space
+    pub fn get_projection_matrix(&self) -> Mat4 {
+        match self.projection {
+            Projection::Perspective {
+                fov_y,
+                z_near,
+                z_far,
+            } => {
+                if self.is_reverse_z {
+                    Mat4::perspective_reverse_z(fov_y, self.aspect_ratio, z_near, z_far)
+                } else {
+                    Mat4::perspective(fov_y, self.aspect_ratio, z_near, z_far)
+                }
+            }
+            Projection::Orthographic {
+                height,
+                z_near,
+                z_far,
+            } => {
+                let half_height = height * 0.5f32;
+                let half_width = half_height * self.aspect_ratio;
+                if self.is_reverse_z {
+                    Mat4::orthographic_reverse_z(
+                        -half_width,
+                        half_width,
+                        -half_height,
+                        half_height,
+                        z_near,
+                        z_far,
+                    )
+                } else {
+                    Mat4::orthographic(
+                        -half_width,
+                        half_width,
+                        -half_height,
+                        half_height,
+                        z_near,
+                        z_far,
+                    )
+                }
+            }
+        }
+    }
+
+    ///Return the matrices of the camera, to upload them to the shaders
+    pub fn get_uniforms(&self) -> CameraUniforms {
+        let view = self.get_view_matrix();
+        let projection = self.get_projection_matrix();
+
+        CameraUniforms {
+            view,
+            projection,
+            view_projection: projection * view,
+            position: self.position.extend(1f32),
+        }
+    }
+}
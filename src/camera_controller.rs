@@ -0,0 +1,316 @@
+use std::f32::consts::FRAC_PI_2;
+
+use glfw::{GamepadAxis, Key, MouseButton};
+
+use crate::{
+    camera::Camera,
+    input::{AxisDirection, InputBinding, InputState},
+    math::{quaternion::Quat, vector::Vec3},
+};
+
+pub const MOVE_FORWARD_ACTION: &str = "move_forward";
+pub const MOVE_BACKWARD_ACTION: &str = "move_backward";
+pub const MOVE_LEFT_ACTION: &str = "move_left";
+pub const MOVE_RIGHT_ACTION: &str = "move_right";
+pub const MOVE_UP_ACTION: &str = "move_up";
+pub const MOVE_DOWN_ACTION: &str = "move_down";
+
+pub const LOOK_LEFT_ACTION: &str = "look_left";
+pub const LOOK_RIGHT_ACTION: &str = "look_right";
+pub const LOOK_UP_ACTION: &str = "look_up";
+pub const LOOK_DOWN_ACTION: &str = "look_down";
+
+///Mouse button rotating the fly camera while the cursor isn't captured
+const FLY_LOOK_MOUSE_BUTTON: MouseButton = MouseButton::Button2;
+
+///Mouse button rotating the orbit camera around its target
+const ORBIT_ROTATE_MOUSE_BUTTON: MouseButton = MouseButton::Button1;
+
+///Mouse button moving the target of the orbit camera
+const ORBIT_PAN_MOUSE_BUTTON: MouseButton = MouseButton::Button3;
+
+///Margin (in radians) keeping the pitch away from the vertical, where the yaw is undefined
+const PITCH_MARGIN: f32 = 0.01f32;
+
+///Bind the movement actions to WASD, Space, Left Control and the left stick of the gamepads,
+///and the look actions to the right stick of the gamepads
+pub fn bind_default_camera_actions(input_state: &mut InputState) {
+    let all_default_bindings = [
+        (MOVE_FORWARD_ACTION, InputBinding::Key(Key::W)),
+        (MOVE_BACKWARD_ACTION, InputBinding::Key(Key::S)),
+        (MOVE_LEFT_ACTION, InputBinding::Key(Key::A)),
+        (MOVE_RIGHT_ACTION, InputBinding::Key(Key::D)),
+        (MOVE_UP_ACTION, InputBinding::Key(Key::Space)),
+        (MOVE_DOWN_ACTION, InputBinding::Key(Key::LeftControl)),
+        //The Y axis of the sticks points down
+        (
+            MOVE_FORWARD_ACTION,
+            InputBinding::GamepadAxis(GamepadAxis::AxisLeftY, AxisDirection::Negative),
+        ),
+        (
+            MOVE_BACKWARD_ACTION,
+            InputBinding::GamepadAxis(GamepadAxis::AxisLeftY, AxisDirection::Positive),
+        ),
+        (
+            MOVE_LEFT_ACTION,
+            InputBinding::GamepadAxis(GamepadAxis::AxisLeftX, AxisDirection::Negative),
+        ),
+        (
+            MOVE_RIGHT_ACTION,
+            InputBinding::GamepadAxis(GamepadAxis::AxisLeftX, AxisDirection::Positive),
+        ),
+        (
+            LOOK_LEFT_ACTION,
+            InputBinding::GamepadAxis(GamepadAxis::AxisRightX, AxisDirection::Negative),
+        ),
+        (
+            LOOK_RIGHT_ACTION,
+            InputBinding::GamepadAxis(GamepadAxis::AxisRightX, AxisDirection::Positive),
+        ),
+        (
+            LOOK_UP_ACTION,
+            InputBinding::GamepadAxis(GamepadAxis::AxisRightY, AxisDirection::Negative),
+        ),
+        (
+            LOOK_DOWN_ACTION,
+            InputBinding::GamepadAxis(GamepadAxis::AxisRightY, AxisDirection::Positive),
+        ),
+    ];
+
+    for (action_name, binding) in all_default_bindings {
+        input_state.bind_action(action_name, binding);
+    }
+}
+
+///Return the rotation of a camera turned by `yaw` around the world +Y axis, then tilted by `pitch` (in radians)
+fn get_yaw_pitch_rotation(yaw: f32, pitch: f32) -> Quat {
+    Quat::from_rotation_y(yaw) * Quat::from_rotation_x(pitch)
+}
+
+///Return the yaw and the pitch (in radians) of a camera looking toward `forward`
+fn get_yaw_pitch(forward: Vec3) -> (f32, f32) {
+    let forward = forward.try_normalize().unwrap_or(-Vec3::Z);
+
+    (
+        (-forward.x).atan2(-forward.z),
+        forward.y.clamp(-1f32, 1f32).asin(),
+    )
+}
+
+///Return the rotation (in radians) of the yaw and the pitch requested by the mouse and the look actions during `delta_time`
+///
+///The mouse is only used if `is_mouse_look_enabled`
+fn get_look_delta(
+    input_state: &InputState,
+    mouse_sensitivity: f32,
+    gamepad_look_speed: f32,
+    is_mouse_look_enabled: bool,
+    delta_time: f32,
+) -> (f32, f32) {
+    let (mut yaw_delta, mut pitch_delta) = (0f32, 0f32);
+
+    //Moving the cursor to the right turns right, and down tilts down
+    if is_mouse_look_enabled {
+        let (mouse_delta_x, mouse_delta_y) = input_state.get_mouse_delta();
+        yaw_delta -= mouse_delta_x as f32 * mouse_sensitivity;
+        pitch_delta -= mouse_delta_y as f32 * mouse_sensitivity;
+    }
+
+    yaw_delta += (input_state.get_action_value(LOOK_LEFT_ACTION)
+        - input_state.get_action_value(LOOK_RIGHT_ACTION))
+        * gamepad_look_speed
+        * delta_time;
+    pitch_delta += (input_state.get_action_value(LOOK_UP_ACTION)
+        - input_state.get_action_value(LOOK_DOWN_ACTION))
+        * gamepad_look_speed
+        * delta_time;
+
+    (yaw_delta, pitch_delta)
+}
+
+///First-person camera controller: the movement actions move the camera along its axes,
+///and the mouse (while the cursor is captured or the right button is down) or the look actions rotate it
+#[derive(Clone, Debug)]
+pub struct FlyCameraController {
+    ///The speed (in world units per second) of the camera
+    pub speed: f32,
+
+    ///The rotation (in radians) per screen coordinate moved by the cursor
+    pub mouse_sensitivity: f32,
+
+    ///The rotation speed (in radians per second) when a look action is fully down
+    pub gamepad_look_speed: f32,
+
+    ///The rotation (in radians) around the world +Y axis
+    yaw: f32,
+
+    ///The rotation (in radians) around the right of the camera, positive when looking up
+    pitch: f32,
+}
+
+impl FlyCameraController {
+    ///Create a controller keeping the direction `camera` looks toward
+    pub fn new(camera: &Camera) -> Self {
+        let (yaw, pitch) = get_yaw_pitch(camera.get_forward());
+
+        Self {
+            speed: 5f32,
+            mouse_sensitivity: 0.002f32,
+            gamepad_look_speed: 2f32,
+            yaw,
+            pitch,
+        }
+    }
+
+    ///Move and rotate `camera` from `input_state`, during `delta_time` (in seconds)
+    pub fn update(&mut self, camera: &mut Camera, input_state: &InputState, delta_time: f32) {
+        let (yaw_delta, pitch_delta) = get_look_delta(
+            input_state,
+            self.mouse_sensitivity,
+            self.gamepad_look_speed,
+            input_state.is_cursor_captured()
+                || input_state.is_mouse_button_down(FLY_LOOK_MOUSE_BUTTON),
+            delta_time,
+        );
+        self.yaw += yaw_delta;
+        self.pitch =
+            (self.pitch + pitch_delta).clamp(-FRAC_PI_2 + PITCH_MARGIN, FRAC_PI_2 - PITCH_MARGIN);
+        camera.rotation = get_yaw_pitch_rotation(self.yaw, self.pitch);
+
+        let movement = camera.get_forward()
+            * (input_state.get_action_value(MOVE_FORWARD_ACTION)
+                - input_state.get_action_value(MOVE_BACKWARD_ACTION))
+            + camera.get_right()
+                * (input_state.get_action_value(MOVE_RIGHT_ACTION)
+                    - input_state.get_action_value(MOVE_LEFT_ACTION))
+            + Vec3::Y
+                * (input_state.get_action_value(MOVE_UP_ACTION)
+                    - input_state.get_action_value(MOVE_DOWN_ACTION));
+
+        //Moving diagonally isn't faster
+        if movement.length() > 1f32 {
+            camera.position += movement.normalize() * self.speed * delta_time;
+        } else {
+            camera.position += movement * self.speed * delta_time;
+        }
+    }
+}
+
+///Camera controller orbiting around a target: the left mouse button or the look actions rotate the camera around it,
+///the scroll zooms toward it and the middle mouse button moves it
+#[derive(Clone, Debug)]
+pub struct OrbitCameraController {
+    ///The point the camera looks at
+    pub target: Vec3,
+
+    ///The distance between the camera and `target`, clamped between `min_distance` and `max_distance`
+    pub distance: f32,
+
+    pub min_distance: f32,
+    pub max_distance: f32,
+
+    ///The rotation (in radians) per screen coordinate moved by the cursor
+    pub mouse_sensitivity: f32,
+
+    ///The rotation speed (in radians per second) when a look action is fully down
+    pub gamepad_look_speed: f32,
+
+    ///The fraction of the distance zoomed per scroll step
+    pub zoom_sensitivity: f32,
+
+    ///The movement of the target per screen coordinate moved by the cursor, relative to the distance
+    pub pan_sensitivity: f32,
+
+    ///The rotation (in radians) around the world +Y axis
+    yaw: f32,
+
+    ///The rotation (in radians) around the right of the camera, positive when looking up
+    pitch: f32,
+}
+
+impl OrbitCameraController {
+    ///Create a controller orbiting around `target`, keeping the position of `camera`
+    pub fn new(camera: &Camera, target: Vec3) -> Self {
+        let offset = target - camera.position;
+        let (yaw, pitch) = get_yaw_pitch(offset);
+
+        Self {
+            target,
+            distance: offset.length(),
+            min_distance: 0.1f32,
+            max_distance: 1000f32,
+            mouse_sensitivity: 0.005f32,
+            gamepad_look_speed: 2f32,
+            zoom_sensitivity: 0.1f32,
+            pan_sensitivity: 0.001f32,
+            yaw,
+            pitch,
+        }
+    }
+
+    ///Rotate `camera` around the target and zoom from `input_state`, during `delta_time` (in seconds)
+    pub fn update(&mut self, camera: &mut Camera, input_state: &InputState, delta_time: f32) {
+        let (yaw_delta, pitch_delta) = get_look_delta(
+            input_state,
+            self.mouse_sensitivity,
+            self.gamepad_look_speed,
+            input_state.is_mouse_button_down(ORBIT_ROTATE_MOUSE_BUTTON),
+            delta_time,
+        );
+        self.yaw += yaw_delta;
+        self.pitch =
+            (self.pitch + pitch_delta).clamp(-FRAC_PI_2 + PITCH_MARGIN, FRAC_PI_2 - PITCH_MARGIN);
+        camera.rotation = get_yaw_pitch_rotation(self.yaw, self.pitch);
+
+        //Each scroll step zooms by the same fraction of the distance
+        let (_, scroll_delta_y) = input_state.get_scroll_delta();
+        self.distance = (self.distance
+            * (1f32 - self.zoom_sensitivity).powf(scroll_delta_y as f32))
+        .clamp(self.min_distance, self.max_distance);
+
+        //The target follows the cursor on the screen
+        if input_state.is_mouse_button_down(ORBIT_PAN_MOUSE_BUTTON) {
+            let (mouse_delta_x, mouse_delta_y) = input_state.get_mouse_delta();
+            let pan_scale = self.pan_sensitivity * self.distance;
+            self.target += (camera.get_up() * mouse_delta_y as f32
+                - camera.get_right() * mouse_delta_x as f32)
+                * pan_scale;
+        }
+
+        camera.position = self.target - camera.get_forward() * self.distance;
+    }
+}
+
+///Controller of the camera, which can be switched at runtime
+#[derive(Clone, Debug)]
+pub enum CameraController {
+    Fly(FlyCameraController),
+    Orbit(OrbitCameraController),
+}
+
+impl CameraController {
+    ///Move and rotate `camera` from `input_state`, during `delta_time` (in seconds)
+    pub fn update(&mut self, camera: &mut Camera, input_state: &InputState, delta_time: f32) {
+        match self {
+            CameraController::Fly(fly_camera_controller) => {
+                fly_camera_controller.update(camera, input_state, delta_time)
+            }
+            CameraController::Orbit(orbit_camera_controller) => {
+                orbit_camera_controller.update(camera, input_state, delta_time)
+            }
+        }
+    }
+
+    ///Switch between a fly controller and an orbit controller, keeping the position and the direction of `camera`
+    ///
+    ///The orbit controller orbits around the point at `orbit_distance` in front of `camera`
+    pub fn toggle(&mut self, camera: &Camera, orbit_distance: f32) {
+        *self = match self {
+            CameraController::Fly(_) => CameraController::Orbit(OrbitCameraController::new(
+                camera,
+                camera.position + camera.get_forward() * orbit_distance,
+            )),
+            CameraController::Orbit(_) => CameraController::Fly(FlyCameraController::new(camera)),
+        };
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{
+    camera::Camera,
+    ecs::storage::Component,
+    scene::{Light, MeshId, Transform},
+};
+
+///Transform of an entity relative to the world
+impl Component for Transform {}
+
+///Camera whose position and rotation follow the Transform of its entity, if any
+impl Component for Camera {}
+
+///Light positioned and oriented by the Transform of its entity
+impl Component for Light {}
+
+///Mesh drawn by the render system with the Transform of its entity
+#[derive(Clone, Copy, Debug)]
+pub struct MeshRenderer {
+    pub mesh: MeshId,
+
+    ///If the mesh is drawn
+    pub is_visible: bool,
+}
+
+impl Component for MeshRenderer {}
+
+impl MeshRenderer {
+    pub fn new(mesh: MeshId) -> Self {
+        Self {
+            mesh,
+            is_visible: true,
+        }
+    }
+}
+
+///Marker of the entity whose Camera is rendered from
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ActiveCamera;
+
+impl Component for ActiveCamera {}
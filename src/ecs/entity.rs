@@ -0,0 +1,142 @@
+///Identifier of an entity of a World
+///
+///The index of a destroyed entity is reused by a later entity with a greater generation,
+///so the old identifier doesn't refer to the new entity
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    ///Return the index of the entity, unique among the living entities
+    pub fn get_index(self) -> usize {
+        self.index as usize
+    }
+
+    pub fn get_generation(self) -> u32 {
+        self.generation
+    }
+}
+
+///Allocator of the entities of a World, reusing the indices of the destroyed entities
+#[derive(Default)]
+pub struct EntityAllocator {
+    ///The current generation of each index
+    all_generations: Vec<u32>,
+
+    ///If the entity of each index is alive
+    all_alive_flags: Vec<bool>,
+
+    ///The indices of the destroyed entities, to reuse
+    all_free_indices: Vec<u32>,
+}
+
+impl EntityAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allocate(&mut self) -> Entity {
+        match self.all_free_indices.pop() {
+            Some(index) => {
+                self.all_alive_flags[index as usize] = true;
+                Entity {
+                    index,
+                    generation: self.all_generations[index as usize],
+                }
+            }
+            None => {
+                let index = u32::try_from(self.all_generations.len())
+                    .expect("failed to allocate an entity, too many entities");
+                self.all_generations.push(0);
+                self.all_alive_flags.push(true);
+                Entity {
+                    index,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    ///Destroy `entity`, whose index can then be reused
+    ///
+    /// # Returns
+    ///
+    /// If `entity` was alive
+    pub fn deallocate(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        let index = entity.get_index();
+        self.all_alive_flags[index] = false;
+        self.all_generations[index] = self.all_generations[index].wrapping_add(1);
+        self.all_free_indices.push(entity.index);
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        let index = entity.get_index();
+        self.all_alive_flags.get(index).copied().unwrap_or(false)
+            && self.all_generations[index] == entity.generation
+    }
+
+    ///Return the living entities, in increasing order of index
+    pub fn get_all_alive(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.all_alive_flags
+            .iter()
+            .enumerate()
+            .filter(|&(_, &is_alive)| is_alive)
+            .map(|(index, _)| Entity {
+                index: index as u32,
+                generation: self.all_generations[index],
+            })
+    }
+
+    pub fn get_alive_count(&self) -> usize {
+        self.all_generations.len() - self.all_free_indices.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deallocated_index_is_reused_with_a_greater_generation() {
+        let mut entity_allocator = EntityAllocator::new();
+        let first_entity = entity_allocator.allocate();
+        let second_entity = entity_allocator.allocate();
+
+        assert!(entity_allocator.deallocate(first_entity));
+        let reused_entity = entity_allocator.allocate();
+
+        assert_eq!(reused_entity.get_index(), first_entity.get_index());
+        assert_eq!(
+            reused_entity.get_generation(),
+            first_entity.get_generation() + 1
+        );
+        assert!(!entity_allocator.is_alive(first_entity));
+        assert!(entity_allocator.is_alive(reused_entity));
+        assert!(entity_allocator.is_alive(second_entity));
+        assert_eq!(entity_allocator.get_alive_count(), 2);
+    }
+
+    #[test]
+    fn stale_entity_cannot_be_deallocated() {
+        let mut entity_allocator = EntityAllocator::new();
+        let entity = entity_allocator.allocate();
+
+        assert!(entity_allocator.deallocate(entity));
+        let reused_entity = entity_allocator.allocate();
+
+        //The old identifier doesn't destroy the new entity reusing its index
+        assert!(!entity_allocator.deallocate(entity));
+        assert!(entity_allocator.is_alive(reused_entity));
+        assert_eq!(
+            entity_allocator.get_all_alive().collect::<Vec<_>>(),
+            [reused_entity]
+        );
+    }
+}
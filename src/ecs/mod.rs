@@ -0,0 +1,7 @@
+pub mod components;
+pub mod entity;
+pub mod query;
+pub mod schedule;
+pub mod storage;
+pub mod systems;
+pub mod world;
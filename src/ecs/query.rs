@@ -0,0 +1,207 @@
+use std::{any::TypeId, marker::PhantomData};
+
+use crate::ecs::{entity::Entity, storage::Component, world::World};
+
+///Components fetched for each entity matched by World::query
+///
+///Implemented by `&T` and `Option<&T>` for the components `T`, and by the tuples of up to 4 queries
+pub trait Query {
+    type Item<'world>;
+
+    ///Return the types of the components an entity must have to be matched
+    fn get_all_required_type_ids() -> Vec<TypeId>;
+
+    ///Return the components of `entity`, None if it isn't matched
+    fn fetch(world: &World, entity: Entity) -> Option<Self::Item<'_>>;
+}
+
+impl<T: Component> Query for &T {
+    type Item<'world> = &'world T;
+
+    fn get_all_required_type_ids() -> Vec<TypeId> {
+        vec![TypeId::of::<T>()]
+    }
+
+    fn fetch(world: &World, entity: Entity) -> Option<Self::Item<'_>> {
+        world.get::<T>(entity)
+    }
+}
+
+///Optional component, which doesn't restrict the matched entities
+impl<T: Component> Query for Option<&T> {
+    type Item<'world> = Option<&'world T>;
+
+    fn get_all_required_type_ids() -> Vec<TypeId> {
+        Vec::new()
+    }
+
+    fn fetch(world: &World, entity: Entity) -> Option<Self::Item<'_>> {
+        Some(world.get::<T>(entity))
+    }
+}
+
+///Implement Query for a tuple of queries, matching the entities matched by all of them
+macro_rules! impl_tuple_query {
+    ($($query:ident),+) => {
+        impl<$($query: Query),+> Query for ($($query,)+) {
+            type Item<'world> = ($($query::Item<'world>,)+);
+
+            fn get_all_required_type_ids() -> Vec<TypeId> {
+                let mut all_type_ids = Vec::new();
+                $(all_type_ids.extend($query::get_all_required_type_ids());)+
+                all_type_ids
+            }
+
+            fn fetch(world: &World, entity: Entity) -> Option<Self::Item<'_>> {
+                Some(($($query::fetch(world, entity)?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple_query!(A);
+impl_tuple_query!(A, B);
+impl_tuple_query!(A, B, C);
+impl_tuple_query!(A, B, C, D);
+
+///Condition on the components of the entities matched by World::query_filtered, without fetching them
+///
+///Implemented by With, Without, `()` which matches all entities, and the tuples of up to 4 filters
+pub trait QueryFilter {
+    fn matches(world: &World, entity: Entity) -> bool;
+}
+
+impl QueryFilter for () {
+    fn matches(_world: &World, _entity: Entity) -> bool {
+        true
+    }
+}
+
+///Filter matching the entities with a component `T`
+pub struct With<T>(PhantomData<T>);
+
+impl<T: Component> QueryFilter for With<T> {
+    fn matches(world: &World, entity: Entity) -> bool {
+        world.has::<T>(entity)
+    }
+}
+
+///Filter matching the entities without a component `T`
+pub struct Without<T>(PhantomData<T>);
+
+impl<T: Component> QueryFilter for Without<T> {
+    fn matches(world: &World, entity: Entity) -> bool {
+        !world.has::<T>(entity)
+    }
+}
+
+///Implement QueryFilter for a tuple of filters, matching the entities matched by all of them
+macro_rules! impl_tuple_filter {
+    ($($filter:ident),+) => {
+        impl<$($filter: QueryFilter),+> QueryFilter for ($($filter,)+) {
+            fn matches(world: &World, entity: Entity) -> bool {
+                true $(&& $filter::matches(world, entity))+
+            }
+        }
+    };
+}
+
+impl_tuple_filter!(A);
+impl_tuple_filter!(A, B);
+impl_tuple_filter!(A, B, C);
+impl_tuple_filter!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Position(i32);
+
+    impl Component for Position {}
+
+    struct Hidden;
+
+    impl Component for Hidden {}
+
+    struct Selected;
+
+    impl Component for Selected {}
+
+    ///Return the entities with a position matched by `F`, with their positions in increasing order of index
+    fn get_all_matched_positions<F: QueryFilter>(world: &World) -> Vec<(Entity, i32)> {
+        let mut all_matched_positions: Vec<(Entity, i32)> = world
+            .query_filtered::<(&Position,), F>()
+            .map(|(entity, (position,))| (entity, position.0))
+            .collect();
+        all_matched_positions.sort();
+        all_matched_positions
+    }
+
+    ///Spawn a visible, a hidden and a selected hidden entity, with positions 0, 1 and 2, and an entity without position
+    fn create_world() -> (World, [Entity; 3]) {
+        let mut world = World::new();
+        let visible_entity = world.spawn();
+        world.insert(visible_entity, Position(0));
+        let hidden_entity = world.spawn();
+        world.insert(hidden_entity, Position(1));
+        world.insert(hidden_entity, Hidden);
+        let selected_entity = world.spawn();
+        world.insert(selected_entity, Position(2));
+        world.insert(selected_entity, Hidden);
+        world.insert(selected_entity, Selected);
+        let unpositioned_entity = world.spawn();
+        world.insert(unpositioned_entity, Selected);
+
+        (world, [visible_entity, hidden_entity, selected_entity])
+    }
+
+    #[test]
+    fn with_filter_matches_the_entities_with_the_component() {
+        let (world, [_, hidden_entity, selected_entity]) = create_world();
+
+        assert_eq!(
+            get_all_matched_positions::<With<Hidden>>(&world),
+            [(hidden_entity, 1), (selected_entity, 2)]
+        );
+        assert_eq!(
+            get_all_matched_positions::<With<Selected>>(&world),
+            [(selected_entity, 2)]
+        );
+    }
+
+    #[test]
+    fn without_filter_matches_the_entities_missing_the_component() {
+        let (world, [visible_entity, hidden_entity, _]) = create_world();
+
+        assert_eq!(
+            get_all_matched_positions::<Without<Hidden>>(&world),
+            [(visible_entity, 0)]
+        );
+        assert_eq!(
+            get_all_matched_positions::<Without<Selected>>(&world),
+            [(visible_entity, 0), (hidden_entity, 1)]
+        );
+    }
+
+    #[test]
+    fn tuple_filter_matches_all_its_filters() {
+        let (world, [_, hidden_entity, _]) = create_world();
+
+        assert_eq!(
+            get_all_matched_positions::<(With<Hidden>, Without<Selected>)>(&world),
+            [(hidden_entity, 1)]
+        );
+    }
+
+    #[test]
+    fn despawned_entity_is_not_matched() {
+        let (mut world, [visible_entity, hidden_entity, selected_entity]) = create_world();
+
+        assert!(world.despawn(hidden_entity));
+
+        assert_eq!(
+            get_all_matched_positions::<()>(&world),
+            [(visible_entity, 0), (selected_entity, 2)]
+        );
+    }
+}
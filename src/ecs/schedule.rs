@@ -0,0 +1,160 @@
+use crate::ecs::world::World;
+
+///Time of the frame the systems are run for
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameTime {
+    ///The time (in seconds) since the start of the application
+    pub time: f64,
+
+    ///The time (in seconds) since the previous frame
+    pub delta_time: f32,
+}
+
+///Function updating the components of a World each frame
+pub type System = Box<dyn FnMut(&mut World, &FrameTime)>;
+
+///Ordered list of named systems, run once per frame by the frame loop
+#[derive(Default)]
+pub struct Schedule {
+    all_systems: Vec<(String, System)>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Add `system`, run after the systems added before
+    ///
+    /// # Panics
+    ///
+    /// If a system is already named `name`
+    pub fn add_system(&mut self, name: &str, system: impl FnMut(&mut World, &FrameTime) + 'static) {
+        assert!(
+            !self.has_system(name),
+            "a system is already named \"{name}\""
+        );
+        self.all_systems.push((name.to_string(), Box::new(system)));
+    }
+
+    ///Add `system`, run right before the system named `next_name`
+    ///
+    /// # Panics
+    ///
+    /// If a system is already named `name`, or if no system is named `next_name`
+    pub fn add_system_before(
+        &mut self,
+        name: &str,
+        next_name: &str,
+        system: impl FnMut(&mut World, &FrameTime) + 'static,
+    ) {
+        assert!(
+            !self.has_system(name),
+            "a system is already named \"{name}\""
+        );
+        let next_position = self
+            .all_systems
+            .iter()
+            .position(|(system_name, _)| system_name == next_name)
+            .expect("failed to find the next system");
+        self.all_systems
+            .insert(next_position, (name.to_string(), Box::new(system)));
+    }
+
+    ///Remove the system named `name`
+    ///
+    /// # Returns
+    ///
+    /// If a system was named `name`
+    pub fn remove_system(&mut self, name: &str) -> bool {
+        let system_count = self.all_systems.len();
+        self.all_systems
+            .retain(|(system_name, _)| system_name != name);
+        self.all_systems.len() != system_count
+    }
+
+    pub fn has_system(&self, name: &str) -> bool {
+        self.all_systems
+            .iter()
+            .any(|(system_name, _)| system_name == name)
+    }
+
+    ///Return the names of the systems, in the order they're run
+    pub fn get_all_system_names(&self) -> impl Iterator<Item = &str> {
+        self.all_systems
+            .iter()
+            .map(|(system_name, _)| system_name.as_str())
+    }
+
+    ///Run all the systems on `world`, in order
+    pub fn run(&mut self, world: &mut World, frame_time: &FrameTime) {
+        for (_, system) in &mut self.all_systems {
+            system(world, frame_time);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    ///Add a system named `name`, pushing its name in `all_run_names` when it's run
+    fn add_recording_system(
+        schedule: &mut Schedule,
+        name: &'static str,
+        next_name_opt: Option<&str>,
+        all_run_names: &Rc<RefCell<Vec<&'static str>>>,
+    ) {
+        let all_run_names = Rc::clone(all_run_names);
+        let system = move |_: &mut World, _: &FrameTime| all_run_names.borrow_mut().push(name);
+        match next_name_opt {
+            Some(next_name) => schedule.add_system_before(name, next_name, system),
+            None => schedule.add_system(name, system),
+        }
+    }
+
+    #[test]
+    fn systems_are_run_in_order() {
+        let all_run_names = Rc::new(RefCell::new(Vec::new()));
+        let mut schedule = Schedule::new();
+        add_recording_system(&mut schedule, "input", None, &all_run_names);
+        add_recording_system(&mut schedule, "render", None, &all_run_names);
+        add_recording_system(&mut schedule, "physics", Some("render"), &all_run_names);
+        add_recording_system(&mut schedule, "animation", Some("physics"), &all_run_names);
+
+        let expected_names = ["input", "animation", "physics", "render"];
+        assert!(schedule.get_all_system_names().eq(expected_names));
+
+        let mut world = World::new();
+        schedule.run(&mut world, &FrameTime::default());
+        schedule.run(&mut world, &FrameTime::default());
+        assert_eq!(
+            *all_run_names.borrow(),
+            [expected_names, expected_names].concat()
+        );
+    }
+
+    #[test]
+    fn removed_system_is_not_run() {
+        let all_run_names = Rc::new(RefCell::new(Vec::new()));
+        let mut schedule = Schedule::new();
+        add_recording_system(&mut schedule, "input", None, &all_run_names);
+        add_recording_system(&mut schedule, "render", None, &all_run_names);
+
+        assert!(schedule.remove_system("input"));
+        assert!(!schedule.remove_system("input"));
+        schedule.run(&mut World::new(), &FrameTime::default());
+
+        assert_eq!(*all_run_names.borrow(), ["render"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "a system is already named \"render\"")]
+    fn duplicate_system_name_panics() {
+        let mut schedule = Schedule::new();
+        schedule.add_system("render", |_, _| {});
+        schedule.add_system("render", |_, _| {});
+    }
+}
@@ -0,0 +1,168 @@
+use std::any::Any;
+
+use crate::ecs::entity::Entity;
+
+///Data attached to entities, stored in a SparseSet per type
+pub trait Component: Any {}
+
+///Sparse set of the components of one type
+///
+///The components are packed in a dense array to iterate them quickly,
+///and the sparse array maps the index of each entity to the position of its component
+pub struct SparseSet<T> {
+    ///The position in the dense arrays of the component of each entity index
+    all_sparse_positions: Vec<Option<usize>>,
+
+    all_dense_entities: Vec<Entity>,
+    all_dense_components: Vec<T>,
+}
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        Self {
+            all_sparse_positions: Vec::new(),
+            all_dense_entities: Vec::new(),
+            all_dense_components: Vec::new(),
+        }
+    }
+}
+
+impl<T> SparseSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Attach `component` to `entity`
+    ///
+    /// # Returns
+    ///
+    /// The component previously attached to `entity`, if any
+    pub fn insert(&mut self, entity: Entity, component: T) -> Option<T> {
+        if let Some(position) = self.get_position(entity) {
+            return Some(std::mem::replace(
+                &mut self.all_dense_components[position],
+                component,
+            ));
+        }
+
+        //The index may be held by a destroyed entity whose component wasn't removed
+        if let Some(stale_position) = self
+            .all_sparse_positions
+            .get(entity.get_index())
+            .copied()
+            .flatten()
+        {
+            self.remove_at(stale_position);
+        }
+
+        let index = entity.get_index();
+        if index >= self.all_sparse_positions.len() {
+            self.all_sparse_positions.resize(index + 1, None);
+        }
+        self.all_sparse_positions[index] = Some(self.all_dense_entities.len());
+        self.all_dense_entities.push(entity);
+        self.all_dense_components.push(component);
+        None
+    }
+
+    ///Detach the component of `entity`
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let position = self.get_position(entity)?;
+        Some(self.remove_at(position))
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        let position = self.get_position(entity)?;
+        Some(&self.all_dense_components[position])
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        let position = self.get_position(entity)?;
+        Some(&mut self.all_dense_components[position])
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.get_position(entity).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.all_dense_entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.all_dense_entities.is_empty()
+    }
+
+    ///Return the entities with a component, in the order of the dense array
+    pub fn get_all_entities(&self) -> &[Entity] {
+        &self.all_dense_entities
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.all_dense_entities
+            .iter()
+            .copied()
+            .zip(self.all_dense_components.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.all_dense_entities
+            .iter()
+            .copied()
+            .zip(self.all_dense_components.iter_mut())
+    }
+
+    ///Remove the component at `position` in the dense arrays, moving the last component there
+    fn remove_at(&mut self, position: usize) -> T {
+        let removed_entity = self.all_dense_entities.swap_remove(position);
+        self.all_sparse_positions[removed_entity.get_index()] = None;
+
+        let component = self.all_dense_components.swap_remove(position);
+        if let Some(moved_entity) = self.all_dense_entities.get(position) {
+            self.all_sparse_positions[moved_entity.get_index()] = Some(position);
+        }
+        component
+    }
+
+    ///Return the position in the dense arrays of the component of `entity`, of the same generation
+    fn get_position(&self, entity: Entity) -> Option<usize> {
+        let position = (*self.all_sparse_positions.get(entity.get_index())?)?;
+        (self.all_dense_entities[position] == entity).then_some(position)
+    }
+}
+
+///SparseSet with its component type erased, to store the sets of all the types together
+pub trait ComponentStorage: Any {
+    ///Detach the component of `entity`, if any
+    fn remove_entity(&mut self, entity: Entity);
+
+    fn contains_entity(&self, entity: Entity) -> bool;
+
+    fn get_all_entities(&self) -> &[Entity];
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Component> ComponentStorage for SparseSet<T> {
+    fn remove_entity(&mut self, entity: Entity) {
+        self.remove(entity);
+    }
+
+    fn contains_entity(&self, entity: Entity) -> bool {
+        self.contains(entity)
+    }
+
+    fn get_all_entities(&self) -> &[Entity] {
+        SparseSet::get_all_entities(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    camera::Camera,
+    ecs::{
+        components::{ActiveCamera, MeshRenderer},
+        entity::Entity,
+        query::With,
+        schedule::FrameTime,
+        world::World,
+    },
+    math::matrix::Mat4,
+    scene::{Light, MeshId, Transform},
+};
+
+///Mesh of an entity to draw, with the world matrix of its Transform
+#[derive(Clone, Copy, Debug)]
+pub struct RenderItem {
+    pub entity: Entity,
+    pub mesh: MeshId,
+    pub world_matrix: Mat4,
+}
+
+///System moving the cameras to the Transform of their entity, ignoring its scale
+pub fn sync_camera_transforms(world: &mut World, _frame_time: &FrameTime) {
+    world.for_each_mut::<Camera, (&Transform,), ()>(|_, camera, (transform,)| {
+        camera.position = transform.translation;
+        camera.rotation = transform.rotation;
+    });
+}
+
+///Return the Camera of the first entity with an ActiveCamera
+pub fn get_active_camera(world: &World) -> Option<&Camera> {
+    world
+        .query_filtered::<(&Camera,), With<ActiveCamera>>()
+        .next()
+        .map(|(_, (camera,))| camera)
+}
+
+///Return the visible meshes to draw, with the world matrices of their entities
+pub fn get_render_items(world: &World) -> Vec<RenderItem> {
+    world
+        .query::<(&MeshRenderer, &Transform)>()
+        .filter(|(_, (mesh_renderer, _))| mesh_renderer.is_visible)
+        .map(|(entity, (mesh_renderer, transform))| RenderItem {
+            entity,
+            mesh: mesh_renderer.mesh,
+            world_matrix: transform.to_matrix(),
+        })
+        .collect()
+}
+
+///Return the lights with the world matrices of their entities, the identity for the ones without a Transform
+pub fn get_all_lights(world: &World) -> Vec<(Light, Mat4)> {
+    world
+        .query::<(&Light, Option<&Transform>)>()
+        .map(|(_, (light, transform_opt))| {
+            (
+                *light,
+                transform_opt.map_or(Mat4::IDENTITY, Transform::to_matrix),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,190 @@
+use std::{
+    any::{TypeId, type_name},
+    collections::HashMap,
+};
+
+use crate::ecs::{
+    entity::{Entity, EntityAllocator},
+    query::{Query, QueryFilter},
+    storage::{Component, ComponentStorage, SparseSet},
+};
+
+///Entities and their components, stored in a SparseSet per component type
+#[derive(Default)]
+pub struct World {
+    entity_allocator: EntityAllocator,
+    all_storages: HashMap<TypeId, Box<dyn ComponentStorage>>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Create an entity without components
+    pub fn spawn(&mut self) -> Entity {
+        self.entity_allocator.allocate()
+    }
+
+    ///Destroy `entity` and its components
+    ///
+    /// # Returns
+    ///
+    /// If `entity` was alive
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.entity_allocator.deallocate(entity) {
+            return false;
+        }
+
+        for storage in self.all_storages.values_mut() {
+            storage.remove_entity(entity);
+        }
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entity_allocator.is_alive(entity)
+    }
+
+    ///Return the living entities, in increasing order of index
+    pub fn get_all_entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entity_allocator.get_all_alive()
+    }
+
+    pub fn get_entity_count(&self) -> usize {
+        self.entity_allocator.get_alive_count()
+    }
+
+    ///Attach `component` to `entity`
+    ///
+    /// # Returns
+    ///
+    /// The component of the same type previously attached to `entity`, if any
+    ///
+    /// # Panics
+    ///
+    /// If `entity` isn't alive
+    pub fn insert<T: Component>(&mut self, entity: Entity, component: T) -> Option<T> {
+        assert!(self.is_alive(entity), "entity {entity:?} isn't alive");
+
+        self.all_storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(SparseSet::<T>::new()))
+            .as_any_mut()
+            .downcast_mut::<SparseSet<T>>()
+            .expect("failed to downcast the component storage")
+            .insert(entity, component)
+    }
+
+    ///Detach the component of type `T` from `entity`
+    pub fn remove<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        self.get_storage_mut::<T>()?.remove(entity)
+    }
+
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        self.get_storage::<T>()?.get(entity)
+    }
+
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
+        self.get_storage_mut::<T>()?.get_mut(entity)
+    }
+
+    ///Return if a component of type `T` is attached to `entity`
+    pub fn has<T: Component>(&self, entity: Entity) -> bool {
+        self.get_storage::<T>()
+            .is_some_and(|storage| storage.contains(entity))
+    }
+
+    ///Return the components of type `T`, None if none was ever inserted
+    pub fn get_storage<T: Component>(&self) -> Option<&SparseSet<T>> {
+        self.all_storages
+            .get(&TypeId::of::<T>())?
+            .as_any()
+            .downcast_ref::<SparseSet<T>>()
+    }
+
+    pub fn get_storage_mut<T: Component>(&mut self) -> Option<&mut SparseSet<T>> {
+        self.all_storages
+            .get_mut(&TypeId::of::<T>())?
+            .as_any_mut()
+            .downcast_mut::<SparseSet<T>>()
+    }
+
+    ///Return the entities matched by `Q` with their components
+    ///
+    ///For example, `world.query::<(&Transform, Option<&MeshRenderer>)>()`
+    pub fn query<Q: Query>(&self) -> impl Iterator<Item = (Entity, Q::Item<'_>)> {
+        self.query_filtered::<Q, ()>()
+    }
+
+    ///Return the entities matched by `Q` and `F` with their components
+    ///
+    ///For example, `world.query_filtered::<(&Camera,), With<ActiveCamera>>()`
+    pub fn query_filtered<Q: Query, F: QueryFilter>(
+        &self,
+    ) -> impl Iterator<Item = (Entity, Q::Item<'_>)> {
+        self.get_all_query_candidates::<Q>()
+            .into_iter()
+            .filter(|&entity| F::matches(self, entity))
+            .filter_map(|entity| Some((entity, Q::fetch(self, entity)?)))
+    }
+
+    ///Call `function` with the component of type `T` of each entity matched by `Q` and `F`, and its components
+    ///
+    ///Meanwhile, the components of type `T` are missing from `&self`: `Option<&T>` in `Q` fetches None
+    ///
+    /// # Panics
+    ///
+    /// If `Q` requires `T`
+    pub fn for_each_mut<T: Component, Q: Query, F: QueryFilter>(
+        &mut self,
+        mut function: impl FnMut(Entity, &mut T, Q::Item<'_>),
+    ) {
+        assert!(
+            !Q::get_all_required_type_ids().contains(&TypeId::of::<T>()),
+            "{} can't be queried while it's borrowed mutably",
+            type_name::<T>()
+        );
+        let Some(mut storage) = self.all_storages.remove(&TypeId::of::<T>()) else {
+            return;
+        };
+
+        let sparse_set = storage
+            .as_any_mut()
+            .downcast_mut::<SparseSet<T>>()
+            .expect("failed to downcast the component storage");
+        for (entity, component) in sparse_set.iter_mut() {
+            if !F::matches(self, entity) {
+                continue;
+            }
+            if let Some(item) = Q::fetch(self, entity) {
+                function(entity, component, item);
+            }
+        }
+
+        self.all_storages.insert(TypeId::of::<T>(), storage);
+    }
+
+    ///Return the entities which may be matched by `Q`, from the smallest storage of its required components
+    fn get_all_query_candidates<Q: Query>(&self) -> Vec<Entity> {
+        let all_required_type_ids = Q::get_all_required_type_ids();
+        if all_required_type_ids.is_empty() {
+            return self.get_all_entities().collect();
+        }
+
+        let mut smallest_entities: Option<&[Entity]> = None;
+        for type_id in all_required_type_ids {
+            //No entity is matched if a required component was never inserted
+            let Some(storage) = self.all_storages.get(&type_id) else {
+                return Vec::new();
+            };
+
+            let all_entities = storage.get_all_entities();
+            if smallest_entities.is_none_or(|smallest| all_entities.len() < smallest.len()) {
+                smallest_entities = Some(all_entities);
+            }
+        }
+
+        smallest_entities.unwrap_or_default().to_vec()
+    }
+}
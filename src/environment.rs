@@ -0,0 +1,172 @@
+use std::{
+    fmt::{self, Display},
+    fs, io,
+    path::Path,
+};
+
+use image::ImageFormat;
+
+use crate::{math::vector::Vec3, vulkan::texture::TextureError};
+
+///Error returned when an environment image cannot be loaded
+#[derive(Debug)]
+pub enum EnvironmentImageError {
+    ///The environment file cannot be read or decoded
+    Texture(TextureError),
+
+    ///The environment image isn't a Radiance HDR or OpenEXR image
+    NotHighDynamicRange,
+}
+
+impl Display for EnvironmentImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvironmentImageError::Texture(error) => {
+                write!(f, "failed to load environment image: {error}")
+            }
+            EnvironmentImageError::NotHighDynamicRange => {
+                write!(f, "environment image isn't a Radiance HDR or OpenEXR image")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvironmentImageError {}
+
+impl From<TextureError> for EnvironmentImageError {
+    fn from(error: TextureError) -> Self {
+        EnvironmentImageError::Texture(error)
+    }
+}
+
+impl From<io::Error> for EnvironmentImageError {
+    fn from(error: io::Error) -> Self {
+        EnvironmentImageError::Texture(TextureError::Io(error))
+    }
+}
+
+impl From<image::ImageError> for EnvironmentImageError {
+    fn from(error: image::ImageError) -> Self {
+        EnvironmentImageError::Texture(TextureError::Image(error))
+    }
+}
+
+///Equirectangular environment decoded from a high dynamic range image, in linear RGBA
+///
+///The longitude goes around the Y axis from the left edge to the right edge, the center of the image facing -Z,
+///and the latitude from +Y at the top edge to -Y at the bottom edge
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvironmentImage {
+    pub width: u32,
+    pub height: u32,
+
+    ///The texels by row, from the top left corner of the image
+    pub all_texels: Vec<[f32; 4]>,
+}
+
+impl EnvironmentImage {
+    ///Return an environment of the linear color `color` in every direction
+    ///
+    ///For example, can light the scenes without environment image with a uniform ambient light
+    pub fn new_solid_color(color: Vec3) -> Self {
+        Self {
+            width: 1,
+            height: 1,
+            all_texels: vec![[color.x, color.y, color.z, 1f32]],
+        }
+    }
+}
+
+///Size of the cube maps and sample count of the image-based lighting created from an environment
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EnvironmentConfig {
+    ///The maximum width and height (in texels) of the faces of the environment cube map, drawn by the skybox
+    pub max_cube_size: u32,
+
+    ///The width and height (in texels) of the faces of the irradiance cube map, lighting the diffuse surfaces
+    pub irradiance_size: u32,
+
+    ///The width and height (in texels) of the faces of the mip level 0 of the prefiltered cube map,
+    ///reflected by the specular surfaces
+    pub prefiltered_size: u32,
+
+    ///The number of mip levels of the prefiltered cube map, each one reflected by a roughness from 0 to 1
+    pub prefiltered_mip_levels: u32,
+
+    ///The width and height (in texels) of the BRDF LUT, by the cosine of the view angle and the roughness
+    pub brdf_lut_size: u32,
+
+    ///The number of samples of the specular BRDF for each texel of the prefiltered cube map and of the BRDF LUT
+    pub sample_count: u32,
+}
+
+impl Default for EnvironmentConfig {
+    ///Faces of 1024x1024 texels at most for the skybox, 6 prefiltered mip levels from 256x256 texels, and 1024 samples
+    fn default() -> Self {
+        Self {
+            max_cube_size: 1024,
+            irradiance_size: 32,
+            prefiltered_size: 256,
+            prefiltered_mip_levels: 6,
+            brdf_lut_size: 256,
+            sample_count: 1024,
+        }
+    }
+}
+
+impl EnvironmentConfig {
+    ///Return the width and height (in texels) of the faces of the environment cube map of `environment_image`
+    ///
+    ///A face covers a quarter of the equator of the equirectangular image, so its size is a quarter of the image width,
+    ///rounded up to a power of 2 and limited to `max_cube_size`
+    pub fn get_cube_size(&self, environment_image: &EnvironmentImage) -> u32 {
+        (environment_image.width / 4)
+            .max(1)
+            .next_power_of_two()
+            .min(self.max_cube_size.max(1))
+    }
+
+    ///Return the number of mip levels of the prefiltered cube map, limited to the full mip chain of `prefiltered_size`
+    pub fn get_prefiltered_mip_levels(&self) -> u32 {
+        self.prefiltered_mip_levels
+            .clamp(1, self.prefiltered_size.max(1).ilog2() + 1)
+    }
+
+    ///Return the roughness reflected by `mip_level` of the prefiltered cube map, from 0 for the mip level 0 to 1 for the last one
+    pub fn get_prefiltered_roughness(&self, mip_level: u32) -> f32 {
+        let last_mip_level = self.get_prefiltered_mip_levels() - 1;
+        if last_mip_level == 0 {
+            return 0f32;
+        }
+
+        mip_level.min(last_mip_level) as f32 / last_mip_level as f32
+    }
+}
+
+///Load the equirectangular environment of the Radiance HDR or OpenEXR image of `file_path`
+pub fn load_environment_image_from_file(
+    file_path: &Path,
+) -> Result<EnvironmentImage, EnvironmentImageError> {
+    let bytes = fs::read(file_path)?;
+
+    load_environment_image_from_bytes(&bytes)
+}
+
+///Load the equirectangular environment of the Radiance HDR or OpenEXR image `bytes`
+///
+///The other formats are refused, since their colors are clamped and not linear
+pub fn load_environment_image_from_bytes(
+    bytes: &[u8],
+) -> Result<EnvironmentImage, EnvironmentImageError> {
+    match image::guess_format(bytes)? {
+        ImageFormat::Hdr | ImageFormat::OpenExr => {}
+        _ => return Err(EnvironmentImageError::NotHighDynamicRange),
+    }
+
+    let rgba_image = image::load_from_memory(bytes)?.into_rgba32f();
+    Ok(EnvironmentImage {
+        width: rgba_image.width(),
+        height: rgba_image.height(),
+        all_texels: rgba_image.pixels().map(|texel| texel.0).collect(),
+    })
+}
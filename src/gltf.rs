@@ -0,0 +1,1438 @@
+use std::{
+    f32::consts::FRAC_PI_4,
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use ash::vk;
+
+use crate::{
+    animation::{
+        Animation, AnimationChannel, AnimationProperty, AnimationSampler, Interpolation, Skin,
+    },
+    camera::Camera,
+    json::{JsonError, JsonValue, parse_json},
+    material::{AlphaMode, Material, MaterialTexture, TextureImage},
+    math::{
+        matrix::Mat4,
+        quaternion::Quat,
+        vector::{Vec2, Vec3, Vec4},
+    },
+    mesh::{MeshData, Submesh, Vertex},
+    scene::{Light, MeshId, NodeId, Scene, SkinId, Transform},
+    vulkan::image::SamplerParameters,
+};
+
+///Magic number starting a binary glTF (GLB) file, "glTF" in ASCII
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+
+///Type of the chunk of a GLB file containing the JSON document, "JSON" in ASCII
+const GLB_JSON_CHUNK_TYPE: u32 = 0x4E4F_534A;
+
+///Type of the chunk of a GLB file containing the first buffer, "BIN\0" in ASCII
+const GLB_BIN_CHUNK_TYPE: u32 = 0x004E_4942;
+
+///Extensions the files may require, the others are reported by GltfError::UnsupportedExtension
+const ALL_SUPPORTED_EXTENSIONS: [&str; 3] = [
+    "KHR_lights_punctual",
+    "KHR_materials_emissive_strength",
+    "KHR_mesh_quantization",
+];
+
+///Distance of the far plane of the cameras with an infinite projection, which isn't supported by Camera
+const INFINITE_CAMERA_Z_FAR: f32 = 1000f32;
+
+///Value of the missing optional objects, whose members all have their default value
+static JSON_NULL: JsonValue = JsonValue::Null;
+
+///Error returned when a glTF file cannot be loaded
+#[derive(Debug)]
+pub enum GltfError {
+    ///The glTF file or one of its buffers cannot be read
+    Io {
+        file_path: PathBuf,
+        error: io::Error,
+    },
+
+    Json(JsonError),
+
+    ///The container of a binary glTF file is invalid
+    InvalidGlb(String),
+
+    ///The document doesn't follow the glTF 2.0 specification
+    Invalid(String),
+
+    ///The document isn't a glTF 2.x document
+    UnsupportedVersion(String),
+
+    ///The document requires an extension which isn't supported
+    UnsupportedExtension(String),
+}
+
+impl Display for GltfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GltfError::Io { file_path, error } => {
+                write!(f, "failed to read {}: {error}", file_path.display())
+            }
+            GltfError::Json(error) => write!(f, "{error}"),
+            GltfError::InvalidGlb(message) => write!(f, "invalid GLB container: {message}"),
+            GltfError::Invalid(message) => write!(f, "invalid glTF document: {message}"),
+            GltfError::UnsupportedVersion(version) => {
+                write!(f, "unsupported glTF version {version}")
+            }
+            GltfError::UnsupportedExtension(extension) => {
+                write!(f, "unsupported required extension {extension}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GltfError {}
+
+impl From<JsonError> for GltfError {
+    fn from(error: JsonError) -> Self {
+        GltfError::Json(error)
+    }
+}
+
+///Mesh of a glTF file, with the skinning data of its vertices
+#[derive(Clone, Debug, Default)]
+pub struct GltfMesh {
+    pub name: String,
+
+    ///The triangles of the mesh, with a submesh per primitive
+    pub mesh: MeshData,
+
+    ///The indices of the 4 joints deforming each vertex in the skin of the node, empty if the mesh isn't skinned
+    pub all_vertex_joints: Vec<[u16; 4]>,
+
+    ///The weights of the joints of `all_vertex_joints`
+    pub all_vertex_weights: Vec<Vec4>,
+}
+
+///Nodes, meshes, materials, skins and animations of a glTF file
+#[derive(Clone, Debug, Default)]
+pub struct GltfModel {
+    ///The nodes of all the scenes of the file, only the ones of its default scene are visible
+    pub scene: Scene,
+
+    ///The meshes indexed by the MeshId of the nodes
+    pub all_meshes: Vec<GltfMesh>,
+
+    ///The materials indexed by the submeshes of the meshes
+    pub all_materials: Vec<Material>,
+
+    ///The skins indexed by the SkinId of the nodes
+    pub all_skins: Vec<Skin>,
+
+    pub all_animations: Vec<Animation>,
+}
+
+///Load the glTF (JSON) or GLB (binary) file at `file_path`, and its external buffers
+///
+///The images of the textures in external files aren't read, only referenced by the materials
+pub fn load_gltf_from_file(file_path: impl AsRef<Path>) -> Result<GltfModel, GltfError> {
+    let file_path = file_path.as_ref();
+    let bytes = read_file(file_path)?;
+
+    parse_gltf(&bytes, file_path.parent().unwrap_or(Path::new("")))
+}
+
+///Parse the glTF or GLB `bytes`, whose external buffers and images are relative to `directory`
+pub fn parse_gltf(bytes: &[u8], directory: &Path) -> Result<GltfModel, GltfError> {
+    let (json_bytes, bin_chunk_opt) = if bytes.starts_with(GLB_MAGIC) {
+        split_glb(bytes)?
+    } else {
+        (bytes, None)
+    };
+    let json_source = std::str::from_utf8(json_bytes)
+        .map_err(|_| invalid("the JSON document isn't encoded in UTF-8"))?;
+    //The byte order mark is forbidden, but written by some exporters
+    let root = parse_json(json_source.trim_start_matches('\u{feff}'))?;
+
+    check_version(&root)?;
+    for extension in get_array(&root, "extensionsRequired") {
+        let extension = extension
+            .as_str()
+            .ok_or_else(|| invalid("extensionsRequired contains a non-string"))?;
+        if !ALL_SUPPORTED_EXTENSIONS.contains(&extension) {
+            return Err(GltfError::UnsupportedExtension(extension.to_string()));
+        }
+    }
+
+    let document = GltfDocument {
+        all_buffers: load_all_buffers(&root, bin_chunk_opt, directory)?,
+        root: &root,
+    };
+
+    let all_images = get_array(&root, "images")
+        .iter()
+        .enumerate()
+        .map(|(image_index, image)| document.load_image(image_index, image, directory))
+        .collect::<Result<Vec<_>, _>>()?;
+    let all_textures = get_array(&root, "textures")
+        .iter()
+        .map(|texture| load_texture(texture, &root, &all_images))
+        .collect::<Result<Vec<_>, _>>()?;
+    let all_materials = get_array(&root, "materials")
+        .iter()
+        .map(|material| load_material(material, &all_textures))
+        .collect::<Result<Vec<_>, _>>()?;
+    let all_meshes = get_array(&root, "meshes")
+        .iter()
+        .map(|mesh| document.load_mesh(mesh, all_materials.len()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (scene, all_node_ids) = document.load_scene(all_meshes.len())?;
+    let all_skins = get_array(&root, "skins")
+        .iter()
+        .map(|skin| document.load_skin(skin, &all_node_ids))
+        .collect::<Result<Vec<_>, _>>()?;
+    let all_animations = get_array(&root, "animations")
+        .iter()
+        .map(|animation| document.load_animation(animation, &all_node_ids))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(GltfModel {
+        scene,
+        all_meshes,
+        all_materials,
+        all_skins,
+        all_animations,
+    })
+}
+
+///JSON document of a glTF file with the content of its buffers
+struct GltfDocument<'json> {
+    root: &'json JsonValue,
+    all_buffers: Vec<Vec<u8>>,
+}
+
+///Elements of an accessor, with their components converted and normalized
+struct AccessorData {
+    ///The number of components of each element, for example 3 for a VEC3
+    component_count: usize,
+
+    all_components: Vec<f64>,
+}
+
+impl GltfDocument<'_> {
+    ///Load the image of `image`, referenced if it's in an external file
+    fn load_image(
+        &self,
+        image_index: usize,
+        image: &JsonValue,
+        directory: &Path,
+    ) -> Result<TextureImage, GltfError> {
+        if let Some(uri) = get_str(image, "uri") {
+            if uri.starts_with("data:") {
+                return Ok(TextureImage::Memory(load_uri(uri, directory)?.into()));
+            }
+            let file_name = String::from_utf8_lossy(&decode_percent_encoding(uri)).into_owned();
+            return Ok(TextureImage::File(directory.join(file_name)));
+        }
+
+        let Some(buffer_view_index) = get_index(image, "bufferView")? else {
+            return Err(invalid(format!(
+                "images[{image_index}] has no uri nor bufferView"
+            )));
+        };
+        let (bytes, _) = self.get_buffer_view(buffer_view_index)?;
+        Ok(TextureImage::Memory(Arc::from(bytes)))
+    }
+
+    ///Load the primitives of `mesh` in a submesh each, only keeping their triangles
+    ///
+    ///The missing normals are generated flat, as required by the specification, and the missing tangents are generated
+    fn load_mesh(&self, mesh: &JsonValue, material_count: usize) -> Result<GltfMesh, GltfError> {
+        let mut gltf_mesh = GltfMesh {
+            name: get_name(mesh),
+            ..GltfMesh::default()
+        };
+
+        for primitive in get_array(mesh, "primitives") {
+            let Some(mut primitive_data) = self.load_primitive(primitive)? else {
+                continue;
+            };
+            let material_index_opt = get_index(primitive, "material")?;
+            if material_index_opt.is_some_and(|material_index| material_index >= material_count) {
+                return Err(invalid("a primitive uses a missing material"));
+            }
+
+            let base_vertex = gltf_mesh.mesh.all_vertices.len();
+            u32::try_from(base_vertex + primitive_data.mesh.all_vertices.len())
+                .map_err(|_| invalid("a mesh has more vertices than can be indexed by u32"))?;
+
+            gltf_mesh.mesh.all_submeshes.push(Submesh {
+                first_index: gltf_mesh.mesh.all_indices.len() as u32,
+                index_count: primitive_data.mesh.all_indices.len() as u32,
+                material_index: material_index_opt,
+            });
+            gltf_mesh.mesh.all_indices.extend(
+                primitive_data
+                    .mesh
+                    .all_indices
+                    .iter()
+                    .map(|&index| index + base_vertex as u32),
+            );
+            gltf_mesh
+                .mesh
+                .all_vertices
+                .append(&mut primitive_data.mesh.all_vertices);
+
+            //The vertices of the primitives without joints are attached to the joint 0 without weight
+            if let Some(mut all_joints) = primitive_data.all_joints_opt {
+                gltf_mesh.all_vertex_joints.resize(base_vertex, [0; 4]);
+                gltf_mesh.all_vertex_joints.append(&mut all_joints);
+            }
+            if let Some(mut all_weights) = primitive_data.all_weights_opt {
+                gltf_mesh.all_vertex_weights.resize(base_vertex, Vec4::ZERO);
+                gltf_mesh.all_vertex_weights.append(&mut all_weights);
+            }
+        }
+
+        let vertex_count = gltf_mesh.mesh.all_vertices.len();
+        if !gltf_mesh.all_vertex_joints.is_empty() {
+            gltf_mesh.all_vertex_joints.resize(vertex_count, [0; 4]);
+        }
+        if !gltf_mesh.all_vertex_weights.is_empty() {
+            gltf_mesh
+                .all_vertex_weights
+                .resize(vertex_count, Vec4::ZERO);
+        }
+
+        Ok(gltf_mesh)
+    }
+
+    ///Load the triangles of `primitive`, None if it's made of points or lines, or has no position
+    fn load_primitive(&self, primitive: &JsonValue) -> Result<Option<PrimitiveData>, GltfError> {
+        let attributes = primitive
+            .get("attributes")
+            .ok_or_else(|| invalid("a primitive has no attributes"))?;
+        let Some(position_accessor_index) = get_index(attributes, "POSITION")? else {
+            return Ok(None);
+        };
+        let all_positions = self.read_vec_accessor::<3>(position_accessor_index)?;
+        let vertex_count = all_positions.len();
+
+        let all_normals_opt =
+            self.read_vertex_attribute::<3>(attributes, "NORMAL", vertex_count)?;
+        let all_tangents_opt =
+            self.read_vertex_attribute::<4>(attributes, "TANGENT", vertex_count)?;
+        let all_uvs_opt =
+            self.read_vertex_attribute::<2>(attributes, "TEXCOORD_0", vertex_count)?;
+        let all_joints_opt =
+            self.read_vertex_attribute::<4>(attributes, "JOINTS_0", vertex_count)?;
+        let all_weights_opt =
+            self.read_vertex_attribute::<4>(attributes, "WEIGHTS_0", vertex_count)?;
+
+        let all_vertex_indices = match get_index(primitive, "indices")? {
+            Some(index_accessor_index) => self.read_index_accessor(index_accessor_index)?,
+            None => (0..vertex_count as u32).collect(),
+        };
+        if all_vertex_indices
+            .iter()
+            .any(|&vertex_index| vertex_index as usize >= vertex_count)
+        {
+            return Err(invalid("a primitive has an index out of its vertices"));
+        }
+
+        let triangle_count = all_vertex_indices.len().saturating_sub(2);
+        let all_indices: Vec<u32> = match get_index(primitive, "mode")?.unwrap_or(4) {
+            //The incomplete last triangle is ignored
+            4 => all_vertex_indices[..all_vertex_indices.len() / 3 * 3].to_vec(),
+            //Triangle strip, whose odd triangles are flipped to keep the winding order
+            5 => (0..triangle_count)
+                .flat_map(|triangle| {
+                    let parity = triangle % 2;
+                    [triangle, triangle + 1 + parity, triangle + 2 - parity]
+                        .map(|corner| all_vertex_indices[corner])
+                })
+                .collect(),
+            //Triangle fan around the first vertex
+            6 => (0..triangle_count)
+                .flat_map(|triangle| {
+                    [triangle + 1, triangle + 2, 0].map(|corner| all_vertex_indices[corner])
+                })
+                .collect(),
+            //Points and lines aren't drawn
+            0..=3 => return Ok(None),
+            mode => return Err(invalid(format!("invalid primitive mode {mode}"))),
+        };
+
+        let mut mesh = MeshData {
+            all_vertices: (0..vertex_count)
+                .map(|vertex_index| Vertex {
+                    position: Vec3::from_array(all_positions[vertex_index]),
+                    normal: all_normals_opt.as_ref().map_or(Vec3::ZERO, |all_normals| {
+                        Vec3::from_array(all_normals[vertex_index])
+                    }),
+                    uv: all_uvs_opt.as_ref().map_or(Vec2::ZERO, |all_uvs| {
+                        Vec2::from_array(all_uvs[vertex_index])
+                    }),
+                    tangent: all_tangents_opt
+                        .as_ref()
+                        .map_or(Vec4::ZERO, |all_tangents| {
+                            Vec4::from_array(all_tangents[vertex_index])
+                        }),
+                })
+                .collect(),
+            all_indices,
+            all_submeshes: Vec::new(),
+        };
+        let mut all_joints_opt = all_joints_opt.map(|all_joints| {
+            all_joints
+                .into_iter()
+                .map(|joints| joints.map(|joint| joint as u16))
+                .collect::<Vec<_>>()
+        });
+        let mut all_weights_opt = all_weights_opt.map(|all_weights| {
+            all_weights
+                .into_iter()
+                .map(Vec4::from_array)
+                .collect::<Vec<_>>()
+        });
+
+        if all_normals_opt.is_none() {
+            //The vertices aren't shared by the triangles, so their generated normals are flat
+            let all_source_indices = std::mem::take(&mut mesh.all_indices);
+            mesh.all_vertices = all_source_indices
+                .iter()
+                .map(|&source_index| mesh.all_vertices[source_index as usize])
+                .collect();
+            mesh.all_indices = (0..all_source_indices.len() as u32).collect();
+            if let Some(all_joints) = all_joints_opt.as_mut() {
+                *all_joints = all_source_indices
+                    .iter()
+                    .map(|&source_index| all_joints[source_index as usize])
+                    .collect();
+            }
+            if let Some(all_weights) = all_weights_opt.as_mut() {
+                *all_weights = all_source_indices
+                    .iter()
+                    .map(|&source_index| all_weights[source_index as usize])
+                    .collect();
+            }
+
+            mesh.generate_normals();
+        }
+        if all_tangents_opt.is_none() {
+            mesh.generate_tangents();
+        }
+
+        Ok(Some(PrimitiveData {
+            mesh,
+            all_joints_opt,
+            all_weights_opt,
+        }))
+    }
+
+    ///Load the nodes of all the scenes in a Scene, the nodes of the default scene first
+    ///
+    /// # Returns
+    ///
+    /// The scene, and the NodeId of each glTF node
+    fn load_scene(&self, mesh_count: usize) -> Result<(Scene, Vec<NodeId>), GltfError> {
+        let all_nodes = get_array(self.root, "nodes");
+        let node_count = all_nodes.len();
+
+        let mut all_parent_indices: Vec<Option<usize>> = vec![None; node_count];
+        for (node_index, node) in all_nodes.iter().enumerate() {
+            for child in get_array(node, "children") {
+                let child_index = child
+                    .as_usize()
+                    .filter(|&child_index| child_index < node_count && child_index != node_index)
+                    .ok_or_else(|| invalid(format!("nodes[{node_index}] has an invalid child")))?;
+                if all_parent_indices[child_index]
+                    .replace(node_index)
+                    .is_some()
+                {
+                    return Err(invalid(format!("nodes[{child_index}] has several parents")));
+                }
+            }
+        }
+
+        //Without scene, all the nodes are shown
+        let scene_index_opt = match get_index(self.root, "scene")? {
+            Some(scene_index) => Some(scene_index),
+            None => (!get_array(self.root, "scenes").is_empty()).then_some(0),
+        };
+        let all_visible_root_indices = match scene_index_opt {
+            Some(scene_index) => get_array(get_element(self.root, "scenes", scene_index)?, "nodes")
+                .iter()
+                .map(|root| {
+                    root.as_usize()
+                        .filter(|&root_index| {
+                            root_index < node_count && all_parent_indices[root_index].is_none()
+                        })
+                        .ok_or_else(|| {
+                            invalid(format!("scenes[{scene_index}] has an invalid root"))
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => (0..node_count)
+                .filter(|&node_index| all_parent_indices[node_index].is_none())
+                .collect(),
+        };
+        let all_hidden_root_indices = (0..node_count).filter(|node_index| {
+            all_parent_indices[*node_index].is_none()
+                && !all_visible_root_indices.contains(node_index)
+        });
+
+        let all_cameras = get_array(self.root, "cameras")
+            .iter()
+            .map(load_camera)
+            .collect::<Result<Vec<_>, _>>()?;
+        let all_lights = get_array(
+            self.root
+                .get("extensions")
+                .and_then(|extensions| extensions.get("KHR_lights_punctual"))
+                .unwrap_or(&JSON_NULL),
+            "lights",
+        )
+        .iter()
+        .map(load_light)
+        .collect::<Result<Vec<_>, _>>()?;
+        let skin_count = get_array(self.root, "skins").len();
+
+        let mut scene = Scene::new();
+        let mut all_node_ids: Vec<Option<NodeId>> = vec![None; node_count];
+        let mut all_pending_nodes: Vec<(usize, Option<NodeId>, bool)> = all_visible_root_indices
+            .iter()
+            .map(|&root_index| (root_index, None, true))
+            .chain(all_hidden_root_indices.map(|root_index| (root_index, None, false)))
+            .rev()
+            .collect();
+        while let Some((node_index, parent_id_opt, is_visible)) = all_pending_nodes.pop() {
+            let node = &all_nodes[node_index];
+            let node_id = scene.add_node(&get_name(node), get_node_transform(node)?, parent_id_opt);
+            all_node_ids[node_index] = Some(node_id);
+
+            let scene_node = scene
+                .get_node_mut(node_id)
+                .expect("failed to find the added node");
+            scene_node.is_visible = is_visible;
+            scene_node.mesh = get_index(node, "mesh")?
+                .map(|mesh_index| {
+                    (mesh_index < mesh_count)
+                        .then_some(MeshId(mesh_index))
+                        .ok_or_else(|| invalid(format!("nodes[{node_index}] has a missing mesh")))
+                })
+                .transpose()?;
+            scene_node.skin = get_index(node, "skin")?
+                .map(|skin_index| {
+                    (skin_index < skin_count)
+                        .then_some(SkinId(skin_index))
+                        .ok_or_else(|| invalid(format!("nodes[{node_index}] has a missing skin")))
+                })
+                .transpose()?;
+            scene_node.camera = get_index(node, "camera")?
+                .map(|camera_index| {
+                    all_cameras
+                        .get(camera_index)
+                        .cloned()
+                        .ok_or_else(|| invalid(format!("nodes[{node_index}] has a missing camera")))
+                })
+                .transpose()?;
+            if let Some(light) = node
+                .get("extensions")
+                .and_then(|extensions| extensions.get("KHR_lights_punctual"))
+            {
+                let light_index = get_index(light, "light")?.ok_or_else(|| {
+                    invalid(format!("nodes[{node_index}] has a light without index"))
+                })?;
+                scene_node.light =
+                    Some(*all_lights.get(light_index).ok_or_else(|| {
+                        invalid(format!("nodes[{node_index}] has a missing light"))
+                    })?);
+            }
+
+            all_pending_nodes.extend(
+                get_array(node, "children")
+                    .iter()
+                    .rev()
+                    .filter_map(JsonValue::as_usize)
+                    .map(|child_index| (child_index, Some(node_id), true)),
+            );
+        }
+
+        //The nodes of a cycle have no root, so they aren't reached
+        let all_node_ids = all_node_ids
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("the node hierarchy has a cycle"))?;
+
+        Ok((scene, all_node_ids))
+    }
+
+    fn load_skin(&self, skin: &JsonValue, all_node_ids: &[NodeId]) -> Result<Skin, GltfError> {
+        let all_joint_ids = get_array(skin, "joints")
+            .iter()
+            .map(|joint| {
+                joint
+                    .as_usize()
+                    .and_then(|node_index| all_node_ids.get(node_index).copied())
+                    .ok_or_else(|| invalid("a skin has an invalid joint"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let all_inverse_bind_matrices = match get_index(skin, "inverseBindMatrices")? {
+            Some(accessor_index) => self
+                .read_vec_accessor::<16>(accessor_index)?
+                .into_iter()
+                .map(mat4_from_cols_array)
+                .collect(),
+            None => vec![Mat4::IDENTITY; all_joint_ids.len()],
+        };
+        if all_inverse_bind_matrices.len() != all_joint_ids.len() {
+            return Err(invalid(
+                "a skin hasn't an inverse bind matrix for each joint",
+            ));
+        }
+
+        Ok(Skin {
+            name: get_name(skin),
+            all_joint_ids,
+            all_inverse_bind_matrices,
+        })
+    }
+
+    ///Load the channels of `animation` animating the transforms of the nodes
+    ///
+    ///The morph target weights aren't loaded, nor the properties animated by extensions
+    fn load_animation(
+        &self,
+        animation: &JsonValue,
+        all_node_ids: &[NodeId],
+    ) -> Result<Animation, GltfError> {
+        let all_samplers = get_array(animation, "samplers");
+        let mut all_channels = Vec::new();
+
+        for channel in get_array(animation, "channels") {
+            let target = channel
+                .get("target")
+                .ok_or_else(|| invalid("an animation channel has no target"))?;
+            let Some(node_index) = get_index(target, "node")? else {
+                continue;
+            };
+            let property = match get_str(target, "path") {
+                Some("translation") => AnimationProperty::Translation,
+                Some("rotation") => AnimationProperty::Rotation,
+                Some("scale") => AnimationProperty::Scale,
+                Some("weights" | "pointer") => continue,
+                _ => return Err(invalid("an animation channel has an invalid path")),
+            };
+
+            let target_id = *all_node_ids
+                .get(node_index)
+                .ok_or_else(|| invalid("an animation channel targets a missing node"))?;
+            let sampler_index = get_index(channel, "sampler")?
+                .ok_or_else(|| invalid("an animation channel has no sampler"))?;
+            let sampler = all_samplers
+                .get(sampler_index)
+                .ok_or_else(|| invalid("an animation channel has a missing sampler"))?;
+
+            all_channels.push(AnimationChannel {
+                target_id,
+                property,
+                sampler: self.load_animation_sampler(sampler, property)?,
+            });
+        }
+
+        Ok(Animation {
+            name: get_name(animation),
+            all_channels,
+        })
+    }
+
+    fn load_animation_sampler(
+        &self,
+        sampler: &JsonValue,
+        property: AnimationProperty,
+    ) -> Result<AnimationSampler, GltfError> {
+        let interpolation = match get_str(sampler, "interpolation") {
+            None | Some("LINEAR") => Interpolation::Linear,
+            Some("STEP") => Interpolation::Step,
+            Some("CUBICSPLINE") => Interpolation::CubicSpline,
+            Some(interpolation) => {
+                return Err(invalid(format!("invalid interpolation {interpolation}")));
+            }
+        };
+
+        let input_accessor_index = get_index(sampler, "input")?
+            .ok_or_else(|| invalid("an animation sampler has no input"))?;
+        let all_times: Vec<f32> = self
+            .read_vec_accessor::<1>(input_accessor_index)?
+            .into_iter()
+            .map(|[time]| time)
+            .collect();
+
+        let output_accessor_index = get_index(sampler, "output")?
+            .ok_or_else(|| invalid("an animation sampler has no output"))?;
+        let all_values: Vec<Vec4> = match property {
+            AnimationProperty::Rotation => self
+                .read_vec_accessor::<4>(output_accessor_index)?
+                .into_iter()
+                .map(Vec4::from_array)
+                .collect(),
+            AnimationProperty::Translation | AnimationProperty::Scale => self
+                .read_vec_accessor::<3>(output_accessor_index)?
+                .into_iter()
+                .map(|value| Vec3::from_array(value).extend(0f32))
+                .collect(),
+        };
+
+        let value_count_by_keyframe = match interpolation {
+            Interpolation::CubicSpline => 3,
+            _ => 1,
+        };
+        if all_values.len() != all_times.len() * value_count_by_keyframe {
+            return Err(invalid(
+                "an animation sampler hasn't as many outputs as keyframes",
+            ));
+        }
+
+        Ok(AnimationSampler {
+            interpolation,
+            all_times,
+            all_values,
+        })
+    }
+
+    ///Read the vertex attribute `name` of `attributes`, None if it's missing
+    fn read_vertex_attribute<const N: usize>(
+        &self,
+        attributes: &JsonValue,
+        name: &str,
+        vertex_count: usize,
+    ) -> Result<Option<Vec<[f32; N]>>, GltfError> {
+        let Some(accessor_index) = get_index(attributes, name)? else {
+            return Ok(None);
+        };
+
+        let all_values = self.read_vec_accessor::<N>(accessor_index)?;
+        if all_values.len() != vertex_count {
+            return Err(invalid(format!(
+                "the {name} attribute hasn't a value for each vertex"
+            )));
+        }
+        Ok(Some(all_values))
+    }
+
+    ///Read the elements of an accessor with `N` components, such as a VEC3 for `N` = 3
+    fn read_vec_accessor<const N: usize>(
+        &self,
+        accessor_index: usize,
+    ) -> Result<Vec<[f32; N]>, GltfError> {
+        let accessor_data = self.read_accessor(accessor_index)?;
+        if accessor_data.component_count != N {
+            return Err(invalid(format!(
+                "accessors[{accessor_index}] has {} components instead of {N}",
+                accessor_data.component_count
+            )));
+        }
+
+        Ok(accessor_data
+            .all_components
+            .chunks_exact(N)
+            .map(|components| std::array::from_fn(|component| components[component] as f32))
+            .collect())
+    }
+
+    ///Read the vertex indices of a SCALAR accessor
+    fn read_index_accessor(&self, accessor_index: usize) -> Result<Vec<u32>, GltfError> {
+        let accessor_data = self.read_accessor(accessor_index)?;
+        if accessor_data.component_count != 1 {
+            return Err(invalid(format!(
+                "accessors[{accessor_index}] of indices isn't a SCALAR"
+            )));
+        }
+
+        Ok(accessor_data
+            .all_components
+            .into_iter()
+            .map(|index| index as u32)
+            .collect())
+    }
+
+    ///Read the elements of an accessor, replaced by its sparse values if any
+    ///
+    ///The columns of the MAT2 and MAT3 of 1 or 2 byte components, which are padded, aren't supported
+    fn read_accessor(&self, accessor_index: usize) -> Result<AccessorData, GltfError> {
+        let accessor = get_element(self.root, "accessors", accessor_index)?;
+        let invalid_accessor =
+            |message: &str| invalid(format!("accessors[{accessor_index}] {message}"));
+
+        let count =
+            get_index(accessor, "count")?.ok_or_else(|| invalid_accessor("has no count"))?;
+        let component_type = get_index(accessor, "componentType")?
+            .ok_or_else(|| invalid_accessor("has no componentType"))?;
+        let component_count = match get_str(accessor, "type") {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4" | "MAT2") => 4,
+            Some("MAT3") => 9,
+            Some("MAT4") => 16,
+            _ => return Err(invalid_accessor("has an invalid type")),
+        };
+
+        //An accessor without buffer view is filled with zeros
+        let mut all_components = match get_index(accessor, "bufferView")? {
+            Some(buffer_view_index) => self.read_elements(
+                buffer_view_index,
+                get_index(accessor, "byteOffset")?.unwrap_or(0),
+                count,
+                (component_type, component_count),
+            )?,
+            None => vec![0f64; count * component_count],
+        };
+
+        if let Some(sparse) = accessor.get("sparse") {
+            let sparse_count = get_index(sparse, "count")?
+                .ok_or_else(|| invalid_accessor("has no sparse count"))?;
+            let sparse_indices = sparse
+                .get("indices")
+                .ok_or_else(|| invalid_accessor("has no sparse indices"))?;
+            let sparse_values = sparse
+                .get("values")
+                .ok_or_else(|| invalid_accessor("has no sparse values"))?;
+
+            let all_element_indices = self.read_elements(
+                get_index(sparse_indices, "bufferView")?
+                    .ok_or_else(|| invalid_accessor("has no sparse indices bufferView"))?,
+                get_index(sparse_indices, "byteOffset")?.unwrap_or(0),
+                sparse_count,
+                (
+                    get_index(sparse_indices, "componentType")?
+                        .ok_or_else(|| invalid_accessor("has no sparse indices componentType"))?,
+                    1,
+                ),
+            )?;
+            let all_element_values = self.read_elements(
+                get_index(sparse_values, "bufferView")?
+                    .ok_or_else(|| invalid_accessor("has no sparse values bufferView"))?,
+                get_index(sparse_values, "byteOffset")?.unwrap_or(0),
+                sparse_count,
+                (component_type, component_count),
+            )?;
+
+            for (&element_index, element_components) in all_element_indices
+                .iter()
+                .zip(all_element_values.chunks_exact(component_count))
+            {
+                let first_component = element_index as usize * component_count;
+                all_components
+                    .get_mut(first_component..first_component + component_count)
+                    .ok_or_else(|| invalid_accessor("has a sparse index out of its elements"))?
+                    .copy_from_slice(element_components);
+            }
+        }
+
+        if accessor.get("normalized").and_then(JsonValue::as_bool) == Some(true) {
+            for component in &mut all_components {
+                *component = normalize_component(*component, component_type);
+            }
+        }
+
+        Ok(AccessorData {
+            component_count,
+            all_components,
+        })
+    }
+
+    ///Read `count` elements of `component_count` components of `component_type`
+    ///at `byte_offset` in a buffer view, converted to f64
+    fn read_elements(
+        &self,
+        buffer_view_index: usize,
+        byte_offset: usize,
+        count: usize,
+        (component_type, component_count): (usize, usize),
+    ) -> Result<Vec<f64>, GltfError> {
+        let (bytes, byte_stride_opt) = self.get_buffer_view(buffer_view_index)?;
+        let component_size = get_component_size(component_type)?;
+        let element_size = component_size * component_count;
+        let byte_stride = byte_stride_opt.unwrap_or(element_size);
+
+        //The last element is checked first, so a corrupted count doesn't allocate memory
+        let end_offset = count
+            .checked_sub(1)
+            .and_then(|last_element| last_element.checked_mul(byte_stride))
+            .and_then(|last_offset| last_offset.checked_add(byte_offset + element_size))
+            .unwrap_or(0);
+        if count > 0 && (end_offset == 0 || end_offset > bytes.len()) {
+            return Err(invalid(format!(
+                "an accessor is out of bufferViews[{buffer_view_index}]"
+            )));
+        }
+
+        let mut all_components = Vec::with_capacity(count * component_count);
+        for element in 0..count {
+            let element_offset = byte_offset + element * byte_stride;
+            all_components.extend(
+                bytes[element_offset..element_offset + element_size]
+                    .chunks_exact(component_size)
+                    .map(|component_bytes| read_component(component_bytes, component_type)),
+            );
+        }
+
+        Ok(all_components)
+    }
+
+    ///Return the bytes of a buffer view and its stride, None if its elements are tightly packed
+    fn get_buffer_view(
+        &self,
+        buffer_view_index: usize,
+    ) -> Result<(&[u8], Option<usize>), GltfError> {
+        let buffer_view = get_element(self.root, "bufferViews", buffer_view_index)?;
+        let invalid_buffer_view =
+            |message: &str| invalid(format!("bufferViews[{buffer_view_index}] {message}"));
+
+        let buffer = get_index(buffer_view, "buffer")?
+            .and_then(|buffer_index| self.all_buffers.get(buffer_index))
+            .ok_or_else(|| invalid_buffer_view("has a missing buffer"))?;
+        let byte_offset = get_index(buffer_view, "byteOffset")?.unwrap_or(0);
+        let byte_length = get_index(buffer_view, "byteLength")?
+            .ok_or_else(|| invalid_buffer_view("has no byteLength"))?;
+
+        let bytes = byte_offset
+            .checked_add(byte_length)
+            .and_then(|end_offset| buffer.get(byte_offset..end_offset))
+            .ok_or_else(|| invalid_buffer_view("is out of its buffer"))?;
+        Ok((bytes, get_index(buffer_view, "byteStride")?))
+    }
+}
+
+///Triangles of a primitive, with the skinning data of its vertices if any
+struct PrimitiveData {
+    mesh: MeshData,
+    all_joints_opt: Option<Vec<[u16; 4]>>,
+    all_weights_opt: Option<Vec<Vec4>>,
+}
+
+///Return the JSON chunk and the BIN chunk (if any) of the GLB file `bytes`
+fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), GltfError> {
+    let read_u32 = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|u32_bytes| u32::from_le_bytes(u32_bytes.try_into().expect("failed to read u32")))
+            .ok_or_else(|| GltfError::InvalidGlb("truncated file".to_string()))
+    };
+
+    let version = read_u32(4)?;
+    if version != 2 {
+        return Err(GltfError::UnsupportedVersion(version.to_string()));
+    }
+    let length = read_u32(8)? as usize;
+    if length > bytes.len() {
+        return Err(GltfError::InvalidGlb("truncated file".to_string()));
+    }
+
+    let mut json_chunk_opt = None;
+    let mut bin_chunk_opt = None;
+    let mut chunk_offset = 12;
+    while chunk_offset + 8 <= length {
+        let chunk_length = read_u32(chunk_offset)? as usize;
+        let chunk_type = read_u32(chunk_offset + 4)?;
+        let chunk_start = chunk_offset + 8;
+        let chunk = bytes
+            .get(chunk_start..chunk_start + chunk_length)
+            .filter(|_| chunk_start + chunk_length <= length)
+            .ok_or_else(|| GltfError::InvalidGlb("truncated chunk".to_string()))?;
+
+        //The JSON chunk is first, followed by an optional BIN chunk, the other chunks are ignored
+        match chunk_type {
+            GLB_JSON_CHUNK_TYPE if json_chunk_opt.is_none() => json_chunk_opt = Some(chunk),
+            GLB_BIN_CHUNK_TYPE if json_chunk_opt.is_some() && bin_chunk_opt.is_none() => {
+                bin_chunk_opt = Some(chunk)
+            }
+            _ => {}
+        }
+        chunk_offset = chunk_start + chunk_length;
+    }
+
+    let json_chunk =
+        json_chunk_opt.ok_or_else(|| GltfError::InvalidGlb("missing JSON chunk".to_string()))?;
+    Ok((json_chunk, bin_chunk_opt))
+}
+
+///Check that the document is a glTF 2.x document whose minimum version is supported
+fn check_version(root: &JsonValue) -> Result<(), GltfError> {
+    let asset = root
+        .get("asset")
+        .ok_or_else(|| invalid("the asset is missing"))?;
+    let version = get_str(asset, "version").ok_or_else(|| invalid("the version is missing"))?;
+    if version.split('.').next() != Some("2") {
+        return Err(GltfError::UnsupportedVersion(version.to_string()));
+    }
+    if let Some(min_version) = get_str(asset, "minVersion")
+        && min_version != "2.0"
+    {
+        return Err(GltfError::UnsupportedVersion(min_version.to_string()));
+    }
+
+    Ok(())
+}
+
+///Load the content of the buffers, the buffer without uri of a GLB file being its BIN chunk
+fn load_all_buffers(
+    root: &JsonValue,
+    bin_chunk_opt: Option<&[u8]>,
+    directory: &Path,
+) -> Result<Vec<Vec<u8>>, GltfError> {
+    get_array(root, "buffers")
+        .iter()
+        .enumerate()
+        .map(|(buffer_index, buffer)| {
+            let bytes = match (get_str(buffer, "uri"), bin_chunk_opt) {
+                (Some(uri), _) => load_uri(uri, directory)?,
+                (None, Some(bin_chunk)) if buffer_index == 0 => bin_chunk.to_vec(),
+                (None, _) => {
+                    return Err(invalid(format!("buffers[{buffer_index}] has no uri")));
+                }
+            };
+
+            let byte_length = get_index(buffer, "byteLength")?
+                .ok_or_else(|| invalid(format!("buffers[{buffer_index}] has no byteLength")))?;
+            if bytes.len() < byte_length {
+                return Err(invalid(format!(
+                    "buffers[{buffer_index}] is shorter than its byteLength"
+                )));
+            }
+            Ok(bytes)
+        })
+        .collect()
+}
+
+///Load the data of a base64 data URI, or the content of a file relative to `directory`
+fn load_uri(uri: &str, directory: &Path) -> Result<Vec<u8>, GltfError> {
+    let Some(data) = uri.strip_prefix("data:") else {
+        let file_name = String::from_utf8_lossy(&decode_percent_encoding(uri)).into_owned();
+        return read_file(&directory.join(file_name));
+    };
+
+    let (media_type, payload) = data
+        .split_once(',')
+        .ok_or_else(|| invalid("a data URI has no data"))?;
+    if media_type.ends_with(";base64") {
+        decode_base64(payload).ok_or_else(|| invalid("a data URI has invalid base64 data"))
+    } else {
+        Ok(decode_percent_encoding(payload))
+    }
+}
+
+///Return the texture of `texture`, None if it has no source (its image is then provided by an optional extension)
+fn load_texture(
+    texture: &JsonValue,
+    root: &JsonValue,
+    all_images: &[TextureImage],
+) -> Result<Option<MaterialTexture>, GltfError> {
+    let Some(image_index) = get_index(texture, "source")? else {
+        return Ok(None);
+    };
+    let image = all_images
+        .get(image_index)
+        .ok_or_else(|| invalid("a texture has a missing image"))?;
+
+    let sampler_parameters = match get_index(texture, "sampler")? {
+        Some(sampler_index) => {
+            get_sampler_parameters(get_element(root, "samplers", sampler_index)?)?
+        }
+        None => SamplerParameters::default(),
+    };
+
+    Ok(Some(MaterialTexture {
+        image: image.clone(),
+        sampler_parameters,
+    }))
+}
+
+///Return the parameters of the glTF `sampler`, whose missing filters are linear
+fn get_sampler_parameters(sampler: &JsonValue) -> Result<SamplerParameters, GltfError> {
+    let mut sampler_parameters = SamplerParameters::default();
+
+    if let Some(mag_filter) = get_index(sampler, "magFilter")? {
+        sampler_parameters.mag_filter = match mag_filter {
+            9728 => vk::Filter::NEAREST,
+            9729 => vk::Filter::LINEAR,
+            _ => return Err(invalid(format!("invalid magFilter {mag_filter}"))),
+        };
+    }
+
+    //The filters without mipmaps still sample the mip levels, which are expected to be the same image
+    if let Some(min_filter) = get_index(sampler, "minFilter")? {
+        (
+            sampler_parameters.min_filter,
+            sampler_parameters.mipmap_mode,
+        ) = match min_filter {
+            9728 | 9984 => (vk::Filter::NEAREST, vk::SamplerMipmapMode::NEAREST),
+            9729 | 9985 => (vk::Filter::LINEAR, vk::SamplerMipmapMode::NEAREST),
+            9986 => (vk::Filter::NEAREST, vk::SamplerMipmapMode::LINEAR),
+            9987 => (vk::Filter::LINEAR, vk::SamplerMipmapMode::LINEAR),
+            _ => return Err(invalid(format!("invalid minFilter {min_filter}"))),
+        };
+    }
+
+    for (address_mode, wrap_name) in sampler_parameters
+        .address_modes
+        .iter_mut()
+        .zip(["wrapS", "wrapT"])
+    {
+        *address_mode = match get_index(sampler, wrap_name)?.unwrap_or(10497) {
+            33071 => vk::SamplerAddressMode::CLAMP_TO_EDGE,
+            33648 => vk::SamplerAddressMode::MIRRORED_REPEAT,
+            10497 => vk::SamplerAddressMode::REPEAT,
+            wrap => return Err(invalid(format!("invalid {wrap_name} {wrap}"))),
+        };
+    }
+
+    Ok(sampler_parameters)
+}
+
+///Load the metallic-roughness parameters of `material`
+///
+///The textures are all sampled with TEXCOORD_0, whatever their texCoord
+fn load_material(
+    material: &JsonValue,
+    all_textures: &[Option<MaterialTexture>],
+) -> Result<Material, GltfError> {
+    let get_texture = |texture_info_opt: Option<&JsonValue>| {
+        let Some(texture_info) = texture_info_opt else {
+            return Ok(None);
+        };
+
+        get_index(texture_info, "index")?
+            .and_then(|texture_index| all_textures.get(texture_index))
+            .cloned()
+            .ok_or_else(|| invalid("a material has a missing texture"))
+    };
+
+    let pbr_metallic_roughness = material.get("pbrMetallicRoughness").unwrap_or(&JSON_NULL);
+    let normal_texture_info = material.get("normalTexture").unwrap_or(&JSON_NULL);
+    let occlusion_texture_info = material.get("occlusionTexture").unwrap_or(&JSON_NULL);
+    let emissive_strength = get_f32(
+        material
+            .get("extensions")
+            .and_then(|extensions| extensions.get("KHR_materials_emissive_strength"))
+            .unwrap_or(&JSON_NULL),
+        "emissiveStrength",
+        1f32,
+    )?;
+
+    Ok(Material {
+        name: get_name(material),
+        base_color_factor: Vec4::from_array(get_floats(
+            pbr_metallic_roughness,
+            "baseColorFactor",
+            [1f32; 4],
+        )?),
+        base_color_texture: get_texture(pbr_metallic_roughness.get("baseColorTexture"))?,
+        metallic_factor: get_f32(pbr_metallic_roughness, "metallicFactor", 1f32)?,
+        roughness_factor: get_f32(pbr_metallic_roughness, "roughnessFactor", 1f32)?,
+        metallic_roughness_texture: get_texture(
+            pbr_metallic_roughness.get("metallicRoughnessTexture"),
+        )?,
+        normal_texture: get_texture(material.get("normalTexture"))?,
+        normal_scale: get_f32(normal_texture_info, "scale", 1f32)?,
+        occlusion_texture: get_texture(material.get("occlusionTexture"))?,
+        occlusion_strength: get_f32(occlusion_texture_info, "strength", 1f32)?,
+        emissive_factor: Vec3::from_array(get_floats(material, "emissiveFactor", [0f32; 3])?)
+            * emissive_strength,
+        emissive_texture: get_texture(material.get("emissiveTexture"))?,
+        alpha_mode: match get_str(material, "alphaMode") {
+            None | Some("OPAQUE") => AlphaMode::Opaque,
+            Some("MASK") => AlphaMode::Mask {
+                cutoff: get_f32(material, "alphaCutoff", 0.5f32)?,
+            },
+            Some("BLEND") => AlphaMode::Blend,
+            Some(alpha_mode) => return Err(invalid(format!("invalid alphaMode {alpha_mode}"))),
+        },
+        is_double_sided: material
+            .get("doubleSided")
+            .and_then(JsonValue::as_bool)
+            .unwrap_or(false),
+    })
+}
+
+fn load_camera(camera: &JsonValue) -> Result<Camera, GltfError> {
+    match get_str(camera, "type") {
+        Some("perspective") => {
+            let perspective = camera
+                .get("perspective")
+                .ok_or_else(|| invalid("a perspective camera has no perspective"))?;
+            let mut loaded_camera = Camera::new_perspective(
+                get_required_f32(perspective, "yfov")?,
+                get_required_f32(perspective, "znear")?,
+                get_f32(perspective, "zfar", INFINITE_CAMERA_Z_FAR)?,
+            );
+            //Without aspect ratio, the one of the rendered images is used
+            if let Some(aspect_ratio) = perspective.get("aspectRatio").and_then(JsonValue::as_f32) {
+                loaded_camera.set_aspect_ratio(aspect_ratio);
+            }
+            Ok(loaded_camera)
+        }
+        Some("orthographic") => {
+            let orthographic = camera
+                .get("orthographic")
+                .ok_or_else(|| invalid("an orthographic camera has no orthographic"))?;
+            //The magnifications are the half sizes of the visible box
+            let x_magnification = get_required_f32(orthographic, "xmag")?;
+            let y_magnification = get_required_f32(orthographic, "ymag")?;
+            let mut loaded_camera = Camera::new_orthographic(
+                2f32 * y_magnification,
+                get_required_f32(orthographic, "znear")?,
+                get_required_f32(orthographic, "zfar")?,
+            );
+            loaded_camera.set_aspect_ratio(x_magnification / y_magnification);
+            Ok(loaded_camera)
+        }
+        _ => Err(invalid("a camera has an invalid type")),
+    }
+}
+
+///Load a light of the KHR_lights_punctual extension
+fn load_light(light: &JsonValue) -> Result<Light, GltfError> {
+    let color = Vec3::from_array(get_floats(light, "color", [1f32; 3])?);
+    let intensity = get_f32(light, "intensity", 1f32)?;
+    let range = light.get("range").and_then(JsonValue::as_f32);
+
+    match get_str(light, "type") {
+        Some("directional") => Ok(Light::Directional { color, intensity }),
+        Some("point") => Ok(Light::Point {
+            color,
+            intensity,
+            range,
+        }),
+        Some("spot") => {
+            let spot = light.get("spot").unwrap_or(&JSON_NULL);
+            Ok(Light::Spot {
+                color,
+                intensity,
+                range,
+                inner_cone_angle: get_f32(spot, "innerConeAngle", 0f32)?,
+                outer_cone_angle: get_f32(spot, "outerConeAngle", FRAC_PI_4)?,
+            })
+        }
+        _ => Err(invalid("a light has an invalid type")),
+    }
+}
+
+///Return the transform of `node`, from its matrix or its translation, rotation and scale
+fn get_node_transform(node: &JsonValue) -> Result<Transform, GltfError> {
+    if node.get("matrix").is_some() {
+        let matrix = mat4_from_cols_array(get_floats(node, "matrix", [0f32; 16])?);
+        //A matrix with a scale of 0 hides the node, whatever its rotation
+        return Ok(Transform::from_matrix(&matrix).unwrap_or(Transform::from_scale(Vec3::ZERO)));
+    }
+
+    let [x, y, z, w] = get_floats(node, "rotation", [0f32, 0f32, 0f32, 1f32])?;
+    Ok(Transform {
+        translation: Vec3::from_array(get_floats(node, "translation", [0f32; 3])?),
+        rotation: Quat::new(x, y, z, w).normalize(),
+        scale: Vec3::from_array(get_floats(node, "scale", [1f32; 3])?),
+    })
+}
+
+fn mat4_from_cols_array(all_components: [f32; 16]) -> Mat4 {
+    let get_column = |column: usize| {
+        Vec4::new(
+            all_components[column * 4],
+            all_components[column * 4 + 1],
+            all_components[column * 4 + 2],
+            all_components[column * 4 + 3],
+        )
+    };
+
+    Mat4::from_cols(get_column(0), get_column(1), get_column(2), get_column(3))
+}
+
+///Return the size (in bytes) of an accessor component of `component_type`
+fn get_component_size(component_type: usize) -> Result<usize, GltfError> {
+    match component_type {
+        //BYTE and UNSIGNED_BYTE
+        5120 | 5121 => Ok(1),
+        //SHORT and UNSIGNED_SHORT
+        5122 | 5123 => Ok(2),
+        //UNSIGNED_INT and FLOAT
+        5125 | 5126 => Ok(4),
+        _ => Err(invalid(format!("invalid componentType {component_type}"))),
+    }
+}
+
+///Read the little endian component `bytes` of `component_type`, whose size is checked
+fn read_component(bytes: &[u8], component_type: usize) -> f64 {
+    match component_type {
+        5120 => i8::from_le_bytes([bytes[0]]) as f64,
+        5121 => bytes[0] as f64,
+        5122 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+        5123 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+        5125 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+        _ => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+    }
+}
+
+///Map an integer `component` of `component_type` to [0, 1] if it's unsigned, else to [-1, 1]
+fn normalize_component(component: f64, component_type: usize) -> f64 {
+    match component_type {
+        5120 => (component / 127f64).max(-1f64),
+        5121 => component / 255f64,
+        5122 => (component / 32767f64).max(-1f64),
+        5123 => component / 65535f64,
+        _ => component,
+    }
+}
+
+///Decode the standard or URL-safe base64 `text`, None if it's invalid
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    let mut bits = 0u32;
+    let mut bit_count = 0;
+
+    for character in text.trim_end_matches('=').bytes() {
+        let value = match character {
+            b'A'..=b'Z' => character - b'A',
+            b'a'..=b'z' => character - b'a' + 26,
+            b'0'..=b'9' => character - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+
+        bits = (bits << 6) | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+
+    Some(bytes)
+}
+
+///Decode the %XX escapes of the URI `text`
+fn decode_percent_encoding(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut decoded_bytes = Vec::with_capacity(bytes.len());
+
+    let mut position = 0;
+    while position < bytes.len() {
+        let escaped_byte_opt = (bytes[position] == b'%')
+            .then(|| bytes.get(position + 1..position + 3))
+            .flatten()
+            .filter(|hex_digits| hex_digits.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex_digits| std::str::from_utf8(hex_digits).ok())
+            .and_then(|hex_digits| u8::from_str_radix(hex_digits, 16).ok());
+
+        match escaped_byte_opt {
+            Some(escaped_byte) => {
+                decoded_bytes.push(escaped_byte);
+                position += 3;
+            }
+            None => {
+                decoded_bytes.push(bytes[position]);
+                position += 1;
+            }
+        }
+    }
+
+    decoded_bytes
+}
+
+fn read_file(file_path: &Path) -> Result<Vec<u8>, GltfError> {
+    fs::read(file_path).map_err(|error| GltfError::Io {
+        file_path: file_path.to_path_buf(),
+        error,
+    })
+}
+
+fn invalid(message: impl Into<String>) -> GltfError {
+    GltfError::Invalid(message.into())
+}
+
+///Return the array member `name` of `object`, empty if it's missing
+fn get_array<'json>(object: &'json JsonValue, name: &str) -> &'json [JsonValue] {
+    object
+        .get(name)
+        .and_then(JsonValue::as_array)
+        .unwrap_or_default()
+}
+
+///Return the element `index` of the array member `name` of `object`
+fn get_element<'json>(
+    object: &'json JsonValue,
+    name: &str,
+    index: usize,
+) -> Result<&'json JsonValue, GltfError> {
+    get_array(object, name)
+        .get(index)
+        .ok_or_else(|| invalid(format!("{name}[{index}] is missing")))
+}
+
+fn get_str<'json>(object: &'json JsonValue, name: &str) -> Option<&'json str> {
+    object.get(name).and_then(JsonValue::as_str)
+}
+
+///Return the name of the glTF object `object`, empty if it hasn't one
+fn get_name(object: &JsonValue) -> String {
+    get_str(object, "name").unwrap_or_default().to_string()
+}
+
+///Return the index or count member `name` of `object`, None if it's missing
+fn get_index(object: &JsonValue, name: &str) -> Result<Option<usize>, GltfError> {
+    object
+        .get(name)
+        .map(|value| {
+            value
+                .as_usize()
+                .ok_or_else(|| invalid(format!("{name} isn't a non-negative integer")))
+        })
+        .transpose()
+}
+
+///Return the number member `name` of `object`, `default` if it's missing
+fn get_f32(object: &JsonValue, name: &str, default: f32) -> Result<f32, GltfError> {
+    match object.get(name) {
+        Some(value) => value
+            .as_f32()
+            .ok_or_else(|| invalid(format!("{name} isn't a number"))),
+        None => Ok(default),
+    }
+}
+
+fn get_required_f32(object: &JsonValue, name: &str) -> Result<f32, GltfError> {
+    let value = object
+        .get(name)
+        .ok_or_else(|| invalid(format!("{name} is missing")))?;
+    value
+        .as_f32()
+        .ok_or_else(|| invalid(format!("{name} isn't a number")))
+}
+
+///Return the member `name` of `object` made of `N` numbers, `default` if it's missing
+fn get_floats<const N: usize>(
+    object: &JsonValue,
+    name: &str,
+    default: [f32; N],
+) -> Result<[f32; N], GltfError> {
+    let Some(value) = object.get(name) else {
+        return Ok(default);
+    };
+
+    let all_values = value
+        .as_array()
+        .filter(|all_values| all_values.len() == N)
+        .ok_or_else(|| invalid(format!("{name} isn't an array of {N} numbers")))?;
+    let mut all_floats = [0f32; N];
+    for (float, value) in all_floats.iter_mut().zip(all_values) {
+        *float = value
+            .as_f32()
+            .ok_or_else(|| invalid(format!("{name} isn't an array of {N} numbers")))?;
+    }
+
+    Ok(all_floats)
+}
@@ -0,0 +1,449 @@
+use std::collections::{HashMap, HashSet};
+
+use glfw::{
+    Action, CursorMode, GamepadAxis, GamepadButton, Glfw, JoystickId, Key, MouseButton, Window,
+    WindowEvent,
+};
+
+///Number of buttons of a GLFW gamepad, from ButtonA to ButtonDpadLeft
+const GAMEPAD_BUTTON_COUNT: usize = 15;
+
+///Number of axes of a GLFW gamepad, from AxisLeftX to AxisRightTrigger
+const GAMEPAD_AXIS_COUNT: usize = 6;
+
+///Number of joysticks handled by GLFW, from Joystick1 to Joystick16
+const JOYSTICK_COUNT: i32 = 16;
+
+///Value above which a gamepad axis, in the direction of a binding, is considered down
+const GAMEPAD_AXIS_THRESHOLD: f32 = 0.5;
+
+///Direction in which a gamepad axis is pushed
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+///Input triggering a named action
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum InputBinding {
+    Key(Key),
+    MouseButton(MouseButton),
+
+    ///The button of any connected gamepad
+    GamepadButton(GamepadButton),
+
+    ///The axis of any connected gamepad, pushed in the direction beyond GAMEPAD_AXIS_THRESHOLD
+    GamepadAxis(GamepadAxis, AxisDirection),
+}
+
+///State of a gamepad, polled once per frame
+#[derive(Clone, Copy, Default)]
+struct GamepadInput {
+    all_axes: [f32; GAMEPAD_AXIS_COUNT],
+    all_buttons_down: [bool; GAMEPAD_BUTTON_COUNT],
+}
+
+impl GamepadInput {
+    fn get_axis(&self, axis: GamepadAxis) -> f32 {
+        self.all_axes[axis as usize]
+    }
+
+    fn is_button_down(&self, button: GamepadButton) -> bool {
+        self.all_buttons_down[button as usize]
+    }
+}
+
+///Keyboard, mouse, scroll and gamepad state of the current frame, built from the GLFW events
+///
+///Named actions are mapped to any number of input bindings
+#[derive(Default)]
+pub struct InputState {
+    all_keys_down: HashSet<Key>,
+    all_keys_pressed: HashSet<Key>,
+    all_keys_released: HashSet<Key>,
+
+    all_mouse_buttons_down: HashSet<MouseButton>,
+    all_mouse_buttons_pressed: HashSet<MouseButton>,
+    all_mouse_buttons_released: HashSet<MouseButton>,
+
+    ///The last position of the cursor, None until the first cursor event (or after the capture changed)
+    mouse_position: Option<(f64, f64)>,
+
+    ///The movement of the cursor during the current frame
+    mouse_delta: (f64, f64),
+
+    ///The scrolling during the current frame
+    scroll_delta: (f64, f64),
+
+    ///If the cursor is hidden and locked to the window
+    is_cursor_captured: bool,
+
+    ///The connected gamepads in the current frame
+    all_gamepads: HashMap<JoystickId, GamepadInput>,
+
+    ///The connected gamepads in the previous frame
+    all_previous_gamepads: HashMap<JoystickId, GamepadInput>,
+
+    all_action_bindings: HashMap<String, Vec<InputBinding>>,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Enable the polling of the GLFW events handled by `handle_event` on `glfw_window`
+    pub fn enable_event_polling(glfw_window: &mut Window) {
+        glfw_window.set_key_polling(true);
+        glfw_window.set_mouse_button_polling(true);
+        glfw_window.set_cursor_pos_polling(true);
+        glfw_window.set_scroll_polling(true);
+        glfw_window.set_focus_polling(true);
+    }
+
+    ///Clear the pressed and released inputs and the deltas of the previous frame
+    ///
+    ///Must be called before polling the events of a new frame
+    pub fn begin_frame(&mut self) {
+        self.all_keys_pressed.clear();
+        self.all_keys_released.clear();
+        self.all_mouse_buttons_pressed.clear();
+        self.all_mouse_buttons_released.clear();
+        self.mouse_delta = (0f64, 0f64);
+        self.scroll_delta = (0f64, 0f64);
+    }
+
+    ///Update the state of the current frame from `event`
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::Key(key, _, action, _) => match action {
+                Action::Press => {
+                    self.all_keys_down.insert(key);
+                    self.all_keys_pressed.insert(key);
+                }
+                Action::Release => {
+                    self.all_keys_down.remove(&key);
+                    self.all_keys_released.insert(key);
+                }
+                //A held key is already down
+                Action::Repeat => {}
+            },
+            WindowEvent::MouseButton(mouse_button, action, _) => match action {
+                Action::Press => {
+                    self.all_mouse_buttons_down.insert(mouse_button);
+                    self.all_mouse_buttons_pressed.insert(mouse_button);
+                }
+                Action::Release => {
+                    self.all_mouse_buttons_down.remove(&mouse_button);
+                    self.all_mouse_buttons_released.insert(mouse_button);
+                }
+                Action::Repeat => {}
+            },
+            WindowEvent::CursorPos(x, y) => {
+                if let Some((last_x, last_y)) = self.mouse_position {
+                    self.mouse_delta.0 += x - last_x;
+                    self.mouse_delta.1 += y - last_y;
+                }
+                self.mouse_position = Some((x, y));
+            }
+            WindowEvent::Scroll(x, y) => {
+                self.scroll_delta.0 += x;
+                self.scroll_delta.1 += y;
+            }
+            //The release events are not received by an unfocused window
+            WindowEvent::Focus(false) => {
+                self.all_keys_released.extend(self.all_keys_down.drain());
+                self.all_mouse_buttons_released
+                    .extend(self.all_mouse_buttons_down.drain());
+            }
+            _ => {}
+        }
+    }
+
+    ///Poll the state of every connected gamepad
+    ///
+    ///Must be called once per frame, after the events are handled
+    pub fn update_gamepads(&mut self, glfw: &Glfw) {
+        self.all_previous_gamepads = std::mem::take(&mut self.all_gamepads);
+
+        for joystick_id in (0..JOYSTICK_COUNT).filter_map(JoystickId::from_i32) {
+            let joystick = glfw.get_joystick(joystick_id);
+            if !joystick.is_gamepad() {
+                continue;
+            }
+            let Some(gamepad_state) = joystick.get_gamepad_state() else {
+                continue;
+            };
+
+            let mut gamepad_input = GamepadInput::default();
+            for (index, axis) in gamepad_input.all_axes.iter_mut().enumerate() {
+                if let Some(gamepad_axis) = GamepadAxis::from_i32(index as i32) {
+                    *axis = gamepad_state.get_axis(gamepad_axis);
+                }
+            }
+            for (index, is_button_down) in gamepad_input.all_buttons_down.iter_mut().enumerate() {
+                if let Some(gamepad_button) = GamepadButton::from_i32(index as i32) {
+                    *is_button_down =
+                        gamepad_state.get_button_state(gamepad_button) == Action::Press;
+                }
+            }
+
+            self.all_gamepads.insert(joystick_id, gamepad_input);
+        }
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.all_keys_down.contains(&key)
+    }
+
+    ///Return if `key` was pressed during the current frame
+    pub fn is_key_pressed(&self, key: Key) -> bool {
+        self.all_keys_pressed.contains(&key)
+    }
+
+    ///Return if `key` was released during the current frame
+    pub fn is_key_released(&self, key: Key) -> bool {
+        self.all_keys_released.contains(&key)
+    }
+
+    pub fn is_mouse_button_down(&self, mouse_button: MouseButton) -> bool {
+        self.all_mouse_buttons_down.contains(&mouse_button)
+    }
+
+    ///Return if `mouse_button` was pressed during the current frame
+    pub fn is_mouse_button_pressed(&self, mouse_button: MouseButton) -> bool {
+        self.all_mouse_buttons_pressed.contains(&mouse_button)
+    }
+
+    ///Return if `mouse_button` was released during the current frame
+    pub fn is_mouse_button_released(&self, mouse_button: MouseButton) -> bool {
+        self.all_mouse_buttons_released.contains(&mouse_button)
+    }
+
+    ///Return the position of the cursor (in screen coordinates, relative to the top left corner of the window)
+    ///
+    /// # Returns
+    ///
+    /// None if the cursor hasn't moved since the window was created or the cursor capture changed
+    pub fn get_mouse_position(&self) -> Option<(f64, f64)> {
+        self.mouse_position
+    }
+
+    ///Return the movement of the cursor during the current frame
+    pub fn get_mouse_delta(&self) -> (f64, f64) {
+        self.mouse_delta
+    }
+
+    ///Return the scrolling during the current frame
+    pub fn get_scroll_delta(&self) -> (f64, f64) {
+        self.scroll_delta
+    }
+
+    pub fn is_cursor_captured(&self) -> bool {
+        self.is_cursor_captured
+    }
+
+    ///Hide the cursor and lock it to `glfw_window`, reporting unaccelerated motion if supported
+    ///
+    ///The mouse delta then keeps growing past the borders of the window, as needed by a first-person camera
+    pub fn set_cursor_captured(&mut self, glfw_window: &mut Window, is_cursor_captured: bool) {
+        glfw_window.set_cursor_mode(if is_cursor_captured {
+            CursorMode::Disabled
+        } else {
+            CursorMode::Normal
+        });
+        if glfw_window.glfw.supports_raw_motion() {
+            glfw_window.set_raw_mouse_motion(is_cursor_captured);
+        }
+
+        //The cursor jumps when its mode changes, which must not be reported as a movement
+        self.mouse_position = None;
+        self.is_cursor_captured = is_cursor_captured;
+    }
+
+    ///Return all the joysticks connected as gamepads in the current frame
+    pub fn get_all_gamepad_ids(&self) -> impl Iterator<Item = JoystickId> + '_ {
+        self.all_gamepads.keys().copied()
+    }
+
+    ///Return the value of `axis` of the gamepad `joystick_id`, in [-1, 1]
+    ///
+    /// # Returns
+    ///
+    /// 0 if the gamepad isn't connected
+    pub fn get_gamepad_axis(&self, joystick_id: JoystickId, axis: GamepadAxis) -> f32 {
+        self.all_gamepads
+            .get(&joystick_id)
+            .map_or(0f32, |gamepad_input| gamepad_input.get_axis(axis))
+    }
+
+    pub fn is_gamepad_button_down(&self, joystick_id: JoystickId, button: GamepadButton) -> bool {
+        is_gamepad_button_down_in(&self.all_gamepads, joystick_id, button)
+    }
+
+    ///Return if `button` of the gamepad `joystick_id` was pressed since the previous frame
+    pub fn is_gamepad_button_pressed(
+        &self,
+        joystick_id: JoystickId,
+        button: GamepadButton,
+    ) -> bool {
+        self.is_gamepad_button_down(joystick_id, button)
+            && !is_gamepad_button_down_in(&self.all_previous_gamepads, joystick_id, button)
+    }
+
+    ///Return if `button` of the gamepad `joystick_id` was released since the previous frame
+    pub fn is_gamepad_button_released(
+        &self,
+        joystick_id: JoystickId,
+        button: GamepadButton,
+    ) -> bool {
+        !self.is_gamepad_button_down(joystick_id, button)
+            && is_gamepad_button_down_in(&self.all_previous_gamepads, joystick_id, button)
+    }
+
+    ///Add `binding` to the inputs triggering the action `action_name`
+    pub fn bind_action(&mut self, action_name: &str, binding: InputBinding) {
+        let all_bindings = self
+            .all_action_bindings
+            .entry(action_name.to_owned())
+            .or_default();
+        if !all_bindings.contains(&binding) {
+            all_bindings.push(binding);
+        }
+    }
+
+    ///Remove all the bindings of the action `action_name`
+    pub fn unbind_action(&mut self, action_name: &str) {
+        self.all_action_bindings.remove(action_name);
+    }
+
+    pub fn get_action_bindings(&self, action_name: &str) -> &[InputBinding] {
+        self.all_action_bindings
+            .get(action_name)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    ///Return if any binding of the action `action_name` is down
+    pub fn is_action_down(&self, action_name: &str) -> bool {
+        self.get_action_bindings(action_name)
+            .iter()
+            .any(|binding| self.is_binding_down(binding))
+    }
+
+    ///Return if any binding of the action `action_name` was pressed during the current frame
+    pub fn is_action_pressed(&self, action_name: &str) -> bool {
+        self.get_action_bindings(action_name)
+            .iter()
+            .any(|binding| self.is_binding_pressed(binding))
+    }
+
+    ///Return if a binding of the action `action_name` was released during the current frame, and none is still down
+    pub fn is_action_released(&self, action_name: &str) -> bool {
+        let all_bindings = self.get_action_bindings(action_name);
+
+        all_bindings
+            .iter()
+            .any(|binding| self.is_binding_released(binding))
+            && !all_bindings
+                .iter()
+                .any(|binding| self.is_binding_down(binding))
+    }
+
+    ///Return the strength of the action `action_name`, in [0, 1]
+    ///
+    ///Buttons are worth 0 or 1, while gamepad axes are worth their value in the direction of their binding
+    pub fn get_action_value(&self, action_name: &str) -> f32 {
+        self.get_action_bindings(action_name)
+            .iter()
+            .map(|binding| match *binding {
+                InputBinding::GamepadAxis(axis, direction) => self
+                    .all_gamepads
+                    .values()
+                    .map(|gamepad_input| {
+                        get_axis_value_in_direction(gamepad_input, axis, direction)
+                    })
+                    .fold(0f32, f32::max),
+                _ => {
+                    if self.is_binding_down(binding) {
+                        1f32
+                    } else {
+                        0f32
+                    }
+                }
+            })
+            .fold(0f32, f32::max)
+    }
+
+    fn is_binding_down(&self, binding: &InputBinding) -> bool {
+        match *binding {
+            InputBinding::Key(key) => self.is_key_down(key),
+            InputBinding::MouseButton(mouse_button) => self.is_mouse_button_down(mouse_button),
+            _ => is_gamepad_binding_down_in(&self.all_gamepads, binding),
+        }
+    }
+
+    fn is_binding_pressed(&self, binding: &InputBinding) -> bool {
+        match *binding {
+            InputBinding::Key(key) => self.is_key_pressed(key),
+            InputBinding::MouseButton(mouse_button) => self.is_mouse_button_pressed(mouse_button),
+            _ => {
+                is_gamepad_binding_down_in(&self.all_gamepads, binding)
+                    && !is_gamepad_binding_down_in(&self.all_previous_gamepads, binding)
+            }
+        }
+    }
+
+    fn is_binding_released(&self, binding: &InputBinding) -> bool {
+        match *binding {
+            InputBinding::Key(key) => self.is_key_released(key),
+            InputBinding::MouseButton(mouse_button) => self.is_mouse_button_released(mouse_button),
+            _ => {
+                !is_gamepad_binding_down_in(&self.all_gamepads, binding)
+                    && is_gamepad_binding_down_in(&self.all_previous_gamepads, binding)
+            }
+        }
+    }
+}
+
+fn is_gamepad_button_down_in(
+    all_gamepads: &HashMap<JoystickId, GamepadInput>,
+    joystick_id: JoystickId,
+    button: GamepadButton,
+) -> bool {
+    all_gamepads
+        .get(&joystick_id)
+        .is_some_and(|gamepad_input| gamepad_input.is_button_down(button))
+}
+
+///Return if the gamepad `binding` is down on any gamepad of `all_gamepads`
+///
+/// # Returns
+///
+/// false if `binding` isn't a gamepad binding
+fn is_gamepad_binding_down_in(
+    all_gamepads: &HashMap<JoystickId, GamepadInput>,
+    binding: &InputBinding,
+) -> bool {
+    all_gamepads.values().any(|gamepad_input| match *binding {
+        InputBinding::GamepadButton(button) => gamepad_input.is_button_down(button),
+        InputBinding::GamepadAxis(axis, direction) => {
+            get_axis_value_in_direction(gamepad_input, axis, direction) > GAMEPAD_AXIS_THRESHOLD
+        }
+        InputBinding::Key(_) | InputBinding::MouseButton(_) => false,
+    })
+}
+
+///Return the value of `axis` of `gamepad_input` in `direction`, in [0, 1]
+fn get_axis_value_in_direction(
+    gamepad_input: &GamepadInput,
+    axis: GamepadAxis,
+    direction: AxisDirection,
+) -> f32 {
+    let value = gamepad_input.get_axis(axis);
+
+    match direction {
+        AxisDirection::Positive => value.max(0f32),
+        AxisDirection::Negative => (-value).max(0f32),
+    }
+}
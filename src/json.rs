@@ -0,0 +1,311 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+///Maximum nesting of the arrays and objects, to fail on hostile documents instead of overflowing the stack
+const MAX_DEPTH: usize = 128;
+
+///Error returned when a JSON document cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    ///The offset (in bytes) in the document where the error was found
+    pub offset: usize,
+    pub message: String,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON at byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+///Value of a JSON document
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+
+    ///The members of an object, the last one is kept if a name is repeated
+    Object(HashMap<String, JsonValue>),
+}
+
+impl JsonValue {
+    ///Return the member `name` of an object, None if it's missing or `&self` isn't an object
+    pub fn get(&self, name: &str) -> Option<&JsonValue> {
+        self.as_object()?.get(name)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f32(&self) -> Option<f32> {
+        self.as_f64().map(|value| value as f32)
+    }
+
+    ///Return the number of `&self` if it's a non-negative integer
+    pub fn as_usize(&self) -> Option<usize> {
+        let value = self.as_f64()?;
+        (value >= 0f64 && value.fract() == 0f64 && value <= usize::MAX as f64)
+            .then_some(value as usize)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(all_values) => Some(all_values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&HashMap<String, JsonValue>> {
+        match self {
+            JsonValue::Object(all_members) => Some(all_members),
+            _ => None,
+        }
+    }
+}
+
+///Parse the JSON document `source`, made of one value surrounded by whitespaces
+pub fn parse_json(source: &str) -> Result<JsonValue, JsonError> {
+    let mut parser = JsonParser {
+        bytes: source.as_bytes(),
+        position: 0,
+    };
+
+    let value = parser.parse_value(0)?;
+    parser.skip_whitespaces();
+    if parser.position < parser.bytes.len() {
+        return Err(parser.error("unexpected data after the value"));
+    }
+
+    Ok(value)
+}
+
+///Recursive descent parser of a JSON document
+struct JsonParser<'source> {
+    bytes: &'source [u8],
+
+    ///The offset of the next byte to parse
+    position: usize,
+}
+
+impl JsonParser<'_> {
+    fn error(&self, message: &str) -> JsonError {
+        JsonError {
+            offset: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.bytes.get(self.position) {
+            self.position += 1;
+        }
+    }
+
+    ///Consume `literal` if the next bytes are equal to it
+    fn consume(&mut self, literal: &str) -> bool {
+        let is_found = self.bytes[self.position..].starts_with(literal.as_bytes());
+        if is_found {
+            self.position += literal.len();
+        }
+        is_found
+    }
+
+    ///Parse the value at the current position, nested in `depth` arrays and objects
+    fn parse_value(&mut self, depth: usize) -> Result<JsonValue, JsonError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("too deeply nested"));
+        }
+
+        self.skip_whitespaces();
+        match self.bytes.get(self.position) {
+            Some(b'{') => self.parse_object(depth),
+            Some(b'[') => self.parse_array(depth),
+            Some(b'"') => Ok(JsonValue::String(self.parse_string()?)),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) if self.consume("true") => Ok(JsonValue::Bool(true)),
+            Some(_) if self.consume("false") => Ok(JsonValue::Bool(false)),
+            Some(_) if self.consume("null") => Ok(JsonValue::Null),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of document")),
+        }
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<JsonValue, JsonError> {
+        //Skip {
+        self.position += 1;
+        let mut all_members = HashMap::new();
+
+        self.skip_whitespaces();
+        if self.consume("}") {
+            return Ok(JsonValue::Object(all_members));
+        }
+
+        loop {
+            self.skip_whitespaces();
+            if self.bytes.get(self.position) != Some(&b'"') {
+                return Err(self.error("expected member name"));
+            }
+            let name = self.parse_string()?;
+
+            self.skip_whitespaces();
+            if !self.consume(":") {
+                return Err(self.error("expected ':' after member name"));
+            }
+            let value = self.parse_value(depth + 1)?;
+            all_members.insert(name, value);
+
+            self.skip_whitespaces();
+            if self.consume("}") {
+                return Ok(JsonValue::Object(all_members));
+            }
+            if !self.consume(",") {
+                return Err(self.error("expected ',' or '}' in object"));
+            }
+        }
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<JsonValue, JsonError> {
+        //Skip [
+        self.position += 1;
+        let mut all_values = Vec::new();
+
+        self.skip_whitespaces();
+        if self.consume("]") {
+            return Ok(JsonValue::Array(all_values));
+        }
+
+        loop {
+            all_values.push(self.parse_value(depth + 1)?);
+
+            self.skip_whitespaces();
+            if self.consume("]") {
+                return Ok(JsonValue::Array(all_values));
+            }
+            if !self.consume(",") {
+                return Err(self.error("expected ',' or ']' in array"));
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, JsonError> {
+        let start = self.position;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') =
+            self.bytes.get(self.position)
+        {
+            self.position += 1;
+        }
+
+        //The characters of a number are ASCII
+        let text = std::str::from_utf8(&self.bytes[start..self.position])
+            .expect("failed to read number characters");
+        //Rust accepts a few forms rejected by JSON, such as a leading +, which aren't checked
+        text.parse::<f64>()
+            .map(JsonValue::Number)
+            .map_err(|_| JsonError {
+                offset: start,
+                message: format!("invalid number {text}"),
+            })
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        //Skip "
+        self.position += 1;
+        let mut string_bytes = Vec::new();
+
+        loop {
+            let Some(&byte) = self.bytes.get(self.position) else {
+                return Err(self.error("unterminated string"));
+            };
+            self.position += 1;
+
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let Some(&escaped_byte) = self.bytes.get(self.position) else {
+                        return Err(self.error("unterminated string"));
+                    };
+                    self.position += 1;
+
+                    let escaped_char = match escaped_byte {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.parse_unicode_escape()?,
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+                    let mut utf8_buffer = [0u8; 4];
+                    string_bytes
+                        .extend_from_slice(escaped_char.encode_utf8(&mut utf8_buffer).as_bytes());
+                }
+                0..0x20 => return Err(self.error("control character in string")),
+                _ => string_bytes.push(byte),
+            }
+        }
+
+        //The source is a str, so the bytes between escapes are valid UTF-8
+        Ok(String::from_utf8(string_bytes).expect("failed to decode string characters"))
+    }
+
+    ///Parse the code point of a \u escape, and the low surrogate following a high surrogate
+    fn parse_unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high_unit = self.parse_hex_unit()?;
+        if !(0xD800..0xDC00).contains(&high_unit) {
+            return char::from_u32(high_unit).ok_or_else(|| self.error("unpaired surrogate"));
+        }
+
+        if !self.consume("\\u") {
+            return Err(self.error("unpaired surrogate"));
+        }
+        let low_unit = self.parse_hex_unit()?;
+        if !(0xDC00..0xE000).contains(&low_unit) {
+            return Err(self.error("unpaired surrogate"));
+        }
+
+        let code_point = 0x10000 + ((high_unit - 0xD800) << 10) + (low_unit - 0xDC00);
+        char::from_u32(code_point).ok_or_else(|| self.error("invalid code point"))
+    }
+
+    ///Parse the 4 hexadecimal digits of a UTF-16 code unit
+    fn parse_hex_unit(&mut self) -> Result<u32, JsonError> {
+        let hex_digits = self
+            .bytes
+            .get(self.position..self.position + 4)
+            .and_then(|hex_digits| std::str::from_utf8(hex_digits).ok())
+            .ok_or_else(|| self.error("truncated unicode escape"))?;
+        let unit = u32::from_str_radix(hex_digits, 16)
+            .map_err(|_| self.error("invalid unicode escape"))?;
+
+        self.position += 4;
+        Ok(unit)
+    }
+}
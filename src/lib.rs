@@ -0,0 +1,19 @@
+pub mod animation;
+pub mod app;
+pub mod camera;
+pub mod camera_controller;
+pub mod ecs;
+pub mod environment;
+pub mod glfw;
+pub mod gltf;
+pub mod input;
+pub mod json;
+pub mod material;
+pub mod math;
+pub mod mesh;
+pub mod obj;
+pub mod render;
+pub mod scene;
+pub mod shadow;
+pub mod vulkan;
+pub mod window;
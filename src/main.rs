@@ -1,14 +1,172 @@
-use crate::{app::Application, glfw::GlfwEntry, vulkan::entry::VulkanEntry};
+use u3d_engine::{
+    app::{Application, MODEL_MESH},
+    ecs::{components::MeshRenderer, schedule::FrameTime, storage::Component, world::World},
+    glfw::GlfwEntry,
+    math::{quaternion::Quat, vector::Vec3},
+    scene::Transform,
+    shadow::ShadowConfig,
+    vulkan::entry::VulkanEntry,
+    window::{DisplayMode, WindowConfig},
+};
 
-mod app;
-mod glfw;
-mod vulkan;
+///Usage printed when the command line arguments are invalid
+const USAGE: &str = concat!(
+    "usage: u3DEngine [--fullscreen | --exclusive-fullscreen] [--vsync] [--hdr]",
+    " [--model <file.obj | file.gltf | file.glb>] [--max-lights <count>]",
+    " [--shadow-cascades <distance,distance,...>] [--environment <file.hdr | file.exr>]"
+);
+
+///Component turning an entity around its Z axis
+struct Spin {
+    ///The angular speed (in radians per second)
+    speed: f32,
+}
+
+impl Component for Spin {}
 
 fn main() {
     let mut glfw_entry = GlfwEntry::init();
     let vulkan_entry = VulkanEntry::init();
 
-    let app = Application::new(&mut glfw_entry, &vulkan_entry, "u3DEngine(Vulkan)");
+    let mut window_config = WindowConfig::new("u3DEngine(Vulkan)");
+    let mut is_hdr_requested = false;
+    let mut model_file_path_opt = None;
+    let mut max_light_count_opt = None;
+    let mut cascade_split_distances_opt = None;
+    let mut environment_file_path_opt = None;
+    let mut all_arguments = std::env::args().skip(1);
+    while let Some(argument) = all_arguments.next() {
+        match argument.as_str() {
+            "--fullscreen" => window_config.display_mode = DisplayMode::BorderlessFullscreen,
+            "--exclusive-fullscreen" => {
+                window_config.display_mode = DisplayMode::ExclusiveFullscreen
+            }
+            "--vsync" => window_config.is_vsync_enabled = true,
+            "--hdr" => is_hdr_requested = true,
+            "--model" => {
+                model_file_path_opt = Some(get_argument_value(&mut all_arguments, &argument))
+            }
+            "--environment" => {
+                environment_file_path_opt = Some(get_argument_value(&mut all_arguments, &argument))
+            }
+            "--max-lights" => {
+                let max_light_count = get_argument_value(&mut all_arguments, &argument);
+                max_light_count_opt = Some(max_light_count.parse().unwrap_or_else(|_| {
+                    exit_with_argument_error(&format!(
+                        "invalid maximum light count \"{max_light_count}\""
+                    ))
+                }))
+            }
+            "--shadow-cascades" => {
+                cascade_split_distances_opt = Some(parse_cascade_split_distances(
+                    &get_argument_value(&mut all_arguments, &argument),
+                ))
+            }
+            _ => exit_with_argument_error(&format!("unknown argument \"{argument}\"")),
+        }
+    }
+
+    let mut app = Application::new(&mut glfw_entry, &vulkan_entry, window_config);
+    if is_hdr_requested {
+        app.request_hdr();
+    }
+    if let Some(model_file_path) = model_file_path_opt {
+        app.set_model_file_path(model_file_path);
+    }
+    if let Some(max_light_count) = max_light_count_opt {
+        app.set_max_light_count(max_light_count);
+    }
+    if let Some(cascade_split_distances) = cascade_split_distances_opt {
+        app.set_shadow_config(ShadowConfig {
+            cascade_split_distances,
+            ..ShadowConfig::default()
+        });
+    }
+    if let Some(environment_file_path) = environment_file_path_opt {
+        app.set_environment_file_path(environment_file_path);
+    }
+
+    //A glTF model is drawn by its own nodes, only the mesh of an OBJ model is drawn by entities
+    if !app.has_gltf_model() {
+        for (translation, speed) in [
+            (Vec3::new(-0.75f32, -1.25f32, 0f32), 1f32),
+            (Vec3::new(0.75f32, -1.25f32, 0f32), -1f32),
+        ] {
+            let world = app.get_world_mut();
+            let model_entity = world.spawn();
+            world.insert(
+                model_entity,
+                Transform {
+                    scale: Vec3::splat(0.5f32),
+                    ..Transform::from_translation(translation)
+                },
+            );
+            world.insert(model_entity, MeshRenderer::new(MODEL_MESH));
+            world.insert(model_entity, Spin { speed });
+        }
+    }
+    app.get_schedule_mut()
+        .add_system("spin_entities", spin_entities);
 
     app.run();
 }
+
+///System turning the entities with a Spin
+fn spin_entities(world: &mut World, frame_time: &FrameTime) {
+    world.for_each_mut::<Transform, (&Spin,), ()>(|_, transform, (spin,)| {
+        transform.rotation =
+            Quat::from_rotation_z(spin.speed * frame_time.delta_time) * transform.rotation;
+    });
+}
+
+///Parse the comma separated view distances of `--shadow-cascades`, exit if they aren't valid cascade split distances
+///
+///The distances must be positive and increasing, with at most a cascade by tile of the default shadow atlas
+fn parse_cascade_split_distances(split_distances: &str) -> Vec<f32> {
+    let cascade_split_distances: Vec<f32> = split_distances
+        .split(',')
+        .map(|split_distance| {
+            split_distance
+                .trim()
+                .parse()
+                .ok()
+                .filter(|split_distance: &f32| split_distance.is_finite() && *split_distance > 0f32)
+                .unwrap_or_else(|| {
+                    exit_with_argument_error(&format!(
+                        "invalid cascade split distance \"{split_distance}\""
+                    ))
+                })
+        })
+        .collect();
+
+    if cascade_split_distances
+        .windows(2)
+        .any(|split_distances| split_distances[0] >= split_distances[1])
+    {
+        exit_with_argument_error(&format!(
+            "the cascade split distances \"{split_distances}\" aren't increasing"
+        ));
+    }
+    let max_cascade_count = ShadowConfig::default().get_tile_count();
+    if cascade_split_distances.len() > max_cascade_count {
+        exit_with_argument_error(&format!(
+            "{} cascades don't fit in the {max_cascade_count} tiles of the shadow atlas",
+            cascade_split_distances.len()
+        ));
+    }
+
+    cascade_split_distances
+}
+
+///Return the value following the flag `argument` in `all_arguments`, exit if there is none
+fn get_argument_value(all_arguments: &mut impl Iterator<Item = String>, argument: &str) -> String {
+    all_arguments
+        .next()
+        .unwrap_or_else(|| exit_with_argument_error(&format!("missing value after \"{argument}\"")))
+}
+
+///Print `message` and the usage, then exit the process with an error status
+fn exit_with_argument_error(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    std::process::exit(2);
+}
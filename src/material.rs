@@ -0,0 +1,191 @@
+use std::{path::PathBuf, sync::Arc};
+
+use crate::{
+    math::{
+        matrix::Mat4,
+        vector::{Vec3, Vec4},
+    },
+    scene::MeshId,
+    vulkan::image::SamplerParameters,
+};
+
+///Encoded PNG, JPEG or KTX2 image of a texture
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TextureImage {
+    ///A file, whose path is relative to the working directory of the application
+    File(PathBuf),
+
+    ///The bytes of a file embedded in the loaded model, shared by the textures using them
+    Memory(Arc<[u8]>),
+}
+
+///Texture of a material, with how it's sampled
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaterialTexture {
+    pub image: TextureImage,
+    pub sampler_parameters: SamplerParameters,
+}
+
+impl MaterialTexture {
+    ///Create a texture of the image file at `file_path`, sampled with the default parameters
+    pub fn from_file(file_path: impl Into<PathBuf>) -> Self {
+        Self {
+            image: TextureImage::File(file_path.into()),
+            sampler_parameters: SamplerParameters::default(),
+        }
+    }
+}
+
+///How the alpha of the base color of a material is used
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AlphaMode {
+    ///The alpha is ignored
+    #[default]
+    Opaque,
+
+    ///The fragments with an alpha lower than `cutoff` are discarded, the others are opaque
+    Mask { cutoff: f32 },
+
+    ///The fragments are blended with the ones behind them
+    Blend,
+}
+
+///Parameters of the metallic-roughness surface model of the engine, shared by the loaded formats
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material {
+    pub name: String,
+
+    ///The linear color of the surface, multiplied with `base_color_texture`
+    pub base_color_factor: Vec4,
+
+    ///The sRGB color of the surface
+    pub base_color_texture: Option<MaterialTexture>,
+
+    ///How metallic the surface is, from 0 (dielectric) to 1 (metal), multiplied with the B channel of `metallic_roughness_texture`
+    pub metallic_factor: f32,
+
+    ///How rough the surface is, from 0 (smooth) to 1 (rough), multiplied with the G channel of `metallic_roughness_texture`
+    pub roughness_factor: f32,
+
+    ///The linear metalness in B and roughness in G
+    pub metallic_roughness_texture: Option<MaterialTexture>,
+
+    ///The tangent space normal map, where +Y points toward +V
+    pub normal_texture: Option<MaterialTexture>,
+
+    ///Multiplied with the X and Y of the normals sampled from `normal_texture`
+    pub normal_scale: f32,
+
+    ///The linear ambient occlusion in R
+    pub occlusion_texture: Option<MaterialTexture>,
+
+    ///How much of the occlusion of `occlusion_texture` is applied, from 0 (none) to 1 (all)
+    pub occlusion_strength: f32,
+
+    ///The linear color emitted by the surface, multiplied with `emissive_texture`
+    pub emissive_factor: Vec3,
+
+    ///The sRGB color emitted by the surface
+    pub emissive_texture: Option<MaterialTexture>,
+
+    pub alpha_mode: AlphaMode,
+
+    ///If the back faces are drawn instead of culled
+    pub is_double_sided: bool,
+}
+
+impl Default for Material {
+    ///Return a white, dielectric and moderately rough material
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            base_color_factor: Vec4::ONE,
+            base_color_texture: None,
+            metallic_factor: 0f32,
+            roughness_factor: 0.5f32,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1f32,
+            occlusion_texture: None,
+            occlusion_strength: 1f32,
+            emissive_factor: Vec3::ZERO,
+            emissive_texture: None,
+            alpha_mode: AlphaMode::Opaque,
+            is_double_sided: false,
+        }
+    }
+}
+
+impl Material {
+    ///Create a default material named `name`
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    ///Return the key of the pipeline drawing `&self`, shared by the materials with the same fixed-function state
+    pub fn get_pipeline_key(&self) -> MaterialPipelineKey {
+        MaterialPipelineKey {
+            is_alpha_blended: self.alpha_mode == AlphaMode::Blend,
+            is_double_sided: self.is_double_sided,
+        }
+    }
+}
+
+///Fixed-function state of the pipeline drawing a material
+///
+///The opaque pipelines are ordered before the blended ones
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaterialPipelineKey {
+    ///If the fragments are blended by their alpha instead of being opaque
+    pub is_alpha_blended: bool,
+
+    ///If the back faces are drawn instead of culled
+    pub is_double_sided: bool,
+}
+
+///Draw of a submesh with a material
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MaterialDraw {
+    ///The key of the pipeline of the material
+    pub pipeline_key: MaterialPipelineKey,
+
+    ///The index of the material in the materials of the draws
+    pub material_index: usize,
+
+    pub mesh: MeshId,
+
+    ///The index of the submesh in the submeshes of `mesh`
+    pub submesh_index: usize,
+
+    ///The transform of the mesh to the world space
+    pub world_matrix: Mat4,
+
+    ///The distance from the camera to the origin of the mesh, used to sort the blended draws
+    pub camera_distance: f32,
+}
+
+///Sort `all_draws` to minimize the state changes between them, then to blend them correctly
+///
+///The opaque draws are grouped by pipeline, then by material, then by mesh.
+///The blended draws follow them, from the farthest to the nearest, whatever their pipeline and material
+pub fn sort_material_draws(all_draws: &mut [MaterialDraw]) {
+    all_draws.sort_by(|draw_1, draw_2| {
+        let is_alpha_blended = draw_1.pipeline_key.is_alpha_blended;
+        if is_alpha_blended != draw_2.pipeline_key.is_alpha_blended {
+            return is_alpha_blended.cmp(&draw_2.pipeline_key.is_alpha_blended);
+        }
+
+        if is_alpha_blended {
+            draw_2.camera_distance.total_cmp(&draw_1.camera_distance)
+        } else {
+            (draw_1.pipeline_key, draw_1.material_index, draw_1.mesh.0).cmp(&(
+                draw_2.pipeline_key,
+                draw_2.material_index,
+                draw_2.mesh.0,
+            ))
+        }
+    });
+}
@@ -0,0 +1,539 @@
+use std::ops::{Mul, MulAssign};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::math::{
+    quaternion::Quat,
+    vector::{Vec3, Vec4},
+};
+
+///3x3 column-major matrix of f32, laid out as a GLSL `mat3` without padding
+///
+///In a std140 or std430 block, each column of a `mat3` is aligned on 16 bytes: it can be uploaded as a Mat4 with `Mat4::from_mat3`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Mat3 {
+    pub x_axis: Vec3,
+    pub y_axis: Vec3,
+    pub z_axis: Vec3,
+}
+
+///4x4 column-major matrix of f32, laid out as a GLSL `mat4`
+///
+///The projections follow the conventions of Vulkan: the Y axis of the clip space points down,
+///and the depth of the visible points is in [0, 1].
+///The view space is right-handed: the camera looks toward -Z, with +Y up
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Mat4 {
+    pub x_axis: Vec4,
+    pub y_axis: Vec4,
+    pub z_axis: Vec4,
+    pub w_axis: Vec4,
+}
+
+impl Default for Mat3 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mat3 {
+    pub const IDENTITY: Self = Self::from_cols(Vec3::X, Vec3::Y, Vec3::Z);
+    pub const ZERO: Self = Self::from_cols(Vec3::ZERO, Vec3::ZERO, Vec3::ZERO);
+
+    pub const fn from_cols(x_axis: Vec3, y_axis: Vec3, z_axis: Vec3) -> Self {
+        Self {
+            x_axis,
+            y_axis,
+            z_axis,
+        }
+    }
+
+    ///Return the matrix scaling each axis by the component of `scale`
+    pub fn from_scale(scale: Vec3) -> Self {
+        Self::from_cols(Vec3::X * scale.x, Vec3::Y * scale.y, Vec3::Z * scale.z)
+    }
+
+    ///Return the rotation matrix of `rotation`
+    pub fn from_quat(rotation: Quat) -> Self {
+        rotation.to_rotation_matrix()
+    }
+
+    ///Return the upper-left 3x3 part of `matrix`, without its translation
+    pub fn from_mat4(matrix: &Mat4) -> Self {
+        Self::from_cols(
+            matrix.x_axis.truncate(),
+            matrix.y_axis.truncate(),
+            matrix.z_axis.truncate(),
+        )
+    }
+
+    pub fn get_row(&self, index: usize) -> Vec3 {
+        Vec3::new(self.x_axis[index], self.y_axis[index], self.z_axis[index])
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_cols(self.get_row(0), self.get_row(1), self.get_row(2))
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.x_axis.dot(self.y_axis.cross(self.z_axis))
+    }
+
+    ///Return the inverse of `&self`, None if `&self` isn't invertible
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant == 0f32 || !determinant.is_finite() {
+            return None;
+        }
+
+        //The rows of the inverse are the cross products of the columns, divided by the determinant
+        let inverse_transpose = Self::from_cols(
+            self.y_axis.cross(self.z_axis),
+            self.z_axis.cross(self.x_axis),
+            self.x_axis.cross(self.y_axis),
+        );
+
+        Some(inverse_transpose.transpose() * (1f32 / determinant))
+    }
+
+    ///Return if each element of `&self` differs from the one of `other` by at most `epsilon`
+    pub fn abs_diff_eq(&self, other: &Self, epsilon: f32) -> bool {
+        self.x_axis.abs_diff_eq(other.x_axis, epsilon)
+            && self.y_axis.abs_diff_eq(other.y_axis, epsilon)
+            && self.z_axis.abs_diff_eq(other.z_axis, epsilon)
+    }
+}
+
+impl Mat4 {
+    pub const IDENTITY: Self = Self::from_cols(Vec4::X, Vec4::Y, Vec4::Z, Vec4::W);
+    pub const ZERO: Self = Self::from_cols(Vec4::ZERO, Vec4::ZERO, Vec4::ZERO, Vec4::ZERO);
+
+    pub const fn from_cols(x_axis: Vec4, y_axis: Vec4, z_axis: Vec4, w_axis: Vec4) -> Self {
+        Self {
+            x_axis,
+            y_axis,
+            z_axis,
+            w_axis,
+        }
+    }
+
+    ///Return the matrix transforming as `matrix`, without translation
+    pub fn from_mat3(matrix: &Mat3) -> Self {
+        Self::from_cols(
+            matrix.x_axis.extend(0f32),
+            matrix.y_axis.extend(0f32),
+            matrix.z_axis.extend(0f32),
+            Vec4::W,
+        )
+    }
+
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self::from_cols(Vec4::X, Vec4::Y, Vec4::Z, translation.extend(1f32))
+    }
+
+    ///Return the matrix scaling each axis by the component of `scale`
+    pub fn from_scale(scale: Vec3) -> Self {
+        Self::from_mat3(&Mat3::from_scale(scale))
+    }
+
+    ///Return the rotation matrix of `rotation`
+    pub fn from_quat(rotation: Quat) -> Self {
+        Self::from_mat3(&Mat3::from_quat(rotation))
+    }
+
+    ///Return the matrix scaling by `scale`, then rotating by `rotation`, then translating by `translation`
+    pub fn from_scale_rotation_translation(scale: Vec3, rotation: Quat, translation: Vec3) -> Self {
+        let rotation_matrix = Mat3::from_quat(rotation);
+
+        Self::from_cols(
+            (rotation_matrix.x_axis * scale.x).extend(0f32),
+            (rotation_matrix.y_axis * scale.y).extend(0f32),
+            (rotation_matrix.z_axis * scale.z).extend(0f32),
+            translation.extend(1f32),
+        )
+    }
+
+    ///Decompose `&self` in the scale, the rotation and the translation composed by `from_scale_rotation_translation`
+    ///
+    ///A negative determinant is represented by a negative X scale.
+    ///`&self` must be affine, without shear
+    ///
+    /// # Returns
+    ///
+    /// None if a scale is 0, because the rotation is then undefined
+    pub fn to_scale_rotation_translation(&self) -> Option<(Vec3, Quat, Vec3)> {
+        let matrix = Mat3::from_mat4(self);
+        let determinant_sign = matrix.determinant().signum();
+
+        let scale = Vec3::new(
+            matrix.x_axis.length() * determinant_sign,
+            matrix.y_axis.length(),
+            matrix.z_axis.length(),
+        );
+        if scale.x == 0f32 || scale.y == 0f32 || scale.z == 0f32 || !scale.is_finite() {
+            return None;
+        }
+
+        let rotation_matrix = Mat3::from_cols(
+            matrix.x_axis / scale.x,
+            matrix.y_axis / scale.y,
+            matrix.z_axis / scale.z,
+        );
+        let rotation = Quat::from_rotation_matrix(&rotation_matrix).normalize();
+
+        Some((scale, rotation, self.w_axis.truncate()))
+    }
+
+    ///Return the view matrix of a camera at `eye` looking at `target`, with `up` pointing up on the screen
+    ///
+    ///`up` must not be parallel to the direction from `eye` to `target`
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        Self::look_to(eye, target - eye, up)
+    }
+
+    ///Return the view matrix of a camera at `eye` looking toward `direction`, with `up` pointing up on the screen
+    ///
+    ///`up` must not be parallel to `direction`
+    pub fn look_to(eye: Vec3, direction: Vec3, up: Vec3) -> Self {
+        let forward = direction.normalize();
+        let right = forward.cross(up).normalize();
+        let up = right.cross(forward);
+
+        Self::from_cols(
+            Vec4::new(right.x, up.x, -forward.x, 0f32),
+            Vec4::new(right.y, up.y, -forward.y, 0f32),
+            Vec4::new(right.z, up.z, -forward.z, 0f32),
+            Vec4::new(-right.dot(eye), -up.dot(eye), forward.dot(eye), 1f32),
+        )
+    }
+
+    ///Return the perspective projection with a vertical field of view of `fov_y` (in radians) and an `aspect_ratio` (width / height)
+    ///
+    ///The points at a distance of `z_near` have a depth of 0, and the ones at a distance of `z_far` a depth of 1
+    pub fn perspective(fov_y: f32, aspect_ratio: f32, z_near: f32, z_far: f32) -> Self {
+        let focal_length = 1f32 / (fov_y * 0.5f32).tan();
+        let depth_scale = z_far / (z_near - z_far);
+
+        Self::from_cols(
+            Vec4::new(focal_length / aspect_ratio, 0f32, 0f32, 0f32),
+            Vec4::new(0f32, -focal_length, 0f32, 0f32),
+            Vec4::new(0f32, 0f32, depth_scale, -1f32),
+            Vec4::new(0f32, 0f32, z_near * depth_scale, 0f32),
+        )
+    }
+
+    ///Return the perspective projection with a vertical field of view of `fov_y` (in radians) and an `aspect_ratio` (width / height)
+    ///
+    ///The points at a distance of `z_near` have a depth of 1, and the ones at a distance of `z_far` a depth of 0.
+    ///The precision of the floating-point depth is then spread more evenly, it must be tested with GREATER
+    pub fn perspective_reverse_z(fov_y: f32, aspect_ratio: f32, z_near: f32, z_far: f32) -> Self {
+        let focal_length = 1f32 / (fov_y * 0.5f32).tan();
+        let depth_scale = z_near / (z_far - z_near);
+
+        Self::from_cols(
+            Vec4::new(focal_length / aspect_ratio, 0f32, 0f32, 0f32),
+            Vec4::new(0f32, -focal_length, 0f32, 0f32),
+            Vec4::new(0f32, 0f32, depth_scale, -1f32),
+            Vec4::new(0f32, 0f32, z_far * depth_scale, 0f32),
+        )
+    }
+
+    ///Return the reverse-Z perspective projection without far plane
+    ///
+    ///The points at a distance of `z_near` have a depth of 1, which tends toward 0 at an infinite distance
+    pub fn perspective_infinite_reverse_z(fov_y: f32, aspect_ratio: f32, z_near: f32) -> Self {
+        let focal_length = 1f32 / (fov_y * 0.5f32).tan();
+
+        Self::from_cols(
+            Vec4::new(focal_length / aspect_ratio, 0f32, 0f32, 0f32),
+            Vec4::new(0f32, -focal_length, 0f32, 0f32),
+            Vec4::new(0f32, 0f32, 0f32, -1f32),
+            Vec4::new(0f32, 0f32, z_near, 0f32),
+        )
+    }
+
+    ///Return the orthographic projection of the box from `left` to `right`, from `bottom` to `top` (in the view space),
+    ///and from a distance of `z_near` (depth of 0) to `z_far` (depth of 1)
+    pub fn orthographic(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        z_near: f32,
+        z_far: f32,
+    ) -> Self {
+        let inverse_depth = 1f32 / (z_far - z_near);
+
+        Self::orthographic_with_depth(
+            (left, right, bottom, top),
+            -inverse_depth,
+            -z_near * inverse_depth,
+        )
+    }
+
+    ///Return the orthographic projection of the box from `left` to `right`, from `bottom` to `top` (in the view space),
+    ///and from a distance of `z_near` (depth of 1) to `z_far` (depth of 0)
+    pub fn orthographic_reverse_z(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        z_near: f32,
+        z_far: f32,
+    ) -> Self {
+        let inverse_depth = 1f32 / (z_far - z_near);
+
+        Self::orthographic_with_depth(
+            (left, right, bottom, top),
+            inverse_depth,
+            z_far * inverse_depth,
+        )
+    }
+
+    ///Return the orthographic projection of the box `(left, right, bottom, top)`,
+    ///whose depth is the Z coordinate in the view space multiplied by `depth_scale`, plus `depth_offset`
+    fn orthographic_with_depth(
+        (left, right, bottom, top): (f32, f32, f32, f32),
+        depth_scale: f32,
+        depth_offset: f32,
+    ) -> Self {
+        let inverse_width = 1f32 / (right - left);
+        let inverse_height = 1f32 / (top - bottom);
+
+        Self::from_cols(
+            Vec4::new(2f32 * inverse_width, 0f32, 0f32, 0f32),
+            Vec4::new(0f32, -2f32 * inverse_height, 0f32, 0f32),
+            Vec4::new(0f32, 0f32, depth_scale, 0f32),
+            Vec4::new(
+                -(right + left) * inverse_width,
+                (top + bottom) * inverse_height,
+                depth_offset,
+                1f32,
+            ),
+        )
+    }
+
+    pub fn get_row(&self, index: usize) -> Vec4 {
+        Vec4::new(
+            self.x_axis[index],
+            self.y_axis[index],
+            self.z_axis[index],
+            self.w_axis[index],
+        )
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_cols(
+            self.get_row(0),
+            self.get_row(1),
+            self.get_row(2),
+            self.get_row(3),
+        )
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let [a, b, c, d] = self.to_cols_array_2d();
+
+        //Determinants of the 2x2 sub-matrices of the 2 last columns
+        let s0 = c[2] * d[3] - d[2] * c[3];
+        let s1 = c[1] * d[3] - d[1] * c[3];
+        let s2 = c[1] * d[2] - d[1] * c[2];
+        let s3 = c[0] * d[3] - d[0] * c[3];
+        let s4 = c[0] * d[2] - d[0] * c[2];
+        let s5 = c[0] * d[1] - d[0] * c[1];
+
+        let cofactor_0 = b[1] * s0 - b[2] * s1 + b[3] * s2;
+        let cofactor_1 = -(b[0] * s0 - b[2] * s3 + b[3] * s4);
+        let cofactor_2 = b[0] * s1 - b[1] * s3 + b[3] * s5;
+        let cofactor_3 = -(b[0] * s2 - b[1] * s4 + b[2] * s5);
+
+        a[0] * cofactor_0 + a[1] * cofactor_1 + a[2] * cofactor_2 + a[3] * cofactor_3
+    }
+
+    ///Return the inverse of `&self`, None if `&self` isn't invertible
+    pub fn inverse(&self) -> Option<Self> {
+        let [a, b, c, d] = self.to_cols_array_2d();
+
+        //Determinants of the 2x2 sub-matrices of the 2 first and the 2 last columns
+        let s0 = a[0] * b[1] - b[0] * a[1];
+        let s1 = a[0] * b[2] - b[0] * a[2];
+        let s2 = a[0] * b[3] - b[0] * a[3];
+        let s3 = a[1] * b[2] - b[1] * a[2];
+        let s4 = a[1] * b[3] - b[1] * a[3];
+        let s5 = a[2] * b[3] - b[2] * a[3];
+
+        let c5 = c[2] * d[3] - d[2] * c[3];
+        let c4 = c[1] * d[3] - d[1] * c[3];
+        let c3 = c[1] * d[2] - d[1] * c[2];
+        let c2 = c[0] * d[3] - d[0] * c[3];
+        let c1 = c[0] * d[2] - d[0] * c[2];
+        let c0 = c[0] * d[1] - d[0] * c[1];
+
+        let determinant = s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0;
+        if determinant == 0f32 || !determinant.is_finite() {
+            return None;
+        }
+        let inverse_determinant = 1f32 / determinant;
+
+        let inverse = Self::from_cols(
+            Vec4::new(
+                b[1] * c5 - b[2] * c4 + b[3] * c3,
+                -a[1] * c5 + a[2] * c4 - a[3] * c3,
+                d[1] * s5 - d[2] * s4 + d[3] * s3,
+                -c[1] * s5 + c[2] * s4 - c[3] * s3,
+            ),
+            Vec4::new(
+                -b[0] * c5 + b[2] * c2 - b[3] * c1,
+                a[0] * c5 - a[2] * c2 + a[3] * c1,
+                -d[0] * s5 + d[2] * s2 - d[3] * s1,
+                c[0] * s5 - c[2] * s2 + c[3] * s1,
+            ),
+            Vec4::new(
+                b[0] * c4 - b[1] * c2 + b[3] * c0,
+                -a[0] * c4 + a[1] * c2 - a[3] * c0,
+                d[0] * s4 - d[1] * s2 + d[3] * s0,
+                -c[0] * s4 + c[1] * s2 - c[3] * s0,
+            ),
+            Vec4::new(
+                -b[0] * c3 + b[1] * c1 - b[2] * c0,
+                a[0] * c3 - a[1] * c1 + a[2] * c0,
+                -d[0] * s3 + d[1] * s1 - d[2] * s0,
+                c[0] * s3 - c[1] * s1 + c[2] * s0,
+            ),
+        );
+
+        Some(inverse * inverse_determinant)
+    }
+
+    ///Return the matrix transforming the normals of the surfaces transformed by `&self`,
+    ///which is the inverse transpose of its upper-left 3x3 part
+    ///
+    ///The transformed normals must be normalized again if `&self` scales
+    ///
+    /// # Returns
+    ///
+    /// None if `&self` isn't invertible
+    pub fn to_normal_matrix(&self) -> Option<Mat3> {
+        Mat3::from_mat4(self)
+            .inverse()
+            .map(|inverse| inverse.transpose())
+    }
+
+    ///Return the transformation of the point `point`, including the translation and the perspective division
+    pub fn transform_point3(&self, point: Vec3) -> Vec3 {
+        let transformed_point = *self * point.extend(1f32);
+
+        transformed_point.truncate() / transformed_point.w
+    }
+
+    ///Return the transformation of the direction `vector`, without the translation
+    pub fn transform_vector3(&self, vector: Vec3) -> Vec3 {
+        (*self * vector.extend(0f32)).truncate()
+    }
+
+    pub fn to_cols_array_2d(&self) -> [[f32; 4]; 4] {
+        [
+            self.x_axis.to_array(),
+            self.y_axis.to_array(),
+            self.z_axis.to_array(),
+            self.w_axis.to_array(),
+        ]
+    }
+
+    ///Return if each element of `&self` differs from the one of `other` by at most `epsilon`
+    pub fn abs_diff_eq(&self, other: &Self, epsilon: f32) -> bool {
+        self.x_axis.abs_diff_eq(other.x_axis, epsilon)
+            && self.y_axis.abs_diff_eq(other.y_axis, epsilon)
+            && self.z_axis.abs_diff_eq(other.z_axis, epsilon)
+            && self.w_axis.abs_diff_eq(other.w_axis, epsilon)
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::from_cols(
+            self * other.x_axis,
+            self * other.y_axis,
+            self * other.z_axis,
+        )
+    }
+}
+
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    fn mul(self, vector: Vec3) -> Vec3 {
+        self.x_axis * vector.x + self.y_axis * vector.y + self.z_axis * vector.z
+    }
+}
+
+impl Mul<f32> for Mat3 {
+    type Output = Self;
+
+    fn mul(self, scalar: f32) -> Self {
+        Self::from_cols(
+            self.x_axis * scalar,
+            self.y_axis * scalar,
+            self.z_axis * scalar,
+        )
+    }
+}
+
+impl MulAssign for Mat3 {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::from_cols(
+            self * other.x_axis,
+            self * other.y_axis,
+            self * other.z_axis,
+            self * other.w_axis,
+        )
+    }
+}
+
+impl Mul<Vec4> for Mat4 {
+    type Output = Vec4;
+
+    fn mul(self, vector: Vec4) -> Vec4 {
+        self.x_axis * vector.x
+            + self.y_axis * vector.y
+            + self.z_axis * vector.z
+            + self.w_axis * vector.w
+    }
+}
+
+impl Mul<f32> for Mat4 {
+    type Output = Self;
+
+    fn mul(self, scalar: f32) -> Self {
+        Self::from_cols(
+            self.x_axis * scalar,
+            self.y_axis * scalar,
+            self.z_axis * scalar,
+            self.w_axis * scalar,
+        )
+    }
+}
+
+impl MulAssign for Mat4 {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
@@ -0,0 +1,262 @@
+use std::ops::{Mul, MulAssign, Neg};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::math::{matrix::Mat3, vector::Vec3};
+
+///Quaternion of f32, representing a rotation when its length is 1
+///
+///`w` is the real part, laid out last as in a GLSL `vec4`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Quat {
+    ///The rotation by an angle of 0
+    pub const IDENTITY: Self = Self::new(0f32, 0f32, 0f32, 1f32);
+
+    pub const fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    ///Return the rotation by `angle` (in radians) around `axis`, which must have a length of 1
+    ///
+    ///The rotation is counterclockwise when `axis` points toward the viewer
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let (sin, cos) = (angle * 0.5f32).sin_cos();
+        let vector = axis * sin;
+
+        Self::new(vector.x, vector.y, vector.z, cos)
+    }
+
+    pub fn from_rotation_x(angle: f32) -> Self {
+        Self::from_axis_angle(Vec3::X, angle)
+    }
+
+    pub fn from_rotation_y(angle: f32) -> Self {
+        Self::from_axis_angle(Vec3::Y, angle)
+    }
+
+    pub fn from_rotation_z(angle: f32) -> Self {
+        Self::from_axis_angle(Vec3::Z, angle)
+    }
+
+    ///Return the rotation of the rotation matrix `matrix`, which must be orthonormal with a determinant of 1
+    pub fn from_rotation_matrix(matrix: &Mat3) -> Self {
+        let (m00, m01, m02) = (matrix.x_axis.x, matrix.x_axis.y, matrix.x_axis.z);
+        let (m10, m11, m12) = (matrix.y_axis.x, matrix.y_axis.y, matrix.y_axis.z);
+        let (m20, m21, m22) = (matrix.z_axis.x, matrix.z_axis.y, matrix.z_axis.z);
+
+        //The largest component is computed from the diagonal first, to avoid dividing by a value close to 0
+        if m22 <= 0f32 {
+            let difference_10 = m11 - m00;
+            let one_minus_m22 = 1f32 - m22;
+            if difference_10 <= 0f32 {
+                let four_x_squared = one_minus_m22 - difference_10;
+                let inverse_four_x = 0.5f32 / four_x_squared.sqrt();
+                Self::new(
+                    four_x_squared * inverse_four_x,
+                    (m01 + m10) * inverse_four_x,
+                    (m02 + m20) * inverse_four_x,
+                    (m12 - m21) * inverse_four_x,
+                )
+            } else {
+                let four_y_squared = one_minus_m22 + difference_10;
+                let inverse_four_y = 0.5f32 / four_y_squared.sqrt();
+                Self::new(
+                    (m01 + m10) * inverse_four_y,
+                    four_y_squared * inverse_four_y,
+                    (m12 + m21) * inverse_four_y,
+                    (m20 - m02) * inverse_four_y,
+                )
+            }
+        } else {
+            let sum_10 = m11 + m00;
+            let one_plus_m22 = 1f32 + m22;
+            if sum_10 <= 0f32 {
+                let four_z_squared = one_plus_m22 - sum_10;
+                let inverse_four_z = 0.5f32 / four_z_squared.sqrt();
+                Self::new(
+                    (m02 + m20) * inverse_four_z,
+                    (m12 + m21) * inverse_four_z,
+                    four_z_squared * inverse_four_z,
+                    (m01 - m10) * inverse_four_z,
+                )
+            } else {
+                let four_w_squared = one_plus_m22 + sum_10;
+                let inverse_four_w = 0.5f32 / four_w_squared.sqrt();
+                Self::new(
+                    (m12 - m21) * inverse_four_w,
+                    (m20 - m02) * inverse_four_w,
+                    (m01 - m10) * inverse_four_w,
+                    four_w_squared * inverse_four_w,
+                )
+            }
+        }
+    }
+
+    ///Return the rotation matrix of `&self`, which must have a length of 1
+    pub fn to_rotation_matrix(self) -> Mat3 {
+        let (x2, y2, z2) = (self.x + self.x, self.y + self.y, self.z + self.z);
+        let (xx, xy, xz) = (self.x * x2, self.x * y2, self.x * z2);
+        let (yy, yz, zz) = (self.y * y2, self.y * z2, self.z * z2);
+        let (wx, wy, wz) = (self.w * x2, self.w * y2, self.w * z2);
+
+        Mat3::from_cols(
+            Vec3::new(1f32 - (yy + zz), xy + wz, xz - wy),
+            Vec3::new(xy - wz, 1f32 - (xx + zz), yz + wx),
+            Vec3::new(xz + wy, yz - wx, 1f32 - (xx + yy)),
+        )
+    }
+
+    ///Return the axis (with a length of 1) and the angle (in radians, in [0, 2π]) of the rotation of `&self`
+    ///
+    /// # Returns
+    ///
+    /// The X axis if the angle is 0
+    pub fn to_axis_angle(self) -> (Vec3, f32) {
+        let sin_half_angle = self.get_vector().length();
+        let angle = 2f32 * sin_half_angle.atan2(self.w);
+
+        match (self.get_vector() / sin_half_angle).try_normalize() {
+            Some(axis) => (axis, angle),
+            None => (Vec3::X, 0f32),
+        }
+    }
+
+    ///Return the imaginary part of `&self`
+    pub fn get_vector(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    pub fn dot(self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(self) -> Self {
+        let inverse_length = 1f32 / self.length();
+
+        Self::new(
+            self.x * inverse_length,
+            self.y * inverse_length,
+            self.z * inverse_length,
+            self.w * inverse_length,
+        )
+    }
+
+    ///Return the quaternion rotating in the opposite direction, which is the inverse of a quaternion with a length of 1
+    pub fn conjugate(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    pub fn inverse(self) -> Self {
+        let conjugate = self.conjugate();
+        let inverse_length_squared = 1f32 / self.dot(self);
+
+        Self::new(
+            conjugate.x * inverse_length_squared,
+            conjugate.y * inverse_length_squared,
+            conjugate.z * inverse_length_squared,
+            conjugate.w * inverse_length_squared,
+        )
+    }
+
+    ///Return the spherical linear interpolation from `&self` (`t` = 0) to `other` (`t` = 1), along the shortest path
+    pub fn slerp(self, other: Self, t: f32) -> Self {
+        let mut cos_angle = self.dot(other);
+        //q and -q are the same rotation, the closest one is interpolated
+        let other = if cos_angle < 0f32 {
+            cos_angle = -cos_angle;
+            -other
+        } else {
+            other
+        };
+
+        //Close rotations are interpolated linearly, since the sine of their angle is close to 0
+        let (self_weight, other_weight) = if cos_angle > 0.9995f32 {
+            (1f32 - t, t)
+        } else {
+            let angle = cos_angle.acos();
+            let sin_angle = angle.sin();
+            (
+                ((1f32 - t) * angle).sin() / sin_angle,
+                (t * angle).sin() / sin_angle,
+            )
+        };
+
+        Self::new(
+            self.x * self_weight + other.x * other_weight,
+            self.y * self_weight + other.y * other_weight,
+            self.z * self_weight + other.z * other_weight,
+            self.w * self_weight + other.w * other_weight,
+        )
+        .normalize()
+    }
+
+    ///Return if `&self` and `other` represent the same rotation, with components differing by at most `epsilon`
+    pub fn abs_diff_eq_rotation(self, other: Self, epsilon: f32) -> bool {
+        let is_close = |other: Self| {
+            (self.x - other.x).abs() <= epsilon
+                && (self.y - other.y).abs() <= epsilon
+                && (self.z - other.z).abs() <= epsilon
+                && (self.w - other.w).abs() <= epsilon
+        };
+
+        is_close(other) || is_close(-other)
+    }
+}
+
+impl Neg for Quat {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, -self.w)
+    }
+}
+
+///Hamilton product: the rotation of `other` followed by the rotation of `self`
+impl Mul for Quat {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+        )
+    }
+}
+
+impl MulAssign for Quat {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+///Rotation of a vector
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+
+    fn mul(self, vector: Vec3) -> Vec3 {
+        let quaternion_vector = self.get_vector();
+        let t = 2f32 * quaternion_vector.cross(vector);
+
+        vector + self.w * t + quaternion_vector.cross(t)
+    }
+}
@@ -0,0 +1,270 @@
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+use bytemuck::{Pod, Zeroable};
+
+///2D vector of f32, laid out as a GLSL `vec2`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+///3D vector of f32, laid out as a GLSL `vec3`
+///
+///In a std140 or std430 block, a `vec3` is aligned on 16 bytes: it must be followed by a f32 or padded
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+///4D vector of f32, laid out as a GLSL `vec4`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+///Implement the component-wise operations shared by all the vector types
+macro_rules! impl_vector {
+    ($vector:ident, $dimension:literal, $($component:ident),+) => {
+        impl $vector {
+            pub const ZERO: Self = Self { $($component: 0f32),+ };
+            pub const ONE: Self = Self { $($component: 1f32),+ };
+
+            pub const fn new($($component: f32),+) -> Self {
+                Self { $($component),+ }
+            }
+
+            ///Return a vector whose components are all `value`
+            pub const fn splat(value: f32) -> Self {
+                Self { $($component: value),+ }
+            }
+
+            pub fn from_array(array: [f32; $dimension]) -> Self {
+                let [$($component),+] = array;
+                Self { $($component),+ }
+            }
+
+            pub fn to_array(self) -> [f32; $dimension] {
+                [$(self.$component),+]
+            }
+
+            pub fn dot(self, other: Self) -> f32 {
+                0f32 $(+ self.$component * other.$component)+
+            }
+
+            pub fn length_squared(self) -> f32 {
+                self.dot(self)
+            }
+
+            pub fn length(self) -> f32 {
+                self.length_squared().sqrt()
+            }
+
+            pub fn distance(self, other: Self) -> f32 {
+                (other - self).length()
+            }
+
+            ///Return `&self` scaled to a length of 1
+            ///
+            ///The components are NaN if `&self` has a length of 0
+            pub fn normalize(self) -> Self {
+                self / self.length()
+            }
+
+            ///Return `&self` scaled to a length of 1, None if its length is too close to 0
+            pub fn try_normalize(self) -> Option<Self> {
+                let length = self.length();
+                (length > f32::EPSILON && length.is_finite()).then(|| self / length)
+            }
+
+            ///Return the linear interpolation from `&self` (`t` = 0) to `other` (`t` = 1)
+            pub fn lerp(self, other: Self, t: f32) -> Self {
+                self + (other - self) * t
+            }
+
+            pub fn min(self, other: Self) -> Self {
+                Self { $($component: self.$component.min(other.$component)),+ }
+            }
+
+            pub fn max(self, other: Self) -> Self {
+                Self { $($component: self.$component.max(other.$component)),+ }
+            }
+
+            pub fn abs(self) -> Self {
+                Self { $($component: self.$component.abs()),+ }
+            }
+
+            ///Return if each component of `&self` differs from the one of `other` by at most `epsilon`
+            pub fn abs_diff_eq(self, other: Self, epsilon: f32) -> bool {
+                true $(&& (self.$component - other.$component).abs() <= epsilon)+
+            }
+
+            pub fn is_finite(self) -> bool {
+                true $(&& self.$component.is_finite())+
+            }
+        }
+
+        impl Add for $vector {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self { $($component: self.$component + other.$component),+ }
+            }
+        }
+
+        impl Sub for $vector {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self { $($component: self.$component - other.$component),+ }
+            }
+        }
+
+        ///Component-wise product
+        impl Mul for $vector {
+            type Output = Self;
+
+            fn mul(self, other: Self) -> Self {
+                Self { $($component: self.$component * other.$component),+ }
+            }
+        }
+
+        impl Mul<f32> for $vector {
+            type Output = Self;
+
+            fn mul(self, scalar: f32) -> Self {
+                Self { $($component: self.$component * scalar),+ }
+            }
+        }
+
+        impl Mul<$vector> for f32 {
+            type Output = $vector;
+
+            fn mul(self, vector: $vector) -> $vector {
+                vector * self
+            }
+        }
+
+        impl Div<f32> for $vector {
+            type Output = Self;
+
+            fn div(self, scalar: f32) -> Self {
+                Self { $($component: self.$component / scalar),+ }
+            }
+        }
+
+        impl Neg for $vector {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($component: -self.$component),+ }
+            }
+        }
+
+        impl AddAssign for $vector {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $vector {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl MulAssign<f32> for $vector {
+            fn mul_assign(&mut self, scalar: f32) {
+                *self = *self * scalar;
+            }
+        }
+
+        impl DivAssign<f32> for $vector {
+            fn div_assign(&mut self, scalar: f32) {
+                *self = *self / scalar;
+            }
+        }
+
+        impl Index<usize> for $vector {
+            type Output = f32;
+
+            fn index(&self, index: usize) -> &f32 {
+                &bytemuck::cast_ref::<Self, [f32; $dimension]>(self)[index]
+            }
+        }
+
+        impl IndexMut<usize> for $vector {
+            fn index_mut(&mut self, index: usize) -> &mut f32 {
+                &mut bytemuck::cast_mut::<Self, [f32; $dimension]>(self)[index]
+            }
+        }
+
+        impl From<[f32; $dimension]> for $vector {
+            fn from(array: [f32; $dimension]) -> Self {
+                Self::from_array(array)
+            }
+        }
+
+        impl From<$vector> for [f32; $dimension] {
+            fn from(vector: $vector) -> Self {
+                vector.to_array()
+            }
+        }
+    };
+}
+
+impl_vector!(Vec2, 2, x, y);
+impl_vector!(Vec3, 3, x, y, z);
+impl_vector!(Vec4, 4, x, y, z, w);
+
+impl Vec2 {
+    pub const X: Self = Self::new(1f32, 0f32);
+    pub const Y: Self = Self::new(0f32, 1f32);
+
+    pub fn extend(self, z: f32) -> Vec3 {
+        Vec3::new(self.x, self.y, z)
+    }
+}
+
+impl Vec3 {
+    pub const X: Self = Self::new(1f32, 0f32, 0f32);
+    pub const Y: Self = Self::new(0f32, 1f32, 0f32);
+    pub const Z: Self = Self::new(0f32, 0f32, 1f32);
+
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn extend(self, w: f32) -> Vec4 {
+        Vec4::new(self.x, self.y, self.z, w)
+    }
+
+    pub fn truncate(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+}
+
+impl Vec4 {
+    pub const X: Self = Self::new(1f32, 0f32, 0f32, 0f32);
+    pub const Y: Self = Self::new(0f32, 1f32, 0f32, 0f32);
+    pub const Z: Self = Self::new(0f32, 0f32, 1f32, 0f32);
+    pub const W: Self = Self::new(0f32, 0f32, 0f32, 1f32);
+
+    pub fn truncate(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
@@ -0,0 +1,150 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::math::vector::{Vec2, Vec3, Vec4};
+
+///Vertex of a mesh, read by the vertex shaders from a vertex buffer
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Vertex {
+    pub position: Vec3,
+
+    ///The normal with a length of 1, 0 if it's unknown
+    pub normal: Vec3,
+
+    ///The texture coordinates, whose origin is the top left corner of the textures
+    pub uv: Vec2,
+
+    ///The tangent with a length of 1 in XYZ, pointing toward +U,
+    ///and in W the sign of the bitangent (pointing toward +V) relative to the cross product of the normal and the tangent
+    pub tangent: Vec4,
+}
+
+///Range of the indices of a mesh drawn with the same material
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Submesh {
+    pub first_index: u32,
+    pub index_count: u32,
+
+    ///The index of the material in the materials loaded with the mesh, None for the default material
+    pub material_index: Option<usize>,
+}
+
+///Indexed triangle list, split in submeshes by material
+#[derive(Clone, Debug, Default)]
+pub struct MeshData {
+    pub all_vertices: Vec<Vertex>,
+
+    ///The indices of the vertices of each triangle, whose front face is counterclockwise
+    pub all_indices: Vec<u32>,
+
+    ///Consecutive ranges covering `all_indices`
+    pub all_submeshes: Vec<Submesh>,
+}
+
+impl MeshData {
+    ///Return the number of triangles of all the submeshes
+    pub fn get_triangle_count(&self) -> usize {
+        self.all_indices.len() / 3
+    }
+
+    ///Generate the normals of the vertices with a normal of 0,
+    ///from the normals of their triangles weighted by their area
+    pub fn generate_normals(&mut self) {
+        let all_generated_flags: Vec<bool> = self
+            .all_vertices
+            .iter()
+            .map(|vertex| vertex.normal == Vec3::ZERO)
+            .collect();
+        if !all_generated_flags.contains(&true) {
+            return;
+        }
+
+        for triangle in self.all_indices.chunks_exact(3) {
+            let [index_0, index_1, index_2] = [0, 1, 2].map(|corner| triangle[corner] as usize);
+            let position_0 = self.all_vertices[index_0].position;
+
+            //The length of the cross product is twice the area of the triangle
+            let area_weighted_normal = (self.all_vertices[index_1].position - position_0)
+                .cross(self.all_vertices[index_2].position - position_0);
+            for index in [index_0, index_1, index_2] {
+                if all_generated_flags[index] {
+                    self.all_vertices[index].normal += area_weighted_normal;
+                }
+            }
+        }
+
+        for (vertex, _) in self
+            .all_vertices
+            .iter_mut()
+            .zip(all_generated_flags)
+            .filter(|(_, is_generated)| *is_generated)
+        {
+            //A vertex of degenerate triangles only gets an arbitrary normal
+            vertex.normal = vertex.normal.try_normalize().unwrap_or(Vec3::Z);
+        }
+    }
+
+    ///Generate the tangents of all the vertices, from the directions of the texture coordinates on their triangles
+    ///
+    ///The normals must be generated first. A vertex without texture coordinates gets an arbitrary tangent
+    pub fn generate_tangents(&mut self) {
+        let mut all_tangents = vec![Vec3::ZERO; self.all_vertices.len()];
+        let mut all_bitangents = vec![Vec3::ZERO; self.all_vertices.len()];
+
+        for triangle in self.all_indices.chunks_exact(3) {
+            let [vertex_0, vertex_1, vertex_2] =
+                [0, 1, 2].map(|corner| self.all_vertices[triangle[corner] as usize]);
+
+            let edge_1 = vertex_1.position - vertex_0.position;
+            let edge_2 = vertex_2.position - vertex_0.position;
+            let delta_uv_1 = vertex_1.uv - vertex_0.uv;
+            let delta_uv_2 = vertex_2.uv - vertex_0.uv;
+
+            let determinant = delta_uv_1.x * delta_uv_2.y - delta_uv_2.x * delta_uv_1.y;
+            if determinant.abs() <= f32::EPSILON {
+                continue;
+            }
+
+            let inverse_determinant = 1f32 / determinant;
+            let tangent = (edge_1 * delta_uv_2.y - edge_2 * delta_uv_1.y) * inverse_determinant;
+            let bitangent = (edge_2 * delta_uv_1.x - edge_1 * delta_uv_2.x) * inverse_determinant;
+            for &index in triangle {
+                all_tangents[index as usize] += tangent;
+                all_bitangents[index as usize] += bitangent;
+            }
+        }
+
+        for ((vertex, tangent), bitangent) in self
+            .all_vertices
+            .iter_mut()
+            .zip(all_tangents)
+            .zip(all_bitangents)
+        {
+            let normal = vertex.normal;
+
+            //Gram-Schmidt orthogonalization of the tangent to the normal
+            let orthogonal_tangent = (tangent - normal * normal.dot(tangent))
+                .try_normalize()
+                .unwrap_or_else(|| get_any_orthogonal(normal));
+            let handedness = if normal.cross(orthogonal_tangent).dot(bitangent) < 0f32 {
+                -1f32
+            } else {
+                1f32
+            };
+
+            vertex.tangent = orthogonal_tangent.extend(handedness);
+        }
+    }
+}
+
+///Return a vector with a length of 1 orthogonal to `vector`
+fn get_any_orthogonal(vector: Vec3) -> Vec3 {
+    //X is only close to parallel to `vector` when its X component is close to its length
+    let axis = if vector.x.abs() < 0.9f32 {
+        Vec3::X
+    } else {
+        Vec3::Y
+    };
+
+    vector.cross(axis).try_normalize().unwrap_or(Vec3::X)
+}
@@ -0,0 +1,436 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    material::{AlphaMode, Material, MaterialTexture},
+    math::vector::{Vec2, Vec3, Vec4},
+    mesh::{MeshData, Submesh, Vertex},
+};
+
+///Error returned when a Wavefront OBJ or MTL file cannot be loaded
+#[derive(Debug)]
+pub enum ObjError {
+    ///The OBJ file or one of its MTL files cannot be read
+    Io {
+        file_path: PathBuf,
+        error: io::Error,
+    },
+
+    ///A statement of the OBJ or MTL file is invalid
+    Parse {
+        ///The number of the invalid line, from 1
+        line_number: usize,
+        message: String,
+    },
+
+    ///The mesh has more vertices than can be indexed by u32
+    TooManyVertices,
+}
+
+impl Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io { file_path, error } => {
+                write!(f, "failed to read {}: {error}", file_path.display())
+            }
+            ObjError::Parse {
+                line_number,
+                message,
+            } => write!(f, "invalid statement at line {line_number}: {message}"),
+            ObjError::TooManyVertices => {
+                write!(f, "mesh has more vertices than can be indexed by u32")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+///Mesh of an OBJ file with the materials of its MTL files
+#[derive(Clone, Debug, Default)]
+pub struct ObjModel {
+    ///The mesh, with a submesh per range of faces using the same material
+    pub mesh: MeshData,
+
+    ///The materials indexed by the submeshes of `mesh`
+    pub all_materials: Vec<Material>,
+}
+
+///Indices of the position, texture coordinates and normal of a face vertex, from 0
+type ObjVertexKey = (usize, Option<usize>, Option<usize>);
+
+///Load the OBJ file at `file_path` and the MTL files it references
+///
+///The polygons are triangulated, the vertices shared by faces are deduplicated,
+///and the missing normals and all the tangents are generated
+pub fn load_obj_from_file(file_path: impl AsRef<Path>) -> Result<ObjModel, ObjError> {
+    let file_path = file_path.as_ref();
+    let source = read_file(file_path)?;
+
+    parse_obj(&source, file_path.parent().unwrap_or(Path::new("")))
+}
+
+///Parse the OBJ `source`, whose MTL files and textures are relative to `directory`
+pub fn parse_obj(source: &str, directory: &Path) -> Result<ObjModel, ObjError> {
+    let mut all_positions: Vec<Vec3> = Vec::new();
+    let mut all_uvs: Vec<Vec2> = Vec::new();
+    let mut all_normals: Vec<Vec3> = Vec::new();
+
+    let mut model = ObjModel::default();
+    let mut all_vertex_indices: HashMap<ObjVertexKey, u32> = HashMap::new();
+
+    //The faces before the first usemtl have the default material
+    model.mesh.all_submeshes.push(Submesh {
+        first_index: 0,
+        index_count: 0,
+        material_index: None,
+    });
+
+    for (line_number, statement) in get_all_statements(source) {
+        let parse_error = |message: String| ObjError::Parse {
+            line_number,
+            message,
+        };
+        let mut all_tokens = statement.split_whitespace();
+        let Some(keyword) = all_tokens.next() else {
+            continue;
+        };
+        let all_arguments: Vec<&str> = all_tokens.collect();
+
+        match keyword {
+            "v" => {
+                let [x, y, z] = parse_floats(&all_arguments).map_err(parse_error)?;
+                all_positions.push(Vec3::new(x, y, z));
+            }
+            "vt" => {
+                let [u, v] = parse_floats(&all_arguments).map_err(parse_error)?;
+                //The V axis of OBJ points up, from the bottom of the textures
+                all_uvs.push(Vec2::new(u, 1f32 - v));
+            }
+            "vn" => {
+                let [x, y, z] = parse_floats(&all_arguments).map_err(parse_error)?;
+                all_normals.push(Vec3::new(x, y, z).try_normalize().unwrap_or(Vec3::ZERO));
+            }
+            "f" => {
+                if all_arguments.len() < 3 {
+                    return Err(parse_error(format!(
+                        "face with {} vertices",
+                        all_arguments.len()
+                    )));
+                }
+
+                let mut all_face_indices = Vec::with_capacity(all_arguments.len());
+                for argument in &all_arguments {
+                    let vertex_key = parse_face_vertex(
+                        argument,
+                        (all_positions.len(), all_uvs.len(), all_normals.len()),
+                    )
+                    .map_err(parse_error)?;
+
+                    let vertex_index = match all_vertex_indices.get(&vertex_key) {
+                        Some(&vertex_index) => vertex_index,
+                        None => {
+                            let vertex_index = u32::try_from(model.mesh.all_vertices.len())
+                                .map_err(|_| ObjError::TooManyVertices)?;
+                            let (position_index, uv_index_opt, normal_index_opt) = vertex_key;
+                            model.mesh.all_vertices.push(Vertex {
+                                position: all_positions[position_index],
+                                normal: normal_index_opt
+                                    .map_or(Vec3::ZERO, |normal_index| all_normals[normal_index]),
+                                uv: uv_index_opt.map_or(Vec2::ZERO, |uv_index| all_uvs[uv_index]),
+                                tangent: Vec4::ZERO,
+                            });
+                            all_vertex_indices.insert(vertex_key, vertex_index);
+                            vertex_index
+                        }
+                    };
+                    all_face_indices.push(vertex_index);
+                }
+
+                //The polygons are assumed convex, and triangulated as a fan
+                for corner in 1..all_face_indices.len() - 1 {
+                    model.mesh.all_indices.extend([
+                        all_face_indices[0],
+                        all_face_indices[corner],
+                        all_face_indices[corner + 1],
+                    ]);
+                }
+            }
+            "mtllib" => {
+                //The file names may contain spaces
+                let mtl_file_path = directory.join(all_arguments.join(" "));
+                let mtl_source = read_file(&mtl_file_path)?;
+                let mtl_directory = mtl_file_path.parent().unwrap_or(directory);
+                model
+                    .all_materials
+                    .extend(parse_mtl(&mtl_source, mtl_directory)?);
+            }
+            "usemtl" => {
+                let material_name = all_arguments.join(" ");
+                let material_index_opt = model
+                    .all_materials
+                    .iter()
+                    .position(|material| material.name == material_name);
+
+                end_last_submesh(&mut model.mesh);
+                model.mesh.all_submeshes.push(Submesh {
+                    first_index: model.mesh.all_indices.len() as u32,
+                    index_count: 0,
+                    material_index: material_index_opt,
+                });
+            }
+            //Objects, groups, smoothing groups, lines and points don't change the triangles
+            _ => {}
+        }
+    }
+
+    end_last_submesh(&mut model.mesh);
+    model
+        .mesh
+        .all_submeshes
+        .retain(|submesh| submesh.index_count > 0);
+
+    model.mesh.generate_normals();
+    model.mesh.generate_tangents();
+
+    Ok(model)
+}
+
+///Parse the MTL `source`, whose textures are relative to `directory`
+///
+///The Phong parameters are converted to the metallic-roughness model,
+///unless the file has the PBR extension parameters (`Pr`, `Pm`, `Ke`, `norm`)
+pub fn parse_mtl(source: &str, directory: &Path) -> Result<Vec<Material>, ObjError> {
+    let mut all_materials: Vec<Material> = Vec::new();
+
+    for (line_number, statement) in get_all_statements(source) {
+        let parse_error = |message: String| ObjError::Parse {
+            line_number,
+            message,
+        };
+        let mut all_tokens = statement.split_whitespace();
+        let Some(keyword) = all_tokens.next() else {
+            continue;
+        };
+        let all_arguments: Vec<&str> = all_tokens.collect();
+
+        if keyword == "newmtl" {
+            let mut material = Material::new(&all_arguments.join(" "));
+            //Without Ns or Pr, a Phong material is rough
+            material.roughness_factor = 1f32;
+            all_materials.push(material);
+            continue;
+        }
+        let Some(material) = all_materials.last_mut() else {
+            return Err(parse_error(format!("{keyword} before newmtl")));
+        };
+
+        match keyword {
+            "Kd" => {
+                let [red, green, blue] = parse_floats(&all_arguments).map_err(parse_error)?;
+                let alpha = material.base_color_factor.w;
+                material.base_color_factor = Vec4::new(red, green, blue, alpha);
+            }
+            "d" | "Tr" => {
+                let [value] = parse_floats(&all_arguments).map_err(parse_error)?;
+                //Tr is the transparency, the opposite of the dissolve d
+                let alpha = if keyword == "d" { value } else { 1f32 - value };
+                material.base_color_factor.w = alpha.clamp(0f32, 1f32);
+                material.alpha_mode = if alpha < 1f32 {
+                    AlphaMode::Blend
+                } else {
+                    AlphaMode::Opaque
+                };
+            }
+            "Ns" => {
+                let [shininess] = parse_floats(&all_arguments).map_err(parse_error)?;
+                //Usual mapping of the Blinn-Phong exponent to the GGX roughness
+                material.roughness_factor = (2f32 / (shininess.max(0f32) + 2f32)).sqrt();
+            }
+            "Pr" => {
+                let [roughness] = parse_floats(&all_arguments).map_err(parse_error)?;
+                material.roughness_factor = roughness;
+            }
+            "Pm" => {
+                let [metallic] = parse_floats(&all_arguments).map_err(parse_error)?;
+                material.metallic_factor = metallic;
+            }
+            "Ke" => {
+                let [red, green, blue] = parse_floats(&all_arguments).map_err(parse_error)?;
+                material.emissive_factor = Vec3::new(red, green, blue);
+            }
+            "map_Kd" | "map_Ke" | "map_Bump" | "map_bump" | "bump" | "norm" => {
+                let (file_name, normal_scale_opt) =
+                    parse_texture_statement(&all_arguments).map_err(parse_error)?;
+                let texture = Some(MaterialTexture::from_file(directory.join(file_name)));
+                match keyword {
+                    "map_Kd" => material.base_color_texture = texture,
+                    "map_Ke" => {
+                        material.emissive_texture = texture;
+                        //The emissive texture is multiplied with Ke, white if it's missing
+                        if material.emissive_factor == Vec3::ZERO {
+                            material.emissive_factor = Vec3::ONE;
+                        }
+                    }
+                    _ => {
+                        material.normal_texture = texture;
+                        material.normal_scale = normal_scale_opt.unwrap_or(1f32);
+                    }
+                }
+            }
+            //The ambient and specular colors, the illumination model and the other maps have no equivalent,
+            //and the separate metalness and roughness maps aren't packed in one texture as expected
+            _ => {}
+        }
+    }
+
+    Ok(all_materials)
+}
+
+///Extend the last submesh of `mesh` to its last index
+fn end_last_submesh(mesh: &mut MeshData) {
+    let index_count = mesh.all_indices.len() as u32;
+    if let Some(last_submesh) = mesh.all_submeshes.last_mut() {
+        last_submesh.index_count = index_count - last_submesh.first_index;
+    }
+}
+
+fn read_file(file_path: &Path) -> Result<String, ObjError> {
+    fs::read_to_string(file_path).map_err(|error| ObjError::Io {
+        file_path: file_path.to_path_buf(),
+        error,
+    })
+}
+
+///Return the statements of the OBJ or MTL `source` with their line numbers (from 1),
+///joining the lines ending with a backslash and removing the comments
+fn get_all_statements(source: &str) -> Vec<(usize, String)> {
+    let mut all_statements = Vec::new();
+    let mut current_statement: Option<(usize, String)> = None;
+
+    for (line_index, line) in source.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        let (line, is_continued) = match line.trim_end().strip_suffix('\\') {
+            Some(continued_line) => (continued_line, true),
+            None => (line, false),
+        };
+
+        let (_, statement) =
+            current_statement.get_or_insert_with(|| (line_index + 1, String::new()));
+        statement.push(' ');
+        statement.push_str(line);
+
+        if !is_continued {
+            all_statements.extend(current_statement.take());
+        }
+    }
+    all_statements.extend(current_statement);
+
+    all_statements
+}
+
+///Parse the first `N` arguments as floats, the next ones are ignored
+///
+///For example, the optional W of a position or the vertex colors of some exporters
+fn parse_floats<const N: usize>(all_arguments: &[&str]) -> Result<[f32; N], String> {
+    if all_arguments.len() < N {
+        return Err(format!("{} numbers instead of {N}", all_arguments.len()));
+    }
+
+    let mut all_floats = [0f32; N];
+    for (float, argument) in all_floats.iter_mut().zip(all_arguments) {
+        *float = argument
+            .parse()
+            .map_err(|_| format!("{argument} isn't a number"))?;
+    }
+    Ok(all_floats)
+}
+
+///Parse a face vertex `v`, `v/vt`, `v//vn` or `v/vt/vn` of 1-based or negative relative indices
+///
+/// # Arguments
+///
+/// * `argument` - the face vertex
+/// * `element_counts` - the numbers of positions, texture coordinates and normals declared before the face
+fn parse_face_vertex(
+    argument: &str,
+    (position_count, uv_count, normal_count): (usize, usize, usize),
+) -> Result<ObjVertexKey, String> {
+    let mut all_parts = argument.split('/');
+    let parse_index = |part: Option<&str>, count: usize| -> Result<Option<usize>, String> {
+        let Some(part) = part.filter(|part| !part.is_empty()) else {
+            return Ok(None);
+        };
+        let index: isize = part
+            .parse()
+            .map_err(|_| format!("{part} isn't an index in {argument}"))?;
+
+        let zero_based_index = match index {
+            1.. => index - 1,
+            ..0 => count as isize + index,
+            0 => -1,
+        };
+        if !(0..count as isize).contains(&zero_based_index) {
+            return Err(format!("index {index} is out of range in {argument}"));
+        }
+        Ok(Some(zero_based_index as usize))
+    };
+
+    let position_index = parse_index(all_parts.next(), position_count)?
+        .ok_or_else(|| format!("face vertex {argument} without position"))?;
+    let uv_index_opt = parse_index(all_parts.next(), uv_count)?;
+    let normal_index_opt = parse_index(all_parts.next(), normal_count)?;
+
+    Ok((position_index, uv_index_opt, normal_index_opt))
+}
+
+///Parse the arguments of a texture statement: options starting with `-`, then the file name
+///
+/// # Returns
+///
+/// The file name and the `-bm` bump multiplier if any
+fn parse_texture_statement(all_arguments: &[&str]) -> Result<(String, Option<f32>), String> {
+    let mut bump_multiplier_opt = None;
+
+    let mut argument_index = 0;
+    while let Some(option) = all_arguments
+        .get(argument_index)
+        .filter(|argument| argument.starts_with('-'))
+    {
+        argument_index += 1;
+
+        //The options -o, -s and -t take 1 to 3 numbers, the others a fixed count of arguments
+        let option_value_count = match *option {
+            "-o" | "-s" | "-t" => all_arguments[argument_index..]
+                .iter()
+                .take(3)
+                .take_while(|argument| argument.parse::<f32>().is_ok())
+                .count(),
+            "-mm" => 2,
+            _ => 1,
+        };
+        if argument_index + option_value_count > all_arguments.len() {
+            return Err(format!("missing value of texture option {option}"));
+        }
+
+        if *option == "-bm" {
+            let value = all_arguments[argument_index];
+            bump_multiplier_opt = Some(
+                value
+                    .parse()
+                    .map_err(|_| format!("{value} isn't a number"))?,
+            );
+        }
+        argument_index += option_value_count;
+    }
+
+    let file_name = all_arguments[argument_index..].join(" ");
+    if file_name.is_empty() {
+        return Err("texture statement without file name".to_string());
+    }
+    Ok((file_name, bump_multiplier_opt))
+}
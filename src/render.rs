@@ -0,0 +1,524 @@
+use ash::vk;
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    camera::{Camera, CameraUniforms},
+    ecs::{
+        systems::{get_active_camera, get_all_lights, get_render_items},
+        world::World,
+    },
+    material::{MaterialDraw, MaterialPipelineKey, sort_material_draws},
+    math::{
+        matrix::Mat4,
+        vector::{Vec3, Vec4},
+    },
+    scene::{Light, Scene},
+    shadow::ShadowConfig,
+    vulkan::{
+        buffer::VulkanBuffer,
+        command_buffer::VulkanCommandBuffer,
+        descriptor::VulkanDescriptorSet,
+        framebuffer::VulkanFramebuffer,
+        frames::VulkanFramesInFlight,
+        graphics_pipeline::VulkanGraphicsPipeline,
+        image::{VulkanImage, VulkanImageView},
+        light::VulkanLightBuffers,
+        material::VulkanMaterialInstance,
+        mesh::VulkanMesh,
+        shadow::{GpuShadow, get_gpu_lights_and_shadows, get_shadow_tile_viewport_and_scissor},
+        swapchain::{OutputColorSpace, VulkanSwapchain},
+    },
+};
+
+pub const CLEAR_COLOR: [f32; 4] = [0f32, 0f32, 0f32, 1f32];
+
+///Set of the pipeline layouts to which the descriptor set of a frame is bound
+pub const FRAME_DESCRIPTOR_SET: u32 = 0;
+
+///Push constants of the PBR shaders
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct PbrPushConstants {
+    ///Transforms the model space of the drawn mesh to the world space
+    model: Mat4,
+
+    ///The OutputColorSpace of the swapchain, in which the colors are tonemapped
+    output_color_space: u32,
+
+    _padding: [u32; 3],
+}
+
+///Push constants of the shadow shaders
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct ShadowPushConstants {
+    ///Transforms the model space of the drawn mesh to the clip space of the light
+    model_view_projection: Mat4,
+}
+
+///Push constants of the skybox shaders
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct SkyboxPushConstants {
+    ///Transforms the clip space of the camera to the world space, ignoring the position of the camera
+    inverse_view_projection: Mat4,
+
+    ///The OutputColorSpace of the swapchain, in which the colors are tonemapped
+    output_color_space: u32,
+
+    _padding: [u32; 3],
+}
+
+///System recording the draws of the meshes of a World and a Scene, seen by the active camera of the World
+///and lit by the lights of both
+///
+///It borrows the resources of the frame loop for the recording of one frame
+pub struct RenderSystem<'render, 'vulkan_device> {
+    ///The meshes indexed by MeshId, None for the meshes without triangle
+    pub all_meshes: &'render [Option<VulkanMesh<'vulkan_device>>],
+
+    ///The materials indexed by the submeshes, the last one drawing the submeshes without a valid material
+    pub all_material_instances: &'render [VulkanMaterialInstance<'render, 'vulkan_device>],
+
+    ///The pipelines drawing the materials, by pipeline key
+    ///
+    ///The render passes of the pipelines are compatible, the first one begins the main pass
+    pub all_graphics_pipelines:
+        &'render [(MaterialPipelineKey, VulkanGraphicsPipeline<'vulkan_device>)],
+
+    ///The pipeline drawing the skybox, None if no skybox is drawn
+    pub skybox_graphics_pipeline_opt: Option<&'render VulkanGraphicsPipeline<'vulkan_device>>,
+
+    ///The descriptor sets of the frames in flight, indexed by frame
+    pub all_frame_descriptor_sets: &'render [VulkanDescriptorSet<'render, 'vulkan_device>],
+
+    ///The CameraUniforms buffers of the frames in flight, indexed by frame
+    pub all_camera_uniform_buffers: &'render [VulkanBuffer<'vulkan_device>],
+
+    pub light_buffers: &'render VulkanLightBuffers<'vulkan_device>,
+
+    ///The framebuffers of the swapchain images, empty with the dynamic rendering
+    pub all_framebuffers: &'render [VulkanFramebuffer<'vulkan_device>],
+
+    ///The depth image tested with the swapchain images, shared by the frames in flight
+    pub depth_image: &'render VulkanImage<'vulkan_device>,
+    pub depth_image_view: &'render VulkanImageView<'render, 'vulkan_device>,
+
+    pub shadow_graphics_pipeline: &'render VulkanGraphicsPipeline<'vulkan_device>,
+    pub shadow_atlas_image: &'render VulkanImage<'vulkan_device>,
+    pub shadow_atlas_image_view: &'render VulkanImageView<'render, 'vulkan_device>,
+
+    ///The framebuffer of the shadow atlas, None with the dynamic rendering
+    pub shadow_framebuffer_opt: Option<&'render VulkanFramebuffer<'vulkan_device>>,
+
+    pub shadow_config: &'render ShadowConfig,
+}
+
+impl RenderSystem<'_, '_> {
+    ///Record the draws of `world` and `scene` in the current frame of `frames_in_flight`, rendering the image `image_index` of `vulkan_swapchain`
+    ///
+    ///The camera uniforms and the lights of the frame are written, then the shadow atlas is rendered before the main pass sampling it.
+    ///The frame must have been begun, so the previous commands reading its uniforms are executed
+    pub fn record_frame(
+        &self,
+        frames_in_flight: &VulkanFramesInFlight,
+        vulkan_swapchain: &VulkanSwapchain,
+        image_index: u32,
+        world: &World,
+        scene: &Scene,
+    ) {
+        let command_buffer = frames_in_flight.get_command_buffer();
+        let frame_index = frames_in_flight.get_current_frame_index();
+
+        let camera_opt = get_active_camera(world);
+        let camera_uniforms = camera_opt.map_or(
+            CameraUniforms {
+                view: Mat4::IDENTITY,
+                projection: Mat4::IDENTITY,
+                view_projection: Mat4::IDENTITY,
+                position: Vec4::W,
+            },
+            Camera::get_uniforms,
+        );
+        self.all_camera_uniform_buffers[frame_index]
+            .write_bytes(0, bytemuck::bytes_of(&camera_uniforms));
+        let all_lights: Vec<(Light, Mat4)> = scene
+            .get_all_lights()
+            .into_iter()
+            .chain(get_all_lights(world))
+            .collect();
+        let (all_gpu_lights, all_shadows) = get_gpu_lights_and_shadows(
+            &all_lights,
+            self.light_buffers.get_max_light_count(),
+            camera_opt,
+            self.shadow_config,
+        );
+        self.light_buffers
+            .write_lights(frames_in_flight, &all_gpu_lights, &all_shadows);
+
+        let all_material_draws =
+            self.get_all_material_draws(world, scene, camera_uniforms.position.truncate());
+
+        self.record_shadow_pass(command_buffer, &all_shadows, &all_material_draws);
+        self.record_main_pass(
+            command_buffer,
+            frame_index,
+            vulkan_swapchain,
+            image_index,
+            &camera_uniforms,
+            &all_material_draws,
+        );
+    }
+
+    ///Return the draws of the submeshes of the meshes of `world` and `scene`, sorted to be recorded in order
+    fn get_all_material_draws(
+        &self,
+        world: &World,
+        scene: &Scene,
+        camera_position: Vec3,
+    ) -> Vec<MaterialDraw> {
+        let default_material_index = self.all_material_instances.len() - 1;
+
+        let all_draw_items = scene
+            .get_draw_list()
+            .into_iter()
+            .map(|draw_item| (draw_item.mesh, draw_item.world_matrix))
+            .chain(
+                get_render_items(world)
+                    .into_iter()
+                    .map(|render_item| (render_item.mesh, render_item.world_matrix)),
+            );
+        let mut all_material_draws = Vec::new();
+        for (mesh_id, world_matrix) in all_draw_items {
+            let Some(Some(mesh)) = self.all_meshes.get(mesh_id.0) else {
+                continue;
+            };
+            let camera_distance =
+                (world_matrix.transform_point3(Vec3::ZERO) - camera_position).length();
+
+            for (submesh_index, submesh) in mesh.get_all_submeshes().iter().enumerate() {
+                let material_index = submesh
+                    .material_index
+                    .filter(|&material_index| material_index < default_material_index)
+                    .unwrap_or(default_material_index);
+
+                all_material_draws.push(MaterialDraw {
+                    pipeline_key: self.all_material_instances[material_index].get_pipeline_key(),
+                    material_index,
+                    mesh: mesh_id,
+                    submesh_index,
+                    world_matrix,
+                    camera_distance,
+                });
+            }
+        }
+        sort_material_draws(&mut all_material_draws);
+
+        all_material_draws
+    }
+
+    ///Record the rendering of each shadow of `all_shadows` in its tile of the shadow atlas, the blended draws cast no shadow
+    fn record_shadow_pass(
+        &self,
+        command_buffer: &VulkanCommandBuffer,
+        all_shadows: &[GpuShadow],
+        all_material_draws: &[MaterialDraw],
+    ) {
+        match self.shadow_framebuffer_opt {
+            Some(shadow_framebuffer) => command_buffer.begin_render_pass(
+                self.shadow_graphics_pipeline,
+                shadow_framebuffer.get_framebuffer(),
+                self.shadow_atlas_image.get_extent(),
+                CLEAR_COLOR,
+            ),
+            None => {
+                command_buffer.transition_image_layout(
+                    self.shadow_atlas_image.get_image(),
+                    get_depth_subresource_range(),
+                    vk::ImageLayout::UNDEFINED,
+                    vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                    (
+                        vk::PipelineStageFlags::FRAGMENT_SHADER,
+                        vk::AccessFlags::empty(),
+                    ),
+                    (
+                        vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS
+                            | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS,
+                        vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                    ),
+                );
+                command_buffer.begin_depth_rendering(
+                    self.shadow_atlas_image_view.get_image_view(),
+                    self.shadow_atlas_image.get_extent(),
+                );
+            }
+        }
+        command_buffer.bind_graphics_pipeline(self.shadow_graphics_pipeline);
+        for (tile_index, shadow) in all_shadows.iter().enumerate() {
+            let (viewport, scissor) =
+                get_shadow_tile_viewport_and_scissor(self.shadow_config, tile_index);
+            command_buffer.set_viewport_and_scissor(viewport, scissor);
+
+            let mut bound_mesh_opt = None;
+            for material_draw in all_material_draws
+                .iter()
+                .filter(|material_draw| !material_draw.pipeline_key.is_alpha_blended)
+            {
+                let Some(Some(mesh)) = self.all_meshes.get(material_draw.mesh.0) else {
+                    continue;
+                };
+                if bound_mesh_opt != Some(material_draw.mesh) {
+                    mesh.bind(command_buffer);
+                    bound_mesh_opt = Some(material_draw.mesh);
+                }
+
+                command_buffer.push_constants(
+                    self.shadow_graphics_pipeline,
+                    vk::ShaderStageFlags::VERTEX,
+                    0,
+                    &ShadowPushConstants {
+                        model_view_projection: shadow.view_projection * material_draw.world_matrix,
+                    },
+                );
+                mesh.draw_submesh(
+                    command_buffer,
+                    &mesh.get_all_submeshes()[material_draw.submesh_index],
+                );
+            }
+        }
+        if self.shadow_framebuffer_opt.is_some() {
+            command_buffer.end_render_pass();
+        } else {
+            command_buffer.end_rendering();
+            command_buffer.transition_image_layout(
+                self.shadow_atlas_image.get_image(),
+                get_depth_subresource_range(),
+                vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                (
+                    vk::PipelineStageFlags::LATE_FRAGMENT_TESTS,
+                    vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                ),
+                (
+                    vk::PipelineStageFlags::FRAGMENT_SHADER,
+                    vk::AccessFlags::SHADER_READ,
+                ),
+            );
+        }
+    }
+
+    ///Record the draws of `all_material_draws` and of the skybox in the image `image_index` of `vulkan_swapchain`,
+    ///left in the PRESENT_SRC_KHR layout
+    fn record_main_pass(
+        &self,
+        command_buffer: &VulkanCommandBuffer,
+        frame_index: usize,
+        vulkan_swapchain: &VulkanSwapchain,
+        image_index: u32,
+        camera_uniforms: &CameraUniforms,
+        all_material_draws: &[MaterialDraw],
+    ) {
+        let image = vulkan_swapchain.get_all_images()[image_index as usize];
+        let image_extent = vulkan_swapchain.get_image_extent();
+        let output_color_space = vulkan_swapchain.get_output_color_space();
+        let frame_descriptor_set = self.all_frame_descriptor_sets[frame_index].get_descriptor_set();
+
+        match self.all_framebuffers.get(image_index as usize) {
+            Some(framebuffer) => command_buffer.begin_render_pass(
+                &self.all_graphics_pipelines[0].1,
+                framebuffer.get_framebuffer(),
+                image_extent,
+                CLEAR_COLOR,
+            ),
+            None => {
+                command_buffer.transition_color_image_layout(
+                    image,
+                    vk::ImageLayout::UNDEFINED,
+                    vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                    (
+                        vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                        vk::AccessFlags::empty(),
+                    ),
+                    (
+                        vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                        vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+                    ),
+                );
+                //The depth image is shared by the frames in flight, cleared once the previous frame tested it
+                command_buffer.transition_image_layout(
+                    self.depth_image.get_image(),
+                    get_depth_subresource_range(),
+                    vk::ImageLayout::UNDEFINED,
+                    vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                    (
+                        vk::PipelineStageFlags::LATE_FRAGMENT_TESTS,
+                        vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                    ),
+                    (
+                        vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS,
+                        vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                    ),
+                );
+                command_buffer.begin_rendering(
+                    vulkan_swapchain.get_all_image_views()[image_index as usize],
+                    Some(self.depth_image_view.get_image_view()),
+                    image_extent,
+                    CLEAR_COLOR,
+                );
+            }
+        }
+
+        //The skybox is drawn after the opaque draws, to only shade the uncovered fragments,
+        //and before the blended draws, which are blended with it
+        let skybox_draw_index_opt = self.skybox_graphics_pipeline_opt.map(|_| {
+            all_material_draws
+                .iter()
+                .position(|material_draw| material_draw.pipeline_key.is_alpha_blended)
+                .unwrap_or(all_material_draws.len())
+        });
+        let draw_skybox = || {
+            if let Some(skybox_graphics_pipeline) = self.skybox_graphics_pipeline_opt {
+                record_skybox_draw(
+                    command_buffer,
+                    skybox_graphics_pipeline,
+                    frame_descriptor_set,
+                    camera_uniforms,
+                    output_color_space,
+                );
+            }
+        };
+
+        //The pipeline, the material and the mesh are only bound when they change from the previous draw
+        let mut bound_pipeline_opt: Option<(MaterialPipelineKey, &VulkanGraphicsPipeline)> = None;
+        let mut bound_material_index_opt = None;
+        let mut bound_mesh_opt = None;
+        for (draw_index, material_draw) in all_material_draws.iter().enumerate() {
+            if skybox_draw_index_opt == Some(draw_index) {
+                draw_skybox();
+                bound_pipeline_opt = None;
+            }
+
+            let graphics_pipeline = match bound_pipeline_opt {
+                Some((pipeline_key, graphics_pipeline))
+                    if pipeline_key == material_draw.pipeline_key =>
+                {
+                    graphics_pipeline
+                }
+                _ => {
+                    let Some((_, graphics_pipeline)) = self
+                        .all_graphics_pipelines
+                        .iter()
+                        .find(|(pipeline_key, _)| *pipeline_key == material_draw.pipeline_key)
+                    else {
+                        continue;
+                    };
+                    command_buffer.bind_graphics_pipeline(graphics_pipeline);
+                    command_buffer.bind_descriptor_sets(
+                        graphics_pipeline,
+                        FRAME_DESCRIPTOR_SET,
+                        &[frame_descriptor_set],
+                    );
+                    bound_pipeline_opt = Some((material_draw.pipeline_key, graphics_pipeline));
+                    //The material set of a previous pipeline may not be compatible with the new one
+                    bound_material_index_opt = None;
+                    graphics_pipeline
+                }
+            };
+
+            if bound_material_index_opt != Some(material_draw.material_index) {
+                self.all_material_instances[material_draw.material_index]
+                    .bind(command_buffer, graphics_pipeline);
+                bound_material_index_opt = Some(material_draw.material_index);
+            }
+
+            let Some(Some(mesh)) = self.all_meshes.get(material_draw.mesh.0) else {
+                continue;
+            };
+            if bound_mesh_opt != Some(material_draw.mesh) {
+                mesh.bind(command_buffer);
+                bound_mesh_opt = Some(material_draw.mesh);
+            }
+
+            command_buffer.push_constants(
+                graphics_pipeline,
+                vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
+                0,
+                &PbrPushConstants {
+                    model: material_draw.world_matrix,
+                    output_color_space: output_color_space as u32,
+                    _padding: [0; 3],
+                },
+            );
+            mesh.draw_submesh(
+                command_buffer,
+                &mesh.get_all_submeshes()[material_draw.submesh_index],
+            );
+        }
+        if skybox_draw_index_opt == Some(all_material_draws.len()) {
+            draw_skybox();
+        }
+
+        if self.all_framebuffers.is_empty() {
+            command_buffer.end_rendering();
+            command_buffer.transition_color_image_layout(
+                image,
+                vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                vk::ImageLayout::PRESENT_SRC_KHR,
+                (
+                    vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                    vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+                ),
+                (
+                    vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                    vk::AccessFlags::empty(),
+                ),
+            );
+        } else {
+            command_buffer.end_render_pass();
+        }
+    }
+}
+
+///Record the draw of the skybox with `skybox_graphics_pipeline`, a triangle covering the screen at the farthest depth
+fn record_skybox_draw(
+    command_buffer: &VulkanCommandBuffer,
+    skybox_graphics_pipeline: &VulkanGraphicsPipeline,
+    frame_descriptor_set: vk::DescriptorSet,
+    camera_uniforms: &CameraUniforms,
+    output_color_space: OutputColorSpace,
+) {
+    command_buffer.bind_graphics_pipeline(skybox_graphics_pipeline);
+    command_buffer.bind_descriptor_sets(
+        skybox_graphics_pipeline,
+        FRAME_DESCRIPTOR_SET,
+        &[frame_descriptor_set],
+    );
+    //The position of the camera is removed from its view, the skybox being infinitely far
+    command_buffer.push_constants(
+        skybox_graphics_pipeline,
+        vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
+        0,
+        &SkyboxPushConstants {
+            inverse_view_projection: (camera_uniforms.projection
+                * Mat4 {
+                    w_axis: Vec4::W,
+                    ..camera_uniforms.view
+                })
+            .inverse()
+            .unwrap_or(Mat4::IDENTITY),
+            output_color_space: output_color_space as u32,
+            _padding: [0; 3],
+        },
+    );
+    command_buffer.draw(3, 1, 0, 0);
+}
+
+///Return the range of the depth of the depth images of the render system, with one mip level and one array layer
+fn get_depth_subresource_range() -> vk::ImageSubresourceRange {
+    vk::ImageSubresourceRange::default()
+        .aspect_mask(vk::ImageAspectFlags::DEPTH)
+        .base_mip_level(0)
+        .level_count(1)
+        .base_array_layer(0)
+        .layer_count(1)
+}
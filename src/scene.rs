@@ -0,0 +1,439 @@
+use crate::{
+    camera::Camera,
+    math::{matrix::Mat4, quaternion::Quat, vector::Vec3},
+};
+
+///Identifier of a node of a Scene, never reused after the node is removed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+///Identifier of a mesh owned by the renderer, drawn with the world transform of the nodes it's attached to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MeshId(pub usize);
+
+///Identifier of a Skin owned by the loaded model, deforming the mesh of the nodes it's attached to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SkinId(pub usize);
+
+///Translation, rotation and scale of a node relative to its parent, applied in the order scale, rotation, translation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3::ONE,
+    };
+
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_rotation(rotation: Quat) -> Self {
+        Self {
+            rotation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_scale(scale: Vec3) -> Self {
+        Self {
+            scale,
+            ..Self::IDENTITY
+        }
+    }
+
+    ///Return the transform of `matrix`, None if it can't be decomposed because its scale is 0 on an axis
+    pub fn from_matrix(matrix: &Mat4) -> Option<Self> {
+        let (scale, rotation, translation) = matrix.to_scale_rotation_translation()?;
+
+        Some(Self {
+            translation,
+            rotation,
+            scale,
+        })
+    }
+
+    pub fn to_matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
+///Light emitted from the position of its node, toward the -Z axis of its node for the directional lights and spotlights
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Light {
+    Directional {
+        ///The linear color of the light
+        color: Vec3,
+
+        ///The illuminance (in lux)
+        intensity: f32,
+    },
+    Point {
+        ///The linear color of the light
+        color: Vec3,
+
+        ///The luminous intensity (in candela)
+        intensity: f32,
+
+        ///The distance at which the light has no effect, infinite if None
+        range: Option<f32>,
+    },
+    Spot {
+        ///The linear color of the light
+        color: Vec3,
+
+        ///The luminous intensity (in candela)
+        intensity: f32,
+
+        ///The distance at which the light has no effect, infinite if None
+        range: Option<f32>,
+
+        ///The angle (in radians) from the direction of the light at which its intensity starts to fall off
+        inner_cone_angle: f32,
+
+        ///The angle (in radians) from the direction of the light at which its intensity reaches 0
+        outer_cone_angle: f32,
+    },
+}
+
+///Node of a Scene, positioned relative to its parent
+#[derive(Clone, Debug)]
+pub struct SceneNode {
+    pub name: String,
+
+    ///If the node and its children are drawn
+    pub is_visible: bool,
+
+    pub mesh: Option<MeshId>,
+
+    ///The skin of the joints deforming `mesh`, which is then placed by the joints instead of the node
+    pub skin: Option<SkinId>,
+
+    pub light: Option<Light>,
+
+    ///The camera whose position and rotation are replaced by the ones of the node, see Scene::get_world_camera
+    pub camera: Option<Camera>,
+
+    transform: Transform,
+
+    ///The transform of the node relative to the world, valid if the node isn't dirty
+    world_matrix: Mat4,
+
+    ///If the local transform changed since the world matrix was last computed
+    is_dirty: bool,
+
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+impl SceneNode {
+    fn new(name: String, transform: Transform, parent: Option<NodeId>) -> Self {
+        Self {
+            name,
+            is_visible: true,
+            mesh: None,
+            skin: None,
+            light: None,
+            camera: None,
+            transform,
+            world_matrix: Mat4::IDENTITY,
+            is_dirty: true,
+            parent,
+            children: Vec::new(),
+        }
+    }
+
+    ///Return the transform of the node relative to its parent
+    pub fn get_transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    ///Return the transform of the node relative to its parent, marking the node as dirty
+    pub fn get_transform_mut(&mut self) -> &mut Transform {
+        self.is_dirty = true;
+        &mut self.transform
+    }
+
+    ///Set the transform of the node relative to its parent
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+        self.is_dirty = true;
+    }
+
+    ///Return the transform of the node relative to the world, as of the last call to Scene::update_world_transforms
+    pub fn get_world_matrix(&self) -> &Mat4 {
+        &self.world_matrix
+    }
+
+    ///Return if the local transform changed since the last call to Scene::update_world_transforms
+    pub fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+
+    pub fn get_parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn get_all_children(&self) -> &[NodeId] {
+        &self.children
+    }
+}
+
+///Mesh to draw with the world transform of its node
+#[derive(Clone, Copy, Debug)]
+pub struct DrawItem {
+    pub node_id: NodeId,
+    pub mesh: MeshId,
+    pub world_matrix: Mat4,
+}
+
+///Hierarchy of nodes, each with a transform relative to its parent
+///
+///The world transforms are cached, and only the subtrees of the dirty nodes are recomputed by update_world_transforms
+#[derive(Clone, Debug, Default)]
+pub struct Scene {
+    ///The nodes indexed by their ID, None once removed
+    all_nodes: Vec<Option<SceneNode>>,
+
+    ///The nodes without a parent, in insertion order
+    all_root_ids: Vec<NodeId>,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Add a node as the last child of `parent_id_opt`, or as a root if None
+    ///
+    /// # Panics
+    ///
+    /// If the parent isn't in the scene
+    pub fn add_node(
+        &mut self,
+        name: &str,
+        transform: Transform,
+        parent_id_opt: Option<NodeId>,
+    ) -> NodeId {
+        let node_id = NodeId(self.all_nodes.len());
+        match parent_id_opt {
+            Some(parent_id) => self
+                .get_node_mut(parent_id)
+                .expect("failed to find the parent node")
+                .children
+                .push(node_id),
+            None => self.all_root_ids.push(node_id),
+        }
+
+        self.all_nodes.push(Some(SceneNode::new(
+            name.to_string(),
+            transform,
+            parent_id_opt,
+        )));
+        node_id
+    }
+
+    ///Remove a node and all its descendants
+    ///
+    /// # Returns
+    ///
+    /// If the node was in the scene
+    pub fn remove_node(&mut self, node_id: NodeId) -> bool {
+        let Some(parent_id_opt) = self.get_node(node_id).map(SceneNode::get_parent) else {
+            return false;
+        };
+        self.detach(node_id, parent_id_opt);
+
+        let mut all_removed_ids = vec![node_id];
+        while let Some(removed_id) = all_removed_ids.pop() {
+            if let Some(removed_node) = self.all_nodes[removed_id.0].take() {
+                all_removed_ids.extend(removed_node.children);
+            }
+        }
+        true
+    }
+
+    ///Move a node, with its descendants, to the last child of `parent_id_opt`, or to the roots if None
+    ///
+    ///The transform of the node stays relative to its parent, so the node moves in the world
+    ///
+    /// # Panics
+    ///
+    /// If a node isn't in the scene, or if the new parent is the node or one of its descendants
+    pub fn set_parent(&mut self, node_id: NodeId, parent_id_opt: Option<NodeId>) {
+        let old_parent_id_opt = self
+            .get_node(node_id)
+            .expect("failed to find the node")
+            .parent;
+        if let Some(parent_id) = parent_id_opt {
+            assert!(
+                !self.is_ancestor_or_self(node_id, parent_id),
+                "node {node_id:?} can't be a child of its descendant {parent_id:?}"
+            );
+        }
+
+        self.detach(node_id, old_parent_id_opt);
+        match parent_id_opt {
+            Some(parent_id) => self
+                .get_node_mut(parent_id)
+                .expect("failed to find the parent node")
+                .children
+                .push(node_id),
+            None => self.all_root_ids.push(node_id),
+        }
+
+        let node = self.get_node_mut(node_id).expect("failed to find the node");
+        node.parent = parent_id_opt;
+        node.is_dirty = true;
+    }
+
+    pub fn get_node(&self, node_id: NodeId) -> Option<&SceneNode> {
+        self.all_nodes.get(node_id.0)?.as_ref()
+    }
+
+    pub fn get_node_mut(&mut self, node_id: NodeId) -> Option<&mut SceneNode> {
+        self.all_nodes.get_mut(node_id.0)?.as_mut()
+    }
+
+    ///Return the first node named `name`, in the order of their IDs
+    pub fn find_node_by_name(&self, name: &str) -> Option<NodeId> {
+        self.get_all_node_ids()
+            .find(|&node_id| self.get_node(node_id).is_some_and(|node| node.name == name))
+    }
+
+    ///Return the IDs of all the nodes of the scene, in increasing order
+    pub fn get_all_node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.all_nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node_opt)| node_opt.is_some())
+            .map(|(index, _)| NodeId(index))
+    }
+
+    pub fn get_all_root_ids(&self) -> &[NodeId] {
+        &self.all_root_ids
+    }
+
+    ///Recompute the world matrices of the dirty nodes and their descendants
+    pub fn update_world_transforms(&mut self) {
+        //Nodes to visit, with the world matrix of their parent and if it changed
+        let mut all_pending_nodes: Vec<(NodeId, Mat4, bool)> = self
+            .all_root_ids
+            .iter()
+            .map(|&root_id| (root_id, Mat4::IDENTITY, false))
+            .collect();
+
+        while let Some((node_id, parent_matrix, is_parent_changed)) = all_pending_nodes.pop() {
+            let Some(node) = self.get_node_mut(node_id) else {
+                continue;
+            };
+
+            let is_changed = node.is_dirty || is_parent_changed;
+            if is_changed {
+                node.world_matrix = parent_matrix * node.transform.to_matrix();
+                node.is_dirty = false;
+            }
+
+            let world_matrix = node.world_matrix;
+            all_pending_nodes.extend(
+                node.children
+                    .iter()
+                    .map(|&child_id| (child_id, world_matrix, is_changed)),
+            );
+        }
+    }
+
+    ///Return the camera attached to a node, positioned and rotated as the node in the world
+    ///
+    ///The scale of the node is ignored
+    pub fn get_world_camera(&self, node_id: NodeId) -> Option<Camera> {
+        let node = self.get_node(node_id)?;
+        let mut camera = node.camera.clone()?;
+        if let Some(world_transform) = Transform::from_matrix(&node.world_matrix) {
+            camera.position = world_transform.translation;
+            camera.rotation = world_transform.rotation;
+        }
+        Some(camera)
+    }
+
+    ///Return the meshes of the visible nodes with their world matrices, depth first in the order of the children
+    ///
+    ///update_world_transforms must be called before, after the transforms changed
+    pub fn get_draw_list(&self) -> Vec<DrawItem> {
+        let mut all_draw_items = Vec::new();
+
+        let mut all_pending_ids: Vec<NodeId> = self.all_root_ids.iter().rev().copied().collect();
+        while let Some(node_id) = all_pending_ids.pop() {
+            let Some(node) = self.get_node(node_id).filter(|node| node.is_visible) else {
+                continue;
+            };
+
+            if let Some(mesh) = node.mesh {
+                all_draw_items.push(DrawItem {
+                    node_id,
+                    mesh,
+                    world_matrix: node.world_matrix,
+                });
+            }
+            all_pending_ids.extend(node.children.iter().rev());
+        }
+
+        all_draw_items
+    }
+
+    ///Return the lights of the visible nodes with their world matrices
+    pub fn get_all_lights(&self) -> Vec<(Light, Mat4)> {
+        let mut all_lights = Vec::new();
+
+        let mut all_pending_ids: Vec<NodeId> = self.all_root_ids.iter().rev().copied().collect();
+        while let Some(node_id) = all_pending_ids.pop() {
+            let Some(node) = self.get_node(node_id).filter(|node| node.is_visible) else {
+                continue;
+            };
+
+            if let Some(light) = node.light {
+                all_lights.push((light, node.world_matrix));
+            }
+            all_pending_ids.extend(node.children.iter().rev());
+        }
+
+        all_lights
+    }
+
+    ///Remove `node_id` from the children of its parent, or from the roots
+    fn detach(&mut self, node_id: NodeId, parent_id_opt: Option<NodeId>) {
+        let all_sibling_ids = match parent_id_opt.and_then(|parent_id| self.get_node_mut(parent_id))
+        {
+            Some(parent) => &mut parent.children,
+            None => &mut self.all_root_ids,
+        };
+        all_sibling_ids.retain(|&sibling_id| sibling_id != node_id);
+    }
+
+    ///Return if `ancestor_id` is `node_id` or one of its ancestors
+    fn is_ancestor_or_self(&self, ancestor_id: NodeId, node_id: NodeId) -> bool {
+        let mut current_id_opt = Some(node_id);
+        while let Some(current_id) = current_id_opt {
+            if current_id == ancestor_id {
+                return true;
+            }
+            current_id_opt = self.get_node(current_id).and_then(SceneNode::get_parent);
+        }
+        false
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{
+    camera::{Camera, Projection},
+    math::{
+        matrix::Mat4,
+        vector::{Vec3, Vec4},
+    },
+    scene::Light,
+    vulkan::graphics_pipeline::DepthBias,
+};
+
+///Distance (in world units) behind the cascades at which the objects still cast shadows in them
+const CASCADE_CASTER_DISTANCE: f32 = 50f32;
+
+///Distance of the shadows of the spotlights without range
+const SPOT_SHADOW_DEFAULT_RANGE: f32 = 100f32;
+
+///Near distance of the shadows of the spotlights, relative to their far distance
+const SPOT_SHADOW_NEAR_RATIO: f32 = 0.001f32;
+
+///Maximum field of view (in radians) of the shadows of the spotlights, whose projection is degenerate at PI
+const SPOT_SHADOW_MAX_FOV: f32 = 3f32;
+
+///Size of the shadow atlas and of its square tiles, and the split distances of the cascaded shadow maps
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShadowConfig {
+    ///The width and height (in texels) of the shadow atlas
+    pub atlas_size: u32,
+
+    ///The width and height (in texels) of a tile of the shadow atlas, rendering one shadow view
+    pub tile_size: u32,
+
+    ///The view distance of the far end of each cascade of the directional lights, increasing,
+    ///the first cascade starting at the near plane of the camera
+    ///
+    ///A cascade is rendered in its own tile, the directional lights have a cascade by split distance
+    pub cascade_split_distances: Vec<f32>,
+
+    ///The bias added to the depth of the shadow maps, avoiding the shadow acne
+    pub depth_bias: DepthBias,
+}
+
+impl Default for ShadowConfig {
+    ///An atlas of 4096x4096 texels with 16 tiles, and 4 cascades up to 100 units from the camera
+    fn default() -> Self {
+        Self {
+            atlas_size: 4096,
+            tile_size: 1024,
+            cascade_split_distances: vec![6f32, 16f32, 40f32, 100f32],
+            depth_bias: DepthBias {
+                constant_factor: 1.25f32,
+                slope_factor: 1.75f32,
+            },
+        }
+    }
+}
+
+impl ShadowConfig {
+    ///Return the number of tiles in the shadow atlas, which is the maximum number of shadow views
+    pub fn get_tile_count(&self) -> usize {
+        let tiles_per_row = (self.atlas_size / self.tile_size.max(1)) as usize;
+        tiles_per_row * tiles_per_row
+    }
+
+    ///Return the offset (in texels) of the top left corner of the tile `tile_index` in the atlas
+    ///
+    ///The tiles are ordered by row, from the top left corner of the atlas
+    pub fn get_tile_offset(&self, tile_index: usize) -> [u32; 2] {
+        let tiles_per_row = (self.atlas_size / self.tile_size.max(1)).max(1) as usize;
+
+        [
+            (tile_index % tiles_per_row) as u32 * self.tile_size,
+            (tile_index / tiles_per_row) as u32 * self.tile_size,
+        ]
+    }
+
+    ///Return the rectangle of the tile `tile_index` in the texture coordinates of the atlas:
+    ///the offset of its top left corner in XY and its size in ZW
+    pub fn get_tile_uv_rect(&self, tile_index: usize) -> Vec4 {
+        let [x, y] = self.get_tile_offset(tile_index);
+        let atlas_size = self.atlas_size as f32;
+        let tile_size = self.tile_size as f32 / atlas_size;
+
+        Vec4::new(
+            x as f32 / atlas_size,
+            y as f32 / atlas_size,
+            tile_size,
+            tile_size,
+        )
+    }
+}
+
+///View of a light rendered in a tile of the shadow atlas
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowView {
+    ///Transforms the world space to the clip space of the light
+    pub view_projection: Mat4,
+
+    ///The view distance from the camera at which the cascade of a directional light ends, 0 for the spotlights
+    pub split_distance: f32,
+}
+
+///Return the shadow views of `light` placed by `world_matrix`: a view by cascade for the directional lights,
+///a view for the spotlights and none for the point lights
+///
+/// # Arguments
+///
+/// * `light` - the light casting the shadows toward the -Z axis of `world_matrix`
+/// * `world_matrix` - the transform of the light to the world space
+/// * `camera` - the camera viewing the shadows, whose view is split in cascades
+/// * `shadow_config` - the config of the cascades and of the tiles
+pub fn get_light_shadow_views(
+    light: &Light,
+    world_matrix: &Mat4,
+    camera: &Camera,
+    shadow_config: &ShadowConfig,
+) -> Vec<ShadowView> {
+    let position = world_matrix.transform_point3(Vec3::ZERO);
+    let direction = world_matrix.transform_vector3(-Vec3::Z).normalize();
+
+    match *light {
+        Light::Directional { .. } => {
+            let z_near = match camera.projection {
+                Projection::Perspective { z_near, .. }
+                | Projection::Orthographic { z_near, .. } => z_near,
+            };
+
+            let mut near_distance = z_near;
+            shadow_config
+                .cascade_split_distances
+                .iter()
+                .map(|&split_distance| {
+                    let view_projection = get_cascade_view_projection(
+                        camera,
+                        direction,
+                        (near_distance, split_distance),
+                        shadow_config.tile_size,
+                    );
+                    near_distance = split_distance;
+
+                    ShadowView {
+                        view_projection,
+                        split_distance,
+                    }
+                })
+                .collect()
+        }
+        Light::Point { .. } => Vec::new(),
+        Light::Spot {
+            range,
+            outer_cone_angle,
+            ..
+        } => vec![ShadowView {
+            view_projection: get_spot_light_view_projection(
+                position,
+                direction,
+                outer_cone_angle,
+                range,
+            ),
+            split_distance: 0f32,
+        }],
+    }
+}
+
+///Return the perspective view projection of a spotlight at `position` toward `direction`, covering its outer cone up to `range`
+///
+///The shadows of the spotlights without range end at SPOT_SHADOW_DEFAULT_RANGE
+pub fn get_spot_light_view_projection(
+    position: Vec3,
+    direction: Vec3,
+    outer_cone_angle: f32,
+    range: Option<f32>,
+) -> Mat4 {
+    let z_far = range.unwrap_or(SPOT_SHADOW_DEFAULT_RANGE);
+    let fov = (outer_cone_angle * 2f32).min(SPOT_SHADOW_MAX_FOV);
+
+    Mat4::perspective(fov, 1f32, z_far * SPOT_SHADOW_NEAR_RATIO, z_far)
+        * Mat4::look_to(position, direction, get_light_up(direction))
+}
+
+///Return the orthographic view projection of a directional light toward `direction`,
+///covering the part of the view of `camera` from the first to the second of `distances`
+///
+///The projection covers the bounding sphere of the part of the view, moved by whole texels of a tile of `tile_size`,
+///so the shadows don't shimmer when the camera moves or turns
+pub fn get_cascade_view_projection(
+    camera: &Camera,
+    direction: Vec3,
+    distances: (f32, f32),
+    tile_size: u32,
+) -> Mat4 {
+    let projection = camera.get_projection_matrix();
+    let Some(inverse_view_projection) = (projection * camera.get_view_matrix()).inverse() else {
+        return Mat4::IDENTITY;
+    };
+
+    //The corners of the part of the view, unprojected from the depths of its near and far distances
+    let all_corners: Vec<Vec3> = [distances.0, distances.1]
+        .into_iter()
+        .flat_map(|distance| {
+            let depth = projection
+                .transform_point3(Vec3::new(0f32, 0f32, -distance))
+                .z;
+            [(-1f32, -1f32), (1f32, -1f32), (-1f32, 1f32), (1f32, 1f32)]
+                .map(|(x, y)| inverse_view_projection.transform_point3(Vec3::new(x, y, depth)))
+        })
+        .collect();
+
+    let center = all_corners
+        .iter()
+        .fold(Vec3::ZERO, |sum, &corner| sum + corner)
+        / all_corners.len() as f32;
+    //The radius is rounded up, so it doesn't change with the rounding errors when the camera turns
+    let radius = all_corners
+        .iter()
+        .map(|&corner| (corner - center).length())
+        .fold(0f32, f32::max)
+        .ceil()
+        .max(1f32);
+
+    //The center is snapped to the texels in the light space
+    let up = get_light_up(direction);
+    let light_rotation = Mat4::look_to(Vec3::ZERO, direction, up);
+    let texel_size = radius * 2f32 / tile_size.max(1) as f32;
+    let light_center = light_rotation.transform_point3(center);
+    let snapped_light_center = Vec3::new(
+        (light_center.x / texel_size).floor() * texel_size,
+        (light_center.y / texel_size).floor() * texel_size,
+        light_center.z,
+    );
+    let center = light_rotation
+        .inverse()
+        .map_or(center, |inverse_light_rotation| {
+            inverse_light_rotation.transform_point3(snapped_light_center)
+        });
+
+    let eye = center - direction * (radius + CASCADE_CASTER_DISTANCE);
+    Mat4::orthographic(
+        -radius,
+        radius,
+        -radius,
+        radius,
+        0f32,
+        radius * 2f32 + CASCADE_CASTER_DISTANCE,
+    ) * Mat4::look_to(eye, direction, up)
+}
+
+///Return the up vector of the views of a light toward `direction`, which isn't parallel to it
+fn get_light_up(direction: Vec3) -> Vec3 {
+    if direction.y.abs() > 0.99f32 {
+        Vec3::Z
+    } else {
+        Vec3::Y
+    }
+}
@@ -0,0 +1,183 @@
+use ash::vk;
+
+use crate::vulkan::{command_buffer::VulkanCommandPool, device::VulkanDevice};
+
+///Represent a Vulkan buffer and the device memory bound to it
+pub struct VulkanBuffer<'vulkan_device> {
+    ///A reference to the Vulkan Device which created this buffer
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan buffer
+    buffer: vk::Buffer,
+
+    ///The device memory bound to `buffer`, owned by this buffer
+    memory: vk::DeviceMemory,
+
+    ///The size (in bytes) of `buffer`
+    size: vk::DeviceSize,
+
+    ///The properties of the memory type of `memory`
+    ///
+    ///For example, `memory` can be mapped if it's HOST_VISIBLE
+    memory_property_flags: vk::MemoryPropertyFlags,
+}
+
+impl<'vulkan_device> VulkanBuffer<'vulkan_device> {
+    ///Create a buffer of `size` bytes used for `usage`, bound to a new memory having all `memory_property_flags`
+    pub fn new(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        size: vk::DeviceSize,
+        usage: vk::BufferUsageFlags,
+        memory_property_flags: vk::MemoryPropertyFlags,
+    ) -> Self {
+        let buffer_create_info = vk::BufferCreateInfo::default()
+            .size(size)
+            .usage(usage)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        let buffer = vulkan_device
+            .create_buffer(&buffer_create_info)
+            .expect("failed to create Vulkan buffer");
+
+        let memory_requirements = vulkan_device.get_buffer_memory_requirements(buffer);
+        let memory = vulkan_device
+            .allocate_memory_for_requirements(memory_requirements, memory_property_flags);
+
+        vulkan_device
+            .bind_buffer_memory(buffer, memory, 0)
+            .expect("failed to bind Vulkan buffer memory");
+
+        Self {
+            vulkan_device,
+            buffer,
+            memory,
+            size,
+            memory_property_flags,
+        }
+    }
+
+    ///Create a buffer visible by the host containing `bytes`, used as source of transfers
+    ///
+    ///For example, can be used to upload data to an image in a memory only visible by the device
+    pub fn new_staging_with_bytes(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        bytes: &[u8],
+    ) -> Self {
+        let staging_buffer = Self::new(
+            vulkan_device,
+            bytes.len() as vk::DeviceSize,
+            vk::BufferUsageFlags::TRANSFER_SRC,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        );
+        staging_buffer.write_bytes(0, bytes);
+
+        staging_buffer
+    }
+
+    ///Create a buffer only visible by the device containing `bytes`, uploaded through a staging buffer
+    ///
+    ///For example, can be used for vertex and index buffers which are never updated
+    ///
+    /// # Arguments
+    ///
+    /// * `vulkan_device` - a reference to a VulkanDevice
+    /// * `command_pool` - the command pool allocating the command buffer uploading `bytes`
+    /// * `bytes` - the content of the buffer, which mustn't be empty
+    /// * `usage` - how the buffer is used, in addition to being the destination of the upload
+    pub fn new_device_local_with_bytes(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        command_pool: &VulkanCommandPool,
+        bytes: &[u8],
+        usage: vk::BufferUsageFlags,
+    ) -> Self {
+        let size = bytes.len() as vk::DeviceSize;
+        let device_local_buffer = Self::new(
+            vulkan_device,
+            size,
+            usage | vk::BufferUsageFlags::TRANSFER_DST,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL,
+        );
+
+        let staging_buffer = Self::new_staging_with_bytes(vulkan_device, bytes);
+        command_pool.execute_one_time_commands(|command_buffer| {
+            command_buffer.copy_buffer(
+                staging_buffer.get_buffer(),
+                device_local_buffer.get_buffer(),
+                &[vk::BufferCopy::default().size(size)],
+            );
+        });
+
+        device_local_buffer
+    }
+
+    pub fn get_buffer(&self) -> vk::Buffer {
+        self.buffer
+    }
+
+    pub fn get_size(&self) -> vk::DeviceSize {
+        self.size
+    }
+
+    ///Copy `bytes` in the memory of `&self`, from `offset` (in bytes)
+    ///
+    /// # Panics
+    ///
+    /// If the memory of `&self` isn't HOST_VISIBLE and HOST_COHERENT, or `bytes` are outside of `&self`
+    pub fn write_bytes(&self, offset: vk::DeviceSize, bytes: &[u8]) {
+        assert!(
+            offset + bytes.len() as vk::DeviceSize <= self.size,
+            "{} bytes from {offset} are outside of the buffer ({} bytes)",
+            bytes.len(),
+            self.size
+        );
+
+        let mapped_memory = self.map_memory();
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                mapped_memory.add(offset as usize),
+                bytes.len(),
+            );
+        }
+        self.vulkan_device.unmap_memory(self.memory);
+    }
+
+    ///Return a copy of all the bytes in the memory of `&self`
+    ///
+    /// # Panics
+    ///
+    /// If the memory of `&self` isn't HOST_VISIBLE and HOST_COHERENT
+    pub fn read_bytes(&self) -> Vec<u8> {
+        let mapped_memory = self.map_memory();
+        let bytes =
+            unsafe { std::slice::from_raw_parts(mapped_memory, self.size as usize) }.to_vec();
+        self.vulkan_device.unmap_memory(self.memory);
+
+        bytes
+    }
+
+    ///Map all the memory of `&self`, which must be unmapped once used
+    fn map_memory(&self) -> *mut u8 {
+        assert!(
+            self.memory_property_flags.contains(
+                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT
+            ),
+            "the memory of the buffer isn't visible by the host ({:?})",
+            self.memory_property_flags
+        );
+
+        let mapped_memory = self
+            .vulkan_device
+            .map_memory(self.memory, 0, self.size)
+            .expect("failed to map Vulkan buffer memory");
+
+        mapped_memory as *mut u8
+    }
+}
+
+impl Drop for VulkanBuffer<'_> {
+    fn drop(&mut self) {
+        self.vulkan_device.destroy_buffer(self.buffer);
+        self.vulkan_device.free_memory(self.memory);
+    }
+}
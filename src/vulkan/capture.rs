@@ -0,0 +1,325 @@
+use std::fmt::{self, Display};
+
+use ash::vk;
+
+use crate::vulkan::{
+    buffer::VulkanBuffer,
+    command_buffer::{VulkanCommandBuffer, VulkanCommandPool},
+    device::VulkanDevice,
+};
+
+///Error returned when an image cannot be captured
+#[derive(Debug)]
+pub enum CaptureError {
+    ///The format of the image cannot be converted to RGBA8
+    UnsupportedFormat(vk::Format),
+
+    ///The PNG file cannot be written
+    Image(image::ImageError),
+}
+
+impl Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::UnsupportedFormat(format) => {
+                write!(f, "image format {format:?} cannot be captured")
+            }
+            CaptureError::Image(error) => write!(f, "failed to write capture: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+impl From<image::ImageError> for CaptureError {
+    fn from(error: image::ImageError) -> Self {
+        CaptureError::Image(error)
+    }
+}
+
+///Represent the capture of a color image in a buffer visible by the host
+///
+///The copy of the image is recorded in a command buffer with `record_copy_from_image`,
+///and the capture can be read once the command buffer is executed
+pub struct VulkanImageCapture<'vulkan_device> {
+    ///The buffer receiving the texels of the captured image
+    buffer: VulkanBuffer<'vulkan_device>,
+
+    ///The format of the captured image
+    format: vk::Format,
+
+    ///The size of the captured image
+    extent: vk::Extent2D,
+}
+
+impl<'vulkan_device> VulkanImageCapture<'vulkan_device> {
+    ///Create a capture of an image of `extent` in `format`
+    ///
+    /// # Returns
+    ///
+    /// CaptureError::UnsupportedFormat if `format` cannot be converted to RGBA8
+    pub fn new(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        format: vk::Format,
+        extent: vk::Extent2D,
+    ) -> Result<Self, CaptureError> {
+        let texel_size = get_texel_size(format).ok_or(CaptureError::UnsupportedFormat(format))?;
+
+        let buffer = VulkanBuffer::new(
+            vulkan_device,
+            extent.width as vk::DeviceSize * extent.height as vk::DeviceSize * texel_size,
+            vk::BufferUsageFlags::TRANSFER_DST,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        );
+
+        Ok(Self {
+            buffer,
+            format,
+            extent,
+        })
+    }
+
+    ///Record in `command_buffer` the copy of the mip level 0 of `image`, which must have been created with the TRANSFER_SRC usage
+    ///
+    ///`image` must be in `layout` and written as a color attachment or by a transfer,
+    ///it's transitioned to TRANSFER_SRC_OPTIMAL for the copy, then back to `layout`
+    pub fn record_copy_from_image(
+        &self,
+        command_buffer: &VulkanCommandBuffer,
+        image: vk::Image,
+        layout: vk::ImageLayout,
+    ) {
+        let color_attachment_or_transfer_write = (
+            vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | vk::PipelineStageFlags::TRANSFER,
+            vk::AccessFlags::COLOR_ATTACHMENT_WRITE | vk::AccessFlags::TRANSFER_WRITE,
+        );
+        let transfer_read = (
+            vk::PipelineStageFlags::TRANSFER,
+            vk::AccessFlags::TRANSFER_READ,
+        );
+
+        command_buffer.transition_color_image_layout(
+            image,
+            layout,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            color_attachment_or_transfer_write,
+            transfer_read,
+        );
+
+        let region = vk::BufferImageCopy::default()
+            .image_subresource(
+                vk::ImageSubresourceLayers::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .mip_level(0)
+                    .base_array_layer(0)
+                    .layer_count(1),
+            )
+            .image_extent(self.extent.into());
+        command_buffer.copy_image_to_buffer(image, self.buffer.get_buffer(), &[region]);
+
+        command_buffer.transition_color_image_layout(
+            image,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            layout,
+            transfer_read,
+            color_attachment_or_transfer_write,
+        );
+
+        //The host reads the buffer once the command buffer is executed
+        command_buffer.memory_barrier(
+            (
+                vk::PipelineStageFlags::TRANSFER,
+                vk::AccessFlags::TRANSFER_WRITE,
+            ),
+            (vk::PipelineStageFlags::HOST, vk::AccessFlags::HOST_READ),
+        );
+    }
+
+    ///Return the texels of the captured image converted to RGBA8, row by row
+    ///
+    ///The texels in R16G16B16A16_SFLOAT (extended linear sRGB) are clamped to [0, 1] and encoded in sRGB.
+    ///The texels in 10 bits formats keep their encoding, so an HDR10 image stays encoded with the PQ curve, without tonemapping.
+    ///
+    ///Must be called once the command buffer recording the copy is executed
+    pub fn read_rgba8(&self) -> Vec<u8> {
+        let bytes = self.buffer.read_bytes();
+
+        convert_to_rgba8(self.format, &bytes)
+            .expect("format checked at the creation of the capture")
+    }
+
+    ///Write the captured image in the PNG file `file_path`
+    ///
+    ///Must be called once the command buffer recording the copy is executed
+    pub fn save_png(&self, file_path: &str) -> Result<(), CaptureError> {
+        image::save_buffer_with_format(
+            file_path,
+            &self.read_rgba8(),
+            self.extent.width,
+            self.extent.height,
+            image::ExtendedColorType::Rgba8,
+            image::ImageFormat::Png,
+        )?;
+
+        Ok(())
+    }
+}
+
+///Capture the mip level 0 of `image` in the PNG file `file_path`, and wait until it's written
+///
+///For example, can be used to save an offscreen color target rendered without window
+///
+/// # Arguments
+///
+/// * `vulkan_device` - a reference to a VulkanDevice
+/// * `command_pool` - the command pool allocating the command buffer copying `image`
+/// * `image` - the image to capture, created with the TRANSFER_SRC usage
+/// * `format` - the format of `image`
+/// * `extent` - the size of `image`
+/// * `layout` - the layout of `image`, which is kept after the capture
+/// * `file_path` - the path of the written PNG file
+pub fn capture_image_to_png(
+    vulkan_device: &VulkanDevice,
+    command_pool: &VulkanCommandPool,
+    image: vk::Image,
+    format: vk::Format,
+    extent: vk::Extent2D,
+    layout: vk::ImageLayout,
+    file_path: &str,
+) -> Result<(), CaptureError> {
+    let image_capture = VulkanImageCapture::new(vulkan_device, format, extent)?;
+
+    command_pool.execute_one_time_commands(|command_buffer| {
+        image_capture.record_copy_from_image(command_buffer, image, layout)
+    });
+
+    image_capture.save_png(file_path)
+}
+
+///Return the size (in bytes) of a texel in `format`, None if `format` cannot be converted to RGBA8
+fn get_texel_size(format: vk::Format) -> Option<vk::DeviceSize> {
+    match format {
+        vk::Format::R8G8B8A8_UNORM
+        | vk::Format::R8G8B8A8_SRGB
+        | vk::Format::B8G8R8A8_UNORM
+        | vk::Format::B8G8R8A8_SRGB
+        | vk::Format::A2B10G10R10_UNORM_PACK32
+        | vk::Format::A2R10G10B10_UNORM_PACK32 => Some(4),
+        vk::Format::R16G16B16A16_SFLOAT => Some(8),
+        _ => None,
+    }
+}
+
+///Convert the texels `bytes` in `format` to RGBA8, the sRGB formats are kept encoded in sRGB
+///
+///The linear colors in R16G16B16A16_SFLOAT are clamped to [0, 1] and encoded in sRGB, their alpha stays linear
+///
+/// # Returns
+///
+/// None if `format` cannot be converted to RGBA8
+fn convert_to_rgba8(format: vk::Format, bytes: &[u8]) -> Option<Vec<u8>> {
+    //Convert a 10 bits channel to 8 bits, rounded to the nearest
+    let to_8_bits = |channel_10_bits: u32| ((channel_10_bits * 255 + 511) / 1023) as u8;
+
+    let rgba8_bytes = match format {
+        vk::Format::R8G8B8A8_UNORM | vk::Format::R8G8B8A8_SRGB => bytes.to_vec(),
+        vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB => bytes
+            .chunks_exact(4)
+            .flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]])
+            .collect(),
+        vk::Format::A2B10G10R10_UNORM_PACK32 | vk::Format::A2R10G10B10_UNORM_PACK32 => bytes
+            .chunks_exact(4)
+            .flat_map(|texel_bytes| {
+                let texel = u32::from_ne_bytes(texel_bytes.try_into().unwrap());
+                let low = to_8_bits(texel & 0x3FF);
+                let green = to_8_bits((texel >> 10) & 0x3FF);
+                let high = to_8_bits((texel >> 20) & 0x3FF);
+                let alpha = ((texel >> 30) * 85) as u8;
+
+                //A2B10G10R10 stores red in the low bits, A2R10G10B10 stores blue
+                if format == vk::Format::A2B10G10R10_UNORM_PACK32 {
+                    [low, green, high, alpha]
+                } else {
+                    [high, green, low, alpha]
+                }
+            })
+            .collect(),
+        vk::Format::R16G16B16A16_SFLOAT => bytes
+            .chunks_exact(8)
+            .flat_map(|texel_bytes| {
+                let [red, green, blue, alpha] = [0, 2, 4, 6].map(|offset| {
+                    f16_to_f32(u16::from_ne_bytes([
+                        texel_bytes[offset],
+                        texel_bytes[offset + 1],
+                    ]))
+                    .clamp(0.0, 1.0)
+                });
+                let unit_to_8_bits = |channel: f32| (channel * 255.0).round() as u8;
+
+                [
+                    unit_to_8_bits(linear_to_srgb(red)),
+                    unit_to_8_bits(linear_to_srgb(green)),
+                    unit_to_8_bits(linear_to_srgb(blue)),
+                    unit_to_8_bits(alpha),
+                ]
+            })
+            .collect(),
+        _ => return None,
+    };
+
+    Some(rgba8_bytes)
+}
+
+///Convert the half-precision float `bits` to f32, the NaNs are converted to 0
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 == 0 { 1.0 } else { -1.0 };
+    let exponent = ((bits >> 10) & 0x1F) as i32;
+    let mantissa = (bits & 0x3FF) as f32;
+
+    match exponent {
+        //Subnormal numbers, without implicit leading bit
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1F if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1F => 0.0,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+///Encode the linear `channel` in [0, 1] with the sRGB transfer function
+fn linear_to_srgb(channel: f32) -> f32 {
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_floats_are_decoded() {
+        assert_eq!(f16_to_f32(0x0000), 0.0);
+        assert_eq!(f16_to_f32(0x3C00), 1.0);
+        assert_eq!(f16_to_f32(0x3800), 0.5);
+        assert_eq!(f16_to_f32(0xC000), -2.0);
+        assert_eq!(f16_to_f32(0x0001), 2f32.powi(-24));
+        assert_eq!(f16_to_f32(0x7C00), f32::INFINITY);
+        assert_eq!(f16_to_f32(0x7E00), 0.0);
+    }
+
+    #[test]
+    fn extended_linear_srgb_is_clamped_and_encoded_in_srgb() {
+        //Red brighter than white, green at half the linear intensity, negative blue and opaque alpha
+        let texel: Vec<u8> = [0x4400u16, 0x3800, 0xBC00, 0x3C00]
+            .into_iter()
+            .flat_map(u16::to_ne_bytes)
+            .collect();
+
+        assert_eq!(
+            convert_to_rgba8(vk::Format::R16G16B16A16_SFLOAT, &texel),
+            Some(vec![255, 188, 0, 255])
+        );
+    }
+}
@@ -0,0 +1,864 @@
+use std::{marker::PhantomData, ops::Range};
+
+use ash::vk;
+use bytemuck::Pod;
+
+use crate::vulkan::{
+    compute_pipeline::VulkanComputePipeline, device::VulkanDevice,
+    graphics_pipeline::VulkanGraphicsPipeline, sync::VulkanFence,
+};
+
+///Represent a Vulkan command pool, which allocates the command buffers submitted to the graphics queue
+pub struct VulkanCommandPool<'vulkan_device> {
+    ///A reference to the Vulkan Device which created this command pool
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan command pool
+    command_pool: vk::CommandPool,
+}
+
+impl<'vulkan_device> VulkanCommandPool<'vulkan_device> {
+    ///Create a command pool for the graphics queue of `vulkan_device`, whose command buffers can be reset individually
+    pub fn new_for_graphics_queue(vulkan_device: &'vulkan_device VulkanDevice) -> Self {
+        Self::new(
+            vulkan_device,
+            vulkan_device.get_graphics_queue_family_index(),
+        )
+    }
+
+    ///Create a command pool for the present queue of `vulkan_device`, whose command buffers can be reset individually
+    ///
+    ///For example, can be used to acquire the ownership of the swapchain images on the present queue
+    pub fn new_for_present_queue(vulkan_device: &'vulkan_device VulkanDevice) -> Self {
+        Self::new(
+            vulkan_device,
+            vulkan_device.get_present_queue_family_index(),
+        )
+    }
+
+    ///Create a command pool for the queue family `queue_family_index`, whose command buffers can be reset individually
+    fn new(vulkan_device: &'vulkan_device VulkanDevice, queue_family_index: u32) -> Self {
+        let command_pool_create_info = vk::CommandPoolCreateInfo::default()
+            .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+            .queue_family_index(queue_family_index);
+
+        let command_pool = vulkan_device
+            .create_command_pool(&command_pool_create_info)
+            .expect("failed to create Vulkan command pool");
+
+        Self {
+            vulkan_device,
+            command_pool,
+        }
+    }
+
+    ///Allocate `count` primary command buffers from `&self`
+    ///
+    ///They are freed when `&self` is dropped
+    pub fn allocate_command_buffers<'command_pool>(
+        &'command_pool self,
+        count: u32,
+    ) -> Vec<VulkanCommandBuffer<'command_pool, 'vulkan_device>> {
+        let command_buffer_allocate_info = vk::CommandBufferAllocateInfo::default()
+            .command_pool(self.command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(count);
+
+        let all_command_buffers = self
+            .vulkan_device
+            .allocate_command_buffers(&command_buffer_allocate_info)
+            .expect("failed to allocate Vulkan command buffers");
+
+        all_command_buffers
+            .into_iter()
+            .map(|command_buffer| VulkanCommandBuffer {
+                vulkan_device: self.vulkan_device,
+                command_buffer,
+                phantom_data_command_pool: PhantomData,
+            })
+            .collect()
+    }
+
+    ///Record commands with `record_commands` in a new command buffer, submit it to the graphics queue and wait until it's executed
+    ///
+    ///For example, can be used to upload data to an image before rendering
+    pub fn execute_one_time_commands<R>(
+        &self,
+        record_commands: impl FnOnce(&VulkanCommandBuffer) -> R,
+    ) -> R {
+        let all_command_buffers = self.allocate_command_buffers(1);
+        let command_buffer = &all_command_buffers[0];
+
+        command_buffer.begin();
+        let result = record_commands(command_buffer);
+        command_buffer.end();
+
+        let fence = VulkanFence::new(self.vulkan_device, false);
+        let command_buffers = [command_buffer.command_buffer];
+        let submit_info = vk::SubmitInfo::default().command_buffers(&command_buffers);
+        self.vulkan_device
+            .submit_to_graphics_queue(&[submit_info], fence.get_fence())
+            .expect("failed to submit one time commands to graphics queue");
+        fence.wait();
+
+        self.vulkan_device
+            .free_command_buffers(self.command_pool, &command_buffers);
+
+        result
+    }
+}
+
+impl Drop for VulkanCommandPool<'_> {
+    fn drop(&mut self) {
+        self.vulkan_device.destroy_command_pool(self.command_pool);
+    }
+}
+
+///Represent a Vulkan command buffer, used to record the commands submitted to a queue
+///
+///The recording functions must be called between `begin` and `end`
+pub struct VulkanCommandBuffer<'command_pool, 'vulkan_device> {
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan command buffer
+    command_buffer: vk::CommandBuffer,
+
+    ///PhantomData used to keep the fact that a command buffer cannot be used without its command pool
+    phantom_data_command_pool: PhantomData<&'command_pool VulkanCommandPool<'vulkan_device>>,
+}
+
+impl VulkanCommandBuffer<'_, '_> {
+    pub fn get_command_buffer(&self) -> vk::CommandBuffer {
+        self.command_buffer
+    }
+
+    ///Reset `&self` and start the recording of commands, submitted only once
+    pub fn begin(&self) {
+        self.vulkan_device
+            .reset_command_buffer(self.command_buffer)
+            .expect("failed to reset Vulkan command buffer");
+
+        let command_buffer_begin_info = vk::CommandBufferBeginInfo::default()
+            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+
+        self.vulkan_device
+            .begin_command_buffer(self.command_buffer, &command_buffer_begin_info)
+            .expect("failed to begin Vulkan command buffer");
+    }
+
+    ///Finish the recording of commands
+    pub fn end(&self) {
+        self.vulkan_device
+            .end_command_buffer(self.command_buffer)
+            .expect("failed to end Vulkan command buffer");
+    }
+
+    ///Begin the render pass of `graphics_pipeline` in `framebuffer`, clearing the color attachment with `clear_color`
+    ///
+    ///`clear_color` is ignored if `graphics_pipeline` only renders the depth
+    ///
+    /// # Panics
+    ///
+    /// If `graphics_pipeline` is used with the dynamic rendering
+    pub fn begin_render_pass(
+        &self,
+        graphics_pipeline: &VulkanGraphicsPipeline,
+        framebuffer: vk::Framebuffer,
+        extent: vk::Extent2D,
+        clear_color: [f32; 4],
+    ) {
+        //The depth attachment, if any, is cleared to the farthest depth
+        let clear_values = [
+            vk::ClearValue {
+                color: vk::ClearColorValue {
+                    float32: clear_color,
+                },
+            },
+            vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue {
+                    depth: 1f32,
+                    stencil: 0,
+                },
+            },
+        ];
+        let first_clear_value = if graphics_pipeline.get_color_format().is_some() {
+            0
+        } else {
+            1
+        };
+        let clear_value_count = if graphics_pipeline.get_depth_format().is_some() {
+            2
+        } else {
+            1
+        };
+
+        let render_pass_begin_info = vk::RenderPassBeginInfo::default()
+            .render_pass(
+                graphics_pipeline
+                    .get_render_pass()
+                    .expect("graphics pipeline without render pass"),
+            )
+            .framebuffer(framebuffer)
+            .render_area(vk::Rect2D::default().extent(extent))
+            .clear_values(&clear_values[first_clear_value..clear_value_count]);
+
+        self.vulkan_device
+            .cmd_begin_render_pass(self.command_buffer, &render_pass_begin_info);
+    }
+
+    pub fn end_render_pass(&self) {
+        self.vulkan_device.cmd_end_render_pass(self.command_buffer);
+    }
+
+    ///Begin a dynamic rendering in `color_image_view` on all `extent`, clearing it with `clear_color`
+    ///
+    ///The depth is tested in `depth_image_view` if it isn't None, cleared to the farthest depth
+    ///
+    ///The image of `color_image_view` must be in the COLOR_ATTACHMENT_OPTIMAL layout,
+    ///and the image of `depth_image_view` in the DEPTH_STENCIL_ATTACHMENT_OPTIMAL layout
+    ///
+    /// # Panics
+    ///
+    /// If the dynamic rendering isn't enabled on the device
+    pub fn begin_rendering(
+        &self,
+        color_image_view: vk::ImageView,
+        depth_image_view: Option<vk::ImageView>,
+        extent: vk::Extent2D,
+        clear_color: [f32; 4],
+    ) {
+        let color_attachment = vk::RenderingAttachmentInfo::default()
+            .image_view(color_image_view)
+            .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(vk::AttachmentStoreOp::STORE)
+            .clear_value(vk::ClearValue {
+                color: vk::ClearColorValue {
+                    float32: clear_color,
+                },
+            });
+
+        let depth_attachment = depth_image_view.map(|depth_image_view| {
+            vk::RenderingAttachmentInfo::default()
+                .image_view(depth_image_view)
+                .image_layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL)
+                .load_op(vk::AttachmentLoadOp::CLEAR)
+                .store_op(vk::AttachmentStoreOp::DONT_CARE)
+                .clear_value(vk::ClearValue {
+                    depth_stencil: vk::ClearDepthStencilValue {
+                        depth: 1f32,
+                        stencil: 0,
+                    },
+                })
+        });
+
+        let color_attachments = [color_attachment];
+        let mut rendering_info = vk::RenderingInfo::default()
+            .render_area(vk::Rect2D::default().extent(extent))
+            .layer_count(1)
+            .color_attachments(&color_attachments);
+        if let Some(depth_attachment) = depth_attachment.as_ref() {
+            rendering_info = rendering_info.depth_attachment(depth_attachment);
+        }
+
+        self.vulkan_device
+            .cmd_begin_dynamic_rendering(self.command_buffer, &rendering_info);
+    }
+
+    ///Begin a dynamic rendering only in `depth_image_view` on all `extent`, cleared to the farthest depth and stored
+    ///
+    ///The image of `depth_image_view` must be in the DEPTH_STENCIL_ATTACHMENT_OPTIMAL layout
+    ///
+    /// # Panics
+    ///
+    /// If the dynamic rendering isn't enabled on the device
+    pub fn begin_depth_rendering(&self, depth_image_view: vk::ImageView, extent: vk::Extent2D) {
+        let depth_attachment = vk::RenderingAttachmentInfo::default()
+            .image_view(depth_image_view)
+            .image_layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL)
+            .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(vk::AttachmentStoreOp::STORE)
+            .clear_value(vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue {
+                    depth: 1f32,
+                    stencil: 0,
+                },
+            });
+
+        let rendering_info = vk::RenderingInfo::default()
+            .render_area(vk::Rect2D::default().extent(extent))
+            .layer_count(1)
+            .depth_attachment(&depth_attachment);
+
+        self.vulkan_device
+            .cmd_begin_dynamic_rendering(self.command_buffer, &rendering_info);
+    }
+
+    pub fn end_rendering(&self) {
+        self.vulkan_device
+            .cmd_end_dynamic_rendering(self.command_buffer);
+    }
+
+    ///Transition the layout of the color `image` from `old_layout` to `new_layout`
+    ///
+    ///The transition waits for the `src_access_mask` accesses of the `src_stage_mask` stages,
+    ///and the `dst_access_mask` accesses of the `dst_stage_mask` stages wait for the transition
+    pub fn transition_color_image_layout(
+        &self,
+        image: vk::Image,
+        old_layout: vk::ImageLayout,
+        new_layout: vk::ImageLayout,
+        src_stage_and_access_masks: (vk::PipelineStageFlags, vk::AccessFlags),
+        dst_stage_and_access_masks: (vk::PipelineStageFlags, vk::AccessFlags),
+    ) {
+        self.transition_image_layout(
+            image,
+            vk::ImageSubresourceRange::default()
+                .aspect_mask(vk::ImageAspectFlags::COLOR)
+                .base_mip_level(0)
+                .level_count(vk::REMAINING_MIP_LEVELS)
+                .base_array_layer(0)
+                .layer_count(vk::REMAINING_ARRAY_LAYERS),
+            old_layout,
+            new_layout,
+            src_stage_and_access_masks,
+            dst_stage_and_access_masks,
+        );
+    }
+
+    ///Transition the layout of the `subresource_range` of `image` from `old_layout` to `new_layout`
+    ///
+    ///The transition waits for the `src_access_mask` accesses of the `src_stage_mask` stages,
+    ///and the `dst_access_mask` accesses of the `dst_stage_mask` stages wait for the transition
+    pub fn transition_image_layout(
+        &self,
+        image: vk::Image,
+        subresource_range: vk::ImageSubresourceRange,
+        old_layout: vk::ImageLayout,
+        new_layout: vk::ImageLayout,
+        (src_stage_mask, src_access_mask): (vk::PipelineStageFlags, vk::AccessFlags),
+        (dst_stage_mask, dst_access_mask): (vk::PipelineStageFlags, vk::AccessFlags),
+    ) {
+        let image_memory_barrier = vk::ImageMemoryBarrier::default()
+            .src_access_mask(src_access_mask)
+            .dst_access_mask(dst_access_mask)
+            .old_layout(old_layout)
+            .new_layout(new_layout)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(subresource_range);
+
+        self.vulkan_device.cmd_pipeline_barrier(
+            self.command_buffer,
+            (src_stage_mask, dst_stage_mask),
+            &[],
+            &[],
+            &[image_memory_barrier],
+        );
+    }
+
+    ///Record the release or the acquire of the ownership of the color `image` in `layout`,
+    ///transferred from the queue family `src_queue_family_index` to `dst_queue_family_index`
+    ///
+    ///The transfer is only complete once the release is executed on a queue of the source family,
+    ///then the acquire (with the same families and layout) on a queue of the destination family.
+    ///For a release, the `dst_access_mask` is ignored, and for an acquire, the `src_access_mask` is ignored
+    pub fn transfer_color_image_ownership(
+        &self,
+        image: vk::Image,
+        layout: vk::ImageLayout,
+        (src_queue_family_index, dst_queue_family_index): (u32, u32),
+        (src_stage_mask, src_access_mask): (vk::PipelineStageFlags, vk::AccessFlags),
+        (dst_stage_mask, dst_access_mask): (vk::PipelineStageFlags, vk::AccessFlags),
+    ) {
+        let image_memory_barrier = vk::ImageMemoryBarrier::default()
+            .src_access_mask(src_access_mask)
+            .dst_access_mask(dst_access_mask)
+            .old_layout(layout)
+            .new_layout(layout)
+            .src_queue_family_index(src_queue_family_index)
+            .dst_queue_family_index(dst_queue_family_index)
+            .image(image)
+            .subresource_range(
+                vk::ImageSubresourceRange::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .base_mip_level(0)
+                    .level_count(vk::REMAINING_MIP_LEVELS)
+                    .base_array_layer(0)
+                    .layer_count(vk::REMAINING_ARRAY_LAYERS),
+            );
+
+        self.vulkan_device.cmd_pipeline_barrier(
+            self.command_buffer,
+            (src_stage_mask, dst_stage_mask),
+            &[],
+            &[],
+            &[image_memory_barrier],
+        );
+    }
+
+    ///Copy the `all_regions` of `buffer` to `image`, which must be in the TRANSFER_DST_OPTIMAL layout
+    pub fn copy_buffer_to_image(
+        &self,
+        buffer: vk::Buffer,
+        image: vk::Image,
+        all_regions: &[vk::BufferImageCopy],
+    ) {
+        self.vulkan_device.cmd_copy_buffer_to_image(
+            self.command_buffer,
+            buffer,
+            (image, vk::ImageLayout::TRANSFER_DST_OPTIMAL),
+            all_regions,
+        );
+    }
+
+    ///Copy the `all_regions` of `src_buffer` to `dst_buffer`
+    pub fn copy_buffer(
+        &self,
+        src_buffer: vk::Buffer,
+        dst_buffer: vk::Buffer,
+        all_regions: &[vk::BufferCopy],
+    ) {
+        self.vulkan_device.cmd_copy_buffer(
+            self.command_buffer,
+            src_buffer,
+            dst_buffer,
+            all_regions,
+        );
+    }
+
+    ///Copy the `all_regions` of `image`, which must be in the TRANSFER_SRC_OPTIMAL layout, to `buffer`
+    pub fn copy_image_to_buffer(
+        &self,
+        image: vk::Image,
+        buffer: vk::Buffer,
+        all_regions: &[vk::BufferImageCopy],
+    ) {
+        self.vulkan_device.cmd_copy_image_to_buffer(
+            self.command_buffer,
+            (image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL),
+            buffer,
+            all_regions,
+        );
+    }
+
+    ///Make the `src_access_mask` accesses of the `src_stage_mask` stages visible to the `dst_access_mask` accesses of the `dst_stage_mask` stages
+    pub fn memory_barrier(
+        &self,
+        (src_stage_mask, src_access_mask): (vk::PipelineStageFlags, vk::AccessFlags),
+        (dst_stage_mask, dst_access_mask): (vk::PipelineStageFlags, vk::AccessFlags),
+    ) {
+        let memory_barrier = vk::MemoryBarrier::default()
+            .src_access_mask(src_access_mask)
+            .dst_access_mask(dst_access_mask);
+
+        self.vulkan_device.cmd_pipeline_barrier(
+            self.command_buffer,
+            (src_stage_mask, dst_stage_mask),
+            &[memory_barrier],
+            &[],
+            &[],
+        );
+    }
+
+    ///Blit `region` from `src_image` in the TRANSFER_SRC_OPTIMAL layout to `dst_image` in the TRANSFER_DST_OPTIMAL layout, scaled with `filter`
+    pub fn blit_image(
+        &self,
+        src_image: vk::Image,
+        dst_image: vk::Image,
+        region: vk::ImageBlit,
+        filter: vk::Filter,
+    ) {
+        self.vulkan_device.cmd_blit_image(
+            self.command_buffer,
+            (src_image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL),
+            (dst_image, vk::ImageLayout::TRANSFER_DST_OPTIMAL),
+            &[region],
+            filter,
+        );
+    }
+
+    ///Bind `graphics_pipeline` and set its dynamic viewport and scissor
+    pub fn bind_graphics_pipeline(&self, graphics_pipeline: &VulkanGraphicsPipeline) {
+        self.vulkan_device.cmd_bind_pipeline(
+            self.command_buffer,
+            vk::PipelineBindPoint::GRAPHICS,
+            graphics_pipeline.get_pipeline(),
+        );
+
+        self.set_viewport_and_scissor(
+            graphics_pipeline.get_viewport(),
+            graphics_pipeline.get_scissor(),
+        );
+    }
+
+    ///Bind `all_descriptor_sets` to the sets of the pipeline layout of `graphics_pipeline`, from the set `first_set`
+    pub fn bind_descriptor_sets(
+        &self,
+        graphics_pipeline: &VulkanGraphicsPipeline,
+        first_set: u32,
+        all_descriptor_sets: &[vk::DescriptorSet],
+    ) {
+        self.vulkan_device.cmd_bind_descriptor_sets(
+            self.command_buffer,
+            vk::PipelineBindPoint::GRAPHICS,
+            graphics_pipeline.get_pipeline_layout(),
+            first_set,
+            all_descriptor_sets,
+        );
+    }
+
+    ///Set the dynamic viewport and scissor, overriding the ones set when the graphics pipeline was bound
+    pub fn set_viewport_and_scissor(&self, viewport: vk::Viewport, scissor: vk::Rect2D) {
+        self.vulkan_device
+            .cmd_set_viewport(self.command_buffer, viewport);
+        self.vulkan_device
+            .cmd_set_scissor(self.command_buffer, scissor);
+    }
+
+    ///Update the push constants of `graphics_pipeline` used by `stage_flags`, from `offset` (in bytes) with `constants`
+    ///
+    /// # Panics
+    ///
+    /// If `offset` or the size of `constants` isn't a multiple of 4,
+    /// if the bytes of `constants` are outside of `maxPushConstantsSize` of the device,
+    /// or aren't declared for all `stage_flags` by the push constant ranges of `graphics_pipeline`
+    pub fn push_constants<T: Pod>(
+        &self,
+        graphics_pipeline: &VulkanGraphicsPipeline,
+        stage_flags: vk::ShaderStageFlags,
+        offset: u32,
+        constants: &T,
+    ) {
+        self.push_constants_to_layout(
+            graphics_pipeline.get_pipeline_layout(),
+            graphics_pipeline.get_all_push_constant_ranges(),
+            stage_flags,
+            offset,
+            constants,
+        );
+    }
+
+    ///Bind `compute_pipeline`, run by the following dispatches
+    pub fn bind_compute_pipeline(&self, compute_pipeline: &VulkanComputePipeline) {
+        self.vulkan_device.cmd_bind_pipeline(
+            self.command_buffer,
+            vk::PipelineBindPoint::COMPUTE,
+            compute_pipeline.get_pipeline(),
+        );
+    }
+
+    ///Bind `all_descriptor_sets` to the sets of the pipeline layout of `compute_pipeline`, from the set `first_set`
+    pub fn bind_compute_descriptor_sets(
+        &self,
+        compute_pipeline: &VulkanComputePipeline,
+        first_set: u32,
+        all_descriptor_sets: &[vk::DescriptorSet],
+    ) {
+        self.vulkan_device.cmd_bind_descriptor_sets(
+            self.command_buffer,
+            vk::PipelineBindPoint::COMPUTE,
+            compute_pipeline.get_pipeline_layout(),
+            first_set,
+            all_descriptor_sets,
+        );
+    }
+
+    ///Update the push constants of `compute_pipeline` used by the compute stage, from `offset` (in bytes) with `constants`
+    ///
+    /// # Panics
+    ///
+    /// As `push_constants`, if the bytes of `constants` aren't declared for the compute stage
+    pub fn push_compute_constants<T: Pod>(
+        &self,
+        compute_pipeline: &VulkanComputePipeline,
+        offset: u32,
+        constants: &T,
+    ) {
+        self.push_constants_to_layout(
+            compute_pipeline.get_pipeline_layout(),
+            compute_pipeline.get_all_push_constant_ranges(),
+            vk::ShaderStageFlags::COMPUTE,
+            offset,
+            constants,
+        );
+    }
+
+    ///Run the bound compute pipeline in `group_counts` workgroups along X, Y and Z
+    pub fn dispatch(&self, group_counts: [u32; 3]) {
+        self.vulkan_device.cmd_dispatch(
+            self.command_buffer,
+            group_counts[0],
+            group_counts[1],
+            group_counts[2],
+        );
+    }
+
+    ///Update the push constants of `pipeline_layout` declaring `all_push_constant_ranges`, after checking them
+    fn push_constants_to_layout<T: Pod>(
+        &self,
+        pipeline_layout: vk::PipelineLayout,
+        all_push_constant_ranges: &[vk::PushConstantRange],
+        stage_flags: vk::ShaderStageFlags,
+        offset: u32,
+        constants: &T,
+    ) {
+        let constants_bytes = bytemuck::bytes_of(constants);
+        let constants_range = offset..offset + constants_bytes.len() as u32;
+
+        assert!(
+            offset.is_multiple_of(4) && constants_bytes.len().is_multiple_of(4),
+            "push constants {constants_range:?} must have an offset and a size multiple of 4"
+        );
+
+        let max_push_constants_size = self.vulkan_device.get_limits().max_push_constants_size;
+        assert!(
+            constants_range.end <= max_push_constants_size,
+            "push constants {constants_range:?} are outside of the maximum push constants size of the device ({max_push_constants_size} bytes)"
+        );
+
+        assert!(
+            is_push_constants_range_declared(
+                all_push_constant_ranges,
+                stage_flags,
+                &constants_range
+            ),
+            "push constants {constants_range:?} for {stage_flags:?} aren't declared by the pipeline layout ({all_push_constant_ranges:?})"
+        );
+
+        self.vulkan_device.cmd_push_constants(
+            self.command_buffer,
+            pipeline_layout,
+            stage_flags,
+            offset,
+            constants_bytes,
+        );
+    }
+
+    ///Bind `all_buffers` to the vertex input bindings from `first_binding`, each read from the start
+    pub fn bind_vertex_buffers(&self, first_binding: u32, all_buffers: &[vk::Buffer]) {
+        let all_offsets = vec![0; all_buffers.len()];
+
+        self.vulkan_device.cmd_bind_vertex_buffers(
+            self.command_buffer,
+            first_binding,
+            all_buffers,
+            &all_offsets,
+        );
+    }
+
+    ///Bind `buffer` containing indices of `index_type`, read from the start
+    pub fn bind_index_buffer(&self, buffer: vk::Buffer, index_type: vk::IndexType) {
+        self.vulkan_device
+            .cmd_bind_index_buffer(self.command_buffer, buffer, 0, index_type);
+    }
+
+    pub fn draw_indexed(
+        &self,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) {
+        self.vulkan_device.cmd_draw_indexed(
+            self.command_buffer,
+            index_count,
+            instance_count,
+            first_index,
+            vertex_offset,
+            first_instance,
+        );
+    }
+
+    pub fn draw(
+        &self,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) {
+        self.vulkan_device.cmd_draw(
+            self.command_buffer,
+            vertex_count,
+            instance_count,
+            first_vertex,
+            first_instance,
+        );
+    }
+}
+
+///Return if `constants_range` can be updated for `stage_flags` with the pipeline layout declaring `all_push_constant_ranges`
+///
+///As required by vkCmdPushConstants, each byte of `constants_range` must be declared for each stage of `stage_flags`,
+///and each range overlapping `constants_range` must only be declared for stages included in `stage_flags`
+fn is_push_constants_range_declared(
+    all_push_constant_ranges: &[vk::PushConstantRange],
+    stage_flags: vk::ShaderStageFlags,
+    constants_range: &Range<u32>,
+) -> bool {
+    if stage_flags.is_empty() || constants_range.is_empty() {
+        return false;
+    }
+
+    for push_constant_range in all_push_constant_ranges {
+        let range_end = push_constant_range.offset + push_constant_range.size;
+        let is_overlapping =
+            push_constant_range.offset < constants_range.end && constants_range.start < range_end;
+
+        if is_overlapping && !stage_flags.contains(push_constant_range.stage_flags) {
+            return false;
+        }
+    }
+
+    for stage_bit in 0..u32::BITS {
+        let stage = vk::ShaderStageFlags::from_raw(1 << stage_bit);
+        if !stage_flags.contains(stage) {
+            continue;
+        }
+
+        let mut covered_end = constants_range.start;
+        while covered_end < constants_range.end {
+            let next_covered_end = all_push_constant_ranges
+                .iter()
+                .filter(|push_constant_range| {
+                    push_constant_range.stage_flags.contains(stage)
+                        && push_constant_range.offset <= covered_end
+                })
+                .map(|push_constant_range| push_constant_range.offset + push_constant_range.size)
+                .max();
+
+            match next_covered_end {
+                Some(next_covered_end) if next_covered_end > covered_end => {
+                    covered_end = next_covered_end
+                }
+                _ => return false,
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_constant_range(
+        stage_flags: vk::ShaderStageFlags,
+        offset: u32,
+        size: u32,
+    ) -> vk::PushConstantRange {
+        vk::PushConstantRange {
+            stage_flags,
+            offset,
+            size,
+        }
+    }
+
+    #[test]
+    fn range_inside_a_declared_range_is_declared() {
+        let all_push_constant_ranges = [push_constant_range(vk::ShaderStageFlags::VERTEX, 0, 64)];
+
+        assert!(is_push_constants_range_declared(
+            &all_push_constant_ranges,
+            vk::ShaderStageFlags::VERTEX,
+            &(16..32)
+        ));
+        assert!(!is_push_constants_range_declared(
+            &all_push_constant_ranges,
+            vk::ShaderStageFlags::VERTEX,
+            &(48..80)
+        ));
+    }
+
+    #[test]
+    fn range_covered_by_adjacent_ranges_is_declared() {
+        let all_push_constant_ranges = [
+            push_constant_range(vk::ShaderStageFlags::FRAGMENT, 0, 16),
+            push_constant_range(vk::ShaderStageFlags::FRAGMENT, 16, 16),
+        ];
+
+        assert!(is_push_constants_range_declared(
+            &all_push_constant_ranges,
+            vk::ShaderStageFlags::FRAGMENT,
+            &(8..24)
+        ));
+    }
+
+    #[test]
+    fn range_with_a_gap_is_not_declared() {
+        let all_push_constant_ranges = [
+            push_constant_range(vk::ShaderStageFlags::VERTEX, 0, 16),
+            push_constant_range(vk::ShaderStageFlags::VERTEX, 32, 16),
+        ];
+
+        assert!(!is_push_constants_range_declared(
+            &all_push_constant_ranges,
+            vk::ShaderStageFlags::VERTEX,
+            &(0..48)
+        ));
+    }
+
+    #[test]
+    fn each_stage_must_be_declared() {
+        let all_push_constant_ranges = [
+            push_constant_range(vk::ShaderStageFlags::VERTEX, 0, 32),
+            push_constant_range(vk::ShaderStageFlags::FRAGMENT, 32, 32),
+        ];
+
+        assert!(!is_push_constants_range_declared(
+            &all_push_constant_ranges,
+            vk::ShaderStageFlags::VERTEX,
+            &(16..48)
+        ));
+        assert!(!is_push_constants_range_declared(
+            &all_push_constant_ranges,
+            vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
+            &(0..16)
+        ));
+    }
+
+    #[test]
+    fn overlapped_range_of_other_stages_is_not_declared() {
+        //vkCmdPushConstants requires the stages of each overlapped range to be updated together
+        let all_push_constant_ranges = [push_constant_range(
+            vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
+            0,
+            64,
+        )];
+
+        assert!(!is_push_constants_range_declared(
+            &all_push_constant_ranges,
+            vk::ShaderStageFlags::VERTEX,
+            &(0..16)
+        ));
+        assert!(is_push_constants_range_declared(
+            &all_push_constant_ranges,
+            vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
+            &(0..16)
+        ));
+    }
+
+    #[test]
+    fn empty_range_or_stages_are_not_declared() {
+        let all_push_constant_ranges = [push_constant_range(vk::ShaderStageFlags::COMPUTE, 0, 16)];
+
+        assert!(!is_push_constants_range_declared(
+            &all_push_constant_ranges,
+            vk::ShaderStageFlags::COMPUTE,
+            &(8..8)
+        ));
+        assert!(!is_push_constants_range_declared(
+            &all_push_constant_ranges,
+            vk::ShaderStageFlags::empty(),
+            &(0..16)
+        ));
+        assert!(!is_push_constants_range_declared(
+            &[],
+            vk::ShaderStageFlags::COMPUTE,
+            &(0..16)
+        ));
+    }
+}
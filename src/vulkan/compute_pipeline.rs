@@ -0,0 +1,84 @@
+use ash::vk;
+
+use crate::vulkan::{device::VulkanDevice, shaders::VulkanShaderStage};
+
+///Represent a compute pipeline and its layout, dispatched outside of the render passes
+pub struct VulkanComputePipeline<'vulkan_device> {
+    vulkan_device: &'vulkan_device VulkanDevice,
+    pipeline: vk::Pipeline,
+    pipeline_layout: vk::PipelineLayout,
+
+    ///The push constant ranges declared by `pipeline_layout`
+    all_push_constant_ranges: Box<[vk::PushConstantRange]>,
+}
+
+impl<'vulkan_device> VulkanComputePipeline<'vulkan_device> {
+    ///Create a compute pipeline running `shader_stage`, which must be a compute stage
+    ///
+    /// # Panics
+    ///
+    /// If a range of `all_push_constant_ranges` doesn't have an offset and a size multiple of 4,
+    /// or is outside of `maxPushConstantsSize` of the device
+    pub fn new(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        shader_stage: &VulkanShaderStage,
+        all_push_constant_ranges: &[vk::PushConstantRange],
+        all_descriptor_set_layouts: &[vk::DescriptorSetLayout],
+    ) -> Self {
+        let max_push_constants_size = vulkan_device.get_limits().max_push_constants_size;
+        for push_constant_range in all_push_constant_ranges {
+            assert!(
+                push_constant_range.offset.is_multiple_of(4)
+                    && push_constant_range.size.is_multiple_of(4),
+                "push constant range {push_constant_range:?} must have an offset and a size multiple of 4"
+            );
+            assert!(
+                push_constant_range.offset + push_constant_range.size <= max_push_constants_size,
+                "push constant range {push_constant_range:?} is outside of the maximum push constants size of the device ({max_push_constants_size} bytes)"
+            );
+        }
+
+        let pipeline_layout_create_info = vk::PipelineLayoutCreateInfo::default()
+            .set_layouts(all_descriptor_set_layouts)
+            .push_constant_ranges(all_push_constant_ranges);
+        let pipeline_layout = vulkan_device
+            .create_pipeline_layout(&pipeline_layout_create_info)
+            .expect("failed to create pipeline layout");
+
+        let specialization_info = shader_stage.get_specialization_info();
+        let compute_pipeline_create_info = vk::ComputePipelineCreateInfo::default()
+            .stage(shader_stage.get_pipeline_stage_create_info(specialization_info.as_ref()))
+            .layout(pipeline_layout);
+        let pipeline = vulkan_device
+            .create_compute_pipeline(compute_pipeline_create_info)
+            .expect("failed to create compute pipeline");
+
+        Self {
+            vulkan_device,
+            pipeline,
+            pipeline_layout,
+            all_push_constant_ranges: all_push_constant_ranges.into(),
+        }
+    }
+
+    pub fn get_pipeline(&self) -> vk::Pipeline {
+        self.pipeline
+    }
+
+    pub fn get_pipeline_layout(&self) -> vk::PipelineLayout {
+        self.pipeline_layout
+    }
+
+    ///Return the push constant ranges declared by the layout of `&self`
+    pub fn get_all_push_constant_ranges(&self) -> &[vk::PushConstantRange] {
+        &self.all_push_constant_ranges
+    }
+}
+
+impl Drop for VulkanComputePipeline<'_> {
+    fn drop(&mut self) {
+        self.vulkan_device.destroy_pipeline(self.pipeline);
+        self.vulkan_device
+            .destroy_pipeline_layout(self.pipeline_layout);
+    }
+}
@@ -0,0 +1,209 @@
+use std::marker::PhantomData;
+
+use ash::vk;
+
+use crate::vulkan::{
+    buffer::VulkanBuffer,
+    device::VulkanDevice,
+    image::{VulkanImageView, VulkanSampler},
+};
+
+///Represent a Vulkan descriptor set layout, describing the resources bound to a set of a pipeline layout
+pub struct VulkanDescriptorSetLayout<'vulkan_device> {
+    ///A reference to the Vulkan Device which created this descriptor set layout
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan descriptor set layout
+    descriptor_set_layout: vk::DescriptorSetLayout,
+}
+
+impl<'vulkan_device> VulkanDescriptorSetLayout<'vulkan_device> {
+    ///Create a descriptor set layout declaring `all_bindings`
+    pub fn new(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        all_bindings: &[vk::DescriptorSetLayoutBinding],
+    ) -> Self {
+        let descriptor_set_layout_create_info =
+            vk::DescriptorSetLayoutCreateInfo::default().bindings(all_bindings);
+
+        let descriptor_set_layout = vulkan_device
+            .create_descriptor_set_layout(&descriptor_set_layout_create_info)
+            .expect("failed to create Vulkan descriptor set layout");
+
+        Self {
+            vulkan_device,
+            descriptor_set_layout,
+        }
+    }
+
+    pub fn get_descriptor_set_layout(&self) -> vk::DescriptorSetLayout {
+        self.descriptor_set_layout
+    }
+}
+
+impl Drop for VulkanDescriptorSetLayout<'_> {
+    fn drop(&mut self) {
+        self.vulkan_device
+            .destroy_descriptor_set_layout(self.descriptor_set_layout);
+    }
+}
+
+///Represent a Vulkan descriptor pool, which allocates the descriptor sets
+pub struct VulkanDescriptorPool<'vulkan_device> {
+    ///A reference to the Vulkan Device which created this descriptor pool
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan descriptor pool
+    descriptor_pool: vk::DescriptorPool,
+}
+
+impl<'vulkan_device> VulkanDescriptorPool<'vulkan_device> {
+    ///Create a descriptor pool allocating at most `max_sets` descriptor sets,
+    ///and for each descriptor type of `all_pool_sizes`, at most its count of descriptors
+    pub fn new(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        max_sets: u32,
+        all_pool_sizes: &[vk::DescriptorPoolSize],
+    ) -> Self {
+        let descriptor_pool_create_info = vk::DescriptorPoolCreateInfo::default()
+            .max_sets(max_sets)
+            .pool_sizes(all_pool_sizes);
+
+        let descriptor_pool = vulkan_device
+            .create_descriptor_pool(&descriptor_pool_create_info)
+            .expect("failed to create Vulkan descriptor pool");
+
+        Self {
+            vulkan_device,
+            descriptor_pool,
+        }
+    }
+
+    ///Allocate a descriptor set from `&self` for each layout of `all_descriptor_set_layouts`
+    ///
+    ///They are freed when `&self` is dropped
+    pub fn allocate_descriptor_sets<'descriptor_pool>(
+        &'descriptor_pool self,
+        all_descriptor_set_layouts: &[vk::DescriptorSetLayout],
+    ) -> Vec<VulkanDescriptorSet<'descriptor_pool, 'vulkan_device>> {
+        let descriptor_set_allocate_info = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(self.descriptor_pool)
+            .set_layouts(all_descriptor_set_layouts);
+
+        let all_descriptor_sets = self
+            .vulkan_device
+            .allocate_descriptor_sets(&descriptor_set_allocate_info)
+            .expect("failed to allocate Vulkan descriptor sets");
+
+        all_descriptor_sets
+            .into_iter()
+            .map(|descriptor_set| VulkanDescriptorSet {
+                vulkan_device: self.vulkan_device,
+                descriptor_set,
+                phantom_data_descriptor_pool: PhantomData,
+            })
+            .collect()
+    }
+}
+
+impl Drop for VulkanDescriptorPool<'_> {
+    fn drop(&mut self) {
+        self.vulkan_device
+            .destroy_descriptor_pool(self.descriptor_pool);
+    }
+}
+
+///Represent a Vulkan descriptor set, binding resources to the shaders
+///
+///The descriptors must be written before the descriptor set is bound in a command buffer
+pub struct VulkanDescriptorSet<'descriptor_pool, 'vulkan_device> {
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan descriptor set
+    descriptor_set: vk::DescriptorSet,
+
+    ///PhantomData used to keep the fact that a descriptor set cannot be used without its descriptor pool
+    phantom_data_descriptor_pool:
+        PhantomData<&'descriptor_pool VulkanDescriptorPool<'vulkan_device>>,
+}
+
+impl VulkanDescriptorSet<'_, '_> {
+    pub fn get_descriptor_set(&self) -> vk::DescriptorSet {
+        self.descriptor_set
+    }
+
+    ///Write `image_view` in the SAMPLED_IMAGE descriptor at `binding`, read in the SHADER_READ_ONLY_OPTIMAL layout
+    pub fn write_sampled_image(&self, binding: u32, image_view: &VulkanImageView) {
+        let image_infos = [vk::DescriptorImageInfo::default()
+            .image_view(image_view.get_image_view())
+            .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)];
+
+        self.write_image_infos(binding, vk::DescriptorType::SAMPLED_IMAGE, &image_infos);
+    }
+
+    ///Write `image_view` in the STORAGE_IMAGE descriptor at `binding`, read and written in the GENERAL layout
+    pub fn write_storage_image(&self, binding: u32, image_view: &VulkanImageView) {
+        let image_infos = [vk::DescriptorImageInfo::default()
+            .image_view(image_view.get_image_view())
+            .image_layout(vk::ImageLayout::GENERAL)];
+
+        self.write_image_infos(binding, vk::DescriptorType::STORAGE_IMAGE, &image_infos);
+    }
+
+    ///Write `sampler` in the SAMPLER descriptor at `binding`
+    pub fn write_sampler(&self, binding: u32, sampler: &VulkanSampler) {
+        let image_infos = [vk::DescriptorImageInfo::default().sampler(sampler.get_sampler())];
+
+        self.write_image_infos(binding, vk::DescriptorType::SAMPLER, &image_infos);
+    }
+
+    ///Write all of `buffer` in the UNIFORM_BUFFER descriptor at `binding`
+    pub fn write_uniform_buffer(&self, binding: u32, buffer: &VulkanBuffer) {
+        self.write_buffer(binding, vk::DescriptorType::UNIFORM_BUFFER, buffer);
+    }
+
+    ///Write all of `buffer` in the STORAGE_BUFFER descriptor at `binding`
+    pub fn write_storage_buffer(&self, binding: u32, buffer: &VulkanBuffer) {
+        self.write_buffer(binding, vk::DescriptorType::STORAGE_BUFFER, buffer);
+    }
+
+    fn write_buffer(
+        &self,
+        binding: u32,
+        descriptor_type: vk::DescriptorType,
+        buffer: &VulkanBuffer,
+    ) {
+        let buffer_infos = [vk::DescriptorBufferInfo::default()
+            .buffer(buffer.get_buffer())
+            .offset(0)
+            .range(vk::WHOLE_SIZE)];
+
+        let write_descriptor_set = vk::WriteDescriptorSet::default()
+            .dst_set(self.descriptor_set)
+            .dst_binding(binding)
+            .dst_array_element(0)
+            .descriptor_type(descriptor_type)
+            .buffer_info(&buffer_infos);
+
+        self.vulkan_device
+            .update_descriptor_sets(&[write_descriptor_set], &[]);
+    }
+
+    ///Write `image_infos` in the descriptors of `descriptor_type` at `binding`
+    fn write_image_infos(
+        &self,
+        binding: u32,
+        descriptor_type: vk::DescriptorType,
+        image_infos: &[vk::DescriptorImageInfo],
+    ) {
+        let write_descriptor_set = vk::WriteDescriptorSet::default()
+            .dst_set(self.descriptor_set)
+            .dst_binding(binding)
+            .dst_array_element(0)
+            .descriptor_type(descriptor_type)
+            .image_info(image_infos);
+
+        self.vulkan_device
+            .update_descriptor_sets(&[write_descriptor_set], &[]);
+    }
+}
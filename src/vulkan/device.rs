@@ -1,14 +1,15 @@
 use std::{
-    ffi::{CStr, c_char},
+    cmp::min,
+    ffi::{CStr, c_char, c_void},
     mem::MaybeUninit,
 };
 
 use ash::{
-    khr,
+    ext, khr,
     prelude::VkResult,
     vk::{
-        self, GraphicsPipelineCreateInfo, PipelineLayoutCreateInfo, QueueFlags,
-        RenderPassCreateInfo,
+        self, ComputePipelineCreateInfo, GraphicsPipelineCreateInfo, PipelineLayoutCreateInfo,
+        QueueFlags, RenderPassCreateInfo,
     },
 };
 
@@ -27,7 +28,10 @@ pub struct SuitableDeviceParam<T> {
 ///Represent the queues of a Logical Device, which are used for graphics and presentations supported for a Vulkan Surface
 enum DeviceQueue {
     ///The graphics and representation are used in the same Logical Queue, because they have the same family queue
-    UniqueQueue(vk::Queue),
+    UniqueQueue {
+        queue_family_index: u32,
+        queue: vk::Queue,
+    },
 
     ///The graphics and representation each have a dedicated queue, because they have different family queues
     TwoQueue {
@@ -37,6 +41,18 @@ enum DeviceQueue {
     },
 }
 
+///Represent how the dynamic rendering is provided by a Logical Device
+enum DynamicRendering {
+    ///The dynamic rendering isn't supported, render passes must be used
+    Unsupported,
+
+    ///The dynamic rendering is a core feature of Vulkan 1.3
+    Core,
+
+    ///The dynamic rendering is provided by the VK_KHR_dynamic_rendering extension
+    Extension(khr::dynamic_rendering::Device),
+}
+
 ///Represent a Vulkan device
 pub struct VulkanDevice {
     ///A Vulkan Physical Device
@@ -44,9 +60,30 @@ pub struct VulkanDevice {
     ///For example, a reference to an RTX 5090
     physical_device: vk::PhysicalDevice,
 
+    ///The Vulkan instance which enumerated ```physical_device```
+    ///
+    ///For example, can be used to query the properties of a format on ```physical_device```
+    instance: ash::Instance,
+
     ///A Vulkan Logical Device, which created by Vulkan and linked to ```physical_device```
     logical_device: ash::Device,
 
+    ///The Vulkan API version usable with ```physical_device```, limited by the version of the Vulkan instance
+    api_version: u32,
+
+    ///The limits of ```physical_device```
+    ///
+    ///For example, the maximum size of the push constants
+    limits: vk::PhysicalDeviceLimits,
+
+    ///The memory heaps and types of ```physical_device```
+    ///
+    ///For example, can be used to find a memory type which is visible by the host
+    memory_properties: vk::PhysicalDeviceMemoryProperties,
+
+    ///The features of ```physical_device``` enabled on ```logical_device```
+    enabled_features: vk::PhysicalDeviceFeatures,
+
     ///The queues of ```logical_device```, which are used for graphics and presentations supported for a Vulkan Surface
     queue: DeviceQueue,
 
@@ -54,6 +91,16 @@ pub struct VulkanDevice {
     ///
     ///For example, can be used to create a swapchain
     swapchain_device: khr::swapchain::Device,
+
+    ///How the dynamic rendering is provided by ```logical_device```
+    ///
+    ///For example, can be used to render without render pass and framebuffer
+    dynamic_rendering: DynamicRendering,
+
+    ///The HDR Metadata Device linked to Vulkan instance and ```logical_device```, None if VK_EXT_hdr_metadata isn't available
+    ///
+    ///For example, can be used to describe the mastering display of a swapchain presenting in HDR
+    hdr_metadata_device: Option<ext::hdr_metadata::Device>,
 }
 
 const REQUIRED_EXTENSION_NAME_FOR_SURFACE: [&CStr; 1] = [vk::KHR_SWAPCHAIN_NAME];
@@ -99,82 +146,500 @@ impl VulkanDevice {
             }
             let graphics_presents_queue_family_id = graphics_presents_queue_family_id_opt.unwrap();
 
-            let mut queue_create_infos: [MaybeUninit<vk::DeviceQueueCreateInfo>; 2] =
-                [MaybeUninit::uninit(); 2];
-
-            let queue_priotities = 1.0f32;
+            return Some(Self::new(
+                vulkan_instance,
+                physical_device,
+                &graphics_presents_queue_family_id,
+                &REQUIRED_EXTENSION_NAME_FOR_SURFACE,
+            ));
+        }
+        None
+    }
 
-            let nb_family_queue = if graphics_presents_queue_family_id.is_same_queue_family() {
-                queue_create_infos[0].write(vk::DeviceQueueCreateInfo {
-                    queue_family_index: graphics_presents_queue_family_id.graphics,
-                    p_queue_priorities: &queue_priotities,
-                    queue_count: 2,
-                    ..Default::default()
-                });
+    /// Create a VulkanDevice without presentation support, used to render without window
+    ///
+    /// # Arguments
+    ///
+    /// * `vulkan_instance` - a reference to a VulkanInstance
+    /// * `device_type` - the type of the selected physical device, any type if None
+    ///
+    /// # Returns
+    ///
+    /// None if none physical device of `device_type` has a graphics queue
+    pub fn select_headless_device(
+        vulkan_instance: &VulkanInstance,
+        device_type: Option<vk::PhysicalDeviceType>,
+    ) -> Option<Self> {
+        let all_available_physical_devices =
+            unsafe { vulkan_instance.enumerate_physical_devices() }
+                .expect("failed to enumerate available physical devices");
 
-                1
-            } else {
-                queue_create_infos[0].write(vk::DeviceQueueCreateInfo {
-                    queue_family_index: graphics_presents_queue_family_id.graphics,
-                    p_queue_priorities: &queue_priotities,
-                    queue_count: 1,
-                    ..Default::default()
-                });
-                queue_create_infos[1].write(vk::DeviceQueueCreateInfo {
-                    queue_family_index: graphics_presents_queue_family_id.presents,
-                    p_queue_priorities: &queue_priotities,
-                    queue_count: 1,
-                    ..Default::default()
-                });
+        for physical_device in all_available_physical_devices {
+            let physical_device_properties =
+                unsafe { vulkan_instance.get_physical_device_properties(physical_device) };
+            if device_type
+                .is_some_and(|device_type| physical_device_properties.device_type != device_type)
+            {
+                continue;
+            }
 
-                2
+            let all_queue_family_properties = unsafe {
+                vulkan_instance.get_physical_device_queue_family_properties(physical_device)
             };
+            let graphics_queue_family_id_opt =
+                all_queue_family_properties
+                    .iter()
+                    .position(|queue_family_properties| {
+                        //The graphics queue also runs the compute shaders, a family supporting both always exists
+                        queue_family_properties
+                            .queue_flags
+                            .contains(QueueFlags::GRAPHICS | QueueFlags::COMPUTE)
+                    });
+            if graphics_queue_family_id_opt.is_none() {
+                continue;
+            }
+            let graphics_queue_family_id = graphics_queue_family_id_opt.unwrap() as u32;
+
+            //Without presentation, the graphics queue is used as the present queue
+            return Some(Self::new(
+                vulkan_instance,
+                physical_device,
+                &GraphicsAndPresentQueueFamilyId {
+                    graphics: graphics_queue_family_id,
+                    presents: graphics_queue_family_id,
+                },
+                &[],
+            ));
+        }
+        None
+    }
+
+    ///Create a VulkanDevice from `physical_device`, with the queues of `graphics_presents_queue_family_id` and `all_required_extension_names` enabled
+    fn new(
+        vulkan_instance: &VulkanInstance,
+        physical_device: vk::PhysicalDevice,
+        graphics_presents_queue_family_id: &GraphicsAndPresentQueueFamilyId,
+        all_required_extension_names: &[&CStr],
+    ) -> Self {
+        let physical_device_properties =
+            unsafe { vulkan_instance.get_physical_device_properties(physical_device) };
+        let api_version = min(
+            physical_device_properties.api_version,
+            vulkan_instance.get_api_version(),
+        );
+
+        let dynamic_rendering_support =
+            get_dynamic_rendering_support(vulkan_instance, physical_device, api_version);
+
+        let mut all_enabled_extension_names: Vec<*const c_char> = all_required_extension_names
+            .iter()
+            .map(|extension_name| extension_name.as_ptr())
+            .collect();
+        if dynamic_rendering_support == DynamicRenderingSupport::Extension {
+            all_enabled_extension_names.push(vk::KHR_DYNAMIC_RENDERING_NAME.as_ptr());
+        }
+
+        //VK_EXT_hdr_metadata depends on VK_KHR_swapchain, and is optional
+        let is_hdr_metadata_supported = all_required_extension_names
+            .contains(&vk::KHR_SWAPCHAIN_NAME)
+            && is_physical_device_supported_extensions(
+                vulkan_instance,
+                physical_device,
+                &[vk::EXT_HDR_METADATA_NAME],
+            );
+        if is_hdr_metadata_supported {
+            all_enabled_extension_names.push(vk::EXT_HDR_METADATA_NAME.as_ptr());
+        }
+
+        let dynamic_rendering_features =
+            vk::PhysicalDeviceDynamicRenderingFeatures::default().dynamic_rendering(true);
+
+        let available_features =
+            unsafe { vulkan_instance.get_physical_device_features(physical_device) };
+        let enabled_features = vk::PhysicalDeviceFeatures::default()
+            .sampler_anisotropy(available_features.sampler_anisotropy == vk::TRUE);
+
+        let mut queue_create_infos: [MaybeUninit<vk::DeviceQueueCreateInfo>; 2] =
+            [MaybeUninit::uninit(); 2];
 
-            let device_create_info = vk::DeviceCreateInfo {
-                p_queue_create_infos: queue_create_infos.as_ptr()
-                    as *const vk::DeviceQueueCreateInfo,
-                queue_create_info_count: nb_family_queue,
-                enabled_extension_count: REQUIRED_EXTENSION_NAME_FOR_SURFACE.len() as u32,
-                pp_enabled_extension_names: REQUIRED_EXTENSION_NAME_FOR_SURFACE.as_ptr()
-                    as *const *const c_char,
+        let queue_priotities = 1.0f32;
+
+        let nb_family_queue = if graphics_presents_queue_family_id.is_same_queue_family() {
+            queue_create_infos[0].write(vk::DeviceQueueCreateInfo {
+                queue_family_index: graphics_presents_queue_family_id.graphics,
+                p_queue_priorities: &queue_priotities,
+                queue_count: 1, //the graphics and presentations use the same queue
                 ..Default::default()
-            };
+            });
+
+            1
+        } else {
+            queue_create_infos[0].write(vk::DeviceQueueCreateInfo {
+                queue_family_index: graphics_presents_queue_family_id.graphics,
+                p_queue_priorities: &queue_priotities,
+                queue_count: 1,
+                ..Default::default()
+            });
+            queue_create_infos[1].write(vk::DeviceQueueCreateInfo {
+                queue_family_index: graphics_presents_queue_family_id.presents,
+                p_queue_priorities: &queue_priotities,
+                queue_count: 1,
+                ..Default::default()
+            });
+
+            2
+        };
+
+        let device_create_info = vk::DeviceCreateInfo {
+            p_next: if dynamic_rendering_support == DynamicRenderingSupport::Unsupported {
+                std::ptr::null()
+            } else {
+                &dynamic_rendering_features as *const _ as *const c_void
+            },
+            p_queue_create_infos: queue_create_infos.as_ptr() as *const vk::DeviceQueueCreateInfo,
+            queue_create_info_count: nb_family_queue,
+            enabled_extension_count: all_enabled_extension_names.len() as u32,
+            pp_enabled_extension_names: all_enabled_extension_names.as_ptr(),
+            p_enabled_features: &enabled_features,
+            ..Default::default()
+        };
+
+        let logical_device =
+            unsafe { vulkan_instance.create_device(physical_device, &device_create_info, None) }
+                .expect("failed to create Vulkan logical device");
 
-            let logical_device = unsafe {
-                vulkan_instance.create_device(physical_device, &device_create_info, None)
+        let queue = unsafe {
+            if graphics_presents_queue_family_id.is_same_queue_family() {
+                DeviceQueue::UniqueQueue {
+                    queue_family_index: graphics_presents_queue_family_id.graphics,
+                    queue: logical_device
+                        .get_device_queue(graphics_presents_queue_family_id.graphics, 0),
+                }
+            } else {
+                DeviceQueue::TwoQueue {
+                    queue_family_indices: [
+                        graphics_presents_queue_family_id.graphics,
+                        graphics_presents_queue_family_id.presents,
+                    ],
+                    graphics: logical_device
+                        .get_device_queue(graphics_presents_queue_family_id.graphics, 0),
+                    presents: logical_device
+                        .get_device_queue(graphics_presents_queue_family_id.presents, 0),
+                }
             }
-            .expect("failed to create Vulkan logical device");
+        };
+
+        let swapchain_device = khr::swapchain::Device::new(vulkan_instance, &logical_device);
+
+        let dynamic_rendering = match dynamic_rendering_support {
+            DynamicRenderingSupport::Unsupported => DynamicRendering::Unsupported,
+            DynamicRenderingSupport::Core => DynamicRendering::Core,
+            DynamicRenderingSupport::Extension => DynamicRendering::Extension(
+                khr::dynamic_rendering::Device::new(vulkan_instance, &logical_device),
+            ),
+        };
+
+        let hdr_metadata_device = is_hdr_metadata_supported
+            .then(|| ext::hdr_metadata::Device::new(vulkan_instance, &logical_device));
+
+        let memory_properties =
+            unsafe { vulkan_instance.get_physical_device_memory_properties(physical_device) };
+
+        Self {
+            logical_device,
+            physical_device,
+            instance: (*vulkan_instance).clone(),
+            api_version,
+            limits: physical_device_properties.limits,
+            memory_properties,
+            enabled_features,
+            queue,
+            swapchain_device,
+            dynamic_rendering,
+            hdr_metadata_device,
+        }
+    }
+
+    ///Return the Vulkan API version usable with `&self`
+    pub fn get_api_version(&self) -> u32 {
+        self.api_version
+    }
+
+    ///Return if the dynamic rendering is enabled on `&self`
+    ///
+    ///If not, the graphics pipelines must be created with a render pass
+    pub fn is_dynamic_rendering_enabled(&self) -> bool {
+        !matches!(self.dynamic_rendering, DynamicRendering::Unsupported)
+    }
+
+    ///Begin a dynamic rendering described by `rendering_info` in `command_buffer`
+    ///
+    /// # Panics
+    ///
+    /// If the dynamic rendering isn't enabled on `&self`
+    pub fn cmd_begin_dynamic_rendering(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        rendering_info: &vk::RenderingInfo,
+    ) {
+        match &self.dynamic_rendering {
+            DynamicRendering::Unsupported => panic!("dynamic rendering isn't enabled"),
+            DynamicRendering::Core => unsafe {
+                self.logical_device
+                    .cmd_begin_rendering(command_buffer, rendering_info)
+            },
+            DynamicRendering::Extension(dynamic_rendering_device) => unsafe {
+                dynamic_rendering_device.cmd_begin_rendering(command_buffer, rendering_info)
+            },
+        }
+    }
+
+    ///End the dynamic rendering of `command_buffer`
+    ///
+    /// # Panics
+    ///
+    /// If the dynamic rendering isn't enabled on `&self`
+    pub fn cmd_end_dynamic_rendering(&self, command_buffer: vk::CommandBuffer) {
+        match &self.dynamic_rendering {
+            DynamicRendering::Unsupported => panic!("dynamic rendering isn't enabled"),
+            DynamicRendering::Core => unsafe {
+                self.logical_device.cmd_end_rendering(command_buffer)
+            },
+            DynamicRendering::Extension(dynamic_rendering_device) => unsafe {
+                dynamic_rendering_device.cmd_end_rendering(command_buffer)
+            },
+        }
+    }
+
+    ///Return the limits of the physical device of `&self`
+    pub fn get_limits(&self) -> &vk::PhysicalDeviceLimits {
+        &self.limits
+    }
+
+    ///Return the features of the physical device enabled on `&self`
+    ///
+    ///For example, the anisotropic filtering can only be used if `sampler_anisotropy` is enabled
+    pub fn get_enabled_features(&self) -> &vk::PhysicalDeviceFeatures {
+        &self.enabled_features
+    }
+
+    ///Return the properties of `format` on the physical device of `&self`
+    ///
+    ///For example, can be used to check if an image in `format` can be filtered linearly
+    pub fn get_format_properties(&self, format: vk::Format) -> vk::FormatProperties {
+        unsafe {
+            self.instance
+                .get_physical_device_format_properties(self.physical_device, format)
+        }
+    }
+
+    ///Return the first depth format usable as an optimal depth attachment by `&self`,
+    ///from the most to the least precise
+    ///
+    /// # Returns
+    ///
+    /// None if none of D32_SFLOAT, X8_D24_UNORM_PACK32 and D16_UNORM is supported
+    pub fn get_supported_depth_format(&self) -> Option<vk::Format> {
+        //Formats without stencil, so that only the depth aspect of the images is used
+        [
+            vk::Format::D32_SFLOAT,
+            vk::Format::X8_D24_UNORM_PACK32,
+            vk::Format::D16_UNORM,
+        ]
+        .into_iter()
+        .find(|&format| {
+            self.get_format_properties(format)
+                .optimal_tiling_features
+                .contains(vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
+        })
+    }
 
-            let queue = unsafe {
-                if graphics_presents_queue_family_id.is_same_queue_family() {
-                    DeviceQueue::UniqueQueue(
-                        logical_device
-                            .get_device_queue(graphics_presents_queue_family_id.graphics, 0),
+    ///Return the first depth format usable as an optimal depth attachment by `&self` and sampled by the shaders,
+    ///from the most to the least precise
+    ///
+    ///For example, can be used by the shadow maps
+    ///
+    /// # Returns
+    ///
+    /// None if none of D32_SFLOAT and D16_UNORM is supported
+    pub fn get_supported_sampled_depth_format(&self) -> Option<vk::Format> {
+        [vk::Format::D32_SFLOAT, vk::Format::D16_UNORM]
+            .into_iter()
+            .find(|&format| {
+                self.get_format_properties(format)
+                    .optimal_tiling_features
+                    .contains(
+                        vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT
+                            | vk::FormatFeatureFlags::SAMPLED_IMAGE,
                     )
-                } else {
-                    DeviceQueue::TwoQueue {
-                        queue_family_indices: [
-                            graphics_presents_queue_family_id.graphics,
-                            graphics_presents_queue_family_id.presents,
-                        ],
-                        graphics: logical_device
-                            .get_device_queue(graphics_presents_queue_family_id.graphics, 0),
-                        presents: logical_device
-                            .get_device_queue(graphics_presents_queue_family_id.presents, 0),
-                    }
-                }
-            };
+            })
+    }
+
+    ///Return the index of a memory type of `&self` allowed by `memory_type_bits` and having all `property_flags`
+    ///
+    /// # Arguments
+    ///
+    /// * `memory_type_bits` - the bit `i` is set if the memory type `i` is allowed, as in vk::MemoryRequirements
+    /// * `property_flags` - the properties required for the memory type
+    ///
+    /// # Returns
+    ///
+    /// None if none memory type is suitable
+    pub fn find_memory_type_index(
+        &self,
+        memory_type_bits: u32,
+        property_flags: vk::MemoryPropertyFlags,
+    ) -> Option<u32> {
+        self.memory_properties.memory_types[..self.memory_properties.memory_type_count as usize]
+            .iter()
+            .enumerate()
+            .find(|(memory_type_index, memory_type)| {
+                memory_type_bits & (1 << memory_type_index) != 0
+                    && memory_type.property_flags.contains(property_flags)
+            })
+            .map(|(memory_type_index, _)| memory_type_index as u32)
+    }
 
-            let swapchain_device = khr::swapchain::Device::new(vulkan_instance, &logical_device);
+    ///Allocate memory for `memory_requirements`, in a memory type having all `property_flags`
+    ///
+    /// # Panics
+    ///
+    /// If none memory type of `&self` is suitable, or the allocation failed
+    pub fn allocate_memory_for_requirements(
+        &self,
+        memory_requirements: vk::MemoryRequirements,
+        property_flags: vk::MemoryPropertyFlags,
+    ) -> vk::DeviceMemory {
+        let memory_type_index = self
+            .find_memory_type_index(memory_requirements.memory_type_bits, property_flags)
+            .unwrap_or_else(|| panic!("no memory type of the device is {property_flags:?}"));
 
-            return Some(Self {
-                logical_device,
-                physical_device,
+        let memory_allocate_info = vk::MemoryAllocateInfo::default()
+            .allocation_size(memory_requirements.size)
+            .memory_type_index(memory_type_index);
+
+        unsafe {
+            self.logical_device
+                .allocate_memory(&memory_allocate_info, None)
+        }
+        .expect("failed to allocate Vulkan device memory")
+    }
+
+    ///Return the queue family index of the graphics queue of `&self`, which also supports the compute
+    ///
+    ///For example, can be used to create a command pool for the graphics queue
+    pub fn get_graphics_queue_family_index(&self) -> u32 {
+        match &self.queue {
+            DeviceQueue::UniqueQueue {
+                queue_family_index,
+                queue: _,
+            } => *queue_family_index,
+            DeviceQueue::TwoQueue {
+                queue_family_indices,
+                graphics: _,
+                presents: _,
+            } => queue_family_indices[0],
+        }
+    }
+
+    ///Return the queue family index of the present queue of `&self`
+    ///
+    ///For example, can be used to transfer the ownership of a swapchain image to the present queue
+    pub fn get_present_queue_family_index(&self) -> u32 {
+        match &self.queue {
+            DeviceQueue::UniqueQueue {
+                queue_family_index,
+                queue: _,
+            } => *queue_family_index,
+            DeviceQueue::TwoQueue {
+                queue_family_indices,
+                graphics: _,
+                presents: _,
+            } => queue_family_indices[1],
+        }
+    }
+
+    ///Return if the graphics and present queues of `&self` are in different queue families
+    pub fn has_separate_present_queue(&self) -> bool {
+        matches!(self.queue, DeviceQueue::TwoQueue { .. })
+    }
+
+    ///Submit `all_submit_infos` to the graphics queue of `&self`, `fence` is signaled once they are executed
+    pub fn submit_to_graphics_queue(
+        &self,
+        all_submit_infos: &[vk::SubmitInfo],
+        fence: vk::Fence,
+    ) -> VkResult<()> {
+        let graphics_queue = match &self.queue {
+            DeviceQueue::UniqueQueue {
+                queue_family_index: _,
                 queue,
-                swapchain_device,
-            });
+            } => *queue,
+            DeviceQueue::TwoQueue {
+                queue_family_indices: _,
+                graphics,
+                presents: _,
+            } => *graphics,
+        };
+
+        unsafe {
+            self.logical_device
+                .queue_submit(graphics_queue, all_submit_infos, fence)
         }
-        None
+    }
+
+    ///Submit `all_submit_infos` to the present queue of `&self`, `fence` is signaled once they are executed
+    pub fn submit_to_present_queue(
+        &self,
+        all_submit_infos: &[vk::SubmitInfo],
+        fence: vk::Fence,
+    ) -> VkResult<()> {
+        let present_queue = match &self.queue {
+            DeviceQueue::UniqueQueue {
+                queue_family_index: _,
+                queue,
+            } => *queue,
+            DeviceQueue::TwoQueue {
+                queue_family_indices: _,
+                graphics: _,
+                presents,
+            } => *presents,
+        };
+
+        unsafe {
+            self.logical_device
+                .queue_submit(present_queue, all_submit_infos, fence)
+        }
+    }
+
+    ///Present swapchain images with the present queue of `&self`
+    ///
+    /// # Returns
+    ///
+    /// True if the swapchain is suboptimal for the surface
+    pub fn present_to_present_queue(&self, present_info: &vk::PresentInfoKHR) -> VkResult<bool> {
+        let present_queue = match &self.queue {
+            DeviceQueue::UniqueQueue {
+                queue_family_index: _,
+                queue,
+            } => *queue,
+            DeviceQueue::TwoQueue {
+                queue_family_indices: _,
+                graphics: _,
+                presents,
+            } => *presents,
+        };
+
+        unsafe {
+            self.swapchain_device
+                .queue_present(present_queue, present_info)
+        }
+    }
+
+    ///Wait until all queues of `&self` are idle
+    pub fn wait_idle(&self) {
+        unsafe { self.logical_device.device_wait_idle() }
+            .expect("failed to wait for Vulkan logical device to become idle")
     }
 
     ///Return all available present modes supported by `&self` and `surface`
@@ -204,17 +669,22 @@ impl VulkanDevice {
     ///Create a SwapchainHKR from `&self` and `swapchain_create_info`
     ///
     ///The image_sharing_mode, queue_family_index_count and p_queue_family_indices fields of `swapchain_create_info` are updated from `&self` to create the Swapchain
+    ///
+    ///If the graphics and present queues of `&self` are in different queue families, the images are shared with `two_queue_sharing_mode`:
+    ///CONCURRENT between both families, or EXCLUSIVE with ownership transfers recorded by the application
     pub fn create_swapchain(
         &self,
         swapchain_create_info: &mut vk::SwapchainCreateInfoKHR,
+        two_queue_sharing_mode: vk::SharingMode,
     ) -> vk::SwapchainKHR {
         if let DeviceQueue::TwoQueue {
             queue_family_indices,
             graphics: _,
             presents: _,
         } = &self.queue
+            && two_queue_sharing_mode == vk::SharingMode::CONCURRENT
         {
-            swapchain_create_info.image_sharing_mode = vk::SharingMode::EXCLUSIVE;
+            swapchain_create_info.image_sharing_mode = vk::SharingMode::CONCURRENT;
             swapchain_create_info.queue_family_index_count = 2;
             swapchain_create_info.p_queue_family_indices = queue_family_indices.as_ptr();
         } else {
@@ -228,6 +698,53 @@ impl VulkanDevice {
         .expect("failed to create Vulkan SwapchainHKR")
     }
 
+    ///Return if the HDR metadata of the swapchains can be set on `&self`
+    pub fn is_hdr_metadata_enabled(&self) -> bool {
+        self.hdr_metadata_device.is_some()
+    }
+
+    ///Set the HDR metadata of `swapchain`, describing the display on which its images were mastered
+    ///
+    /// # Panics
+    ///
+    /// If VK_EXT_hdr_metadata isn't enabled on `&self`
+    pub fn set_hdr_metadata(&self, swapchain: vk::SwapchainKHR, hdr_metadata: &vk::HdrMetadataEXT) {
+        let hdr_metadata_device = self
+            .hdr_metadata_device
+            .as_ref()
+            .expect("VK_EXT_hdr_metadata isn't enabled");
+
+        unsafe {
+            hdr_metadata_device.set_hdr_metadata(&[swapchain], std::slice::from_ref(hdr_metadata))
+        };
+    }
+
+    ///Return all images of `swapchain`
+    pub fn get_swapchain_images(&self, swapchain: vk::SwapchainKHR) -> Vec<vk::Image> {
+        unsafe { self.swapchain_device.get_swapchain_images(swapchain) }
+            .expect("failed to get Vulkan swapchain images")
+    }
+
+    ///Acquire the next available image of `swapchain`, `semaphore` is signaled once it can be used
+    ///
+    /// # Returns
+    ///
+    /// The index of the image and true if the swapchain is suboptimal for the surface
+    pub fn acquire_next_swapchain_image(
+        &self,
+        swapchain: vk::SwapchainKHR,
+        semaphore: vk::Semaphore,
+    ) -> VkResult<(u32, bool)> {
+        unsafe {
+            self.swapchain_device.acquire_next_image(
+                swapchain,
+                u64::MAX,
+                semaphore,
+                vk::Fence::null(),
+            )
+        }
+    }
+
     ///Destroy `swapchain` with swapchain_device present in `&self`
     pub fn destroy_swapchain(&self, swapchain: vk::SwapchainKHR) {
         unsafe { self.swapchain_device.destroy_swapchain(swapchain, None) };
@@ -299,9 +816,543 @@ impl VulkanDevice {
         }
     }
 
+    pub fn create_compute_pipeline(
+        &self,
+        compute_pipeline_create_info: ComputePipelineCreateInfo,
+    ) -> VkResult<vk::Pipeline> {
+        let create_res = unsafe {
+            self.logical_device.create_compute_pipelines(
+                vk::PipelineCache::null(),
+                &[compute_pipeline_create_info],
+                None,
+            )
+        };
+
+        match create_res {
+            Ok(pipeline_vec) => Ok(pipeline_vec[0]),
+            Err(error) => Err(error.1),
+        }
+    }
+
     pub fn destroy_pipeline(&self, pipeline: vk::Pipeline) {
         unsafe { self.logical_device.destroy_pipeline(pipeline, None) }
     }
+
+    pub fn create_buffer(&self, buffer_create_info: &vk::BufferCreateInfo) -> VkResult<vk::Buffer> {
+        unsafe { self.logical_device.create_buffer(buffer_create_info, None) }
+    }
+
+    pub fn destroy_buffer(&self, buffer: vk::Buffer) {
+        unsafe { self.logical_device.destroy_buffer(buffer, None) }
+    }
+
+    pub fn get_buffer_memory_requirements(&self, buffer: vk::Buffer) -> vk::MemoryRequirements {
+        unsafe { self.logical_device.get_buffer_memory_requirements(buffer) }
+    }
+
+    pub fn bind_buffer_memory(
+        &self,
+        buffer: vk::Buffer,
+        memory: vk::DeviceMemory,
+        memory_offset: vk::DeviceSize,
+    ) -> VkResult<()> {
+        unsafe {
+            self.logical_device
+                .bind_buffer_memory(buffer, memory, memory_offset)
+        }
+    }
+
+    pub fn create_image(&self, image_create_info: &vk::ImageCreateInfo) -> VkResult<vk::Image> {
+        unsafe { self.logical_device.create_image(image_create_info, None) }
+    }
+
+    pub fn destroy_image(&self, image: vk::Image) {
+        unsafe { self.logical_device.destroy_image(image, None) }
+    }
+
+    pub fn get_image_memory_requirements(&self, image: vk::Image) -> vk::MemoryRequirements {
+        unsafe { self.logical_device.get_image_memory_requirements(image) }
+    }
+
+    pub fn bind_image_memory(
+        &self,
+        image: vk::Image,
+        memory: vk::DeviceMemory,
+        memory_offset: vk::DeviceSize,
+    ) -> VkResult<()> {
+        unsafe {
+            self.logical_device
+                .bind_image_memory(image, memory, memory_offset)
+        }
+    }
+
+    ///Map `size` bytes of `memory` from `offset`, which must be unmapped once used
+    pub fn map_memory(
+        &self,
+        memory: vk::DeviceMemory,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> VkResult<*mut c_void> {
+        unsafe {
+            self.logical_device
+                .map_memory(memory, offset, size, vk::MemoryMapFlags::empty())
+        }
+    }
+
+    pub fn unmap_memory(&self, memory: vk::DeviceMemory) {
+        unsafe { self.logical_device.unmap_memory(memory) }
+    }
+
+    pub fn free_memory(&self, memory: vk::DeviceMemory) {
+        unsafe { self.logical_device.free_memory(memory, None) }
+    }
+
+    pub fn create_image_view(
+        &self,
+        image_view_create_info: &vk::ImageViewCreateInfo,
+    ) -> VkResult<vk::ImageView> {
+        unsafe {
+            self.logical_device
+                .create_image_view(image_view_create_info, None)
+        }
+    }
+
+    pub fn destroy_image_view(&self, image_view: vk::ImageView) {
+        unsafe { self.logical_device.destroy_image_view(image_view, None) }
+    }
+
+    pub fn create_sampler(
+        &self,
+        sampler_create_info: &vk::SamplerCreateInfo,
+    ) -> VkResult<vk::Sampler> {
+        unsafe {
+            self.logical_device
+                .create_sampler(sampler_create_info, None)
+        }
+    }
+
+    pub fn destroy_sampler(&self, sampler: vk::Sampler) {
+        unsafe { self.logical_device.destroy_sampler(sampler, None) }
+    }
+
+    pub fn create_framebuffer(
+        &self,
+        framebuffer_create_info: &vk::FramebufferCreateInfo,
+    ) -> VkResult<vk::Framebuffer> {
+        unsafe {
+            self.logical_device
+                .create_framebuffer(framebuffer_create_info, None)
+        }
+    }
+
+    pub fn destroy_framebuffer(&self, framebuffer: vk::Framebuffer) {
+        unsafe { self.logical_device.destroy_framebuffer(framebuffer, None) }
+    }
+
+    pub fn create_descriptor_set_layout(
+        &self,
+        descriptor_set_layout_create_info: &vk::DescriptorSetLayoutCreateInfo,
+    ) -> VkResult<vk::DescriptorSetLayout> {
+        unsafe {
+            self.logical_device
+                .create_descriptor_set_layout(descriptor_set_layout_create_info, None)
+        }
+    }
+
+    pub fn destroy_descriptor_set_layout(&self, descriptor_set_layout: vk::DescriptorSetLayout) {
+        unsafe {
+            self.logical_device
+                .destroy_descriptor_set_layout(descriptor_set_layout, None)
+        }
+    }
+
+    pub fn create_descriptor_pool(
+        &self,
+        descriptor_pool_create_info: &vk::DescriptorPoolCreateInfo,
+    ) -> VkResult<vk::DescriptorPool> {
+        unsafe {
+            self.logical_device
+                .create_descriptor_pool(descriptor_pool_create_info, None)
+        }
+    }
+
+    pub fn destroy_descriptor_pool(&self, descriptor_pool: vk::DescriptorPool) {
+        unsafe {
+            self.logical_device
+                .destroy_descriptor_pool(descriptor_pool, None)
+        }
+    }
+
+    pub fn allocate_descriptor_sets(
+        &self,
+        descriptor_set_allocate_info: &vk::DescriptorSetAllocateInfo,
+    ) -> VkResult<Vec<vk::DescriptorSet>> {
+        unsafe {
+            self.logical_device
+                .allocate_descriptor_sets(descriptor_set_allocate_info)
+        }
+    }
+
+    pub fn update_descriptor_sets(
+        &self,
+        all_write_descriptor_sets: &[vk::WriteDescriptorSet],
+        all_copy_descriptor_sets: &[vk::CopyDescriptorSet],
+    ) {
+        unsafe {
+            self.logical_device
+                .update_descriptor_sets(all_write_descriptor_sets, all_copy_descriptor_sets)
+        }
+    }
+
+    pub fn create_semaphore(
+        &self,
+        semaphore_create_info: &vk::SemaphoreCreateInfo,
+    ) -> VkResult<vk::Semaphore> {
+        unsafe {
+            self.logical_device
+                .create_semaphore(semaphore_create_info, None)
+        }
+    }
+
+    pub fn destroy_semaphore(&self, semaphore: vk::Semaphore) {
+        unsafe { self.logical_device.destroy_semaphore(semaphore, None) }
+    }
+
+    pub fn create_fence(&self, fence_create_info: &vk::FenceCreateInfo) -> VkResult<vk::Fence> {
+        unsafe { self.logical_device.create_fence(fence_create_info, None) }
+    }
+
+    pub fn destroy_fence(&self, fence: vk::Fence) {
+        unsafe { self.logical_device.destroy_fence(fence, None) }
+    }
+
+    ///Wait until all `fences` are signaled, or `timeout` nanoseconds have passed
+    pub fn wait_for_all_fences(&self, fences: &[vk::Fence], timeout: u64) -> VkResult<()> {
+        unsafe { self.logical_device.wait_for_fences(fences, true, timeout) }
+    }
+
+    pub fn reset_fences(&self, fences: &[vk::Fence]) -> VkResult<()> {
+        unsafe { self.logical_device.reset_fences(fences) }
+    }
+
+    pub fn create_command_pool(
+        &self,
+        command_pool_create_info: &vk::CommandPoolCreateInfo,
+    ) -> VkResult<vk::CommandPool> {
+        unsafe {
+            self.logical_device
+                .create_command_pool(command_pool_create_info, None)
+        }
+    }
+
+    pub fn destroy_command_pool(&self, command_pool: vk::CommandPool) {
+        unsafe { self.logical_device.destroy_command_pool(command_pool, None) }
+    }
+
+    pub fn allocate_command_buffers(
+        &self,
+        command_buffer_allocate_info: &vk::CommandBufferAllocateInfo,
+    ) -> VkResult<Vec<vk::CommandBuffer>> {
+        unsafe {
+            self.logical_device
+                .allocate_command_buffers(command_buffer_allocate_info)
+        }
+    }
+
+    pub fn free_command_buffers(
+        &self,
+        command_pool: vk::CommandPool,
+        command_buffers: &[vk::CommandBuffer],
+    ) {
+        unsafe {
+            self.logical_device
+                .free_command_buffers(command_pool, command_buffers)
+        }
+    }
+
+    pub fn reset_command_buffer(&self, command_buffer: vk::CommandBuffer) -> VkResult<()> {
+        unsafe {
+            self.logical_device
+                .reset_command_buffer(command_buffer, vk::CommandBufferResetFlags::empty())
+        }
+    }
+
+    pub fn begin_command_buffer(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        command_buffer_begin_info: &vk::CommandBufferBeginInfo,
+    ) -> VkResult<()> {
+        unsafe {
+            self.logical_device
+                .begin_command_buffer(command_buffer, command_buffer_begin_info)
+        }
+    }
+
+    pub fn end_command_buffer(&self, command_buffer: vk::CommandBuffer) -> VkResult<()> {
+        unsafe { self.logical_device.end_command_buffer(command_buffer) }
+    }
+
+    pub fn cmd_begin_render_pass(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        render_pass_begin_info: &vk::RenderPassBeginInfo,
+    ) {
+        unsafe {
+            self.logical_device.cmd_begin_render_pass(
+                command_buffer,
+                render_pass_begin_info,
+                vk::SubpassContents::INLINE,
+            )
+        }
+    }
+
+    pub fn cmd_end_render_pass(&self, command_buffer: vk::CommandBuffer) {
+        unsafe { self.logical_device.cmd_end_render_pass(command_buffer) }
+    }
+
+    ///Record a barrier between the `src_stage_mask` and `dst_stage_mask` stages in `command_buffer`
+    pub fn cmd_pipeline_barrier(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        (src_stage_mask, dst_stage_mask): (vk::PipelineStageFlags, vk::PipelineStageFlags),
+        all_memory_barriers: &[vk::MemoryBarrier],
+        all_buffer_memory_barriers: &[vk::BufferMemoryBarrier],
+        all_image_memory_barriers: &[vk::ImageMemoryBarrier],
+    ) {
+        unsafe {
+            self.logical_device.cmd_pipeline_barrier(
+                command_buffer,
+                src_stage_mask,
+                dst_stage_mask,
+                vk::DependencyFlags::empty(),
+                all_memory_barriers,
+                all_buffer_memory_barriers,
+                all_image_memory_barriers,
+            )
+        }
+    }
+
+    pub fn cmd_copy_buffer(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        src_buffer: vk::Buffer,
+        dst_buffer: vk::Buffer,
+        all_regions: &[vk::BufferCopy],
+    ) {
+        unsafe {
+            self.logical_device
+                .cmd_copy_buffer(command_buffer, src_buffer, dst_buffer, all_regions)
+        }
+    }
+
+    pub fn cmd_copy_buffer_to_image(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        src_buffer: vk::Buffer,
+        (dst_image, dst_image_layout): (vk::Image, vk::ImageLayout),
+        all_regions: &[vk::BufferImageCopy],
+    ) {
+        unsafe {
+            self.logical_device.cmd_copy_buffer_to_image(
+                command_buffer,
+                src_buffer,
+                dst_image,
+                dst_image_layout,
+                all_regions,
+            )
+        }
+    }
+
+    pub fn cmd_copy_image_to_buffer(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        (src_image, src_image_layout): (vk::Image, vk::ImageLayout),
+        dst_buffer: vk::Buffer,
+        all_regions: &[vk::BufferImageCopy],
+    ) {
+        unsafe {
+            self.logical_device.cmd_copy_image_to_buffer(
+                command_buffer,
+                src_image,
+                src_image_layout,
+                dst_buffer,
+                all_regions,
+            )
+        }
+    }
+
+    pub fn cmd_blit_image(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        (src_image, src_image_layout): (vk::Image, vk::ImageLayout),
+        (dst_image, dst_image_layout): (vk::Image, vk::ImageLayout),
+        all_regions: &[vk::ImageBlit],
+        filter: vk::Filter,
+    ) {
+        unsafe {
+            self.logical_device.cmd_blit_image(
+                command_buffer,
+                src_image,
+                src_image_layout,
+                dst_image,
+                dst_image_layout,
+                all_regions,
+                filter,
+            )
+        }
+    }
+
+    pub fn cmd_bind_pipeline(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        pipeline_bind_point: vk::PipelineBindPoint,
+        pipeline: vk::Pipeline,
+    ) {
+        unsafe {
+            self.logical_device
+                .cmd_bind_pipeline(command_buffer, pipeline_bind_point, pipeline)
+        }
+    }
+
+    pub fn cmd_bind_descriptor_sets(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        pipeline_bind_point: vk::PipelineBindPoint,
+        pipeline_layout: vk::PipelineLayout,
+        first_set: u32,
+        all_descriptor_sets: &[vk::DescriptorSet],
+    ) {
+        unsafe {
+            self.logical_device.cmd_bind_descriptor_sets(
+                command_buffer,
+                pipeline_bind_point,
+                pipeline_layout,
+                first_set,
+                all_descriptor_sets,
+                &[],
+            )
+        }
+    }
+
+    pub fn cmd_set_viewport(&self, command_buffer: vk::CommandBuffer, viewport: vk::Viewport) {
+        unsafe {
+            self.logical_device
+                .cmd_set_viewport(command_buffer, 0, &[viewport])
+        }
+    }
+
+    pub fn cmd_set_scissor(&self, command_buffer: vk::CommandBuffer, scissor: vk::Rect2D) {
+        unsafe {
+            self.logical_device
+                .cmd_set_scissor(command_buffer, 0, &[scissor])
+        }
+    }
+
+    pub fn cmd_push_constants(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        pipeline_layout: vk::PipelineLayout,
+        stage_flags: vk::ShaderStageFlags,
+        offset: u32,
+        constants_bytes: &[u8],
+    ) {
+        unsafe {
+            self.logical_device.cmd_push_constants(
+                command_buffer,
+                pipeline_layout,
+                stage_flags,
+                offset,
+                constants_bytes,
+            )
+        }
+    }
+
+    pub fn cmd_bind_vertex_buffers(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        first_binding: u32,
+        all_buffers: &[vk::Buffer],
+        all_offsets: &[vk::DeviceSize],
+    ) {
+        unsafe {
+            self.logical_device.cmd_bind_vertex_buffers(
+                command_buffer,
+                first_binding,
+                all_buffers,
+                all_offsets,
+            )
+        }
+    }
+
+    pub fn cmd_bind_index_buffer(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        buffer: vk::Buffer,
+        offset: vk::DeviceSize,
+        index_type: vk::IndexType,
+    ) {
+        unsafe {
+            self.logical_device
+                .cmd_bind_index_buffer(command_buffer, buffer, offset, index_type)
+        }
+    }
+
+    pub fn cmd_draw_indexed(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) {
+        unsafe {
+            self.logical_device.cmd_draw_indexed(
+                command_buffer,
+                index_count,
+                instance_count,
+                first_index,
+                vertex_offset,
+                first_instance,
+            )
+        }
+    }
+
+    pub fn cmd_draw(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) {
+        unsafe {
+            self.logical_device.cmd_draw(
+                command_buffer,
+                vertex_count,
+                instance_count,
+                first_vertex,
+                first_instance,
+            )
+        }
+    }
+
+    pub fn cmd_dispatch(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+    ) {
+        unsafe {
+            self.logical_device.cmd_dispatch(
+                command_buffer,
+                group_count_x,
+                group_count_y,
+                group_count_z,
+            )
+        }
+    }
 }
 
 impl Drop for VulkanDevice {
@@ -345,6 +1396,55 @@ fn is_physical_device_supported_extensions(
     true
 }
 
+///Represent how the dynamic rendering can be enabled on a physical device
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DynamicRenderingSupport {
+    Unsupported,
+    Core,
+    Extension,
+}
+
+/// Return how the dynamic rendering can be enabled on `physical_device`
+///
+/// # Arguments
+///
+/// * `vulkan_instance` - a reference to a VulkanInstance
+/// * `physical_device` - a Vulkan physical devices
+/// * `api_version` - the Vulkan API version usable with `physical_device`
+fn get_dynamic_rendering_support(
+    vulkan_instance: &VulkanInstance,
+    physical_device: vk::PhysicalDevice,
+    api_version: u32,
+) -> DynamicRenderingSupport {
+    let dynamic_rendering_support = if api_version >= vk::API_VERSION_1_3 {
+        DynamicRenderingSupport::Core
+    } else if api_version >= vk::API_VERSION_1_2 //VK_KHR_dynamic_rendering depends on Vulkan 1.2 features
+        && is_physical_device_supported_extensions(
+            vulkan_instance,
+            physical_device,
+            &[vk::KHR_DYNAMIC_RENDERING_NAME],
+        )
+    {
+        DynamicRenderingSupport::Extension
+    } else {
+        return DynamicRenderingSupport::Unsupported;
+    };
+
+    let mut dynamic_rendering_features = vk::PhysicalDeviceDynamicRenderingFeatures::default();
+    let mut physical_device_features =
+        vk::PhysicalDeviceFeatures2::default().push_next(&mut dynamic_rendering_features);
+    unsafe {
+        vulkan_instance
+            .get_physical_device_features2(physical_device, &mut physical_device_features)
+    };
+
+    if dynamic_rendering_features.dynamic_rendering == vk::TRUE {
+        dynamic_rendering_support
+    } else {
+        DynamicRenderingSupport::Unsupported
+    }
+}
+
 struct GraphicsAndPresentQueueFamilyId {
     graphics: u32,
     presents: u32,
@@ -364,10 +1464,11 @@ impl GraphicsAndPresentQueueFamilyId {
         for (queue_family_id, queue_family_properties) in
             all_queue_family_properties.iter().enumerate()
         {
+            //As for the headless devices, the compute shaders also run on the graphics queue
             if graphics_queue_family_id_opt.is_none()
                 && queue_family_properties
                     .queue_flags
-                    .contains(QueueFlags::GRAPHICS)
+                    .contains(QueueFlags::GRAPHICS | QueueFlags::COMPUTE)
             {
                 graphics_queue_family_id_opt = Some(queue_family_id as u32);
             }
@@ -0,0 +1,610 @@
+use std::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use ash::vk;
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    environment::{EnvironmentConfig, EnvironmentImage},
+    vulkan::{
+        command_buffer::VulkanCommandPool,
+        compute_pipeline::VulkanComputePipeline,
+        descriptor::{VulkanDescriptorPool, VulkanDescriptorSet, VulkanDescriptorSetLayout},
+        device::VulkanDevice,
+        graphics_pipeline::push_constant_range,
+        image::{SamplerParameters, VulkanImage, VulkanImageView, VulkanSampler},
+        shaders::{
+            DEFAULT_SHADER_ENTRYPOINT, ShaderModuleError, VulkanShaderModule, VulkanShaderStage,
+        },
+        texture::{TextureError, create_float_texture},
+    },
+};
+
+///SPIR-V files of the compute shaders creating the images of the image-based lighting
+const EQUIRECTANGULAR_TO_CUBE_SHADER_FILE_PATH: &str =
+    "shaders/spir-v/equirectangular_to_cube.comp.spv";
+const IRRADIANCE_SHADER_FILE_PATH: &str = "shaders/spir-v/irradiance.comp.spv";
+const PREFILTER_SHADER_FILE_PATH: &str = "shaders/spir-v/prefilter.comp.spv";
+const BRDF_LUT_SHADER_FILE_PATH: &str = "shaders/spir-v/brdf_lut.comp.spv";
+
+///Format of the cube maps and of the BRDF LUT, which all devices can filter, blit and write as storage images
+const ENVIRONMENT_FORMAT: vk::Format = vk::Format::R16G16B16A16_SFLOAT;
+
+///Width and height (in invocations) of the workgroups of the compute shaders, as declared by them
+const WORKGROUP_SIZE: u32 = 8;
+
+///Bindings of the descriptor sets of the compute shaders: the image read, its sampler and the storage image written
+const SOURCE_IMAGE_BINDING: u32 = 0;
+const SOURCE_SAMPLER_BINDING: u32 = 1;
+const DESTINATION_IMAGE_BINDING: u32 = 2;
+
+///Push constants of the compute shaders
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct EnvironmentPushConstants {
+    ///The roughness prefiltered in the mip level written
+    roughness: f32,
+
+    ///The number of samples of the specular BRDF by texel
+    sample_count: u32,
+
+    ///The width and height (in texels) of the faces of the mip level 0 of the environment cube map
+    environment_size: f32,
+
+    _padding: u32,
+}
+
+///Error returned when the images of the image-based lighting of an environment cannot be created
+#[derive(Debug)]
+pub enum EnvironmentError {
+    ///The equirectangular environment cannot be uploaded
+    Texture(TextureError),
+
+    ///A compute shader cannot be loaded
+    ShaderModule(ShaderModuleError),
+}
+
+impl Display for EnvironmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvironmentError::Texture(error) => {
+                write!(f, "failed to upload environment: {error}")
+            }
+            EnvironmentError::ShaderModule(error) => {
+                write!(f, "failed to load environment compute shader: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvironmentError {}
+
+impl From<TextureError> for EnvironmentError {
+    fn from(error: TextureError) -> Self {
+        EnvironmentError::Texture(error)
+    }
+}
+
+impl From<ShaderModuleError> for EnvironmentError {
+    fn from(error: ShaderModuleError) -> Self {
+        EnvironmentError::ShaderModule(error)
+    }
+}
+
+///Represent the images of the image-based lighting of an environment, computed on the device from an equirectangular image
+///
+///The images are in the SHADER_READ_ONLY_OPTIMAL layout, sampled with the parameters of `get_environment_sampler_parameters`
+pub struct VulkanEnvironmentImages<'vulkan_device> {
+    ///The cube map of the environment with all its mip levels, drawn by the skybox
+    environment_image: VulkanImage<'vulkan_device>,
+
+    ///The cube map of the irradiance divided by PI, lighting the diffuse surfaces by their normal
+    irradiance_image: VulkanImage<'vulkan_device>,
+
+    ///The cube map of the environment prefiltered by the GGX distribution, each mip level for a roughness,
+    ///reflected by the specular surfaces
+    prefiltered_image: VulkanImage<'vulkan_device>,
+
+    ///The scale and the bias of the specular reflectance at normal incidence in R and G,
+    ///by the cosine of the view angle in U and the roughness in V
+    brdf_lut_image: VulkanImage<'vulkan_device>,
+}
+
+impl<'vulkan_device> VulkanEnvironmentImages<'vulkan_device> {
+    ///Create the images of the image-based lighting of `environment_image`, sized by `environment_config`
+    ///
+    ///The equirectangular image is converted to a cube map, from which the irradiance and the prefiltered cube maps
+    ///are computed with the BRDF LUT by compute shaders, waiting until they are executed
+    ///
+    /// # Returns
+    ///
+    /// An error if `environment_image` cannot be uploaded, or if the compute shaders cannot be loaded
+    pub fn new(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        command_pool: &VulkanCommandPool,
+        environment_image: &EnvironmentImage,
+        environment_config: &EnvironmentConfig,
+    ) -> Result<Self, EnvironmentError> {
+        let all_shader_modules = [
+            EQUIRECTANGULAR_TO_CUBE_SHADER_FILE_PATH,
+            IRRADIANCE_SHADER_FILE_PATH,
+            PREFILTER_SHADER_FILE_PATH,
+            BRDF_LUT_SHADER_FILE_PATH,
+        ]
+        .into_iter()
+        .map(|file_path| {
+            VulkanShaderModule::from_file(vulkan_device, file_path, vk::ShaderStageFlags::COMPUTE)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let equirectangular_image = create_float_texture(
+            vulkan_device,
+            command_pool,
+            vk::Extent2D {
+                width: environment_image.width,
+                height: environment_image.height,
+            },
+            &environment_image.all_texels,
+        )?;
+
+        let cube_size = environment_config.get_cube_size(environment_image);
+        let environment_images = Self {
+            //The mip levels of the environment are blitted, then read when filtering it
+            environment_image: create_environment_image(
+                vulkan_device,
+                cube_size,
+                cube_size.ilog2() + 1,
+                true,
+                vk::ImageUsageFlags::TRANSFER_SRC | vk::ImageUsageFlags::TRANSFER_DST,
+            ),
+            irradiance_image: create_environment_image(
+                vulkan_device,
+                environment_config.irradiance_size.max(1),
+                1,
+                true,
+                vk::ImageUsageFlags::empty(),
+            ),
+            prefiltered_image: create_environment_image(
+                vulkan_device,
+                environment_config.prefiltered_size.max(1),
+                environment_config.get_prefiltered_mip_levels(),
+                true,
+                vk::ImageUsageFlags::empty(),
+            ),
+            brdf_lut_image: create_environment_image(
+                vulkan_device,
+                environment_config.brdf_lut_size.max(1),
+                1,
+                false,
+                vk::ImageUsageFlags::empty(),
+            ),
+        };
+
+        environment_images.compute(
+            vulkan_device,
+            command_pool,
+            &all_shader_modules,
+            &equirectangular_image,
+            environment_config,
+        )?;
+
+        Ok(environment_images)
+    }
+
+    ///Return the cube map of the environment, with all its mip levels
+    pub fn get_environment_image(&self) -> &VulkanImage<'vulkan_device> {
+        &self.environment_image
+    }
+
+    ///Return the cube map of the irradiance divided by PI, by normal
+    pub fn get_irradiance_image(&self) -> &VulkanImage<'vulkan_device> {
+        &self.irradiance_image
+    }
+
+    ///Return the cube map of the prefiltered environment, whose mip levels are evenly spaced from the roughness 0 to 1
+    pub fn get_prefiltered_image(&self) -> &VulkanImage<'vulkan_device> {
+        &self.prefiltered_image
+    }
+
+    ///Return the scale and the bias of the specular reflectance, by the cosine of the view angle and the roughness
+    pub fn get_brdf_lut_image(&self) -> &VulkanImage<'vulkan_device> {
+        &self.brdf_lut_image
+    }
+
+    ///Compute all the images of `&self` from `equirectangular_image`, with the compute shaders of `all_shader_modules`
+    ///
+    ///The images are written in the GENERAL layout, except the mip levels of the environment, which are blitted
+    fn compute(
+        &self,
+        vulkan_device: &VulkanDevice,
+        command_pool: &VulkanCommandPool,
+        all_shader_modules: &[VulkanShaderModule],
+        equirectangular_image: &VulkanImage,
+        environment_config: &EnvironmentConfig,
+    ) -> Result<(), ShaderModuleError> {
+        let descriptor_set_layout = VulkanDescriptorSetLayout::new(
+            vulkan_device,
+            &[
+                vk::DescriptorSetLayoutBinding::default()
+                    .binding(SOURCE_IMAGE_BINDING)
+                    .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+                    .descriptor_count(1)
+                    .stage_flags(vk::ShaderStageFlags::COMPUTE),
+                vk::DescriptorSetLayoutBinding::default()
+                    .binding(SOURCE_SAMPLER_BINDING)
+                    .descriptor_type(vk::DescriptorType::SAMPLER)
+                    .descriptor_count(1)
+                    .stage_flags(vk::ShaderStageFlags::COMPUTE),
+                vk::DescriptorSetLayoutBinding::default()
+                    .binding(DESTINATION_IMAGE_BINDING)
+                    .descriptor_type(vk::DescriptorType::STORAGE_IMAGE)
+                    .descriptor_count(1)
+                    .stage_flags(vk::ShaderStageFlags::COMPUTE),
+            ],
+        );
+
+        let all_compute_pipelines = all_shader_modules
+            .iter()
+            .map(|shader_module| {
+                Ok(VulkanComputePipeline::new(
+                    vulkan_device,
+                    &VulkanShaderStage::new(shader_module, DEFAULT_SHADER_ENTRYPOINT)?,
+                    &[push_constant_range::<EnvironmentPushConstants>(
+                        vk::ShaderStageFlags::COMPUTE,
+                        0,
+                    )],
+                    &[descriptor_set_layout.get_descriptor_set_layout()],
+                ))
+            })
+            .collect::<Result<Vec<_>, ShaderModuleError>>()?;
+        let [
+            equirectangular_to_cube_pipeline,
+            irradiance_pipeline,
+            prefilter_pipeline,
+            brdf_lut_pipeline,
+        ] = &all_compute_pipelines[..]
+        else {
+            unreachable!("an environment compute pipeline is missing");
+        };
+
+        //A set converting the equirectangular image, one computing the irradiance,
+        //one computing the BRDF LUT, and one for each prefiltered mip level
+        let prefiltered_mip_levels = self.prefiltered_image.get_mip_levels();
+        let set_count = 3 + prefiltered_mip_levels;
+        let descriptor_pool = VulkanDescriptorPool::new(
+            vulkan_device,
+            set_count,
+            &[
+                vk::DescriptorPoolSize::default()
+                    .ty(vk::DescriptorType::SAMPLED_IMAGE)
+                    .descriptor_count(set_count),
+                vk::DescriptorPoolSize::default()
+                    .ty(vk::DescriptorType::SAMPLER)
+                    .descriptor_count(set_count),
+                vk::DescriptorPoolSize::default()
+                    .ty(vk::DescriptorType::STORAGE_IMAGE)
+                    .descriptor_count(set_count),
+            ],
+        );
+        let all_descriptor_sets = descriptor_pool.allocate_descriptor_sets(&vec![
+                descriptor_set_layout
+                    .get_descriptor_set_layout();
+                set_count as usize
+            ]);
+
+        let sampler = VulkanSampler::new(vulkan_device, &get_environment_sampler_parameters());
+        let equirectangular_image_view = VulkanImageView::new_color(equirectangular_image);
+        let environment_image_view = VulkanImageView::new_color(&self.environment_image);
+        let environment_storage_view =
+            VulkanImageView::new_color_mip_level(&self.environment_image, 0);
+        let irradiance_storage_view =
+            VulkanImageView::new_color_mip_level(&self.irradiance_image, 0);
+        let brdf_lut_storage_view = VulkanImageView::new_color_mip_level(&self.brdf_lut_image, 0);
+        let all_prefiltered_storage_views: Vec<_> = (0..prefiltered_mip_levels)
+            .map(|mip_level| {
+                VulkanImageView::new_color_mip_level(&self.prefiltered_image, mip_level)
+            })
+            .collect();
+
+        let write_descriptor_set =
+            |descriptor_set: &VulkanDescriptorSet,
+             source_image_view_opt: Option<&VulkanImageView>,
+             destination_image_view: &VulkanImageView| {
+                if let Some(source_image_view) = source_image_view_opt {
+                    descriptor_set.write_sampled_image(SOURCE_IMAGE_BINDING, source_image_view);
+                    descriptor_set.write_sampler(SOURCE_SAMPLER_BINDING, &sampler);
+                }
+                descriptor_set
+                    .write_storage_image(DESTINATION_IMAGE_BINDING, destination_image_view);
+            };
+        let [
+            equirectangular_descriptor_set,
+            irradiance_descriptor_set,
+            brdf_lut_descriptor_set,
+            all_prefilter_descriptor_sets @ ..,
+        ] = &all_descriptor_sets[..]
+        else {
+            unreachable!("an environment descriptor set is missing");
+        };
+        write_descriptor_set(
+            equirectangular_descriptor_set,
+            Some(&equirectangular_image_view),
+            &environment_storage_view,
+        );
+        write_descriptor_set(
+            irradiance_descriptor_set,
+            Some(&environment_image_view),
+            &irradiance_storage_view,
+        );
+        write_descriptor_set(brdf_lut_descriptor_set, None, &brdf_lut_storage_view);
+        for (descriptor_set, prefiltered_storage_view) in all_prefilter_descriptor_sets
+            .iter()
+            .zip(&all_prefiltered_storage_views)
+        {
+            write_descriptor_set(
+                descriptor_set,
+                Some(&environment_image_view),
+                prefiltered_storage_view,
+            );
+        }
+
+        let cube_size = self.environment_image.get_extent().width;
+        let environment_mip_levels = self.environment_image.get_mip_levels();
+        let push_constants = EnvironmentPushConstants {
+            roughness: 0f32,
+            sample_count: environment_config.sample_count.max(1),
+            environment_size: cube_size as f32,
+            _padding: 0,
+        };
+
+        command_pool.execute_one_time_commands(|command_buffer| {
+            let dispatch = |compute_pipeline: &VulkanComputePipeline,
+                            descriptor_set: &VulkanDescriptorSet,
+                            push_constants: &EnvironmentPushConstants,
+                            image: &VulkanImage,
+                            mip_level: u32| {
+                let size = (image.get_extent().width >> mip_level).max(1);
+                command_buffer.bind_compute_pipeline(compute_pipeline);
+                command_buffer.bind_compute_descriptor_sets(
+                    compute_pipeline,
+                    0,
+                    &[descriptor_set.get_descriptor_set()],
+                );
+                command_buffer.push_compute_constants(compute_pipeline, 0, push_constants);
+                command_buffer.dispatch([
+                    size.div_ceil(WORKGROUP_SIZE),
+                    size.div_ceil(WORKGROUP_SIZE),
+                    image.get_array_layers(),
+                ]);
+            };
+            let transition_to_general = |image: &VulkanImage, mip_levels: Range<u32>| {
+                command_buffer.transition_image_layout(
+                    image.get_image(),
+                    get_color_subresource_range(image, mip_levels),
+                    vk::ImageLayout::UNDEFINED,
+                    vk::ImageLayout::GENERAL,
+                    (
+                        vk::PipelineStageFlags::TOP_OF_PIPE,
+                        vk::AccessFlags::empty(),
+                    ),
+                    (
+                        vk::PipelineStageFlags::COMPUTE_SHADER,
+                        vk::AccessFlags::SHADER_WRITE,
+                    ),
+                );
+            };
+
+            transition_to_general(&self.environment_image, 0..1);
+            dispatch(
+                equirectangular_to_cube_pipeline,
+                equirectangular_descriptor_set,
+                &push_constants,
+                &self.environment_image,
+                0,
+            );
+
+            //Each mip level of the environment is blitted from the previous one, which is then ready to be blitted from
+            command_buffer.transition_image_layout(
+                self.environment_image.get_image(),
+                get_color_subresource_range(&self.environment_image, 0..1),
+                vk::ImageLayout::GENERAL,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                (
+                    vk::PipelineStageFlags::COMPUTE_SHADER,
+                    vk::AccessFlags::SHADER_WRITE,
+                ),
+                (
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::AccessFlags::TRANSFER_READ,
+                ),
+            );
+            for mip_level in 1..environment_mip_levels {
+                let mip_level_range =
+                    get_color_subresource_range(&self.environment_image, mip_level..mip_level + 1);
+                command_buffer.transition_image_layout(
+                    self.environment_image.get_image(),
+                    mip_level_range,
+                    vk::ImageLayout::UNDEFINED,
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    (
+                        vk::PipelineStageFlags::TOP_OF_PIPE,
+                        vk::AccessFlags::empty(),
+                    ),
+                    (
+                        vk::PipelineStageFlags::TRANSFER,
+                        vk::AccessFlags::TRANSFER_WRITE,
+                    ),
+                );
+
+                command_buffer.blit_image(
+                    self.environment_image.get_image(),
+                    self.environment_image.get_image(),
+                    get_cube_mip_level_blit(cube_size, mip_level),
+                    vk::Filter::LINEAR,
+                );
+
+                command_buffer.transition_image_layout(
+                    self.environment_image.get_image(),
+                    mip_level_range,
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    (
+                        vk::PipelineStageFlags::TRANSFER,
+                        vk::AccessFlags::TRANSFER_WRITE,
+                    ),
+                    (
+                        vk::PipelineStageFlags::TRANSFER,
+                        vk::AccessFlags::TRANSFER_READ,
+                    ),
+                );
+            }
+            command_buffer.transition_image_layout(
+                self.environment_image.get_image(),
+                get_color_subresource_range(&self.environment_image, 0..environment_mip_levels),
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                (
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::AccessFlags::TRANSFER_READ,
+                ),
+                (
+                    vk::PipelineStageFlags::COMPUTE_SHADER
+                        | vk::PipelineStageFlags::FRAGMENT_SHADER,
+                    vk::AccessFlags::SHADER_READ,
+                ),
+            );
+
+            transition_to_general(&self.irradiance_image, 0..1);
+            dispatch(
+                irradiance_pipeline,
+                irradiance_descriptor_set,
+                &push_constants,
+                &self.irradiance_image,
+                0,
+            );
+
+            transition_to_general(&self.prefiltered_image, 0..prefiltered_mip_levels);
+            for (mip_level, descriptor_set) in
+                (0..prefiltered_mip_levels).zip(all_prefilter_descriptor_sets)
+            {
+                dispatch(
+                    prefilter_pipeline,
+                    descriptor_set,
+                    &EnvironmentPushConstants {
+                        roughness: environment_config.get_prefiltered_roughness(mip_level),
+                        ..push_constants
+                    },
+                    &self.prefiltered_image,
+                    mip_level,
+                );
+            }
+
+            transition_to_general(&self.brdf_lut_image, 0..1);
+            dispatch(
+                brdf_lut_pipeline,
+                brdf_lut_descriptor_set,
+                &push_constants,
+                &self.brdf_lut_image,
+                0,
+            );
+
+            for image in [
+                &self.irradiance_image,
+                &self.prefiltered_image,
+                &self.brdf_lut_image,
+            ] {
+                command_buffer.transition_image_layout(
+                    image.get_image(),
+                    get_color_subresource_range(image, 0..image.get_mip_levels()),
+                    vk::ImageLayout::GENERAL,
+                    vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                    (
+                        vk::PipelineStageFlags::COMPUTE_SHADER,
+                        vk::AccessFlags::SHADER_WRITE,
+                    ),
+                    (
+                        vk::PipelineStageFlags::FRAGMENT_SHADER,
+                        vk::AccessFlags::SHADER_READ,
+                    ),
+                );
+            }
+        });
+
+        Ok(())
+    }
+}
+
+///Return the parameters of the sampler of the images of VulkanEnvironmentImages,
+///filtering the texels and the mip levels linearly without repeating the BRDF LUT
+pub fn get_environment_sampler_parameters() -> SamplerParameters {
+    SamplerParameters {
+        mag_filter: vk::Filter::LINEAR,
+        min_filter: vk::Filter::LINEAR,
+        mipmap_mode: vk::SamplerMipmapMode::LINEAR,
+        address_modes: [vk::SamplerAddressMode::CLAMP_TO_EDGE; 3],
+        max_anisotropy: None,
+    }
+}
+
+///Create a square image in ENVIRONMENT_FORMAT of `size` texels with `mip_levels`,
+///a cube map if `is_cube` is true, written by the compute shaders then sampled, and used for `usage`
+fn create_environment_image<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    size: u32,
+    mip_levels: u32,
+    is_cube: bool,
+    usage: vk::ImageUsageFlags,
+) -> VulkanImage<'vulkan_device> {
+    VulkanImage::new_2d(
+        vulkan_device,
+        vk::Extent2D {
+            width: size,
+            height: size,
+        },
+        ENVIRONMENT_FORMAT,
+        mip_levels,
+        if is_cube { 6 } else { 1 },
+        is_cube,
+        vk::ImageUsageFlags::STORAGE | vk::ImageUsageFlags::SAMPLED | usage,
+    )
+}
+
+///Return the range of the color of the `mip_levels` of all the array layers of `image`
+fn get_color_subresource_range(
+    image: &VulkanImage,
+    mip_levels: Range<u32>,
+) -> vk::ImageSubresourceRange {
+    vk::ImageSubresourceRange::default()
+        .aspect_mask(vk::ImageAspectFlags::COLOR)
+        .base_mip_level(mip_levels.start)
+        .level_count(mip_levels.len() as u32)
+        .base_array_layer(0)
+        .layer_count(image.get_array_layers())
+}
+
+///Return the blit scaling the 6 faces of `mip_level - 1` of a cube map of `cube_size` texels to `mip_level`
+fn get_cube_mip_level_blit(cube_size: u32, mip_level: u32) -> vk::ImageBlit {
+    let get_subresource_layers = |mip_level: u32| {
+        vk::ImageSubresourceLayers::default()
+            .aspect_mask(vk::ImageAspectFlags::COLOR)
+            .mip_level(mip_level)
+            .base_array_layer(0)
+            .layer_count(6)
+    };
+    let get_max_offset = |mip_level: u32| {
+        let size = (cube_size >> mip_level).max(1) as i32;
+        vk::Offset3D {
+            x: size,
+            y: size,
+            z: 1,
+        }
+    };
+
+    vk::ImageBlit::default()
+        .src_subresource(get_subresource_layers(mip_level - 1))
+        .src_offsets([vk::Offset3D::default(), get_max_offset(mip_level - 1)])
+        .dst_subresource(get_subresource_layers(mip_level))
+        .dst_offsets([vk::Offset3D::default(), get_max_offset(mip_level)])
+}
@@ -0,0 +1,100 @@
+use ash::vk;
+
+use crate::vulkan::{device::VulkanDevice, graphics_pipeline::VulkanGraphicsPipeline};
+
+///Represent a Vulkan framebuffer, containing the attachments used by a render pass
+pub struct VulkanFramebuffer<'vulkan_device> {
+    ///A reference to the Vulkan Device which created this framebuffer
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan framebuffer
+    framebuffer: vk::Framebuffer,
+}
+
+impl<'vulkan_device> VulkanFramebuffer<'vulkan_device> {
+    ///Create a framebuffer usable by the render pass of `graphics_pipeline`, with `color_image_view` as color attachment
+    ///and `depth_image_view` as depth attachment if `graphics_pipeline` tests the depth
+    ///
+    /// # Panics
+    ///
+    /// If `graphics_pipeline` is used with the dynamic rendering,
+    /// or `depth_image_view` is None while `graphics_pipeline` tests the depth
+    pub fn new_for_image_views(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        graphics_pipeline: &VulkanGraphicsPipeline,
+        color_image_view: vk::ImageView,
+        depth_image_view: Option<vk::ImageView>,
+        extent: vk::Extent2D,
+    ) -> Self {
+        let mut attachments = vec![color_image_view];
+        if graphics_pipeline.get_depth_format().is_some() {
+            attachments.push(
+                depth_image_view
+                    .expect("graphics pipeline testing the depth without depth image view"),
+            );
+        }
+        let framebuffer_create_info = vk::FramebufferCreateInfo::default()
+            .render_pass(
+                graphics_pipeline
+                    .get_render_pass()
+                    .expect("graphics pipeline without render pass"),
+            )
+            .attachments(&attachments)
+            .width(extent.width)
+            .height(extent.height)
+            .layers(1);
+
+        let framebuffer = vulkan_device
+            .create_framebuffer(&framebuffer_create_info)
+            .expect("failed to create Vulkan framebuffer");
+
+        Self {
+            vulkan_device,
+            framebuffer,
+        }
+    }
+
+    ///Create a framebuffer usable by the render pass of the depth-only `graphics_pipeline`,
+    ///with `depth_image_view` as depth attachment
+    ///
+    /// # Panics
+    ///
+    /// If `graphics_pipeline` is used with the dynamic rendering
+    pub fn new_for_depth_image_view(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        graphics_pipeline: &VulkanGraphicsPipeline,
+        depth_image_view: vk::ImageView,
+        extent: vk::Extent2D,
+    ) -> Self {
+        let attachments = [depth_image_view];
+        let framebuffer_create_info = vk::FramebufferCreateInfo::default()
+            .render_pass(
+                graphics_pipeline
+                    .get_render_pass()
+                    .expect("graphics pipeline without render pass"),
+            )
+            .attachments(&attachments)
+            .width(extent.width)
+            .height(extent.height)
+            .layers(1);
+
+        let framebuffer = vulkan_device
+            .create_framebuffer(&framebuffer_create_info)
+            .expect("failed to create Vulkan framebuffer");
+
+        Self {
+            vulkan_device,
+            framebuffer,
+        }
+    }
+
+    pub fn get_framebuffer(&self) -> vk::Framebuffer {
+        self.framebuffer
+    }
+}
+
+impl Drop for VulkanFramebuffer<'_> {
+    fn drop(&mut self) {
+        self.vulkan_device.destroy_framebuffer(self.framebuffer);
+    }
+}
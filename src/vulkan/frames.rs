@@ -0,0 +1,257 @@
+use ash::vk;
+
+use crate::vulkan::{
+    command_buffer::{VulkanCommandBuffer, VulkanCommandPool},
+    device::VulkanDevice,
+    swapchain::VulkanSwapchain,
+    sync::{VulkanFence, VulkanSemaphore},
+};
+
+///Number of frames which can be recorded by the host while the previous ones are rendered by the device
+pub const MAX_FRAMES_IN_FLIGHT: usize = 2;
+
+///Represent the resources used to record and submit one frame
+struct FrameInFlight<'command_pool, 'vulkan_device> {
+    command_buffer: VulkanCommandBuffer<'command_pool, 'vulkan_device>,
+
+    ///The command buffer acquiring the ownership of the swapchain image on the present queue,
+    ///None if the frames in flight were created without present command pool
+    present_command_buffer: Option<VulkanCommandBuffer<'command_pool, 'vulkan_device>>,
+
+    ///Signaled once the acquired swapchain image can be rendered
+    image_available_semaphore: VulkanSemaphore<'vulkan_device>,
+
+    ///Signaled once the device finished to render the frame
+    in_flight_fence: VulkanFence<'vulkan_device>,
+}
+
+///Represent the frames in flight: up to `MAX_FRAMES_IN_FLIGHT` frames can be rendered by the device at the same time
+///
+///A frame is recorded in the command buffer returned by `get_command_buffer`, between `begin_frame` and `end_frame`
+pub struct VulkanFramesInFlight<'command_pool, 'vulkan_device> {
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    all_frames: Box<[FrameInFlight<'command_pool, 'vulkan_device>]>,
+
+    ///A semaphore for each swapchain image, signaled once the device finished to render it
+    ///
+    ///It's indexed by swapchain image, because a swapchain image may be presented while its semaphore is still in use
+    all_render_finished_semaphores: Vec<VulkanSemaphore<'vulkan_device>>,
+
+    ///A semaphore for each swapchain image, signaled once the present queue acquired its ownership
+    ///
+    ///Only used if the swapchain requires ownership transfers
+    all_ownership_acquired_semaphores: Vec<VulkanSemaphore<'vulkan_device>>,
+
+    ///Index in `all_frames` of the frame being recorded
+    current_frame_index: usize,
+}
+
+impl<'command_pool, 'vulkan_device> VulkanFramesInFlight<'command_pool, 'vulkan_device> {
+    ///Create the frames in flight, with command buffers allocated from `command_pool`
+    ///
+    ///`present_command_pool` allocates the command buffers acquiring the ownership of the swapchain images on the present queue,
+    ///it's only necessary if the swapchain requires ownership transfers
+    pub fn new(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        command_pool: &'command_pool VulkanCommandPool<'vulkan_device>,
+        present_command_pool: Option<&'command_pool VulkanCommandPool<'vulkan_device>>,
+    ) -> Self {
+        let mut all_present_command_buffers = present_command_pool
+            .map(|present_command_pool| {
+                present_command_pool.allocate_command_buffers(MAX_FRAMES_IN_FLIGHT as u32)
+            })
+            .unwrap_or_default()
+            .into_iter();
+
+        let all_frames = command_pool
+            .allocate_command_buffers(MAX_FRAMES_IN_FLIGHT as u32)
+            .into_iter()
+            .map(|command_buffer| FrameInFlight {
+                command_buffer,
+                present_command_buffer: all_present_command_buffers.next(),
+                image_available_semaphore: VulkanSemaphore::new(vulkan_device),
+                in_flight_fence: VulkanFence::new(vulkan_device, true),
+            })
+            .collect();
+
+        Self {
+            vulkan_device,
+            all_frames,
+            all_render_finished_semaphores: Vec::new(),
+            all_ownership_acquired_semaphores: Vec::new(),
+            current_frame_index: 0,
+        }
+    }
+
+    ///Return the index (lower than `MAX_FRAMES_IN_FLIGHT`) of the frame being recorded
+    ///
+    ///For example, can be used to select the per-frame resources not used by the device
+    pub fn get_current_frame_index(&self) -> usize {
+        self.current_frame_index
+    }
+
+    ///Wait until the device finished to render all the frames in flight submitted by `end_frame`
+    ///
+    ///For example, the resources recorded by their command buffers can then be destroyed,
+    ///without waiting for the other work of the device
+    pub fn wait_for_all_frames(&self) {
+        for frame in &self.all_frames {
+            frame.in_flight_fence.wait();
+        }
+    }
+
+    ///Return the command buffer of the frame being recorded
+    pub fn get_command_buffer(&self) -> &VulkanCommandBuffer<'command_pool, 'vulkan_device> {
+        &self.all_frames[self.current_frame_index].command_buffer
+    }
+
+    ///Wait until the current frame isn't used by the device, acquire the next image of `swapchain` and begin the command buffer
+    ///
+    /// # Returns
+    ///
+    /// The index of the acquired swapchain image, None if `swapchain` is out of date and must be recreated
+    pub fn begin_frame(&mut self, swapchain: &VulkanSwapchain) -> Option<u32> {
+        let frame = &self.all_frames[self.current_frame_index];
+
+        frame.in_flight_fence.wait();
+
+        let image_index =
+            match swapchain.acquire_next_image(frame.image_available_semaphore.get_semaphore()) {
+                Ok((image_index, _suboptimal)) => image_index,
+                Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => return None,
+                Err(error) => panic!("failed to acquire next swapchain image: {error}"),
+            };
+
+        frame.in_flight_fence.reset();
+        frame.command_buffer.begin();
+
+        Some(image_index)
+    }
+
+    ///End the command buffer, submit it to the graphics queue and present the image `image_index` of `swapchain`
+    ///
+    ///The image must be in the PRESENT_SRC_KHR layout.
+    ///If `swapchain` requires it, its ownership is transferred from the graphics queue to the present queue before the presentation
+    ///
+    /// # Returns
+    ///
+    /// True if `swapchain` is suboptimal or out of date and should be recreated
+    ///
+    /// # Panics
+    ///
+    /// If `swapchain` requires ownership transfers and `&self` was created without present command pool
+    pub fn end_frame(&mut self, swapchain: &VulkanSwapchain, image_index: u32) -> bool {
+        while self.all_render_finished_semaphores.len() <= image_index as usize {
+            self.all_render_finished_semaphores
+                .push(VulkanSemaphore::new(self.vulkan_device));
+            self.all_ownership_acquired_semaphores
+                .push(VulkanSemaphore::new(self.vulkan_device));
+        }
+
+        let frame = &self.all_frames[self.current_frame_index];
+        let render_finished_semaphore =
+            self.all_render_finished_semaphores[image_index as usize].get_semaphore();
+
+        let is_ownership_transfer_required = swapchain.is_ownership_transfer_required();
+        let image = swapchain.get_all_images()[image_index as usize];
+        let queue_family_indices = (
+            self.vulkan_device.get_graphics_queue_family_index(),
+            self.vulkan_device.get_present_queue_family_index(),
+        );
+
+        //The image is released by the graphics queue once rendered (or captured)
+        if is_ownership_transfer_required {
+            frame.command_buffer.transfer_color_image_ownership(
+                image,
+                vk::ImageLayout::PRESENT_SRC_KHR,
+                queue_family_indices,
+                (
+                    vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT
+                        | vk::PipelineStageFlags::TRANSFER,
+                    vk::AccessFlags::COLOR_ATTACHMENT_WRITE | vk::AccessFlags::TRANSFER_WRITE,
+                ),
+                (
+                    vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                    vk::AccessFlags::empty(),
+                ),
+            );
+        }
+
+        frame.command_buffer.end();
+
+        let wait_semaphores = [frame.image_available_semaphore.get_semaphore()];
+        let wait_dst_stage_masks = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
+        let command_buffers = [frame.command_buffer.get_command_buffer()];
+        let signal_semaphores = [render_finished_semaphore];
+        let submit_info = vk::SubmitInfo::default()
+            .wait_semaphores(&wait_semaphores)
+            .wait_dst_stage_mask(&wait_dst_stage_masks)
+            .command_buffers(&command_buffers)
+            .signal_semaphores(&signal_semaphores);
+
+        //With an ownership transfer, the fence is signaled by the present queue, which waits for the graphics queue
+        self.vulkan_device
+            .submit_to_graphics_queue(
+                &[submit_info],
+                if is_ownership_transfer_required {
+                    vk::Fence::null()
+                } else {
+                    frame.in_flight_fence.get_fence()
+                },
+            )
+            .expect("failed to submit frame to graphics queue");
+
+        let present_wait_semaphore = if is_ownership_transfer_required {
+            let present_command_buffer = frame
+                .present_command_buffer
+                .as_ref()
+                .expect("swapchain requires ownership transfers without present command pool");
+            let ownership_acquired_semaphore =
+                self.all_ownership_acquired_semaphores[image_index as usize].get_semaphore();
+
+            //The present queue may not support the graphics stages
+            present_command_buffer.begin();
+            present_command_buffer.transfer_color_image_ownership(
+                image,
+                vk::ImageLayout::PRESENT_SRC_KHR,
+                queue_family_indices,
+                (
+                    vk::PipelineStageFlags::ALL_COMMANDS,
+                    vk::AccessFlags::empty(),
+                ),
+                (
+                    vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                    vk::AccessFlags::empty(),
+                ),
+            );
+            present_command_buffer.end();
+
+            let wait_semaphores = [render_finished_semaphore];
+            let wait_dst_stage_masks = [vk::PipelineStageFlags::ALL_COMMANDS];
+            let command_buffers = [present_command_buffer.get_command_buffer()];
+            let signal_semaphores = [ownership_acquired_semaphore];
+            let submit_info = vk::SubmitInfo::default()
+                .wait_semaphores(&wait_semaphores)
+                .wait_dst_stage_mask(&wait_dst_stage_masks)
+                .command_buffers(&command_buffers)
+                .signal_semaphores(&signal_semaphores);
+
+            self.vulkan_device
+                .submit_to_present_queue(&[submit_info], frame.in_flight_fence.get_fence())
+                .expect("failed to submit ownership acquire to present queue");
+
+            ownership_acquired_semaphore
+        } else {
+            render_finished_semaphore
+        };
+
+        self.current_frame_index = (self.current_frame_index + 1) % MAX_FRAMES_IN_FLIGHT;
+
+        match swapchain.present(image_index, present_wait_semaphore) {
+            Ok(suboptimal) => suboptimal,
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => true,
+            Err(error) => panic!("failed to present swapchain image: {error}"),
+        }
+    }
+}
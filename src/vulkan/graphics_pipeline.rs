@@ -1,148 +1,399 @@
-use std::mem::MaybeUninit;
-
-use ash::vk::{
-    self, AttachmentLoadOp, AttachmentStoreOp, ColorComponentFlags, CullModeFlags, Extent2D,
-    Format, FrontFace, ImageLayout, Offset2D, PipelineBindPoint, PipelineLayout,
-    PipelineViewportStateCreateInfo, PolygonMode, PrimitiveTopology, SampleCountFlags,
+use ash::{
+    prelude::VkResult,
+    vk::{
+        self, AccessFlags, AttachmentLoadOp, AttachmentStoreOp, ColorComponentFlags, CompareOp,
+        CullModeFlags, Extent2D, Format, FrontFace, ImageLayout, Offset2D, PipelineBindPoint,
+        PipelineLayout, PipelineStageFlags, PipelineViewportStateCreateInfo, PolygonMode,
+        PrimitiveTopology, SampleCountFlags,
+    },
 };
 
-use crate::vulkan::{device::VulkanDevice, shaders::VulkanShaderModule};
+use bytemuck::Pod;
+
+use crate::vulkan::{device::VulkanDevice, shaders::VulkanShaderStage, swapchain::VulkanSwapchain};
 
 pub struct VulkanGraphicsPipeline<'vulkan_device> {
     vulkan_device: &'vulkan_device VulkanDevice,
     pipeline: vk::Pipeline,
     pipeline_layout: PipelineLayout,
-    render_pass: vk::RenderPass,
+
+    ///The render pass used by `pipeline`, None if `pipeline` is used with the dynamic rendering
+    render_pass: Option<vk::RenderPass>,
+
+    ///The format of the color attachment rendered by `pipeline`, None if `pipeline` only renders the depth
+    color_format: Option<Format>,
+
+    ///The depth attachment and fixed-function state of `pipeline`
+    config: GraphicsPipelineConfig,
+
+    ///The dynamic viewport set when `pipeline` is bound, covering the extent of the rendered images
+    viewport: vk::Viewport,
+
+    ///The dynamic scissor set when `pipeline` is bound, covering the extent of the rendered images
+    scissor: vk::Rect2D,
+
+    ///Paths of the SPIR-V files of the shader modules used to create `pipeline`
+    all_shader_file_paths: Box<[Box<str>]>,
+
+    ///The push constant ranges declared by `pipeline_layout`
+    all_push_constant_ranges: Box<[vk::PushConstantRange]>,
+}
+
+///Represent the depth attachment and the fixed-function state of a graphics pipeline
+///
+///For example, the pipelines drawing materials differ by their culling and blending
+#[derive(Clone, Copy, Debug)]
+pub struct GraphicsPipelineConfig {
+    ///The vertex buffer bindings read by the vertex shader
+    ///
+    ///Without vertex input, the vertex shader computes its vertices from their index
+    pub all_vertex_binding_descriptions: &'static [vk::VertexInputBindingDescription],
+
+    ///The vertex attributes read by the vertex shader, from `all_vertex_binding_descriptions`
+    pub all_vertex_attribute_descriptions: &'static [vk::VertexInputAttributeDescription],
+
+    ///The format of the depth attachment tested, None if the depth isn't tested
+    pub depth_format: Option<Format>,
+
+    ///The faces which are not drawn, the front faces being counterclockwise
+    pub cull_mode: CullModeFlags,
+
+    ///If the fragments are blended with the color attachment by their alpha
+    ///
+    ///The blended fragments are tested against the depth attachment, but don't write it
+    pub is_alpha_blended: bool,
+
+    ///The bias added to the depth of the fragments, None to keep their depth
+    pub depth_bias: Option<DepthBias>,
+
+    ///The comparison of the depth of the fragments with the depth attachment, the fragments passing it are drawn
+    ///
+    ///For example, the skybox drawn at the far plane passes LESS_OR_EQUAL where the depth attachment is still cleared
+    pub depth_compare_op: CompareOp,
+}
+
+///Bias added to the depth of the rasterized fragments, as with vkCmdSetDepthBias
+///
+///For example, the shadow maps are biased to avoid the shadow acne on the surfaces lit at grazing angles
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DepthBias {
+    ///Multiplied with the minimum resolvable difference of the depth format
+    pub constant_factor: f32,
+
+    ///Multiplied with the slope of the depth of the fragments
+    pub slope_factor: f32,
+}
+
+impl GraphicsPipelineConfig {
+    ///Return the default config testing a depth attachment in `depth_format`
+    pub fn with_depth(depth_format: Format) -> Self {
+        Self {
+            depth_format: Some(depth_format),
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for GraphicsPipelineConfig {
+    ///Opaque fragments of the front faces, without vertex input nor depth attachment
+    fn default() -> Self {
+        Self {
+            all_vertex_binding_descriptions: &[],
+            all_vertex_attribute_descriptions: &[],
+            depth_format: None,
+            cull_mode: CullModeFlags::BACK,
+            is_alpha_blended: false,
+            depth_bias: None,
+            depth_compare_op: CompareOp::LESS,
+        }
+    }
+}
+
+///Return the push constant range of the type `T` starting at `offset` (in bytes), used by `stage_flags`
+pub fn push_constant_range<T: Pod>(
+    stage_flags: vk::ShaderStageFlags,
+    offset: u32,
+) -> vk::PushConstantRange {
+    vk::PushConstantRange::default()
+        .stage_flags(stage_flags)
+        .offset(offset)
+        .size(size_of::<T>() as u32)
 }
 
 impl<'vulkan_device> VulkanGraphicsPipeline<'vulkan_device> {
+    ///Create a graphics pipeline rendering the images of `swapchain`, in their format and extent
+    ///
+    ///After `swapchain` is recreated, `update_for_swapchain` must be called
+    pub fn new_for_swapchain(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        swapchain: &VulkanSwapchain,
+        all_shader_stages: &[VulkanShaderStage],
+        all_push_constant_ranges: &[vk::PushConstantRange],
+        all_descriptor_set_layouts: &[vk::DescriptorSetLayout],
+        config: &GraphicsPipelineConfig,
+    ) -> Self {
+        Self::new(
+            vulkan_device,
+            swapchain.get_image_extent(),
+            swapchain.get_image_format(),
+            all_shader_stages,
+            all_push_constant_ranges,
+            all_descriptor_set_layouts,
+            config,
+        )
+    }
+
+    ///Create a graphics pipeline rendering color images in `color_format`,
+    ///with a viewport and a scissor covering `extent`
     pub fn new(
         vulkan_device: &'vulkan_device VulkanDevice,
-        swapchain_extend: Extent2D,
-        swapchain_format: Format,
-        all_shader_modules: &[VulkanShaderModule],
+        extent: Extent2D,
+        color_format: Format,
+        all_shader_stages: &[VulkanShaderStage],
+        all_push_constant_ranges: &[vk::PushConstantRange],
+        all_descriptor_set_layouts: &[vk::DescriptorSetLayout],
+        config: &GraphicsPipelineConfig,
     ) -> Self {
-        let mut all_shader_stages_maybe_uninit: Box<
-            [MaybeUninit<vk::PipelineShaderStageCreateInfo>],
-        > = vec![MaybeUninit::uninit(); all_shader_modules.len()].into_boxed_slice();
+        Self::new_with_color_format(
+            vulkan_device,
+            extent,
+            Some(color_format),
+            all_shader_stages,
+            all_push_constant_ranges,
+            all_descriptor_set_layouts,
+            config,
+        )
+    }
 
-        for (index, shader_module) in all_shader_modules.iter().enumerate() {
-            let pipeline_shader_stage_create_info_maybeuninit = MaybeUninit::uninit();
-            let mut pipeline_shader_stage_create_info =
-                unsafe { pipeline_shader_stage_create_info_maybeuninit.assume_init() };
+    ///Create a graphics pipeline only rendering the depth attachment of `config`,
+    ///with a viewport and a scissor covering `extent`
+    ///
+    ///For example, can render shadow maps, which are then sampled in the SHADER_READ_ONLY_OPTIMAL layout
+    ///
+    /// # Panics
+    ///
+    /// If `config` has no depth format
+    pub fn new_depth_only(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        extent: Extent2D,
+        all_shader_stages: &[VulkanShaderStage],
+        all_push_constant_ranges: &[vk::PushConstantRange],
+        all_descriptor_set_layouts: &[vk::DescriptorSetLayout],
+        config: &GraphicsPipelineConfig,
+    ) -> Self {
+        assert!(
+            config.depth_format.is_some(),
+            "a depth-only graphics pipeline must have a depth format"
+        );
 
-            shader_module.set_pipeline_stage(&mut pipeline_shader_stage_create_info);
-            all_shader_stages_maybe_uninit[index].write(pipeline_shader_stage_create_info);
+        Self::new_with_color_format(
+            vulkan_device,
+            extent,
+            None,
+            all_shader_stages,
+            all_push_constant_ranges,
+            all_descriptor_set_layouts,
+            config,
+        )
+    }
+
+    fn new_with_color_format(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        extent: Extent2D,
+        color_format: Option<Format>,
+        all_shader_stages: &[VulkanShaderStage],
+        all_push_constant_ranges: &[vk::PushConstantRange],
+        all_descriptor_set_layouts: &[vk::DescriptorSetLayout],
+        config: &GraphicsPipelineConfig,
+    ) -> Self {
+        let max_push_constants_size = vulkan_device.get_limits().max_push_constants_size;
+        for push_constant_range in all_push_constant_ranges {
+            assert!(
+                push_constant_range.offset.is_multiple_of(4)
+                    && push_constant_range.size.is_multiple_of(4),
+                "push constant range {push_constant_range:?} must have an offset and a size multiple of 4"
+            );
+            assert!(
+                push_constant_range.offset + push_constant_range.size <= max_push_constants_size,
+                "push constant range {push_constant_range:?} is outside of the maximum push constants size of the device ({max_push_constants_size} bytes)"
+            );
         }
 
-        let all_shader_stages = unsafe { all_shader_stages_maybe_uninit.assume_init() };
-
-        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
-        let pipeline_dynamic_state_create_info =
-            vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
-
-        let vertex_input_state_create_info = vk::PipelineVertexInputStateCreateInfo::default();
-
-        let input_assembly_state_create_info = vk::PipelineInputAssemblyStateCreateInfo::default()
-            .topology(PrimitiveTopology::TRIANGLE_LIST)
-            .primitive_restart_enable(false);
-
-        let viewport = vk::Viewport::default()
-            .x(0f32)
-            .y(0f32)
-            .width(swapchain_extend.width as f32)
-            .height(swapchain_extend.height as f32)
-            .min_depth(0f32)
-            .max_depth(1f32);
-
-        let scissor = vk::Rect2D::default()
-            .offset(Offset2D::default().x(0).y(0))
-            .extent(swapchain_extend);
-
-        let viewport_state_create_info = PipelineViewportStateCreateInfo::default()
-            .viewport_count(1)
-            .scissor_count(1);
-
-        let rasterization_state_create_info = vk::PipelineRasterizationStateCreateInfo::default()
-            .depth_clamp_enable(false)
-            .rasterizer_discard_enable(false)
-            .polygon_mode(PolygonMode::FILL)
-            .line_width(1f32)
-            .cull_mode(CullModeFlags::BACK)
-            .front_face(FrontFace::CLOCKWISE)
-            .depth_bias_enable(false);
-
-        let multisampling_state_create_info = vk::PipelineMultisampleStateCreateInfo::default()
-            .sample_shading_enable(false)
-            .rasterization_samples(SampleCountFlags::TYPE_1);
-
-        let color_blend_attachment_state = vk::PipelineColorBlendAttachmentState::default()
-            .color_write_mask(ColorComponentFlags::RGBA)
-            .blend_enable(false);
-
-        let color_blend_attchement = [color_blend_attachment_state];
-        let color_blend_state_create_info = vk::PipelineColorBlendStateCreateInfo::default()
-            .logic_op_enable(false)
-            .attachments(&color_blend_attchement);
-
-        let pipeline_layout_create_info = vk::PipelineLayoutCreateInfo::default();
+        let pipeline_layout_create_info = vk::PipelineLayoutCreateInfo::default()
+            .set_layouts(all_descriptor_set_layouts)
+            .push_constant_ranges(all_push_constant_ranges);
         let pipeline_layout = vulkan_device
             .create_pipeline_layout(&pipeline_layout_create_info)
             .expect("failed to create pipeline layout");
 
-        let attachement_description = vk::AttachmentDescription::default()
-            .format(swapchain_format)
-            .samples(SampleCountFlags::TYPE_1)
-            .load_op(AttachmentLoadOp::CLEAR)
-            .store_op(AttachmentStoreOp::STORE)
-            .stencil_load_op(AttachmentLoadOp::DONT_CARE)
-            .stencil_store_op(AttachmentStoreOp::DONT_CARE)
-            .initial_layout(ImageLayout::UNDEFINED)
-            .final_layout(ImageLayout::PRESENT_SRC_KHR);
-
-        let color_attachement = vk::AttachmentReference::default()
-            .layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
-            .attachment(0);
-
-        let color_attachements = [color_attachement];
-        let subpass_description = vk::SubpassDescription::default()
-            .color_attachments(&color_attachements)
-            .pipeline_bind_point(PipelineBindPoint::GRAPHICS);
-
-        let attachments = [attachement_description];
-        let subpasses = [subpass_description];
-        let render_pass_create_info = vk::RenderPassCreateInfo::default()
-            .attachments(&attachments)
-            .subpasses(&subpasses);
-
-        let render_pass = vulkan_device
-            .create_render_pass(&render_pass_create_info)
-            .expect("failed to create render pass");
-
-        let graphics_pipeline_create_info = vk::GraphicsPipelineCreateInfo::default()
-            .stages(&all_shader_stages)
-            .vertex_input_state(&vertex_input_state_create_info)
-            .input_assembly_state(&input_assembly_state_create_info)
-            .viewport_state(&viewport_state_create_info)
-            .rasterization_state(&rasterization_state_create_info)
-            .multisample_state(&multisampling_state_create_info)
-            .color_blend_state(&color_blend_state_create_info)
-            .dynamic_state(&pipeline_dynamic_state_create_info)
-            .layout(pipeline_layout)
-            .render_pass(render_pass)
-            .subpass(0);
+        //The render pass is only necessary without the dynamic rendering
+        let render_pass = if vulkan_device.is_dynamic_rendering_enabled() {
+            None
+        } else {
+            Some(create_render_pass(
+                vulkan_device,
+                color_format,
+                config.depth_format,
+            ))
+        };
 
-        let pipeline = vulkan_device
-            .create_graphics_pipeline(graphics_pipeline_create_info)
-            .expect("failed to create graphics pipeline");
+        let pipeline = create_pipeline(
+            vulkan_device,
+            pipeline_layout,
+            render_pass,
+            color_format,
+            config,
+            all_shader_stages,
+        )
+        .expect("failed to create graphics pipeline");
 
         Self {
             vulkan_device,
             pipeline,
             pipeline_layout,
             render_pass,
+            color_format,
+            config: *config,
+            viewport: get_viewport(extent),
+            scissor: get_scissor(extent),
+            all_shader_file_paths: get_all_shader_file_paths(all_shader_stages),
+            all_push_constant_ranges: all_push_constant_ranges.into(),
         }
     }
+
+    pub fn get_pipeline(&self) -> vk::Pipeline {
+        self.pipeline
+    }
+
+    pub fn get_pipeline_layout(&self) -> PipelineLayout {
+        self.pipeline_layout
+    }
+
+    ///Return the render pass of `&self`, None if `&self` must be used with the dynamic rendering
+    pub fn get_render_pass(&self) -> Option<vk::RenderPass> {
+        self.render_pass
+    }
+
+    ///Return the format of the color attachment rendered by `&self`, None if `&self` only renders the depth
+    pub fn get_color_format(&self) -> Option<Format> {
+        self.color_format
+    }
+
+    ///Return the format of the depth attachment tested by `&self`, None if `&self` doesn't test the depth
+    pub fn get_depth_format(&self) -> Option<Format> {
+        self.config.depth_format
+    }
+
+    pub fn get_config(&self) -> &GraphicsPipelineConfig {
+        &self.config
+    }
+
+    ///Return the dynamic viewport set when `&self` is bound
+    pub fn get_viewport(&self) -> vk::Viewport {
+        self.viewport
+    }
+
+    ///Return the dynamic scissor set when `&self` is bound
+    pub fn get_scissor(&self) -> vk::Rect2D {
+        self.scissor
+    }
+
+    ///Set the extent covered by the dynamic viewport and scissor of `&self`
+    ///
+    ///The pipeline doesn't depend on the extent, so it's not rebuilt
+    pub fn set_extent(&mut self, extent: Extent2D) {
+        self.viewport = get_viewport(extent);
+        self.scissor = get_scissor(extent);
+    }
+
+    ///Update `&self` for `swapchain`, after it was recreated
+    ///
+    ///The dynamic viewport and scissor cover the new extent of the images,
+    ///and if their format changed, the render pass and the pipeline are recreated from `all_shader_stages`
+    ///
+    ///The framebuffers created with the old render pass must be recreated
+    pub fn update_for_swapchain(
+        &mut self,
+        swapchain: &VulkanSwapchain,
+        all_shader_stages: &[VulkanShaderStage],
+    ) -> VkResult<()> {
+        self.set_extent(swapchain.get_image_extent());
+
+        let color_format = Some(swapchain.get_image_format());
+        if color_format == self.color_format {
+            return Ok(());
+        }
+
+        let render_pass = self.render_pass.map(|_| {
+            create_render_pass(self.vulkan_device, color_format, self.config.depth_format)
+        });
+        let pipeline = create_pipeline(
+            self.vulkan_device,
+            self.pipeline_layout,
+            render_pass,
+            color_format,
+            &self.config,
+            all_shader_stages,
+        )
+        .inspect_err(|_| {
+            if let Some(render_pass) = render_pass {
+                self.vulkan_device.destroy_render_pass(render_pass);
+            }
+        })?;
+
+        self.vulkan_device.wait_idle();
+        self.vulkan_device.destroy_pipeline(self.pipeline);
+        if let Some(old_render_pass) = self.render_pass {
+            self.vulkan_device.destroy_render_pass(old_render_pass);
+        }
+        self.pipeline = pipeline;
+        self.render_pass = render_pass;
+        self.color_format = color_format;
+
+        self.all_shader_file_paths = get_all_shader_file_paths(all_shader_stages);
+
+        Ok(())
+    }
+
+    ///Return if `&self` renders in the format of the images of `swapchain`,
+    ///else it must be updated by `update_for_swapchain` before rendering in them
+    pub fn is_compatible_with_swapchain(&self, swapchain: &VulkanSwapchain) -> bool {
+        self.color_format == Some(swapchain.get_image_format())
+    }
+
+    ///Return the push constant ranges declared by the pipeline layout of `&self`
+    pub fn get_all_push_constant_ranges(&self) -> &[vk::PushConstantRange] {
+        &self.all_push_constant_ranges
+    }
+
+    ///Return if `file_path` is the SPIR-V file of one of the shader modules used by `&self`
+    pub fn is_using_shader_file(&self, file_path: &str) -> bool {
+        self.all_shader_file_paths
+            .iter()
+            .any(|shader_file_path| shader_file_path.as_ref() == file_path)
+    }
+
+    ///Rebuild the pipeline of `&self` from `all_shader_stages`
+    ///
+    ///The old pipeline is destroyed, it's kept if the new one cannot be created.
+    ///The command buffers recording it must have been executed
+    pub fn rebuild(&mut self, all_shader_stages: &[VulkanShaderStage]) -> VkResult<()> {
+        let pipeline = create_pipeline(
+            self.vulkan_device,
+            self.pipeline_layout,
+            self.render_pass,
+            self.color_format,
+            &self.config,
+            all_shader_stages,
+        )?;
+
+        self.vulkan_device.destroy_pipeline(self.pipeline);
+        self.pipeline = pipeline;
+
+        self.all_shader_file_paths = get_all_shader_file_paths(all_shader_stages);
+
+        Ok(())
+    }
 }
 
 impl Drop for VulkanGraphicsPipeline<'_> {
@@ -150,6 +401,294 @@ impl Drop for VulkanGraphicsPipeline<'_> {
         self.vulkan_device.destroy_pipeline(self.pipeline);
         self.vulkan_device
             .destroy_pipeline_layout(self.pipeline_layout);
-        self.vulkan_device.destroy_render_pass(self.render_pass);
+        if let Some(render_pass) = self.render_pass {
+            self.vulkan_device.destroy_render_pass(render_pass);
+        }
+    }
+}
+
+///Return the viewport covering `extent`, with depths from 0 to 1
+fn get_viewport(extent: Extent2D) -> vk::Viewport {
+    vk::Viewport::default()
+        .x(0f32)
+        .y(0f32)
+        .width(extent.width as f32)
+        .height(extent.height as f32)
+        .min_depth(0f32)
+        .max_depth(1f32)
+}
+
+///Return the scissor covering `extent`
+fn get_scissor(extent: Extent2D) -> vk::Rect2D {
+    vk::Rect2D::default()
+        .offset(Offset2D::default().x(0).y(0))
+        .extent(extent)
+}
+
+///Return the SPIR-V file paths of the shader modules of `all_shader_stages` loaded from a file
+fn get_all_shader_file_paths(all_shader_stages: &[VulkanShaderStage]) -> Box<[Box<str>]> {
+    all_shader_stages
+        .iter()
+        .filter_map(|shader_stage| shader_stage.get_shader_module().get_file_path())
+        .map(Box::from)
+        .collect()
+}
+
+///Create a render pass with one subpass rendering a color attachment in `color_format`, presented once rendered
+///
+///If `depth_format` isn't None, the subpass also tests a depth attachment in `depth_format`, cleared at the start of the render pass
+///
+///Without `color_format`, the subpass only renders the depth attachment, which is then read by the fragment shaders
+fn create_render_pass(
+    vulkan_device: &VulkanDevice,
+    color_format: Option<Format>,
+    depth_format: Option<Format>,
+) -> vk::RenderPass {
+    let Some(color_format) = color_format else {
+        return create_depth_only_render_pass(
+            vulkan_device,
+            depth_format.expect("render pass without attachment"),
+        );
+    };
+
+    let attachement_description = vk::AttachmentDescription::default()
+        .format(color_format)
+        .samples(SampleCountFlags::TYPE_1)
+        .load_op(AttachmentLoadOp::CLEAR)
+        .store_op(AttachmentStoreOp::STORE)
+        .stencil_load_op(AttachmentLoadOp::DONT_CARE)
+        .stencil_store_op(AttachmentStoreOp::DONT_CARE)
+        .initial_layout(ImageLayout::UNDEFINED)
+        .final_layout(ImageLayout::PRESENT_SRC_KHR);
+
+    let color_attachement = vk::AttachmentReference::default()
+        .layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+        .attachment(0);
+
+    let depth_attachement = vk::AttachmentReference::default()
+        .layout(ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL)
+        .attachment(1);
+
+    let color_attachements = [color_attachement];
+    let mut subpass_description = vk::SubpassDescription::default()
+        .color_attachments(&color_attachements)
+        .pipeline_bind_point(PipelineBindPoint::GRAPHICS);
+    if depth_format.is_some() {
+        subpass_description = subpass_description.depth_stencil_attachment(&depth_attachement);
+    }
+
+    //The layout transition of the color attachment must wait for the image to be acquired from the swapchain,
+    //and the clear of the depth attachment must wait for the depth tests of the previous frame
+    let subpass_dependency = vk::SubpassDependency::default()
+        .src_subpass(vk::SUBPASS_EXTERNAL)
+        .dst_subpass(0)
+        .src_stage_mask(
+            PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | PipelineStageFlags::LATE_FRAGMENT_TESTS,
+        )
+        .src_access_mask(AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE)
+        .dst_stage_mask(
+            PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | PipelineStageFlags::EARLY_FRAGMENT_TESTS,
+        )
+        .dst_access_mask(
+            AccessFlags::COLOR_ATTACHMENT_WRITE | AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+        );
+
+    let mut attachments = vec![attachement_description];
+    if let Some(depth_format) = depth_format {
+        attachments.push(
+            vk::AttachmentDescription::default()
+                .format(depth_format)
+                .samples(SampleCountFlags::TYPE_1)
+                .load_op(AttachmentLoadOp::CLEAR)
+                .store_op(AttachmentStoreOp::DONT_CARE)
+                .stencil_load_op(AttachmentLoadOp::DONT_CARE)
+                .stencil_store_op(AttachmentStoreOp::DONT_CARE)
+                .initial_layout(ImageLayout::UNDEFINED)
+                .final_layout(ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL),
+        );
     }
+    let subpasses = [subpass_description];
+    let dependencies = [subpass_dependency];
+    let render_pass_create_info = vk::RenderPassCreateInfo::default()
+        .attachments(&attachments)
+        .subpasses(&subpasses)
+        .dependencies(&dependencies);
+
+    vulkan_device
+        .create_render_pass(&render_pass_create_info)
+        .expect("failed to create render pass")
+}
+
+///Create a render pass with one subpass rendering a depth attachment in `depth_format`,
+///cleared at the start of the render pass and read by the fragment shaders once rendered
+fn create_depth_only_render_pass(
+    vulkan_device: &VulkanDevice,
+    depth_format: Format,
+) -> vk::RenderPass {
+    let attachments = [vk::AttachmentDescription::default()
+        .format(depth_format)
+        .samples(SampleCountFlags::TYPE_1)
+        .load_op(AttachmentLoadOp::CLEAR)
+        .store_op(AttachmentStoreOp::STORE)
+        .stencil_load_op(AttachmentLoadOp::DONT_CARE)
+        .stencil_store_op(AttachmentStoreOp::DONT_CARE)
+        .initial_layout(ImageLayout::UNDEFINED)
+        .final_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)];
+
+    let depth_attachement = vk::AttachmentReference::default()
+        .layout(ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL)
+        .attachment(0);
+    let subpasses = [vk::SubpassDescription::default()
+        .depth_stencil_attachment(&depth_attachement)
+        .pipeline_bind_point(PipelineBindPoint::GRAPHICS)];
+
+    //The clear of the depth attachment must wait for the fragment shaders of the previous frame reading it,
+    //and the fragment shaders of the next render passes must wait for the depth to be rendered
+    let dependencies = [
+        vk::SubpassDependency::default()
+            .src_subpass(vk::SUBPASS_EXTERNAL)
+            .dst_subpass(0)
+            .src_stage_mask(PipelineStageFlags::FRAGMENT_SHADER)
+            .src_access_mask(AccessFlags::empty())
+            .dst_stage_mask(
+                PipelineStageFlags::EARLY_FRAGMENT_TESTS | PipelineStageFlags::LATE_FRAGMENT_TESTS,
+            )
+            .dst_access_mask(AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE),
+        vk::SubpassDependency::default()
+            .src_subpass(0)
+            .dst_subpass(vk::SUBPASS_EXTERNAL)
+            .src_stage_mask(PipelineStageFlags::LATE_FRAGMENT_TESTS)
+            .src_access_mask(AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE)
+            .dst_stage_mask(PipelineStageFlags::FRAGMENT_SHADER)
+            .dst_access_mask(AccessFlags::SHADER_READ),
+    ];
+    let render_pass_create_info = vk::RenderPassCreateInfo::default()
+        .attachments(&attachments)
+        .subpasses(&subpasses)
+        .dependencies(&dependencies);
+
+    vulkan_device
+        .create_render_pass(&render_pass_create_info)
+        .expect("failed to create render pass")
+}
+
+///Create a graphics pipeline using `pipeline_layout` and `all_shader_stages`
+///
+///The pipeline renders a color attachment in `color_format` (if any) with the vertex input, the depth attachment and the state of `config`,
+///in the subpass 0 of `render_pass`, or with the dynamic rendering if `render_pass` is None
+fn create_pipeline(
+    vulkan_device: &VulkanDevice,
+    pipeline_layout: PipelineLayout,
+    render_pass: Option<vk::RenderPass>,
+    color_format: Option<Format>,
+    config: &GraphicsPipelineConfig,
+    all_shader_stages: &[VulkanShaderStage],
+) -> VkResult<vk::Pipeline> {
+    let all_specialization_infos: Box<[Option<vk::SpecializationInfo>]> = all_shader_stages
+        .iter()
+        .map(|shader_stage| shader_stage.get_specialization_info())
+        .collect();
+
+    let all_shader_stage_create_infos: Box<[vk::PipelineShaderStageCreateInfo]> = all_shader_stages
+        .iter()
+        .zip(all_specialization_infos.iter())
+        .map(|(shader_stage, specialization_info)| {
+            shader_stage.get_pipeline_stage_create_info(specialization_info.as_ref())
+        })
+        .collect();
+
+    let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+    let pipeline_dynamic_state_create_info =
+        vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+    let vertex_input_state_create_info = vk::PipelineVertexInputStateCreateInfo::default()
+        .vertex_binding_descriptions(config.all_vertex_binding_descriptions)
+        .vertex_attribute_descriptions(config.all_vertex_attribute_descriptions);
+
+    let input_assembly_state_create_info = vk::PipelineInputAssemblyStateCreateInfo::default()
+        .topology(PrimitiveTopology::TRIANGLE_LIST)
+        .primitive_restart_enable(false);
+
+    let viewport_state_create_info = PipelineViewportStateCreateInfo::default()
+        .viewport_count(1)
+        .scissor_count(1);
+
+    let rasterization_state_create_info = vk::PipelineRasterizationStateCreateInfo::default()
+        .depth_clamp_enable(false)
+        .rasterizer_discard_enable(false)
+        .polygon_mode(PolygonMode::FILL)
+        .line_width(1f32)
+        .cull_mode(config.cull_mode)
+        //As in OBJ and glTF, the front faces are counterclockwise on the screen
+        .front_face(FrontFace::COUNTER_CLOCKWISE)
+        .depth_bias_enable(config.depth_bias.is_some())
+        .depth_bias_constant_factor(
+            config
+                .depth_bias
+                .map_or(0f32, |depth_bias| depth_bias.constant_factor),
+        )
+        .depth_bias_slope_factor(
+            config
+                .depth_bias
+                .map_or(0f32, |depth_bias| depth_bias.slope_factor),
+        )
+        .depth_bias_clamp(0f32);
+
+    let multisampling_state_create_info = vk::PipelineMultisampleStateCreateInfo::default()
+        .sample_shading_enable(false)
+        .rasterization_samples(SampleCountFlags::TYPE_1);
+
+    //The blended colors are "over" the color attachment, whose alpha accumulates the coverage
+    let color_blend_attachment_state = vk::PipelineColorBlendAttachmentState::default()
+        .color_write_mask(ColorComponentFlags::RGBA)
+        .blend_enable(config.is_alpha_blended)
+        .src_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
+        .dst_color_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+        .color_blend_op(vk::BlendOp::ADD)
+        .src_alpha_blend_factor(vk::BlendFactor::ONE)
+        .dst_alpha_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+        .alpha_blend_op(vk::BlendOp::ADD);
+
+    let color_blend_attchement = [color_blend_attachment_state];
+    let color_blend_state_create_info = vk::PipelineColorBlendStateCreateInfo::default()
+        .logic_op_enable(false)
+        .attachments(if color_format.is_some() {
+            &color_blend_attchement
+        } else {
+            &[]
+        });
+
+    let depth_format = config.depth_format;
+    let depth_stencil_state_create_info = vk::PipelineDepthStencilStateCreateInfo::default()
+        .depth_test_enable(depth_format.is_some())
+        .depth_write_enable(depth_format.is_some() && !config.is_alpha_blended)
+        .depth_compare_op(config.depth_compare_op)
+        .depth_bounds_test_enable(false)
+        .stencil_test_enable(false);
+
+    let color_attachment_formats: Vec<Format> = color_format.into_iter().collect();
+    let mut pipeline_rendering_create_info = vk::PipelineRenderingCreateInfo::default()
+        .color_attachment_formats(&color_attachment_formats)
+        .depth_attachment_format(depth_format.unwrap_or(Format::UNDEFINED));
+
+    let mut graphics_pipeline_create_info = vk::GraphicsPipelineCreateInfo::default()
+        .stages(&all_shader_stage_create_infos)
+        .vertex_input_state(&vertex_input_state_create_info)
+        .input_assembly_state(&input_assembly_state_create_info)
+        .viewport_state(&viewport_state_create_info)
+        .rasterization_state(&rasterization_state_create_info)
+        .multisample_state(&multisampling_state_create_info)
+        .depth_stencil_state(&depth_stencil_state_create_info)
+        .color_blend_state(&color_blend_state_create_info)
+        .dynamic_state(&pipeline_dynamic_state_create_info)
+        .layout(pipeline_layout);
+
+    graphics_pipeline_create_info = match render_pass {
+        Some(render_pass) => graphics_pipeline_create_info
+            .render_pass(render_pass)
+            .subpass(0),
+        None => graphics_pipeline_create_info.push_next(&mut pipeline_rendering_create_info),
+    };
+
+    vulkan_device.create_graphics_pipeline(graphics_pipeline_create_info)
 }
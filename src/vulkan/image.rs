@@ -0,0 +1,328 @@
+use std::{marker::PhantomData, ops::Range};
+
+use ash::vk;
+
+use crate::vulkan::device::VulkanDevice;
+
+///Represent a 2D Vulkan image and the device memory bound to it
+pub struct VulkanImage<'vulkan_device> {
+    ///A reference to the Vulkan Device which created this image
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan image
+    image: vk::Image,
+
+    ///The device memory bound to `image`, owned by this image
+    memory: vk::DeviceMemory,
+
+    format: vk::Format,
+    extent: vk::Extent2D,
+    mip_levels: u32,
+    array_layers: u32,
+
+    ///If `image` contains cube maps, each one made of 6 array layers
+    is_cube: bool,
+}
+
+impl<'vulkan_device> VulkanImage<'vulkan_device> {
+    ///Create a 2D image used for `usage`, bound to a new memory only visible by the device
+    ///
+    /// # Arguments
+    ///
+    /// * `vulkan_device` - a reference to a VulkanDevice
+    /// * `extent` - the size of the mip level 0 of the image
+    /// * `format` - the format of the texels of the image
+    /// * `mip_levels` - the number of mip levels of the image
+    /// * `array_layers` - the number of array layers of the image, 6 by cube map if `is_cube` is true
+    /// * `is_cube` - if the image contains cube maps
+    /// * `usage` - how the image is used
+    pub fn new_2d(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        extent: vk::Extent2D,
+        format: vk::Format,
+        mip_levels: u32,
+        array_layers: u32,
+        is_cube: bool,
+        usage: vk::ImageUsageFlags,
+    ) -> Self {
+        assert!(
+            !is_cube || array_layers.is_multiple_of(6),
+            "a cube image must have 6 array layers by cube map ({array_layers} array layers)"
+        );
+
+        let image_create_info = vk::ImageCreateInfo::default()
+            .flags(if is_cube {
+                vk::ImageCreateFlags::CUBE_COMPATIBLE
+            } else {
+                vk::ImageCreateFlags::empty()
+            })
+            .image_type(vk::ImageType::TYPE_2D)
+            .format(format)
+            .extent(extent.into())
+            .mip_levels(mip_levels)
+            .array_layers(array_layers)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(usage)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+
+        let image = vulkan_device
+            .create_image(&image_create_info)
+            .expect("failed to create Vulkan image");
+
+        let memory_requirements = vulkan_device.get_image_memory_requirements(image);
+        let memory = vulkan_device.allocate_memory_for_requirements(
+            memory_requirements,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL,
+        );
+
+        vulkan_device
+            .bind_image_memory(image, memory, 0)
+            .expect("failed to bind Vulkan image memory");
+
+        Self {
+            vulkan_device,
+            image,
+            memory,
+            format,
+            extent,
+            mip_levels,
+            array_layers,
+            is_cube,
+        }
+    }
+
+    pub fn get_image(&self) -> vk::Image {
+        self.image
+    }
+
+    pub fn get_format(&self) -> vk::Format {
+        self.format
+    }
+
+    ///Return the size of the mip level 0 of `&self`
+    pub fn get_extent(&self) -> vk::Extent2D {
+        self.extent
+    }
+
+    pub fn get_mip_levels(&self) -> u32 {
+        self.mip_levels
+    }
+
+    pub fn get_array_layers(&self) -> u32 {
+        self.array_layers
+    }
+
+    pub fn is_cube(&self) -> bool {
+        self.is_cube
+    }
+}
+
+impl Drop for VulkanImage<'_> {
+    fn drop(&mut self) {
+        self.vulkan_device.destroy_image(self.image);
+        self.vulkan_device.free_memory(self.memory);
+    }
+}
+
+///Represent a Vulkan image view on the mip levels and array layers of a VulkanImage
+pub struct VulkanImageView<'vulkan_image, 'vulkan_device> {
+    ///A reference to the Vulkan Device which created this image view
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan image view
+    image_view: vk::ImageView,
+
+    ///PhantomData used to keep the fact that an image view cannot be used without its image
+    phantom_data_image: PhantomData<&'vulkan_image VulkanImage<'vulkan_device>>,
+}
+
+impl<'vulkan_image, 'vulkan_device> VulkanImageView<'vulkan_image, 'vulkan_device> {
+    ///Create a view on the color of `vulkan_image`
+    ///
+    ///The view is a cube (array) if `vulkan_image` contains cube maps, a 2D array if it has several array layers, else a 2D view
+    pub fn new_color(vulkan_image: &'vulkan_image VulkanImage<'vulkan_device>) -> Self {
+        Self::new(vulkan_image, vk::ImageAspectFlags::COLOR)
+    }
+
+    ///Create a view on the depth of `vulkan_image`, which must be in a depth format
+    ///
+    ///For example, can be used as depth attachment of a graphics pipeline
+    pub fn new_depth(vulkan_image: &'vulkan_image VulkanImage<'vulkan_device>) -> Self {
+        Self::new(vulkan_image, vk::ImageAspectFlags::DEPTH)
+    }
+
+    ///Create a view on the color of the mip level `mip_level` of all the array layers of `vulkan_image`,
+    ///a 2D array if it has several array layers (even if it contains cube maps), else a 2D view
+    ///
+    ///For example, a mip level of a cube map can be written by the compute shaders as a storage image
+    pub fn new_color_mip_level(
+        vulkan_image: &'vulkan_image VulkanImage<'vulkan_device>,
+        mip_level: u32,
+    ) -> Self {
+        let view_type = if vulkan_image.array_layers == 1 {
+            vk::ImageViewType::TYPE_2D
+        } else {
+            vk::ImageViewType::TYPE_2D_ARRAY
+        };
+
+        Self::new_with_mip_levels(
+            vulkan_image,
+            view_type,
+            vk::ImageAspectFlags::COLOR,
+            mip_level..mip_level + 1,
+        )
+    }
+
+    pub fn get_image_view(&self) -> vk::ImageView {
+        self.image_view
+    }
+
+    ///Create a view on the `aspect_mask` of all the mip levels and array layers of `vulkan_image`
+    fn new(
+        vulkan_image: &'vulkan_image VulkanImage<'vulkan_device>,
+        aspect_mask: vk::ImageAspectFlags,
+    ) -> Self {
+        let view_type = match (vulkan_image.is_cube, vulkan_image.array_layers) {
+            (true, 6) => vk::ImageViewType::CUBE,
+            (true, _) => vk::ImageViewType::CUBE_ARRAY,
+            (false, 1) => vk::ImageViewType::TYPE_2D,
+            (false, _) => vk::ImageViewType::TYPE_2D_ARRAY,
+        };
+
+        Self::new_with_mip_levels(
+            vulkan_image,
+            view_type,
+            aspect_mask,
+            0..vulkan_image.mip_levels,
+        )
+    }
+
+    ///Create a view of `view_type` on the `aspect_mask` of the `mip_levels` of all the array layers of `vulkan_image`
+    fn new_with_mip_levels(
+        vulkan_image: &'vulkan_image VulkanImage<'vulkan_device>,
+        view_type: vk::ImageViewType,
+        aspect_mask: vk::ImageAspectFlags,
+        mip_levels: Range<u32>,
+    ) -> Self {
+        let image_view_create_info = vk::ImageViewCreateInfo::default()
+            .image(vulkan_image.image)
+            .view_type(view_type)
+            .format(vulkan_image.format)
+            .subresource_range(
+                vk::ImageSubresourceRange::default()
+                    .aspect_mask(aspect_mask)
+                    .base_mip_level(mip_levels.start)
+                    .level_count(mip_levels.len() as u32)
+                    .base_array_layer(0)
+                    .layer_count(vulkan_image.array_layers),
+            );
+
+        let image_view = vulkan_image
+            .vulkan_device
+            .create_image_view(&image_view_create_info)
+            .expect("failed to create Vulkan image view");
+
+        Self {
+            vulkan_device: vulkan_image.vulkan_device,
+            image_view,
+            phantom_data_image: PhantomData,
+        }
+    }
+}
+
+impl Drop for VulkanImageView<'_, '_> {
+    fn drop(&mut self) {
+        self.vulkan_device.destroy_image_view(self.image_view);
+    }
+}
+
+///Represent how a VulkanSampler filters and addresses the texels of an image
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplerParameters {
+    ///The filter used when the image is magnified
+    pub mag_filter: vk::Filter,
+
+    ///The filter used when the image is minified
+    pub min_filter: vk::Filter,
+
+    ///How the mip levels are filtered
+    pub mipmap_mode: vk::SamplerMipmapMode,
+
+    ///How the coordinates (u, v and w) outside of the image are handled
+    pub address_modes: [vk::SamplerAddressMode; 3],
+
+    ///The maximum anisotropy of the filtering, None to disable the anisotropic filtering
+    ///
+    ///It's clamped to the limit of the device, and ignored if the device doesn't support it
+    pub max_anisotropy: Option<f32>,
+}
+
+impl Default for SamplerParameters {
+    ///Linear filtering of the texels and mip levels, repeating the image, with the maximum anisotropy of the device
+    fn default() -> Self {
+        Self {
+            mag_filter: vk::Filter::LINEAR,
+            min_filter: vk::Filter::LINEAR,
+            mipmap_mode: vk::SamplerMipmapMode::LINEAR,
+            address_modes: [vk::SamplerAddressMode::REPEAT; 3],
+            max_anisotropy: Some(f32::MAX),
+        }
+    }
+}
+
+///Represent a Vulkan sampler, used by the shaders to read the texels of an image
+pub struct VulkanSampler<'vulkan_device> {
+    ///A reference to the Vulkan Device which created this sampler
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan sampler
+    sampler: vk::Sampler,
+}
+
+impl<'vulkan_device> VulkanSampler<'vulkan_device> {
+    ///Create a sampler filtering and addressing the texels as described by `sampler_parameters`
+    pub fn new(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        sampler_parameters: &SamplerParameters,
+    ) -> Self {
+        let max_anisotropy = sampler_parameters
+            .max_anisotropy
+            .filter(|_| vulkan_device.get_enabled_features().sampler_anisotropy == vk::TRUE);
+
+        let sampler_create_info = vk::SamplerCreateInfo::default()
+            .mag_filter(sampler_parameters.mag_filter)
+            .min_filter(sampler_parameters.min_filter)
+            .mipmap_mode(sampler_parameters.mipmap_mode)
+            .address_mode_u(sampler_parameters.address_modes[0])
+            .address_mode_v(sampler_parameters.address_modes[1])
+            .address_mode_w(sampler_parameters.address_modes[2])
+            .anisotropy_enable(max_anisotropy.is_some())
+            .max_anisotropy(max_anisotropy.map_or(1f32, |max_anisotropy| {
+                max_anisotropy.clamp(1f32, vulkan_device.get_limits().max_sampler_anisotropy)
+            }))
+            .min_lod(0f32)
+            .max_lod(vk::LOD_CLAMP_NONE)
+            .border_color(vk::BorderColor::FLOAT_OPAQUE_BLACK);
+
+        let sampler = vulkan_device
+            .create_sampler(&sampler_create_info)
+            .expect("failed to create Vulkan sampler");
+
+        Self {
+            vulkan_device,
+            sampler,
+        }
+    }
+
+    pub fn get_sampler(&self) -> vk::Sampler {
+        self.sampler
+    }
+}
+
+impl Drop for VulkanSampler<'_> {
+    fn drop(&mut self) {
+        self.vulkan_device.destroy_sampler(self.sampler);
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    cmp::min,
     ffi::{CStr, c_char},
     ops::Deref,
 };
@@ -21,6 +22,9 @@ pub struct VulkanInstance {
     ///
     ///For example, can be used to create a SurfaceKHR supported by Windows
     win32_surface_instance: khr::win32_surface::Instance,
+
+    ///The Vulkan API version requested by `instance`
+    api_version: u32,
 }
 
 static VALIDATION_LAYER_NAME_C: &CStr = c"VK_LAYER_KHRONOS_validation";
@@ -34,10 +38,22 @@ const VULKAN_INSTANCE_LAYER_NAMES_C: [*const c_char; 1] =
 
 const VULKAN_INSTANCE_LAYER_NAMES: [&str; 1] = [VALIDATION_LAYER_NAME];
 
+static SWAPCHAIN_COLORSPACE_EXTENSION_NAME: &str = match vk::EXT_SWAPCHAIN_COLORSPACE_NAME.to_str()
+{
+    Ok(v) => v,
+    Err(_) => panic!("EXT_SWAPCHAIN_COLORSPACE_NAME.to_str() failed"),
+};
+
+///The highest Vulkan API version requested by the instance
+///
+///For example, Vulkan 1.3 provides the dynamic rendering
+const VULKAN_MAX_API_VERSION: u32 = vk::API_VERSION_1_3;
+
 impl VulkanInstance {
+    ///Create a Vulkan instance with the extensions required by GLFW and the validation layer
+    ///
+    ///VK_EXT_swapchain_colorspace is also enabled if it's available, so the surfaces can present in HDR color spaces
     pub fn new_from_glfw(vulkan_entry: &VulkanEntry, glfw_entry: &GlfwEntry) -> Self {
-        let app_info = vk::ApplicationInfo::default();
-
         if !vulkan_entry.is_extensions_supported(&glfw_entry.all_req_vk_inst_ext_names) {
             panic!("one or more requested extensions aren't supported by Vulkan")
         }
@@ -46,22 +62,71 @@ impl VulkanInstance {
             panic!("one or more requested layers aren't supported by Vulkan")
         }
 
+        let mut all_extension_names_c = glfw_entry.all_req_vk_inst_ext_names_c.to_vec();
+        if vulkan_entry.is_extensions_supported(&[SWAPCHAIN_COLORSPACE_EXTENSION_NAME]) {
+            all_extension_names_c.push(vk::EXT_SWAPCHAIN_COLORSPACE_NAME.as_ptr());
+        }
+
+        Self::new(
+            vulkan_entry,
+            &VULKAN_INSTANCE_LAYER_NAMES_C,
+            &all_extension_names_c,
+        )
+        .expect("failed to create Vulkan instance")
+    }
+
+    ///Create a Vulkan instance without surface extension, used to render without window
+    ///
+    ///The validation layer is only enabled if it's available
+    ///
+    /// # Returns
+    ///
+    /// An error if the Vulkan instance cannot be created, for example because none Vulkan driver is installed
+    pub fn new_headless(vulkan_entry: &VulkanEntry) -> VkResult<Self> {
+        let all_layer_names_c: &[*const c_char] =
+            if vulkan_entry.is_layers_supported(&VULKAN_INSTANCE_LAYER_NAMES) {
+                &VULKAN_INSTANCE_LAYER_NAMES_C
+            } else {
+                &[]
+            };
+
+        Self::new(vulkan_entry, all_layer_names_c, &[])
+    }
+
+    ///Create a Vulkan instance with `all_layer_names_c` and `all_extension_names_c` enabled
+    fn new(
+        vulkan_entry: &VulkanEntry,
+        all_layer_names_c: &[*const c_char],
+        all_extension_names_c: &[*const c_char],
+    ) -> VkResult<Self> {
+        //A Vulkan 1.0 library doesn't accept a higher version
+        let library_api_version = unsafe { vulkan_entry.try_enumerate_instance_version() }?
+            .unwrap_or(vk::API_VERSION_1_0);
+        let api_version = min(library_api_version, VULKAN_MAX_API_VERSION);
+
+        let app_info = vk::ApplicationInfo::default().api_version(api_version);
+
         let instance_create_info = vk::InstanceCreateInfo::default()
             .application_info(&app_info)
-            .enabled_layer_names(&VULKAN_INSTANCE_LAYER_NAMES_C)
-            .enabled_extension_names(glfw_entry.all_req_vk_inst_ext_names_c);
+            .enabled_layer_names(all_layer_names_c)
+            .enabled_extension_names(all_extension_names_c);
 
-        let instance = unsafe { vulkan_entry.create_instance(&instance_create_info, None) }
-            .expect("failed to create Vulkan instance");
+        let instance = unsafe { vulkan_entry.create_instance(&instance_create_info, None) }?;
 
         let surface_instance = khr::surface::Instance::new(vulkan_entry, &instance);
         let win32_surface_instance = khr::win32_surface::Instance::new(vulkan_entry, &instance);
 
-        Self {
+        Ok(Self {
             instance,
             surface_instance,
             win32_surface_instance,
-        }
+            api_version,
+        })
+    }
+
+    ///Return the Vulkan API version requested by `&self`, devices cannot use a higher version
+    pub fn get_api_version(&self) -> u32 {
+        self.api_version
     }
 
     pub fn create_win32_surface(
@@ -0,0 +1,232 @@
+use ash::vk;
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    math::{matrix::Mat4, vector::Vec3},
+    scene::Light,
+    vulkan::{
+        buffer::VulkanBuffer,
+        descriptor::VulkanDescriptorSet,
+        device::VulkanDevice,
+        frames::{MAX_FRAMES_IN_FLIGHT, VulkanFramesInFlight},
+        shadow::GpuShadow,
+    },
+};
+
+///Maximum number of lights written in the light buffers, if it's not configured
+pub const DEFAULT_MAX_LIGHT_COUNT: usize = 64;
+
+///Light types, as declared by the shaders
+const LIGHT_TYPE_DIRECTIONAL: u32 = 0;
+const LIGHT_TYPE_POINT: u32 = 1;
+const LIGHT_TYPE_SPOT: u32 = 2;
+
+///Light in the world space, laid out as the std430 Light struct of the shaders
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct GpuLight {
+    ///The position of the point lights and spotlights
+    pub position: [f32; 3],
+
+    ///The distance at which the point lights and spotlights have no effect, 0 if infinite
+    pub range: f32,
+
+    ///The normalized direction of the directional lights and spotlights
+    pub direction: [f32; 3],
+
+    ///LIGHT_TYPE_DIRECTIONAL, LIGHT_TYPE_POINT or LIGHT_TYPE_SPOT
+    pub light_type: u32,
+
+    ///The linear color of the light, multiplied by its intensity
+    pub color: [f32; 3],
+
+    ///The cosine of the inner cone angle of the spotlights
+    pub inner_cone_cos: f32,
+
+    ///The cosine of the outer cone angle of the spotlights
+    pub outer_cone_cos: f32,
+
+    ///The index of the first shadow of the light in the shadows written with the lights
+    pub first_shadow_index: u32,
+
+    ///The number of shadows of the light: a shadow by cascade for the directional lights, 0 without shadow
+    pub shadow_count: u32,
+
+    pub _padding: u32,
+}
+
+impl GpuLight {
+    ///Return `light` placed by `world_matrix`, emitted toward its -Z axis, without shadow
+    pub fn new(light: &Light, world_matrix: &Mat4) -> Self {
+        let position = world_matrix.transform_point3(Vec3::ZERO);
+        let direction = world_matrix.transform_vector3(-Vec3::Z).normalize();
+
+        let (light_type, color, intensity, range_opt, inner_cone_cos, outer_cone_cos) = match *light
+        {
+            Light::Directional { color, intensity } => {
+                (LIGHT_TYPE_DIRECTIONAL, color, intensity, None, 1f32, 1f32)
+            }
+            Light::Point {
+                color,
+                intensity,
+                range,
+            } => (LIGHT_TYPE_POINT, color, intensity, range, -1f32, -1f32),
+            Light::Spot {
+                color,
+                intensity,
+                range,
+                inner_cone_angle,
+                outer_cone_angle,
+            } => (
+                LIGHT_TYPE_SPOT,
+                color,
+                intensity,
+                range,
+                inner_cone_angle.cos(),
+                outer_cone_angle.cos(),
+            ),
+        };
+        let color = color * intensity;
+
+        Self {
+            position: [position.x, position.y, position.z],
+            range: range_opt.unwrap_or(0f32),
+            direction: [direction.x, direction.y, direction.z],
+            light_type,
+            color: [color.x, color.y, color.z],
+            inner_cone_cos,
+            outer_cone_cos,
+            first_shadow_index: 0,
+            shadow_count: 0,
+            _padding: 0,
+        }
+    }
+}
+
+///Header of the light buffers, followed by the lights
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct LightBufferHeader {
+    ///The number of lights following the header, read by the shaders
+    light_count: u32,
+
+    _padding: [u32; 3],
+}
+
+///Represent the storage buffers of the lights and of their shadows, one of each for each frame in flight
+///
+///The buffers of a frame are written while the device renders the other frames, with the lights of the frame
+pub struct VulkanLightBuffers<'vulkan_device> {
+    all_buffers: Vec<VulkanBuffer<'vulkan_device>>,
+
+    ///The buffers of the GpuShadow referenced by the lights
+    all_shadow_buffers: Vec<VulkanBuffer<'vulkan_device>>,
+
+    ///The maximum number of lights in a buffer, the other lights are ignored
+    max_light_count: usize,
+
+    ///The maximum number of shadows in a buffer, the other shadows are ignored
+    max_shadow_count: usize,
+}
+
+impl<'vulkan_device> VulkanLightBuffers<'vulkan_device> {
+    ///Create a storage buffer of `max_light_count` lights and one of `max_shadow_count` shadows (at least 1)
+    ///for each frame in flight, visible by the host
+    pub fn new(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        max_light_count: usize,
+        max_shadow_count: usize,
+    ) -> Self {
+        let max_light_count = max_light_count.max(1);
+        let max_shadow_count = max_shadow_count.max(1);
+        let size = size_of::<LightBufferHeader>() + max_light_count * size_of::<GpuLight>();
+
+        let all_buffers = (0..MAX_FRAMES_IN_FLIGHT)
+            .map(|_| {
+                let buffer = VulkanBuffer::new(
+                    vulkan_device,
+                    size as vk::DeviceSize,
+                    vk::BufferUsageFlags::STORAGE_BUFFER,
+                    vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+                );
+                //No light is read before the first write
+                buffer.write_bytes(0, bytemuck::bytes_of(&LightBufferHeader::zeroed()));
+                buffer
+            })
+            .collect();
+        let all_shadow_buffers = (0..MAX_FRAMES_IN_FLIGHT)
+            .map(|_| {
+                VulkanBuffer::new(
+                    vulkan_device,
+                    (max_shadow_count * size_of::<GpuShadow>()) as vk::DeviceSize,
+                    vk::BufferUsageFlags::STORAGE_BUFFER,
+                    vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+                )
+            })
+            .collect();
+
+        Self {
+            all_buffers,
+            all_shadow_buffers,
+            max_light_count,
+            max_shadow_count,
+        }
+    }
+
+    pub fn get_max_light_count(&self) -> usize {
+        self.max_light_count
+    }
+
+    pub fn get_max_shadow_count(&self) -> usize {
+        self.max_shadow_count
+    }
+
+    ///Write the buffers of the frame `frame_index` in the STORAGE_BUFFER descriptors of `descriptor_set`,
+    ///the lights at `binding` and the shadows at `shadow_binding`
+    pub fn write_descriptor_set(
+        &self,
+        frame_index: usize,
+        descriptor_set: &VulkanDescriptorSet,
+        binding: u32,
+        shadow_binding: u32,
+    ) {
+        descriptor_set.write_storage_buffer(binding, &self.all_buffers[frame_index]);
+        descriptor_set.write_storage_buffer(shadow_binding, &self.all_shadow_buffers[frame_index]);
+    }
+
+    ///Write `all_lights` and `all_shadows` in the buffers of the frame being recorded by `frames_in_flight`,
+    ///after its `begin_frame`
+    ///
+    /// # Returns
+    ///
+    /// The number of lights written, the lights after the maximum number of lights are ignored.
+    /// The lights must not reference the shadows after the maximum number of shadows
+    pub fn write_lights(
+        &self,
+        frames_in_flight: &VulkanFramesInFlight,
+        all_lights: &[GpuLight],
+        all_shadows: &[GpuShadow],
+    ) -> usize {
+        let light_count = all_lights.len().min(self.max_light_count);
+        let frame_index = frames_in_flight.get_current_frame_index();
+        let buffer = &self.all_buffers[frame_index];
+
+        let shadow_count = all_shadows.len().min(self.max_shadow_count);
+        self.all_shadow_buffers[frame_index]
+            .write_bytes(0, bytemuck::cast_slice(&all_shadows[..shadow_count]));
+
+        buffer.write_bytes(
+            size_of::<LightBufferHeader>() as vk::DeviceSize,
+            bytemuck::cast_slice(&all_lights[..light_count]),
+        );
+        buffer.write_bytes(
+            0,
+            bytemuck::bytes_of(&LightBufferHeader {
+                light_count: light_count as u32,
+                _padding: [0; 3],
+            }),
+        );
+
+        light_count
+    }
+}
@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+
+use ash::vk;
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    material::{AlphaMode, Material, MaterialPipelineKey, MaterialTexture, TextureImage},
+    vulkan::{
+        buffer::VulkanBuffer,
+        command_buffer::{VulkanCommandBuffer, VulkanCommandPool},
+        descriptor::{VulkanDescriptorPool, VulkanDescriptorSet, VulkanDescriptorSetLayout},
+        device::VulkanDevice,
+        graphics_pipeline::VulkanGraphicsPipeline,
+        image::{SamplerParameters, VulkanImage, VulkanImageView, VulkanSampler},
+        texture::{
+            TextureColorSpace, create_solid_color_texture, load_texture_from_bytes,
+            load_texture_from_file,
+        },
+    },
+};
+
+///Set of the pipeline layouts to which the descriptor set of a material is bound
+pub const MATERIAL_DESCRIPTOR_SET: u32 = 1;
+
+///Number of textures of a material: base color, metallic-roughness, normal, occlusion and emissive
+pub const MATERIAL_TEXTURE_COUNT: usize = 5;
+
+///Binding of the MaterialUniforms buffer in the descriptor set of a material
+const UNIFORMS_BINDING: u32 = 0;
+
+///Binding of the sampled image of the first texture of a material, followed by the other textures
+const FIRST_TEXTURE_BINDING: u32 = 1;
+
+///Binding of the sampler of the first texture of a material, followed by the samplers of the other textures
+const FIRST_SAMPLER_BINDING: u32 = FIRST_TEXTURE_BINDING + MATERIAL_TEXTURE_COUNT as u32;
+
+///Alpha modes, as declared by the fragment shaders of the materials
+const ALPHA_MODE_OPAQUE: u32 = 0;
+const ALPHA_MODE_MASK: u32 = 1;
+const ALPHA_MODE_BLEND: u32 = 2;
+
+///Color of the missing color textures, which doesn't change the factors multiplied with them
+const WHITE_TEXEL: [u8; 4] = [255, 255, 255, 255];
+
+///Color of the missing normal maps, whose normal is the one of the vertices
+const FLAT_NORMAL_TEXEL: [u8; 4] = [128, 128, 255, 255];
+
+///Parameters of a material, laid out as the std140 MaterialUniforms block of the shaders
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct MaterialUniforms {
+    pub base_color_factor: [f32; 4],
+
+    ///The emissive factor, with an unused W
+    pub emissive_factor: [f32; 4],
+
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    pub normal_scale: f32,
+    pub occlusion_strength: f32,
+
+    ///The alpha below which the fragments are discarded, only used by ALPHA_MODE_MASK
+    pub alpha_cutoff: f32,
+
+    ///ALPHA_MODE_OPAQUE, ALPHA_MODE_MASK or ALPHA_MODE_BLEND
+    pub alpha_mode: u32,
+
+    pub _padding: [u32; 2],
+}
+
+impl From<&Material> for MaterialUniforms {
+    fn from(material: &Material) -> Self {
+        let (alpha_mode, alpha_cutoff) = match material.alpha_mode {
+            AlphaMode::Opaque => (ALPHA_MODE_OPAQUE, 0f32),
+            AlphaMode::Mask { cutoff } => (ALPHA_MODE_MASK, cutoff),
+            AlphaMode::Blend => (ALPHA_MODE_BLEND, 0f32),
+        };
+        let emissive_factor = material.emissive_factor;
+
+        Self {
+            base_color_factor: material.base_color_factor.to_array(),
+            emissive_factor: [
+                emissive_factor.x,
+                emissive_factor.y,
+                emissive_factor.z,
+                0f32,
+            ],
+            metallic_factor: material.metallic_factor,
+            roughness_factor: material.roughness_factor,
+            normal_scale: material.normal_scale,
+            occlusion_strength: material.occlusion_strength,
+            alpha_cutoff,
+            alpha_mode,
+            _padding: [0; 2],
+        }
+    }
+}
+
+///Create the layout of the descriptor set of a material, read by the fragment shaders
+///
+///The MaterialUniforms buffer is at the binding 0, followed by the sampled images of the textures then their samplers
+pub fn create_material_descriptor_set_layout(
+    vulkan_device: &VulkanDevice,
+) -> VulkanDescriptorSetLayout<'_> {
+    let get_binding = |binding: u32, descriptor_type: vk::DescriptorType| {
+        vk::DescriptorSetLayoutBinding::default()
+            .binding(binding)
+            .descriptor_type(descriptor_type)
+            .descriptor_count(1)
+            .stage_flags(vk::ShaderStageFlags::FRAGMENT)
+    };
+
+    let all_bindings: Vec<vk::DescriptorSetLayoutBinding> = std::iter::once(get_binding(
+        UNIFORMS_BINDING,
+        vk::DescriptorType::UNIFORM_BUFFER,
+    ))
+    .chain((0..MATERIAL_TEXTURE_COUNT as u32).flat_map(|texture| {
+        [
+            get_binding(
+                FIRST_TEXTURE_BINDING + texture,
+                vk::DescriptorType::SAMPLED_IMAGE,
+            ),
+            get_binding(FIRST_SAMPLER_BINDING + texture, vk::DescriptorType::SAMPLER),
+        ]
+    }))
+    .collect();
+
+    VulkanDescriptorSetLayout::new(vulkan_device, &all_bindings)
+}
+
+///Create a descriptor pool allocating the descriptor sets of `material_count` materials
+pub fn create_material_descriptor_pool(
+    vulkan_device: &VulkanDevice,
+    material_count: usize,
+) -> VulkanDescriptorPool<'_> {
+    let material_count = material_count.max(1) as u32;
+
+    VulkanDescriptorPool::new(
+        vulkan_device,
+        material_count,
+        &[
+            vk::DescriptorPoolSize::default()
+                .ty(vk::DescriptorType::UNIFORM_BUFFER)
+                .descriptor_count(material_count),
+            vk::DescriptorPoolSize::default()
+                .ty(vk::DescriptorType::SAMPLED_IMAGE)
+                .descriptor_count(material_count * MATERIAL_TEXTURE_COUNT as u32),
+            vk::DescriptorPoolSize::default()
+                .ty(vk::DescriptorType::SAMPLER)
+                .descriptor_count(material_count * MATERIAL_TEXTURE_COUNT as u32),
+        ],
+    )
+}
+
+///Represent the images and samplers of the textures of materials, shared by the materials using the same ones
+///
+///The missing textures are replaced by 1x1 images which don't change the factors of the materials
+pub struct VulkanMaterialTextures<'vulkan_device> {
+    ///The images of the textures, starting with the default images
+    all_images: Vec<VulkanImage<'vulkan_device>>,
+
+    ///The samplers of the textures, starting with the default sampler
+    all_samplers: Vec<VulkanSampler<'vulkan_device>>,
+
+    ///For each material, the indices in `all_images` and `all_samplers` of its textures, in the order of their bindings
+    all_material_texture_indices: Vec<[(usize, usize); MATERIAL_TEXTURE_COUNT]>,
+}
+
+impl<'vulkan_device> VulkanMaterialTextures<'vulkan_device> {
+    ///Load the textures of `all_materials`, the textures which cannot be loaded are replaced by the default ones
+    pub fn load(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        command_pool: &VulkanCommandPool,
+        all_materials: &[Material],
+    ) -> Self {
+        let all_default_texels = [WHITE_TEXEL, WHITE_TEXEL, FLAT_NORMAL_TEXEL];
+        let all_default_color_spaces = [
+            TextureColorSpace::Srgb,
+            TextureColorSpace::Linear,
+            TextureColorSpace::Linear,
+        ];
+        let mut all_images: Vec<VulkanImage> = all_default_texels
+            .into_iter()
+            .zip(all_default_color_spaces)
+            .map(|(texel, color_space)| {
+                create_solid_color_texture(vulkan_device, command_pool, texel, color_space)
+            })
+            .collect();
+        let mut all_samplers = vec![VulkanSampler::new(
+            vulkan_device,
+            &SamplerParameters::default(),
+        )];
+        let mut all_sampler_parameters = vec![SamplerParameters::default()];
+
+        let mut all_image_indices: HashMap<(TextureImage, TextureColorSpace), usize> =
+            HashMap::new();
+        let mut all_material_texture_indices = Vec::with_capacity(all_materials.len());
+        for material in all_materials {
+            //The default image of each texture, its texture and its color space
+            let all_textures = [
+                (0, &material.base_color_texture, TextureColorSpace::Srgb),
+                (
+                    1,
+                    &material.metallic_roughness_texture,
+                    TextureColorSpace::Linear,
+                ),
+                (2, &material.normal_texture, TextureColorSpace::Linear),
+                (1, &material.occlusion_texture, TextureColorSpace::Linear),
+                (0, &material.emissive_texture, TextureColorSpace::Srgb),
+            ];
+
+            all_material_texture_indices.push(all_textures.map(
+                |(default_image_index, texture_opt, color_space)| {
+                    let Some(MaterialTexture {
+                        image,
+                        sampler_parameters,
+                    }) = texture_opt
+                    else {
+                        return (default_image_index, 0);
+                    };
+
+                    let image_key = (image.clone(), color_space);
+                    let image_index = match all_image_indices.get(&image_key) {
+                        Some(&image_index) => image_index,
+                        None => {
+                            let image_index = match load_texture_image(
+                                vulkan_device,
+                                command_pool,
+                                image,
+                                color_space,
+                            ) {
+                                Some(vulkan_image) => {
+                                    all_images.push(vulkan_image);
+                                    all_images.len() - 1
+                                }
+                                None => default_image_index,
+                            };
+                            all_image_indices.insert(image_key, image_index);
+                            image_index
+                        }
+                    };
+
+                    let sampler_index = all_sampler_parameters
+                        .iter()
+                        .position(|parameters| parameters == sampler_parameters)
+                        .unwrap_or_else(|| {
+                            all_samplers
+                                .push(VulkanSampler::new(vulkan_device, sampler_parameters));
+                            all_sampler_parameters.push(*sampler_parameters);
+                            all_samplers.len() - 1
+                        });
+
+                    (image_index, sampler_index)
+                },
+            ));
+        }
+
+        Self {
+            all_images,
+            all_samplers,
+            all_material_texture_indices,
+        }
+    }
+
+    ///Return the images of the textures, whose views must be passed to `write_material_textures`
+    pub fn get_all_images(&self) -> &[VulkanImage<'vulkan_device>] {
+        &self.all_images
+    }
+
+    ///Write the textures of the material `material_index` in `descriptor_set`
+    ///
+    /// # Arguments
+    ///
+    /// * `material_index` - the index of the material in the materials `&self` was loaded from
+    /// * `descriptor_set` - a descriptor set of the layout of create_material_descriptor_set_layout
+    /// * `all_image_views` - a view on each image of `get_all_images`
+    pub fn write_material_textures(
+        &self,
+        material_index: usize,
+        descriptor_set: &VulkanDescriptorSet,
+        all_image_views: &[VulkanImageView],
+    ) {
+        for (texture, &(image_index, sampler_index)) in self.all_material_texture_indices
+            [material_index]
+            .iter()
+            .enumerate()
+        {
+            descriptor_set.write_sampled_image(
+                FIRST_TEXTURE_BINDING + texture as u32,
+                &all_image_views[image_index],
+            );
+            descriptor_set.write_sampler(
+                FIRST_SAMPLER_BINDING + texture as u32,
+                &self.all_samplers[sampler_index],
+            );
+        }
+    }
+}
+
+///Load the image of a texture, None after reporting the error if it cannot be loaded
+fn load_texture_image<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    command_pool: &VulkanCommandPool,
+    image: &TextureImage,
+    color_space: TextureColorSpace,
+) -> Option<VulkanImage<'vulkan_device>> {
+    let result = match image {
+        TextureImage::File(file_path) => load_texture_from_file(
+            vulkan_device,
+            command_pool,
+            &file_path.to_string_lossy(),
+            color_space,
+        ),
+        TextureImage::Memory(bytes) => {
+            load_texture_from_bytes(vulkan_device, command_pool, bytes, color_space)
+        }
+    };
+
+    result
+        .inspect_err(|error| match image {
+            TextureImage::File(file_path) => eprintln!(
+                "failed to load texture {}, the default texture is used: {error}",
+                file_path.display()
+            ),
+            TextureImage::Memory(_) => {
+                eprintln!("failed to load embedded texture, the default texture is used: {error}")
+            }
+        })
+        .ok()
+}
+
+///Represent an instance of a material: its parameters and textures bound to the pipeline of its key
+///
+///The instances with the same pipeline key share their pipeline, but each one has its own descriptor set
+pub struct VulkanMaterialInstance<'descriptor_pool, 'vulkan_device> {
+    pipeline_key: MaterialPipelineKey,
+
+    ///The MaterialUniforms of the material, only visible by the device
+    uniform_buffer: VulkanBuffer<'vulkan_device>,
+
+    descriptor_set: VulkanDescriptorSet<'descriptor_pool, 'vulkan_device>,
+}
+
+impl<'descriptor_pool, 'vulkan_device> VulkanMaterialInstance<'descriptor_pool, 'vulkan_device> {
+    ///Create the instances of `all_materials`, with their descriptor sets allocated from `descriptor_pool`
+    ///
+    /// # Arguments
+    ///
+    /// * `vulkan_device` - a reference to a VulkanDevice
+    /// * `command_pool` - the command pool allocating the command buffer uploading the uniforms
+    /// * `descriptor_pool` - a pool of create_material_descriptor_pool, with a descriptor set for each material
+    /// * `descriptor_set_layout` - the layout of create_material_descriptor_set_layout
+    /// * `all_materials` - the materials to instantiate
+    /// * `material_textures` - the textures loaded from `all_materials`
+    /// * `all_image_views` - a view on each image of `material_textures`
+    pub fn new_all(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        command_pool: &VulkanCommandPool,
+        descriptor_pool: &'descriptor_pool VulkanDescriptorPool<'vulkan_device>,
+        descriptor_set_layout: &VulkanDescriptorSetLayout,
+        all_materials: &[Material],
+        material_textures: &VulkanMaterialTextures,
+        all_image_views: &[VulkanImageView],
+    ) -> Vec<Self> {
+        if all_materials.is_empty() {
+            return Vec::new();
+        }
+
+        let all_descriptor_sets = descriptor_pool.allocate_descriptor_sets(&vec![
+            descriptor_set_layout
+                .get_descriptor_set_layout();
+            all_materials.len()
+        ]);
+
+        all_materials
+            .iter()
+            .zip(all_descriptor_sets)
+            .enumerate()
+            .map(|(material_index, (material, descriptor_set))| {
+                let uniform_buffer = VulkanBuffer::new_device_local_with_bytes(
+                    vulkan_device,
+                    command_pool,
+                    bytemuck::bytes_of(&MaterialUniforms::from(material)),
+                    vk::BufferUsageFlags::UNIFORM_BUFFER,
+                );
+                descriptor_set.write_uniform_buffer(UNIFORMS_BINDING, &uniform_buffer);
+                material_textures.write_material_textures(
+                    material_index,
+                    &descriptor_set,
+                    all_image_views,
+                );
+
+                Self {
+                    pipeline_key: material.get_pipeline_key(),
+                    uniform_buffer,
+                    descriptor_set,
+                }
+            })
+            .collect()
+    }
+
+    pub fn get_pipeline_key(&self) -> MaterialPipelineKey {
+        self.pipeline_key
+    }
+
+    ///Return the buffer of the MaterialUniforms of `&self`
+    pub fn get_uniform_buffer(&self) -> &VulkanBuffer<'vulkan_device> {
+        &self.uniform_buffer
+    }
+
+    ///Bind the descriptor set of `&self` to MATERIAL_DESCRIPTOR_SET of `graphics_pipeline`, which must be bound
+    pub fn bind(
+        &self,
+        command_buffer: &VulkanCommandBuffer,
+        graphics_pipeline: &VulkanGraphicsPipeline,
+    ) {
+        command_buffer.bind_descriptor_sets(
+            graphics_pipeline,
+            MATERIAL_DESCRIPTOR_SET,
+            &[self.descriptor_set.get_descriptor_set()],
+        );
+    }
+}
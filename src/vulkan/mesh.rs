@@ -0,0 +1,119 @@
+use std::mem::offset_of;
+
+use ash::vk;
+
+use crate::{
+    mesh::{MeshData, Submesh, Vertex},
+    vulkan::{
+        buffer::VulkanBuffer,
+        command_buffer::{VulkanCommandBuffer, VulkanCommandPool},
+        device::VulkanDevice,
+    },
+};
+
+///The vertex buffer binding of the vertices of a VulkanMesh, for GraphicsPipelineConfig
+pub const VERTEX_BINDING_DESCRIPTIONS: [vk::VertexInputBindingDescription; 1] =
+    [vk::VertexInputBindingDescription {
+        binding: 0,
+        stride: size_of::<Vertex>() as u32,
+        input_rate: vk::VertexInputRate::VERTEX,
+    }];
+
+///The vertex attributes of the vertices of a VulkanMesh, for GraphicsPipelineConfig
+///
+///The position, normal, texture coordinates and tangent are at the locations 0, 1, 2 and 3
+pub const VERTEX_ATTRIBUTE_DESCRIPTIONS: [vk::VertexInputAttributeDescription; 4] = [
+    vk::VertexInputAttributeDescription {
+        location: 0,
+        binding: 0,
+        format: vk::Format::R32G32B32_SFLOAT,
+        offset: offset_of!(Vertex, position) as u32,
+    },
+    vk::VertexInputAttributeDescription {
+        location: 1,
+        binding: 0,
+        format: vk::Format::R32G32B32_SFLOAT,
+        offset: offset_of!(Vertex, normal) as u32,
+    },
+    vk::VertexInputAttributeDescription {
+        location: 2,
+        binding: 0,
+        format: vk::Format::R32G32_SFLOAT,
+        offset: offset_of!(Vertex, uv) as u32,
+    },
+    vk::VertexInputAttributeDescription {
+        location: 3,
+        binding: 0,
+        format: vk::Format::R32G32B32A32_SFLOAT,
+        offset: offset_of!(Vertex, tangent) as u32,
+    },
+];
+
+///Represent a mesh uploaded in a vertex buffer and an index buffer only visible by the device
+pub struct VulkanMesh<'vulkan_device> {
+    vertex_buffer: VulkanBuffer<'vulkan_device>,
+
+    ///The u32 indices of the triangles
+    index_buffer: VulkanBuffer<'vulkan_device>,
+
+    index_count: u32,
+    all_submeshes: Box<[Submesh]>,
+}
+
+impl<'vulkan_device> VulkanMesh<'vulkan_device> {
+    ///Upload `mesh_data` in new buffers
+    ///
+    /// # Panics
+    ///
+    /// If `mesh_data` has no triangle
+    pub fn new(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        command_pool: &VulkanCommandPool,
+        mesh_data: &MeshData,
+    ) -> Self {
+        assert!(
+            mesh_data.get_triangle_count() > 0,
+            "a mesh must have at least one triangle"
+        );
+
+        let vertex_buffer = VulkanBuffer::new_device_local_with_bytes(
+            vulkan_device,
+            command_pool,
+            bytemuck::cast_slice(&mesh_data.all_vertices),
+            vk::BufferUsageFlags::VERTEX_BUFFER,
+        );
+        let index_buffer = VulkanBuffer::new_device_local_with_bytes(
+            vulkan_device,
+            command_pool,
+            bytemuck::cast_slice(&mesh_data.all_indices),
+            vk::BufferUsageFlags::INDEX_BUFFER,
+        );
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            index_count: mesh_data.all_indices.len() as u32,
+            all_submeshes: mesh_data.all_submeshes.clone().into(),
+        }
+    }
+
+    pub fn get_index_count(&self) -> u32 {
+        self.index_count
+    }
+
+    ///Return the ranges of indices drawn with the same material
+    pub fn get_all_submeshes(&self) -> &[Submesh] {
+        &self.all_submeshes
+    }
+
+    ///Bind the vertex buffer and the index buffer of `&self`, to draw its submeshes
+    pub fn bind(&self, command_buffer: &VulkanCommandBuffer) {
+        command_buffer.bind_vertex_buffers(0, &[self.vertex_buffer.get_buffer()]);
+        command_buffer.bind_index_buffer(self.index_buffer.get_buffer(), vk::IndexType::UINT32);
+    }
+
+    ///Draw `submesh` of `&self`, which must be bound
+    pub fn draw_submesh(&self, command_buffer: &VulkanCommandBuffer, submesh: &Submesh) {
+        command_buffer.draw_indexed(submesh.index_count, 1, submesh.first_index, 0, 0);
+    }
+}
@@ -1,7 +1,24 @@
+pub mod buffer;
+pub mod capture;
+pub mod command_buffer;
+pub mod compute_pipeline;
+pub mod descriptor;
 pub mod device;
 pub mod entry;
+pub mod environment;
+pub mod framebuffer;
+pub mod frames;
 pub mod graphics_pipeline;
+pub mod image;
 pub mod instance;
+pub mod light;
+pub mod material;
+pub mod mesh;
+pub mod shader_watcher;
 pub mod shaders;
+pub mod shadow;
+pub mod spirv;
 pub mod surface;
 pub mod swapchain;
+pub mod sync;
+pub mod texture;
@@ -0,0 +1,166 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::SystemTime,
+};
+
+use crate::vulkan::shaders::VulkanShaderModule;
+
+///Name of the directory containing the SPIR-V files
+const SPIRV_DIRECTORY_NAME: &str = "spir-v";
+
+///Name of the directory containing the GLSL sources, next to `SPIRV_DIRECTORY_NAME`
+const GLSL_DIRECTORY_NAME: &str = "glsl";
+
+///Program used to compile a GLSL source in a SPIR-V file
+const GLSL_COMPILER: &str = "glslc";
+
+///Represent a GLSL source watched by a ShaderWatcher
+struct WatchedShader {
+    ///Path of the SPIR-V file loaded by the shader module
+    spirv_file_path: Box<str>,
+
+    ///Path of the GLSL source compiled in `spirv_file_path`
+    glsl_file_path: PathBuf,
+
+    ///Last known modification time of `glsl_file_path`
+    last_modification: Option<SystemTime>,
+}
+
+///Watch the GLSL sources of the shader modules and recompile them when they are modified
+///
+///For example, `shaders/spir-v/base.vert.spv` is recompiled from `shaders/glsl/base.vert`
+#[derive(Default)]
+pub struct ShaderWatcher {
+    all_watched_shaders: Vec<WatchedShader>,
+}
+
+impl ShaderWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///Start to watch the GLSL source of `shader_module`
+    ///
+    ///Nothing is watched if `shader_module` is loaded from memory or if the GLSL source cannot be found
+    pub fn watch(&mut self, shader_module: &VulkanShaderModule) {
+        let Some(spirv_file_path) = shader_module.get_file_path() else {
+            return;
+        };
+
+        if self
+            .all_watched_shaders
+            .iter()
+            .any(|watched_shader| watched_shader.spirv_file_path.as_ref() == spirv_file_path)
+        {
+            return;
+        }
+
+        let Some(glsl_file_path) = get_glsl_file_path(Path::new(spirv_file_path)) else {
+            eprintln!("no GLSL source found for shader \"{spirv_file_path}\", it isn't watched");
+            return;
+        };
+
+        self.all_watched_shaders.push(WatchedShader {
+            spirv_file_path: spirv_file_path.into(),
+            last_modification: get_modification_time(&glsl_file_path),
+            glsl_file_path,
+        });
+    }
+
+    ///Recompile the modified GLSL sources and reload the shader modules of `all_shader_modules` using them
+    ///
+    ///Compilation and loading errors are printed, the old shader modules are kept in this case
+    ///
+    /// # Returns
+    ///
+    /// The SPIR-V file paths of the reloaded shader modules
+    pub fn reload_modified_shaders(
+        &mut self,
+        all_shader_modules: &mut [VulkanShaderModule],
+    ) -> Vec<Box<str>> {
+        let mut all_reloaded_file_paths = Vec::new();
+
+        for watched_shader in self.all_watched_shaders.iter_mut() {
+            let modification = get_modification_time(&watched_shader.glsl_file_path);
+            if modification.is_none() || modification == watched_shader.last_modification {
+                continue;
+            }
+            watched_shader.last_modification = modification;
+
+            if let Err(error) = compile_glsl(
+                &watched_shader.glsl_file_path,
+                &watched_shader.spirv_file_path,
+            ) {
+                eprintln!(
+                    "failed to compile shader \"{}\":\n{error}",
+                    watched_shader.glsl_file_path.display()
+                );
+                continue;
+            }
+
+            let mut reloaded = false;
+            for shader_module in all_shader_modules.iter_mut().filter(|shader_module| {
+                shader_module.get_file_path() == Some(&watched_shader.spirv_file_path)
+            }) {
+                match shader_module.reload() {
+                    Ok(()) => reloaded = true,
+                    Err(error) => eprintln!(
+                        "failed to reload shader \"{}\": {error}",
+                        watched_shader.spirv_file_path
+                    ),
+                }
+            }
+
+            if reloaded {
+                all_reloaded_file_paths.push(watched_shader.spirv_file_path.clone());
+            }
+        }
+
+        all_reloaded_file_paths
+    }
+}
+
+///Return the GLSL source path of `spirv_file_path`, if it exists
+fn get_glsl_file_path(spirv_file_path: &Path) -> Option<PathBuf> {
+    let glsl_file_name = spirv_file_path.file_stem()?;
+    let spirv_directory = spirv_file_path.parent()?;
+
+    let glsl_directory = if spirv_directory.file_name()? == SPIRV_DIRECTORY_NAME {
+        spirv_directory.with_file_name(GLSL_DIRECTORY_NAME)
+    } else {
+        spirv_directory.to_path_buf()
+    };
+
+    let glsl_file_path = glsl_directory.join(glsl_file_name);
+    glsl_file_path.is_file().then_some(glsl_file_path)
+}
+
+fn get_modification_time(file_path: &Path) -> Option<SystemTime> {
+    fs::metadata(file_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+///Compile `glsl_file_path` in `spirv_file_path` with `GLSL_COMPILER`
+///
+///`spirv_file_path` isn't modified if the compilation failed
+///
+/// # Returns
+///
+/// The compiler output if the compilation failed
+fn compile_glsl(glsl_file_path: &Path, spirv_file_path: &str) -> Result<(), String> {
+    let output = Command::new(GLSL_COMPILER)
+        .arg(glsl_file_path)
+        .arg("-o")
+        .arg(spirv_file_path)
+        .output()
+        .map_err(|error| format!("failed to run {GLSL_COMPILER}: {error}"))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+}
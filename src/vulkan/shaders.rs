@@ -1,54 +1,184 @@
-use core::panic;
-use std::{ffi::CStr, fs::File, io::Read};
+use std::{
+    ffi::{CStr, CString},
+    fmt::{self, Display},
+    fs, io,
+};
 
 use ash::vk;
+use bytemuck::Pod;
 
-use crate::vulkan::device::VulkanDevice;
+use crate::vulkan::{
+    device::VulkanDevice,
+    spirv::{SpirvCode, SpirvError, SpirvVersion},
+};
 
-const SHADER_ENTRYPOINT: &CStr = c"main";
+///The usual entry point name of a shader
+pub const DEFAULT_SHADER_ENTRYPOINT: &CStr = c"main";
+
+///Error returned when a SPIR-V code cannot be loaded in a shader module
+#[derive(Debug)]
+pub enum ShaderModuleError {
+    ///The SPIR-V file cannot be read
+    Io(io::Error),
+
+    ///The code isn't a valid SPIR-V module
+    InvalidSpirv(SpirvError),
+
+    ///The SPIR-V version of the code is higher than the one supported by the device
+    UnsupportedSpirvVersion {
+        version: SpirvVersion,
+        max_supported_version: SpirvVersion,
+    },
+
+    ///The code doesn't declare any entry point for `shader_stage`
+    NoEntryPoint(vk::ShaderStageFlags),
+
+    ///The code doesn't declare the entry point `name` for `shader_stage`
+    MissingEntryPoint {
+        name: Box<str>,
+        shader_stage: vk::ShaderStageFlags,
+    },
+
+    ///Vulkan failed to create the shader module
+    Vulkan(vk::Result),
+}
+
+impl Display for ShaderModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderModuleError::Io(error) => write!(f, "failed to read shader file: {error}"),
+            ShaderModuleError::InvalidSpirv(error) => write!(f, "{error}"),
+            ShaderModuleError::UnsupportedSpirvVersion {
+                version,
+                max_supported_version,
+            } => write!(
+                f,
+                "SPIR-V {version} isn't supported by the device (maximum SPIR-V {max_supported_version})"
+            ),
+            ShaderModuleError::NoEntryPoint(shader_stage) => {
+                write!(f, "no entry point found for {shader_stage:?} stage")
+            }
+            ShaderModuleError::MissingEntryPoint { name, shader_stage } => {
+                write!(
+                    f,
+                    "no entry point \"{name}\" found for {shader_stage:?} stage"
+                )
+            }
+            ShaderModuleError::Vulkan(error) => {
+                write!(f, "failed to create a shader module: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShaderModuleError {}
+
+impl From<io::Error> for ShaderModuleError {
+    fn from(error: io::Error) -> Self {
+        ShaderModuleError::Io(error)
+    }
+}
+
+impl From<SpirvError> for ShaderModuleError {
+    fn from(error: SpirvError) -> Self {
+        ShaderModuleError::InvalidSpirv(error)
+    }
+}
 
 pub struct VulkanShaderModule<'vulkan_device> {
     vulkan_device: &'vulkan_device VulkanDevice,
     shader_module: vk::ShaderModule,
     shader_state: vk::ShaderStageFlags,
+
+    ///Path of the SPIR-V file loaded in `shader_module`, None if it's loaded from memory
+    file_path: Option<Box<str>>,
+
+    ///Names of all entry points of `shader_module` usable for `shader_state`
+    all_entry_point_names: Box<[Box<str>]>,
 }
 
 impl<'vulkan_device> VulkanShaderModule<'vulkan_device> {
+    ///Create a shader module for `shader_state` from the SPIR-V file at `file_path`
+    ///
+    ///The SPIR-V code is checked before the creation, see `VulkanShaderModule::from_bytes`
     pub fn from_file(
         vulkan_device: &'vulkan_device VulkanDevice,
         file_path: &str,
         shader_state: vk::ShaderStageFlags,
-    ) -> Self {
-        let mut shader_file = File::open(file_path).unwrap();
-        let mut shader_file_buf = Vec::new(); //TODO: Use a buffer pool
-        shader_file.read_to_end(&mut shader_file_buf).unwrap();
-
-        if shader_file_buf.len() % 4 != 0 {
-            panic!("corrupted shader");
-        }
+    ) -> Result<Self, ShaderModuleError> {
+        let LoadedShaderModule {
+            shader_module,
+            all_entry_point_names,
+        } = load_shader_module_from_file(vulkan_device, file_path, shader_state)?;
 
-        let shader_code: &[u32] = bytemuck::cast_slice(shader_file_buf.as_slice());
+        Ok(VulkanShaderModule {
+            vulkan_device,
+            shader_module,
+            shader_state,
+            file_path: Some(file_path.into()),
+            all_entry_point_names,
+        })
+    }
 
-        let shader_module_create_info = vk::ShaderModuleCreateInfo::default().code(shader_code);
-        let shader_module = vulkan_device
-            .create_shader_module(&shader_module_create_info)
-            .expect("failed to create a shader module");
+    ///Create a shader module for `shader_state` from the SPIR-V code in `bytes`
+    ///
+    ///The SPIR-V code can be little or big endian and `bytes` doesn't need to be aligned.
+    ///It's checked to have a SPIR-V version supported by `vulkan_device` and at least one entry point for `shader_state`
+    pub fn from_bytes(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        bytes: &[u8],
+        shader_state: vk::ShaderStageFlags,
+    ) -> Result<Self, ShaderModuleError> {
+        let LoadedShaderModule {
+            shader_module,
+            all_entry_point_names,
+        } = load_shader_module(vulkan_device, bytes, shader_state)?;
 
-        VulkanShaderModule {
+        Ok(VulkanShaderModule {
             vulkan_device,
             shader_module,
             shader_state,
-        }
+            file_path: None,
+            all_entry_point_names,
+        })
+    }
+
+    ///Return the path of the SPIR-V file loaded in `&self`, None if it's loaded from memory
+    pub fn get_file_path(&self) -> Option<&str> {
+        self.file_path.as_deref()
+    }
+
+    ///Return the shader stage `&self` is created for
+    pub fn get_shader_stage(&self) -> vk::ShaderStageFlags {
+        self.shader_state
+    }
+
+    ///Load again the SPIR-V file of `&self` in a new shader module
+    ///
+    ///The current shader module is kept if the new one cannot be created, nothing is done if `&self` is loaded from memory.
+    ///Pipelines already created from `&self` aren't affected and must be rebuilt to use the new code
+    pub fn reload(&mut self) -> Result<(), ShaderModuleError> {
+        let Some(file_path) = &self.file_path else {
+            return Ok(());
+        };
+
+        let LoadedShaderModule {
+            shader_module,
+            all_entry_point_names,
+        } = load_shader_module_from_file(self.vulkan_device, file_path, self.shader_state)?;
+
+        self.vulkan_device.destroy_shader_module(self.shader_module);
+        self.shader_module = shader_module;
+        self.all_entry_point_names = all_entry_point_names;
+
+        Ok(())
     }
 
-    pub fn set_pipeline_stage(
-        &self,
-        pipeline_shader_stage_create_info: &mut vk::PipelineShaderStageCreateInfo,
-    ) {
-        *pipeline_shader_stage_create_info = vk::PipelineShaderStageCreateInfo::default()
-            .stage(self.shader_state)
-            .module(self.shader_module)
-            .name(SHADER_ENTRYPOINT)
+    ///Return if `&self` has the entry point `name` for its shader stage
+    pub fn has_entry_point(&self, name: &str) -> bool {
+        self.all_entry_point_names
+            .iter()
+            .any(|entry_point_name| entry_point_name.as_ref() == name)
     }
 }
 
@@ -57,3 +187,161 @@ impl Drop for VulkanShaderModule<'_> {
         self.vulkan_device.destroy_shader_module(self.shader_module)
     }
 }
+
+///Represent a stage of a pipeline: a shader module with the entry point and the specialization constants to use
+///
+///The same shader module can be used by several stages to create pipeline variants
+pub struct VulkanShaderStage<'shader_module, 'vulkan_device> {
+    shader_module: &'shader_module VulkanShaderModule<'vulkan_device>,
+    entry_point: CString,
+
+    ///Entries mapping the constant IDs of the shader to ranges of `specialization_data`
+    all_specialization_map_entries: Box<[vk::SpecializationMapEntry]>,
+
+    ///Values of the specialization constants
+    specialization_data: Box<[u8]>,
+}
+
+impl<'shader_module, 'vulkan_device> VulkanShaderStage<'shader_module, 'vulkan_device> {
+    ///Create a stage running the entry point `entry_point` of `shader_module`, without specialization constants
+    ///
+    /// # Returns
+    ///
+    /// An error if `shader_module` hasn't the entry point `entry_point` for its shader stage
+    pub fn new(
+        shader_module: &'shader_module VulkanShaderModule<'vulkan_device>,
+        entry_point: &CStr,
+    ) -> Result<Self, ShaderModuleError> {
+        let entry_point_name = entry_point.to_string_lossy();
+        if !shader_module.has_entry_point(&entry_point_name) {
+            return Err(ShaderModuleError::MissingEntryPoint {
+                name: entry_point_name.into(),
+                shader_stage: shader_module.shader_state,
+            });
+        }
+
+        Ok(Self {
+            shader_module,
+            entry_point: entry_point.to_owned(),
+            all_specialization_map_entries: Box::new([]),
+            specialization_data: Box::new([]),
+        })
+    }
+
+    ///Set the values of the specialization constants of `&self` from `constants`
+    ///
+    ///Each entry of `all_map_entries` gives the constant ID of a field of `T` from its offset and size.
+    ///For example, `vk::SpecializationMapEntry::default().constant_id(0).offset(offset_of!(T, field) as u32).size(size_of::<u32>())`
+    ///
+    /// # Panics
+    ///
+    /// If an entry of `all_map_entries` is outside of `T`
+    pub fn specialization_constants<T: Pod>(
+        mut self,
+        constants: &T,
+        all_map_entries: &[vk::SpecializationMapEntry],
+    ) -> Self {
+        for map_entry in all_map_entries {
+            assert!(
+                map_entry.offset as usize + map_entry.size <= size_of::<T>(),
+                "specialization constant {} is outside of the constants data",
+                map_entry.constant_id
+            );
+        }
+
+        self.all_specialization_map_entries = all_map_entries.into();
+        self.specialization_data = bytemuck::bytes_of(constants).into();
+        self
+    }
+
+    pub fn get_shader_module(&self) -> &VulkanShaderModule<'vulkan_device> {
+        self.shader_module
+    }
+
+    ///Return the specialization info of `&self`, None if it hasn't specialization constants
+    pub fn get_specialization_info(&self) -> Option<vk::SpecializationInfo<'_>> {
+        if self.all_specialization_map_entries.is_empty() {
+            return None;
+        }
+
+        Some(
+            vk::SpecializationInfo::default()
+                .map_entries(&self.all_specialization_map_entries)
+                .data(&self.specialization_data),
+        )
+    }
+
+    ///Return the create info of `&self` to create a pipeline
+    ///
+    /// # Arguments
+    ///
+    /// * `specialization_info` - the specialization info returned by `get_specialization_info`
+    pub fn get_pipeline_stage_create_info<'a>(
+        &'a self,
+        specialization_info: Option<&'a vk::SpecializationInfo<'a>>,
+    ) -> vk::PipelineShaderStageCreateInfo<'a> {
+        let pipeline_shader_stage_create_info = vk::PipelineShaderStageCreateInfo::default()
+            .stage(self.shader_module.shader_state)
+            .module(self.shader_module.shader_module)
+            .name(&self.entry_point);
+
+        match specialization_info {
+            Some(specialization_info) => {
+                pipeline_shader_stage_create_info.specialization_info(specialization_info)
+            }
+            None => pipeline_shader_stage_create_info,
+        }
+    }
+}
+
+///Represent a shader module created from a checked SPIR-V code
+struct LoadedShaderModule {
+    shader_module: vk::ShaderModule,
+
+    ///Names of all entry points of `shader_module` for the shader stage it's loaded for
+    all_entry_point_names: Box<[Box<str>]>,
+}
+
+///Create a shader module for `shader_state` from the SPIR-V file at `file_path`
+fn load_shader_module_from_file(
+    vulkan_device: &VulkanDevice,
+    file_path: &str,
+    shader_state: vk::ShaderStageFlags,
+) -> Result<LoadedShaderModule, ShaderModuleError> {
+    let shader_file_buf = fs::read(file_path)?; //TODO: Use a buffer pool
+
+    load_shader_module(vulkan_device, &shader_file_buf, shader_state)
+}
+
+///Create a shader module for `shader_state` from the SPIR-V code in `bytes`, after checking it's usable by `vulkan_device`
+fn load_shader_module(
+    vulkan_device: &VulkanDevice,
+    bytes: &[u8],
+    shader_state: vk::ShaderStageFlags,
+) -> Result<LoadedShaderModule, ShaderModuleError> {
+    let spirv_code = SpirvCode::from_bytes(bytes)?;
+
+    let vulkan_api_version = vulkan_device.get_api_version();
+    if !spirv_code.is_supported_by_vulkan(vulkan_api_version) {
+        return Err(ShaderModuleError::UnsupportedSpirvVersion {
+            version: spirv_code.get_version(),
+            max_supported_version: SpirvVersion::max_supported_by_vulkan(vulkan_api_version),
+        });
+    }
+
+    let all_entry_point_names = spirv_code.get_all_entry_point_names(shader_state);
+    if all_entry_point_names.is_empty() {
+        return Err(ShaderModuleError::NoEntryPoint(shader_state));
+    }
+
+    let shader_module_create_info =
+        vk::ShaderModuleCreateInfo::default().code(spirv_code.get_words());
+    let shader_module = vulkan_device
+        .create_shader_module(&shader_module_create_info)
+        .map_err(ShaderModuleError::Vulkan)?;
+
+    Ok(LoadedShaderModule {
+        shader_module,
+        all_entry_point_names,
+    })
+}
@@ -0,0 +1,153 @@
+use ash::vk;
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    camera::Camera,
+    math::matrix::Mat4,
+    scene::Light,
+    shadow::{ShadowConfig, ShadowView, get_light_shadow_views},
+    vulkan::{
+        device::VulkanDevice,
+        image::{SamplerParameters, VulkanImage},
+        light::GpuLight,
+    },
+};
+
+///Shadow view of a light in a tile of the shadow atlas, laid out as the std430 Shadow struct of the shaders
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct GpuShadow {
+    ///Transforms the world space to the clip space of the light
+    pub view_projection: Mat4,
+
+    ///The offset of the tile in the texture coordinates of the atlas in XY, and its size in ZW
+    pub atlas_rect: [f32; 4],
+
+    ///The view distance from the camera at which the cascade of a directional light ends
+    pub split_distance: f32,
+
+    ///The size of a texel in the texture coordinates of the atlas, the offset of the PCF samples
+    pub texel_size: f32,
+
+    pub _padding: [u32; 2],
+}
+
+impl GpuShadow {
+    ///Return `shadow_view` rendered in the tile `tile_index` of the atlas of `shadow_config`
+    pub fn new(shadow_view: &ShadowView, shadow_config: &ShadowConfig, tile_index: usize) -> Self {
+        Self {
+            view_projection: shadow_view.view_projection,
+            atlas_rect: shadow_config.get_tile_uv_rect(tile_index).to_array(),
+            split_distance: shadow_view.split_distance,
+            texel_size: 1f32 / shadow_config.atlas_size as f32,
+            _padding: [0; 2],
+        }
+    }
+}
+
+///Return the lights of `all_lights` placed by their world matrices, at most `max_light_count`,
+///with their shadows seen by `camera_opt`, at most a shadow by tile of the atlas of `shadow_config`
+///
+///The shadows are indexed by their tile. The lights whose shadows don't fit in the remaining tiles have no shadow,
+///and without camera, no light has shadows
+pub fn get_gpu_lights_and_shadows(
+    all_lights: &[(Light, Mat4)],
+    max_light_count: usize,
+    camera_opt: Option<&Camera>,
+    shadow_config: &ShadowConfig,
+) -> (Vec<GpuLight>, Vec<GpuShadow>) {
+    let tile_count = shadow_config.get_tile_count();
+    let mut all_shadows = Vec::new();
+
+    let all_gpu_lights = all_lights
+        .iter()
+        .take(max_light_count)
+        .map(|(light, world_matrix)| {
+            let mut gpu_light = GpuLight::new(light, world_matrix);
+
+            let all_shadow_views = camera_opt
+                .map(|camera| get_light_shadow_views(light, world_matrix, camera, shadow_config))
+                .unwrap_or_default();
+            if !all_shadow_views.is_empty()
+                && all_shadows.len() + all_shadow_views.len() <= tile_count
+            {
+                gpu_light.first_shadow_index = all_shadows.len() as u32;
+                gpu_light.shadow_count = all_shadow_views.len() as u32;
+                for shadow_view in &all_shadow_views {
+                    all_shadows.push(GpuShadow::new(
+                        shadow_view,
+                        shadow_config,
+                        all_shadows.len(),
+                    ));
+                }
+            }
+
+            gpu_light
+        })
+        .collect();
+
+    (all_gpu_lights, all_shadows)
+}
+
+///Return the viewport and the scissor covering the tile `tile_index` of the atlas of `shadow_config`
+pub fn get_shadow_tile_viewport_and_scissor(
+    shadow_config: &ShadowConfig,
+    tile_index: usize,
+) -> (vk::Viewport, vk::Rect2D) {
+    let [x, y] = shadow_config.get_tile_offset(tile_index);
+    let tile_size = shadow_config.tile_size;
+
+    (
+        vk::Viewport::default()
+            .x(x as f32)
+            .y(y as f32)
+            .width(tile_size as f32)
+            .height(tile_size as f32)
+            .min_depth(0f32)
+            .max_depth(1f32),
+        vk::Rect2D::default()
+            .offset(vk::Offset2D {
+                x: x as i32,
+                y: y as i32,
+            })
+            .extent(vk::Extent2D {
+                width: tile_size,
+                height: tile_size,
+            }),
+    )
+}
+
+///Create the depth image of the shadow atlas of `shadow_config`, rendered by depth-only pipelines then sampled
+///
+///`depth_format` must be a sampled depth format, as returned by VulkanDevice::get_supported_sampled_depth_format
+pub fn create_shadow_atlas_image<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    depth_format: vk::Format,
+    shadow_config: &ShadowConfig,
+) -> VulkanImage<'vulkan_device> {
+    VulkanImage::new_2d(
+        vulkan_device,
+        vk::Extent2D {
+            width: shadow_config.atlas_size,
+            height: shadow_config.atlas_size,
+        },
+        depth_format,
+        1,
+        1,
+        false,
+        vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT | vk::ImageUsageFlags::SAMPLED,
+    )
+}
+
+///Return the parameters of the sampler of the shadow atlas, reading the exact depths of its texels
+///
+///The samples are filtered by the shaders, which compare each depth before averaging them
+pub fn get_shadow_sampler_parameters() -> SamplerParameters {
+    SamplerParameters {
+        mag_filter: vk::Filter::NEAREST,
+        min_filter: vk::Filter::NEAREST,
+        mipmap_mode: vk::SamplerMipmapMode::NEAREST,
+        address_modes: [vk::SamplerAddressMode::CLAMP_TO_EDGE; 3],
+        max_anisotropy: None,
+    }
+}
@@ -0,0 +1,369 @@
+use std::fmt::{self, Display};
+
+use ash::vk;
+
+///Magic number of the first word of a SPIR-V module
+const SPIRV_MAGIC_NUMBER: u32 = 0x0723_0203;
+
+///Number of words in the header of a SPIR-V module
+const SPIRV_HEADER_WORD_COUNT: usize = 5;
+
+///Opcode of the OpEntryPoint instruction
+const OP_ENTRY_POINT: u32 = 15;
+
+///Error returned when bytes aren't a valid SPIR-V module
+#[derive(Debug, PartialEq, Eq)]
+pub enum SpirvError {
+    ///The size (in bytes) isn't a multiple of 4 or is too small for a SPIR-V header
+    WrongSize(usize),
+
+    ///The first word isn't the SPIR-V magic number, in any endianness
+    UnknownMagicNumber(u32),
+
+    ///The instruction at the word `offset` has a word count of 0 or goes beyond the end of the module
+    MalformedInstruction { offset: usize },
+}
+
+impl Display for SpirvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpirvError::WrongSize(size) => write!(
+                f,
+                "invalid SPIR-V size of {size} bytes, must be a multiple of 4 and contain a header"
+            ),
+            SpirvError::UnknownMagicNumber(magic_number) => write!(
+                f,
+                "invalid SPIR-V magic number {magic_number:#010x}, expected {SPIRV_MAGIC_NUMBER:#010x}"
+            ),
+            SpirvError::MalformedInstruction { offset } => {
+                write!(f, "invalid SPIR-V instruction at word {offset}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpirvError {}
+
+///Represent a SPIR-V version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SpirvVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl SpirvVersion {
+    ///Return the highest SPIR-V version which must be supported by a device of `vulkan_api_version`
+    pub fn max_supported_by_vulkan(vulkan_api_version: u32) -> Self {
+        let minor = match (
+            vk::api_version_major(vulkan_api_version),
+            vk::api_version_minor(vulkan_api_version),
+        ) {
+            (1, 0) => 0,
+            (1, 1) => 3,
+            (1, 2) => 5,
+            _ => 6,
+        };
+
+        SpirvVersion { major: 1, minor }
+    }
+}
+
+impl Display for SpirvVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+///Represent an entry point declared in a SPIR-V module
+pub struct SpirvEntryPoint {
+    ///The SPIR-V execution model of the entry point
+    ///
+    ///For example, 0 for a vertex shader or 4 for a fragment shader
+    pub execution_model: u32,
+
+    pub name: Box<str>,
+}
+
+///Represent a SPIR-V module checked and stored in host endianness
+pub struct SpirvCode {
+    ///All words of the module, correctly aligned to be given to Vulkan
+    words: Box<[u32]>,
+
+    version: SpirvVersion,
+
+    all_entry_points: Box<[SpirvEntryPoint]>,
+}
+
+impl SpirvCode {
+    ///Parse `bytes` as a SPIR-V module, in little or big endian
+    ///
+    ///The bytes are copied to be correctly aligned, so `bytes` doesn't need to be aligned to 4
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpirvError> {
+        if !bytes.len().is_multiple_of(4) || bytes.len() < SPIRV_HEADER_WORD_COUNT * 4 {
+            return Err(SpirvError::WrongSize(bytes.len()));
+        }
+
+        let mut words: Box<[u32]> = bytes
+            .chunks_exact(4)
+            .map(|word_bytes| u32::from_ne_bytes(word_bytes.try_into().unwrap()))
+            .collect();
+
+        if words[0] != SPIRV_MAGIC_NUMBER {
+            if words[0].swap_bytes() != SPIRV_MAGIC_NUMBER {
+                return Err(SpirvError::UnknownMagicNumber(words[0]));
+            }
+
+            for word in words.iter_mut() {
+                *word = word.swap_bytes();
+            }
+        }
+
+        let version = SpirvVersion {
+            major: (words[1] >> 16) as u8,
+            minor: (words[1] >> 8) as u8,
+        };
+
+        let all_entry_points = parse_all_entry_points(&words)?;
+
+        Ok(SpirvCode {
+            words,
+            version,
+            all_entry_points,
+        })
+    }
+
+    pub fn get_words(&self) -> &[u32] {
+        &self.words
+    }
+
+    pub fn get_version(&self) -> SpirvVersion {
+        self.version
+    }
+
+    pub fn get_all_entry_points(&self) -> &[SpirvEntryPoint] {
+        &self.all_entry_points
+    }
+
+    ///Return if the SPIR-V version of `&self` must be supported by a device of `vulkan_api_version`
+    pub fn is_supported_by_vulkan(&self, vulkan_api_version: u32) -> bool {
+        self.version <= SpirvVersion::max_supported_by_vulkan(vulkan_api_version)
+    }
+
+    ///Return the names of the entry points of `&self` whose execution model matches `shader_stage`
+    pub fn get_all_entry_point_names(&self, shader_stage: vk::ShaderStageFlags) -> Box<[Box<str>]> {
+        let execution_model = get_execution_model(shader_stage);
+
+        self.all_entry_points
+            .iter()
+            .filter(|entry_point| Some(entry_point.execution_model) == execution_model)
+            .map(|entry_point| entry_point.name.clone())
+            .collect()
+    }
+}
+
+///Return the SPIR-V execution model matching `shader_stage`
+///
+/// # Returns
+///
+/// None if `shader_stage` isn't a single graphics or compute stage
+pub fn get_execution_model(shader_stage: vk::ShaderStageFlags) -> Option<u32> {
+    match shader_stage {
+        vk::ShaderStageFlags::VERTEX => Some(0),
+        vk::ShaderStageFlags::TESSELLATION_CONTROL => Some(1),
+        vk::ShaderStageFlags::TESSELLATION_EVALUATION => Some(2),
+        vk::ShaderStageFlags::GEOMETRY => Some(3),
+        vk::ShaderStageFlags::FRAGMENT => Some(4),
+        vk::ShaderStageFlags::COMPUTE => Some(5),
+        _ => None,
+    }
+}
+
+///Return all entry points declared by OpEntryPoint instructions in `words`
+fn parse_all_entry_points(words: &[u32]) -> Result<Box<[SpirvEntryPoint]>, SpirvError> {
+    let mut all_entry_points = Vec::new();
+
+    let mut offset = SPIRV_HEADER_WORD_COUNT;
+    while offset < words.len() {
+        let word_count = (words[offset] >> 16) as usize;
+        let opcode = words[offset] & 0xFFFF;
+
+        if word_count == 0 || offset + word_count > words.len() {
+            return Err(SpirvError::MalformedInstruction { offset });
+        }
+
+        //OpEntryPoint: opcode, execution model, function id, name (literal string), interface ids
+        if opcode == OP_ENTRY_POINT {
+            if word_count < 4 {
+                return Err(SpirvError::MalformedInstruction { offset });
+            }
+
+            all_entry_points.push(SpirvEntryPoint {
+                execution_model: words[offset + 1],
+                name: decode_literal_string(&words[offset + 3..offset + word_count]),
+            });
+        }
+
+        offset += word_count;
+    }
+
+    Ok(all_entry_points.into_boxed_slice())
+}
+
+///Decode a SPIR-V literal string, which is a nul-terminated UTF-8 string packed with the lowest-order byte first
+fn decode_literal_string(words: &[u32]) -> Box<str> {
+    let bytes: Vec<u8> = words
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .take_while(|byte| *byte != 0)
+        .collect();
+
+    String::from_utf8_lossy(&bytes).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///Encode the words of a SPIR-V module of `version` declaring `entry_point_name` for `execution_model`
+    fn create_module_words(
+        version: SpirvVersion,
+        execution_model: u32,
+        entry_point_name: &str,
+    ) -> Vec<u32> {
+        let mut name_bytes = entry_point_name.as_bytes().to_vec();
+        name_bytes.resize(name_bytes.len() / 4 * 4 + 4, 0);
+        let name_words = name_bytes
+            .chunks_exact(4)
+            .map(|word_bytes| u32::from_le_bytes(word_bytes.try_into().unwrap()));
+
+        let word_count = 3 + name_bytes.len() as u32 / 4;
+        [
+            SPIRV_MAGIC_NUMBER,
+            (version.major as u32) << 16 | (version.minor as u32) << 8,
+            0,
+            2,
+            0,
+            word_count << 16 | OP_ENTRY_POINT,
+            execution_model,
+            1,
+        ]
+        .into_iter()
+        .chain(name_words)
+        .collect()
+    }
+
+    fn to_bytes(words: &[u32], to_word_bytes: fn(u32) -> [u8; 4]) -> Vec<u8> {
+        words.iter().flat_map(|word| to_word_bytes(*word)).collect()
+    }
+
+    const VERSION_1_3: SpirvVersion = SpirvVersion { major: 1, minor: 3 };
+
+    #[test]
+    fn entry_points_are_parsed() {
+        let words = create_module_words(VERSION_1_3, 4, "main");
+
+        let spirv_code = SpirvCode::from_bytes(&to_bytes(&words, u32::to_ne_bytes)).unwrap();
+
+        assert_eq!(spirv_code.get_words(), words);
+        assert_eq!(spirv_code.get_version(), VERSION_1_3);
+        assert_eq!(
+            spirv_code.get_all_entry_point_names(vk::ShaderStageFlags::FRAGMENT),
+            ["main".into()].into()
+        );
+    }
+
+    #[test]
+    fn wrong_magic_number_is_refused() {
+        let mut words = create_module_words(VERSION_1_3, 4, "main");
+        words[0] = 0xDEAD_BEEF;
+
+        assert_eq!(
+            SpirvCode::from_bytes(&to_bytes(&words, u32::to_ne_bytes)).err(),
+            Some(SpirvError::UnknownMagicNumber(0xDEAD_BEEF))
+        );
+    }
+
+    #[test]
+    fn byte_swapped_module_is_read_in_host_endianness() {
+        let words = create_module_words(VERSION_1_3, 0, "vertexMain");
+
+        let spirv_code =
+            SpirvCode::from_bytes(&to_bytes(&words, |word| word.swap_bytes().to_ne_bytes()))
+                .unwrap();
+
+        assert_eq!(spirv_code.get_words(), words);
+        assert_eq!(spirv_code.get_version(), VERSION_1_3);
+        assert_eq!(
+            spirv_code.get_all_entry_point_names(vk::ShaderStageFlags::VERTEX),
+            ["vertexMain".into()].into()
+        );
+    }
+
+    #[test]
+    fn unaligned_bytes_are_copied() {
+        let words = create_module_words(VERSION_1_3, 4, "main");
+        //The module starts 1 byte after the 4-byte alignment of the vector
+        let mut bytes = vec![0u8];
+        bytes.extend(to_bytes(&words, u32::to_ne_bytes));
+
+        let spirv_code = SpirvCode::from_bytes(&bytes[1..]).unwrap();
+
+        assert_eq!(spirv_code.get_words(), words);
+    }
+
+    #[test]
+    fn size_not_multiple_of_4_is_refused() {
+        let mut bytes = to_bytes(
+            &create_module_words(VERSION_1_3, 4, "main"),
+            u32::to_ne_bytes,
+        );
+        bytes.pop();
+
+        assert_eq!(
+            SpirvCode::from_bytes(&bytes).err(),
+            Some(SpirvError::WrongSize(bytes.len()))
+        );
+    }
+
+    #[test]
+    fn instruction_beyond_the_end_is_refused() {
+        let mut words = create_module_words(VERSION_1_3, 4, "main");
+        words.pop();
+
+        assert_eq!(
+            SpirvCode::from_bytes(&to_bytes(&words, u32::to_ne_bytes)).err(),
+            Some(SpirvError::MalformedInstruction {
+                offset: SPIRV_HEADER_WORD_COUNT
+            })
+        );
+    }
+
+    #[test]
+    fn version_above_the_device_is_unsupported() {
+        let words = create_module_words(SpirvVersion { major: 1, minor: 6 }, 4, "main");
+
+        let spirv_code = SpirvCode::from_bytes(&to_bytes(&words, u32::to_ne_bytes)).unwrap();
+
+        assert!(!spirv_code.is_supported_by_vulkan(vk::API_VERSION_1_1));
+        assert!(!spirv_code.is_supported_by_vulkan(vk::API_VERSION_1_2));
+        assert!(spirv_code.is_supported_by_vulkan(vk::API_VERSION_1_3));
+    }
+
+    #[test]
+    fn entry_point_of_another_stage_is_missing() {
+        let words = create_module_words(VERSION_1_3, 0, "main");
+
+        let spirv_code = SpirvCode::from_bytes(&to_bytes(&words, u32::to_ne_bytes)).unwrap();
+
+        assert!(
+            spirv_code
+                .get_all_entry_point_names(vk::ShaderStageFlags::FRAGMENT)
+                .is_empty()
+        );
+        assert!(
+            !spirv_code
+                .get_all_entry_point_names(vk::ShaderStageFlags::VERTEX)
+                .contains(&"other".into())
+        );
+    }
+}
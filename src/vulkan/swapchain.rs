@@ -1,14 +1,173 @@
-use std::{cmp::max, marker::PhantomData};
-
-use ash::vk::{self, CompositeAlphaFlagsKHR, ImageUsageFlags, PresentModeKHR, SwapchainKHR};
+use ash::{
+    prelude::VkResult,
+    vk::{self, CompositeAlphaFlagsKHR, ImageUsageFlags, PresentModeKHR, SwapchainKHR},
+};
 
 use crate::vulkan::{device::VulkanDevice, surface::VulkanSurface};
 
-const SUITABLE_PRESENT_MODES: vk::PresentModeKHR = vk::PresentModeKHR::MAILBOX;
-const SUITABLE_SURFACE_FORMAT: vk::SurfaceFormatKHR = vk::SurfaceFormatKHR {
-    format: vk::Format::B8G8R8A8_SRGB,
-    color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
-};
+///Present modes waiting for the vertical blank, without tearing
+///
+///FIFO_RELAXED isn't included because it tears when a frame is late
+pub const VSYNC_PRESENT_MODES: [PresentModeKHR; 1] = [PresentModeKHR::FIFO];
+
+///Present modes with the lowest latency: uncapped (IMMEDIATE, which can tear), then the latest frame at the vertical blank (MAILBOX)
+pub const UNCAPPED_PRESENT_MODES: [PresentModeKHR; 2] =
+    [PresentModeKHR::IMMEDIATE, PresentModeKHR::MAILBOX];
+
+///Present modes with a low latency and without tearing: the latest frame at the vertical blank (MAILBOX),
+///then the vertical blank unless a frame is late (FIFO_RELAXED)
+pub const LOW_LATENCY_PRESENT_MODES: [PresentModeKHR; 2] =
+    [PresentModeKHR::MAILBOX, PresentModeKHR::FIFO_RELAXED];
+
+///Surface formats in 8 bits sRGB, as expected by the shaders writing linear colors
+pub const SRGB_SURFACE_FORMATS: [vk::SurfaceFormatKHR; 2] = [
+    vk::SurfaceFormatKHR {
+        format: vk::Format::B8G8R8A8_SRGB,
+        color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+    },
+    vk::SurfaceFormatKHR {
+        format: vk::Format::R8G8B8A8_SRGB,
+        color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+    },
+];
+
+///Surface formats in HDR10: 10 bits per channel, encoded with the ST 2084 (PQ) curve in the BT.2020 primaries
+pub const HDR10_SURFACE_FORMATS: [vk::SurfaceFormatKHR; 2] = [
+    vk::SurfaceFormatKHR {
+        format: vk::Format::A2B10G10R10_UNORM_PACK32,
+        color_space: vk::ColorSpaceKHR::HDR10_ST2084_EXT,
+    },
+    vk::SurfaceFormatKHR {
+        format: vk::Format::A2R10G10B10_UNORM_PACK32,
+        color_space: vk::ColorSpaceKHR::HDR10_ST2084_EXT,
+    },
+];
+
+///Surface formats in scRGB: linear 16 bits floats in the sRGB primaries, exceeding 1.0 above the SDR white (80 nits)
+pub const SCRGB_SURFACE_FORMATS: [vk::SurfaceFormatKHR; 1] = [vk::SurfaceFormatKHR {
+    format: vk::Format::R16G16B16A16_SFLOAT,
+    color_space: vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT,
+}];
+
+///Represent the color space in which the shaders must write the colors presented by a VulkanSwapchain
+///
+///Its value is given to the shaders, so they tonemap and encode the colors for the output
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputColorSpace {
+    ///Colors in [0, 1], encoded in sRGB by the swapchain format
+    Srgb = 0,
+
+    ///Colors in nits, converted to BT.2020 and encoded with the ST 2084 (PQ) curve by the shaders
+    Hdr10 = 1,
+
+    ///Linear colors in the sRGB primaries, where 1.0 is 80 nits
+    ScRgb = 2,
+}
+
+impl OutputColorSpace {
+    ///Return the output color space of the images presented in `color_space`
+    ///
+    ///The color spaces not handled by the shaders are considered as sRGB
+    fn from_color_space(color_space: vk::ColorSpaceKHR) -> Self {
+        match color_space {
+            vk::ColorSpaceKHR::HDR10_ST2084_EXT => Self::Hdr10,
+            vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT => Self::ScRgb,
+            _ => Self::Srgb,
+        }
+    }
+}
+
+///Represent how the swapchain images are shared when the graphics and present queues are in different queue families
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PresentQueueSharing {
+    ///The images are used concurrently by both queue families, without ownership transfer
+    #[default]
+    Concurrent,
+
+    ///The images are owned by one queue family at a time, and their ownership is transferred
+    ///from the graphics queue to the present queue before each presentation
+    ///
+    ///It may be faster than the concurrent sharing, but requires a command buffer submitted to the present queue
+    OwnershipTransfer,
+}
+
+///Represent how a VulkanSwapchain presents its images
+#[derive(Clone, Debug)]
+pub struct SwapchainConfig {
+    ///The present modes in order of preference, FIFO is used if none of them is available
+    pub all_preferred_present_modes: Vec<PresentModeKHR>,
+
+    ///The surface formats and color spaces in order of preference,
+    ///the first format of the surface is used if none of them is available
+    pub all_preferred_surface_formats: Vec<vk::SurfaceFormatKHR>,
+
+    ///The desired number of images, clamped to the number of images supported by the surface
+    pub desired_image_count: u32,
+
+    ///The mastering display metadata set on the swapchains presenting in HDR, if VK_EXT_hdr_metadata is enabled on the device
+    pub hdr_metadata: vk::HdrMetadataEXT<'static>,
+
+    ///How the images are shared if the graphics and present queues are in different queue families
+    pub present_queue_sharing: PresentQueueSharing,
+}
+
+impl SwapchainConfig {
+    ///Prefer VSYNC_PRESENT_MODES if `vsync` is true, else UNCAPPED_PRESENT_MODES
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.all_preferred_present_modes = if vsync {
+            VSYNC_PRESENT_MODES.to_vec()
+        } else {
+            UNCAPPED_PRESENT_MODES.to_vec()
+        };
+    }
+
+    ///Prefer HDR10_SURFACE_FORMATS then SCRGB_SURFACE_FORMATS if `hdr` is true, falling back to SRGB_SURFACE_FORMATS
+    ///
+    ///The HDR color spaces are only available if VK_EXT_swapchain_colorspace is enabled on the instance
+    pub fn set_hdr(&mut self, hdr: bool) {
+        self.all_preferred_surface_formats = if hdr {
+            [
+                HDR10_SURFACE_FORMATS.as_slice(),
+                &SCRGB_SURFACE_FORMATS,
+                &SRGB_SURFACE_FORMATS,
+            ]
+            .concat()
+        } else {
+            SRGB_SURFACE_FORMATS.to_vec()
+        };
+    }
+}
+
+impl Default for SwapchainConfig {
+    ///LOW_LATENCY_PRESENT_MODES in SRGB_SURFACE_FORMATS, with 3 images so the host never waits for an image with MAILBOX
+    fn default() -> Self {
+        Self {
+            all_preferred_present_modes: LOW_LATENCY_PRESENT_MODES.to_vec(),
+            all_preferred_surface_formats: SRGB_SURFACE_FORMATS.to_vec(),
+            desired_image_count: 3,
+            hdr_metadata: get_default_hdr_metadata(),
+            present_queue_sharing: PresentQueueSharing::default(),
+        }
+    }
+}
+
+///Return the HDR metadata of a mastering display in the BT.2020 primaries with a D65 white point,
+///from 0.001 to 1000 nits, with a maximum content light level of 1000 nits and frame average light level of 400 nits
+fn get_default_hdr_metadata() -> vk::HdrMetadataEXT<'static> {
+    vk::HdrMetadataEXT::default()
+        .display_primary_red(vk::XYColorEXT { x: 0.708, y: 0.292 })
+        .display_primary_green(vk::XYColorEXT { x: 0.170, y: 0.797 })
+        .display_primary_blue(vk::XYColorEXT { x: 0.131, y: 0.046 })
+        .white_point(vk::XYColorEXT {
+            x: 0.3127,
+            y: 0.3290,
+        })
+        .max_luminance(1000f32)
+        .min_luminance(0.001)
+        .max_content_light_level(1000f32)
+        .max_frame_average_light_level(400f32)
+}
 
 ///Represent a Vulkan Swapchain
 pub struct VulkanSwapchain<'vulkan_instance, 'vulkan_device, 'vulkan_surface> {
@@ -20,16 +179,93 @@ pub struct VulkanSwapchain<'vulkan_instance, 'vulkan_device, 'vulkan_surface> {
     ///For example, can be used to destroy this swapchain
     vulkan_device: &'vulkan_device VulkanDevice,
 
-    ///PhantomData used to keep the fact that a Swapchain cannot be used without its surface
-    phantom_data_surface: PhantomData<&'vulkan_surface VulkanSurface<'vulkan_instance>>,
+    ///A reference to the Vulkan surface presenting the images of this swapchain
+    ///
+    ///For example, can be used to recreate this swapchain
+    vulkan_surface: &'vulkan_surface VulkanSurface<'vulkan_instance>,
+
+    ///The configuration used to create `swapchain`
+    swapchain_config: SwapchainConfig,
+
+    ///The present mode of `swapchain`, selected from `swapchain_config`
+    present_mode: PresentModeKHR,
+
+    ///The color space in which the shaders must write the colors of the images of `swapchain`
+    output_color_space: OutputColorSpace,
+
+    ///If the ownership of the images of `swapchain` must be transferred from the graphics queue to the present queue before presenting them
+    is_ownership_transfer_required: bool,
+
+    ///The format of the images of `swapchain`
+    image_format: vk::Format,
+
+    ///The size of the images of `swapchain`
+    image_extent: vk::Extent2D,
+
+    ///How the images of `swapchain` can be used
+    ///
+    ///For example, they can only be captured if it contains TRANSFER_SRC
+    image_usage: ImageUsageFlags,
+
+    ///All images of `swapchain`, owned by the swapchain
+    all_images: Box<[vk::Image]>,
+
+    ///A color image view of each image of `all_images`
+    all_image_views: Box<[vk::ImageView]>,
 }
 
 impl<'vulkan_instance, 'vulkan_device, 'vulkan_surface>
     VulkanSwapchain<'vulkan_instance, 'vulkan_device, 'vulkan_surface>
 {
+    ///Create a swapchain presenting to `vulkan_surface` as described by `swapchain_config`
+    ///
+    ///`framebuffer_extent` is the size (in pixels) of the framebuffer of the window,
+    ///used as size of the images if the surface lets the application decide
     pub fn new_from_device_and_surface(
         vulkan_device: &'vulkan_device VulkanDevice,
-        vulkan_surface: &'vulkan_surface VulkanSurface,
+        vulkan_surface: &'vulkan_surface VulkanSurface<'vulkan_instance>,
+        swapchain_config: SwapchainConfig,
+        framebuffer_extent: vk::Extent2D,
+    ) -> Self {
+        Self::create(
+            vulkan_device,
+            vulkan_surface,
+            swapchain_config,
+            framebuffer_extent,
+            SwapchainKHR::null(),
+        )
+    }
+
+    ///Recreate the swapchain of `&self` as described by `swapchain_config`, once the device is idle
+    ///
+    ///The images and image views of `&self` are replaced, so everything created from them (for example the framebuffers) must be recreated
+    ///
+    ///`framebuffer_extent` is the size (in pixels) of the framebuffer of the window,
+    ///used as size of the images if the surface lets the application decide
+    pub fn recreate(
+        &mut self,
+        swapchain_config: SwapchainConfig,
+        framebuffer_extent: vk::Extent2D,
+    ) {
+        self.vulkan_device.wait_idle();
+
+        //The old swapchain is retired by the new one, then destroyed when it's dropped
+        *self = Self::create(
+            self.vulkan_device,
+            self.vulkan_surface,
+            swapchain_config,
+            framebuffer_extent,
+            self.swapchain,
+        );
+    }
+
+    ///Create a swapchain presenting to `vulkan_surface` as described by `swapchain_config`, replacing `old_swapchain` if it isn't null
+    fn create(
+        vulkan_device: &'vulkan_device VulkanDevice,
+        vulkan_surface: &'vulkan_surface VulkanSurface<'vulkan_instance>,
+        swapchain_config: SwapchainConfig,
+        framebuffer_extent: vk::Extent2D,
+        old_swapchain: SwapchainKHR,
     ) -> Self {
         let available_surface_and_device_capabilities =
             vulkan_device.get_available_capabilities_for_surface(vulkan_surface);
@@ -41,47 +277,231 @@ impl<'vulkan_instance, 'vulkan_device, 'vulkan_surface>
             vulkan_device.get_available_present_modes_for_surface(vulkan_surface);
 
         let mut swapchain_create_info = vk::SwapchainCreateInfoKHR::default()
-            .min_image_count(max(
-                available_surface_and_device_capabilities.min_image_count,
-                2,
+            .min_image_count(get_image_count(
+                &available_surface_and_device_capabilities,
+                swapchain_config.desired_image_count,
+            ))
+            .image_extent(get_image_extent(
+                &available_surface_and_device_capabilities,
+                framebuffer_extent,
             ))
-            .image_extent(available_surface_and_device_capabilities.current_extent)
             .image_array_layers(1)
-            .image_usage(ImageUsageFlags::COLOR_ATTACHMENT)
+            .image_usage(get_image_usage(&available_surface_and_device_capabilities))
             .pre_transform(available_surface_and_device_capabilities.current_transform)
             .composite_alpha(CompositeAlphaFlagsKHR::OPAQUE)
-            .clipped(true);
+            .clipped(true)
+            .old_swapchain(old_swapchain);
 
-        swapchain_create_info.present_mode =
-            if available_surface_and_device_present_modes.contains(&SUITABLE_PRESENT_MODES) {
-                SUITABLE_PRESENT_MODES
-            } else {
-                PresentModeKHR::FIFO //guaranteed to be available
-            };
+        swapchain_create_info.present_mode = swapchain_config
+            .all_preferred_present_modes
+            .iter()
+            .find(|present_mode| available_surface_and_device_present_modes.contains(present_mode))
+            .copied()
+            .unwrap_or(PresentModeKHR::FIFO); //guaranteed to be available
 
-        let selected_format =
-            if available_surface_and_device_formats.contains(&SUITABLE_SURFACE_FORMAT) {
-                SUITABLE_SURFACE_FORMAT
-            } else {
-                available_surface_and_device_formats[0] //guaranteed to be available
-            };
+        let selected_format = swapchain_config
+            .all_preferred_surface_formats
+            .iter()
+            .find(|surface_format| available_surface_and_device_formats.contains(surface_format))
+            .copied()
+            .unwrap_or(available_surface_and_device_formats[0]); //guaranteed to be available
         swapchain_create_info.image_format = selected_format.format;
         swapchain_create_info.image_color_space = selected_format.color_space;
 
         vulkan_surface.set_surface_in_swapchain_create_info_khr(&mut swapchain_create_info);
 
-        let swapchain = vulkan_device.create_swapchain(&mut swapchain_create_info);
+        let is_ownership_transfer_required = vulkan_device.has_separate_present_queue()
+            && swapchain_config.present_queue_sharing == PresentQueueSharing::OwnershipTransfer;
+
+        let swapchain = vulkan_device.create_swapchain(
+            &mut swapchain_create_info,
+            if is_ownership_transfer_required {
+                vk::SharingMode::EXCLUSIVE
+            } else {
+                vk::SharingMode::CONCURRENT
+            },
+        );
+
+        let output_color_space = OutputColorSpace::from_color_space(selected_format.color_space);
+        if output_color_space != OutputColorSpace::Srgb && vulkan_device.is_hdr_metadata_enabled() {
+            vulkan_device.set_hdr_metadata(swapchain, &swapchain_config.hdr_metadata);
+        }
+
+        let all_images = vulkan_device
+            .get_swapchain_images(swapchain)
+            .into_boxed_slice();
+        let all_image_views = all_images
+            .iter()
+            .map(|image| {
+                create_color_image_view(vulkan_device, *image, swapchain_create_info.image_format)
+            })
+            .collect();
 
         Self {
             swapchain,
             vulkan_device,
-            phantom_data_surface: PhantomData,
+            vulkan_surface,
+            swapchain_config,
+            present_mode: swapchain_create_info.present_mode,
+            output_color_space,
+            is_ownership_transfer_required,
+            image_format: swapchain_create_info.image_format,
+            image_extent: swapchain_create_info.image_extent,
+            image_usage: swapchain_create_info.image_usage,
+            all_images,
+            all_image_views,
         }
     }
+
+    ///Return the configuration used to create `&self`
+    pub fn get_config(&self) -> &SwapchainConfig {
+        &self.swapchain_config
+    }
+
+    ///Return the present mode selected from the configuration of `&self`
+    pub fn get_present_mode(&self) -> PresentModeKHR {
+        self.present_mode
+    }
+
+    ///Return the color space in which the shaders must write the colors of the images of `&self`
+    pub fn get_output_color_space(&self) -> OutputColorSpace {
+        self.output_color_space
+    }
+
+    ///Return if the ownership of the images of `&self` must be transferred from the graphics queue to the present queue before presenting them
+    ///
+    ///If not, the images can be presented by the present queue without ownership transfer
+    pub fn is_ownership_transfer_required(&self) -> bool {
+        self.is_ownership_transfer_required
+    }
+
+    pub fn get_image_format(&self) -> vk::Format {
+        self.image_format
+    }
+
+    pub fn get_image_extent(&self) -> vk::Extent2D {
+        self.image_extent
+    }
+
+    pub fn get_image_usage(&self) -> ImageUsageFlags {
+        self.image_usage
+    }
+
+    pub fn get_all_images(&self) -> &[vk::Image] {
+        &self.all_images
+    }
+
+    pub fn get_all_image_views(&self) -> &[vk::ImageView] {
+        &self.all_image_views
+    }
+
+    ///Acquire the next available image of `&self`, `semaphore` is signaled once it can be used
+    ///
+    /// # Returns
+    ///
+    /// The index of the image and true if `&self` is suboptimal for the surface
+    pub fn acquire_next_image(&self, semaphore: vk::Semaphore) -> VkResult<(u32, bool)> {
+        self.vulkan_device
+            .acquire_next_swapchain_image(self.swapchain, semaphore)
+    }
+
+    ///Present the image `image_index` of `&self` once `wait_semaphore` is signaled
+    ///
+    /// # Returns
+    ///
+    /// True if `&self` is suboptimal for the surface
+    pub fn present(&self, image_index: u32, wait_semaphore: vk::Semaphore) -> VkResult<bool> {
+        let swapchains = [self.swapchain];
+        let image_indices = [image_index];
+        let wait_semaphores = [wait_semaphore];
+        let present_info = vk::PresentInfoKHR::default()
+            .wait_semaphores(&wait_semaphores)
+            .swapchains(&swapchains)
+            .image_indices(&image_indices);
+
+        self.vulkan_device.present_to_present_queue(&present_info)
+    }
 }
 
 impl Drop for VulkanSwapchain<'_, '_, '_> {
     fn drop(&mut self) {
+        for image_view in self.all_image_views.iter().cloned() {
+            self.vulkan_device.destroy_image_view(image_view);
+        }
         self.vulkan_device.destroy_swapchain(self.swapchain);
     }
 }
+
+///Return `desired_image_count` clamped to the number of images supported by the surface
+fn get_image_count(
+    surface_capabilities: &vk::SurfaceCapabilitiesKHR,
+    desired_image_count: u32,
+) -> u32 {
+    let image_count = desired_image_count.max(surface_capabilities.min_image_count);
+
+    //A max_image_count of 0 means that the number of images is unlimited
+    if surface_capabilities.max_image_count > 0 {
+        image_count.min(surface_capabilities.max_image_count)
+    } else {
+        image_count
+    }
+}
+
+///Return the size of the swapchain images: the current size of the surface,
+///or `framebuffer_extent` clamped to the supported sizes if the surface lets the application decide
+fn get_image_extent(
+    surface_capabilities: &vk::SurfaceCapabilitiesKHR,
+    framebuffer_extent: vk::Extent2D,
+) -> vk::Extent2D {
+    //A current_extent of 0xFFFFFFFF means that the size is decided by the swapchain, for example on Wayland
+    if surface_capabilities.current_extent.width != u32::MAX {
+        return surface_capabilities.current_extent;
+    }
+
+    vk::Extent2D {
+        width: framebuffer_extent.width.clamp(
+            surface_capabilities.min_image_extent.width,
+            surface_capabilities.max_image_extent.width,
+        ),
+        height: framebuffer_extent.height.clamp(
+            surface_capabilities.min_image_extent.height,
+            surface_capabilities.max_image_extent.height,
+        ),
+    }
+}
+
+///Return the usage of the swapchain images: rendered as color attachment, and captured if the surface supports it
+fn get_image_usage(surface_capabilities: &vk::SurfaceCapabilitiesKHR) -> ImageUsageFlags {
+    if surface_capabilities
+        .supported_usage_flags
+        .contains(ImageUsageFlags::TRANSFER_SRC)
+    {
+        ImageUsageFlags::COLOR_ATTACHMENT | ImageUsageFlags::TRANSFER_SRC
+    } else {
+        ImageUsageFlags::COLOR_ATTACHMENT
+    }
+}
+
+///Create a 2D image view on the color of `image`
+fn create_color_image_view(
+    vulkan_device: &VulkanDevice,
+    image: vk::Image,
+    format: vk::Format,
+) -> vk::ImageView {
+    let image_view_create_info = vk::ImageViewCreateInfo::default()
+        .image(image)
+        .view_type(vk::ImageViewType::TYPE_2D)
+        .format(format)
+        .subresource_range(
+            vk::ImageSubresourceRange::default()
+                .aspect_mask(vk::ImageAspectFlags::COLOR)
+                .base_mip_level(0)
+                .level_count(1)
+                .base_array_layer(0)
+                .layer_count(1),
+        );
+
+    vulkan_device
+        .create_image_view(&image_view_create_info)
+        .expect("failed to create Vulkan swapchain image view")
+}
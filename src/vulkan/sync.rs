@@ -0,0 +1,88 @@
+use ash::vk;
+
+use crate::vulkan::device::VulkanDevice;
+
+///Represent a Vulkan semaphore, used to order the work submitted to the queues
+pub struct VulkanSemaphore<'vulkan_device> {
+    ///A reference to the Vulkan Device which created this semaphore
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan semaphore
+    semaphore: vk::Semaphore,
+}
+
+impl<'vulkan_device> VulkanSemaphore<'vulkan_device> {
+    pub fn new(vulkan_device: &'vulkan_device VulkanDevice) -> Self {
+        let semaphore_create_info = vk::SemaphoreCreateInfo::default();
+        let semaphore = vulkan_device
+            .create_semaphore(&semaphore_create_info)
+            .expect("failed to create Vulkan semaphore");
+
+        Self {
+            vulkan_device,
+            semaphore,
+        }
+    }
+
+    pub fn get_semaphore(&self) -> vk::Semaphore {
+        self.semaphore
+    }
+}
+
+impl Drop for VulkanSemaphore<'_> {
+    fn drop(&mut self) {
+        self.vulkan_device.destroy_semaphore(self.semaphore);
+    }
+}
+
+///Represent a Vulkan fence, used to wait on the host for the work submitted to a queue
+pub struct VulkanFence<'vulkan_device> {
+    ///A reference to the Vulkan Device which created this fence
+    vulkan_device: &'vulkan_device VulkanDevice,
+
+    ///The Vulkan fence
+    fence: vk::Fence,
+}
+
+impl<'vulkan_device> VulkanFence<'vulkan_device> {
+    ///Create a fence, already signaled if `signaled` is true
+    pub fn new(vulkan_device: &'vulkan_device VulkanDevice, signaled: bool) -> Self {
+        let fence_create_info = vk::FenceCreateInfo::default().flags(if signaled {
+            vk::FenceCreateFlags::SIGNALED
+        } else {
+            vk::FenceCreateFlags::empty()
+        });
+        let fence = vulkan_device
+            .create_fence(&fence_create_info)
+            .expect("failed to create Vulkan fence");
+
+        Self {
+            vulkan_device,
+            fence,
+        }
+    }
+
+    pub fn get_fence(&self) -> vk::Fence {
+        self.fence
+    }
+
+    ///Block until `&self` is signaled
+    pub fn wait(&self) {
+        self.vulkan_device
+            .wait_for_all_fences(&[self.fence], u64::MAX)
+            .expect("failed to wait for Vulkan fence");
+    }
+
+    ///Set `&self` to the unsignaled state
+    pub fn reset(&self) {
+        self.vulkan_device
+            .reset_fences(&[self.fence])
+            .expect("failed to reset Vulkan fence");
+    }
+}
+
+impl Drop for VulkanFence<'_> {
+    fn drop(&mut self) {
+        self.vulkan_device.destroy_fence(self.fence);
+    }
+}
@@ -0,0 +1,495 @@
+use std::{
+    fmt::{self, Display},
+    fs, io,
+};
+
+use ash::vk;
+
+use crate::vulkan::{
+    buffer::VulkanBuffer, command_buffer::VulkanCommandPool, device::VulkanDevice,
+    image::VulkanImage,
+};
+
+///The identifier at the start of a KTX2 file
+const KTX2_IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+///Alignment (in bytes) of each mip level in the staging buffer
+///
+///vkCmdCopyBufferToImage requires a buffer offset multiple of the texel block size of the format
+const MIP_LEVEL_STAGING_ALIGNMENT: usize = 16;
+
+///Error returned when a texture cannot be loaded in an image
+#[derive(Debug)]
+pub enum TextureError {
+    ///The texture file cannot be read
+    Io(io::Error),
+
+    ///The PNG or JPEG texture cannot be decoded
+    Image(image::ImageError),
+
+    ///The KTX2 texture cannot be parsed
+    Ktx2(ktx2::ParseError),
+
+    ///The KTX2 texture doesn't declare a Vulkan format, for example because it's Basis Universal encoded
+    Ktx2UndefinedFormat,
+
+    ///The KTX2 texture is supercompressed, which isn't supported
+    Ktx2Supercompressed(ktx2::SupercompressionScheme),
+
+    ///The KTX2 texture is a 3D texture, which isn't supported
+    Ktx2ThreeDimensional,
+
+    ///The format of the texture cannot be sampled on the device
+    UnsupportedFormat(vk::Format),
+}
+
+impl Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextureError::Io(error) => write!(f, "failed to read texture file: {error}"),
+            TextureError::Image(error) => write!(f, "failed to decode texture: {error}"),
+            TextureError::Ktx2(error) => write!(f, "failed to parse KTX2 texture: {error}"),
+            TextureError::Ktx2UndefinedFormat => {
+                write!(f, "KTX2 texture without Vulkan format isn't supported")
+            }
+            TextureError::Ktx2Supercompressed(supercompression_scheme) => write!(
+                f,
+                "KTX2 texture supercompressed with {supercompression_scheme:?} isn't supported"
+            ),
+            TextureError::Ktx2ThreeDimensional => {
+                write!(f, "KTX2 3D texture isn't supported")
+            }
+            TextureError::UnsupportedFormat(format) => {
+                write!(
+                    f,
+                    "texture format {format:?} cannot be sampled on the device"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for TextureError {}
+
+impl From<io::Error> for TextureError {
+    fn from(error: io::Error) -> Self {
+        TextureError::Io(error)
+    }
+}
+
+impl From<image::ImageError> for TextureError {
+    fn from(error: image::ImageError) -> Self {
+        TextureError::Image(error)
+    }
+}
+
+impl From<ktx2::ParseError> for TextureError {
+    fn from(error: ktx2::ParseError) -> Self {
+        TextureError::Ktx2(error)
+    }
+}
+
+///Represent how the color of a PNG or JPEG texture is encoded
+///
+///For example, a color texture is in sRGB and a normal map is linear
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextureColorSpace {
+    Srgb,
+    Linear,
+}
+
+///Represent a texture decoded from a file, ready to be uploaded in an image
+struct DecodedTexture {
+    format: vk::Format,
+
+    ///The size of the mip level 0
+    extent: vk::Extent2D,
+
+    array_layers: u32,
+    is_cube: bool,
+
+    ///The bytes of each mip level, containing all array layers
+    all_mip_level_bytes: Vec<Vec<u8>>,
+
+    ///If the mip levels after the level 0 must be generated from it
+    generate_mip_levels: bool,
+}
+
+///Load the PNG, JPEG or KTX2 texture of `file_path` in a new image, sampled by the shaders
+///
+/// # Arguments
+///
+/// * `vulkan_device` - a reference to a VulkanDevice
+/// * `command_pool` - the command pool allocating the command buffer uploading the texture
+/// * `file_path` - the path of the texture file
+/// * `color_space` - the color space of a PNG or JPEG texture, the format of a KTX2 texture is declared by the file
+pub fn load_texture_from_file<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    command_pool: &VulkanCommandPool,
+    file_path: &str,
+    color_space: TextureColorSpace,
+) -> Result<VulkanImage<'vulkan_device>, TextureError> {
+    let bytes = fs::read(file_path)?;
+
+    load_texture_from_bytes(vulkan_device, command_pool, &bytes, color_space)
+}
+
+///Load the PNG, JPEG or KTX2 texture `bytes` in a new image, sampled by the shaders
+///
+///The mip levels are loaded from a KTX2 texture which contains them, else generated on the device if the format can be filtered linearly
+///
+/// # Arguments
+///
+/// * `vulkan_device` - a reference to a VulkanDevice
+/// * `command_pool` - the command pool allocating the command buffer uploading the texture
+/// * `bytes` - the content of a texture file
+/// * `color_space` - the color space of a PNG or JPEG texture, the format of a KTX2 texture is declared by the file
+pub fn load_texture_from_bytes<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    command_pool: &VulkanCommandPool,
+    bytes: &[u8],
+    color_space: TextureColorSpace,
+) -> Result<VulkanImage<'vulkan_device>, TextureError> {
+    let decoded_texture = if bytes.starts_with(&KTX2_IDENTIFIER) {
+        decode_ktx2_texture(bytes)?
+    } else {
+        decode_image_texture(bytes, color_space)?
+    };
+
+    upload_texture(vulkan_device, command_pool, &decoded_texture)
+}
+
+///Create a 1x1 texture of the RGBA8 color `rgba`, sampled by the shaders
+///
+///For example, can replace the missing textures of a material by a neutral color
+pub fn create_solid_color_texture<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    command_pool: &VulkanCommandPool,
+    rgba: [u8; 4],
+    color_space: TextureColorSpace,
+) -> VulkanImage<'vulkan_device> {
+    let decoded_texture = DecodedTexture {
+        format: match color_space {
+            TextureColorSpace::Srgb => vk::Format::R8G8B8A8_SRGB,
+            TextureColorSpace::Linear => vk::Format::R8G8B8A8_UNORM,
+        },
+        extent: vk::Extent2D {
+            width: 1,
+            height: 1,
+        },
+        array_layers: 1,
+        is_cube: false,
+        all_mip_level_bytes: vec![rgba.to_vec()],
+        generate_mip_levels: false,
+    };
+
+    upload_texture(vulkan_device, command_pool, &decoded_texture)
+        .expect("failed to create solid color texture")
+}
+
+///Create a texture of the linear RGBA colors `all_texels`, in rows of `extent.width` texels, sampled by the shaders
+///
+///The texture is in R32G32B32A32_SFLOAT without mip levels, whose linear filtering isn't supported by all devices.
+///For example, can hold a high dynamic range environment, read by the shaders with `texelFetch`
+///
+/// # Panics
+///
+/// If `all_texels` hasn't `extent.width * extent.height` texels
+pub fn create_float_texture<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    command_pool: &VulkanCommandPool,
+    extent: vk::Extent2D,
+    all_texels: &[[f32; 4]],
+) -> Result<VulkanImage<'vulkan_device>, TextureError> {
+    assert_eq!(
+        all_texels.len(),
+        (extent.width * extent.height) as usize,
+        "the texels don't fill the float texture of {extent:?}"
+    );
+
+    let decoded_texture = DecodedTexture {
+        format: vk::Format::R32G32B32A32_SFLOAT,
+        extent,
+        array_layers: 1,
+        is_cube: false,
+        all_mip_level_bytes: vec![bytemuck::cast_slice(all_texels).to_vec()],
+        generate_mip_levels: false,
+    };
+
+    upload_texture(vulkan_device, command_pool, &decoded_texture)
+}
+
+///Decode a PNG or JPEG texture to RGBA8
+fn decode_image_texture(
+    bytes: &[u8],
+    color_space: TextureColorSpace,
+) -> Result<DecodedTexture, TextureError> {
+    let rgba_image = image::load_from_memory(bytes)?.into_rgba8();
+
+    Ok(DecodedTexture {
+        format: match color_space {
+            TextureColorSpace::Srgb => vk::Format::R8G8B8A8_SRGB,
+            TextureColorSpace::Linear => vk::Format::R8G8B8A8_UNORM,
+        },
+        extent: vk::Extent2D {
+            width: rgba_image.width(),
+            height: rgba_image.height(),
+        },
+        array_layers: 1,
+        is_cube: false,
+        all_mip_level_bytes: vec![rgba_image.into_raw()],
+        generate_mip_levels: true,
+    })
+}
+
+///Decode a KTX2 texture, whose data is already in a Vulkan format
+fn decode_ktx2_texture(bytes: &[u8]) -> Result<DecodedTexture, TextureError> {
+    let reader = ktx2::Reader::new(bytes)?;
+    let header = reader.header();
+
+    let format = header.format.ok_or(TextureError::Ktx2UndefinedFormat)?;
+    if let Some(supercompression_scheme) = header.supercompression_scheme {
+        return Err(TextureError::Ktx2Supercompressed(supercompression_scheme));
+    }
+    if header.pixel_depth > 1 {
+        return Err(TextureError::Ktx2ThreeDimensional);
+    }
+
+    //A level count of 0 requests the generation of the mip levels
+    let generate_mip_levels = header.level_count == 0;
+    let all_mip_level_bytes = reader.levels().map(|level| level.data.to_vec()).collect();
+
+    Ok(DecodedTexture {
+        format: vk::Format::from_raw(format.value() as i32),
+        extent: vk::Extent2D {
+            width: header.pixel_width,
+            height: header.pixel_height.max(1), //a 1D texture has a height of 0
+        },
+        array_layers: header.layer_count.max(1) * header.face_count,
+        is_cube: header.face_count == 6,
+        all_mip_level_bytes,
+        generate_mip_levels,
+    })
+}
+
+///Upload `decoded_texture` in a new image through a staging buffer, and generate its mip levels if requested
+///
+///Once uploaded, the image is in the SHADER_READ_ONLY_OPTIMAL layout
+fn upload_texture<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    command_pool: &VulkanCommandPool,
+    decoded_texture: &DecodedTexture,
+) -> Result<VulkanImage<'vulkan_device>, TextureError> {
+    let format_features = vulkan_device
+        .get_format_properties(decoded_texture.format)
+        .optimal_tiling_features;
+    if !format_features
+        .contains(vk::FormatFeatureFlags::SAMPLED_IMAGE | vk::FormatFeatureFlags::TRANSFER_DST)
+    {
+        return Err(TextureError::UnsupportedFormat(decoded_texture.format));
+    }
+
+    //The mip levels are generated by linear blits, they are not generated if the format doesn't support them
+    let can_blit_linearly = format_features.contains(
+        vk::FormatFeatureFlags::BLIT_SRC
+            | vk::FormatFeatureFlags::BLIT_DST
+            | vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR,
+    );
+    let mip_levels = match (decoded_texture.generate_mip_levels, can_blit_linearly) {
+        (true, true) => get_full_mip_levels(decoded_texture.extent),
+        (true, false) => 1,
+        (false, _) => decoded_texture.all_mip_level_bytes.len() as u32,
+    };
+    let generate_mip_levels = decoded_texture.generate_mip_levels && mip_levels > 1;
+
+    let mut staging_bytes = Vec::new();
+    let mut all_copy_regions = Vec::new();
+    for (mip_level, mip_level_bytes) in decoded_texture
+        .all_mip_level_bytes
+        .iter()
+        .take(if generate_mip_levels {
+            1
+        } else {
+            mip_levels as usize
+        })
+        .enumerate()
+    {
+        staging_bytes.resize(
+            staging_bytes
+                .len()
+                .next_multiple_of(MIP_LEVEL_STAGING_ALIGNMENT),
+            0,
+        );
+
+        all_copy_regions.push(
+            vk::BufferImageCopy::default()
+                .buffer_offset(staging_bytes.len() as vk::DeviceSize)
+                .image_subresource(get_color_subresource_layers(
+                    mip_level as u32,
+                    decoded_texture.array_layers,
+                ))
+                .image_extent(
+                    get_mip_level_extent(decoded_texture.extent, mip_level as u32).into(),
+                ),
+        );
+
+        staging_bytes.extend_from_slice(mip_level_bytes);
+    }
+    let staging_buffer = VulkanBuffer::new_staging_with_bytes(vulkan_device, &staging_bytes);
+
+    let mut usage = vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED;
+    if generate_mip_levels {
+        usage |= vk::ImageUsageFlags::TRANSFER_SRC;
+    }
+    let vulkan_image = VulkanImage::new_2d(
+        vulkan_device,
+        decoded_texture.extent,
+        decoded_texture.format,
+        mip_levels,
+        decoded_texture.array_layers,
+        decoded_texture.is_cube,
+        usage,
+    );
+
+    let image = vulkan_image.get_image();
+    let get_mip_levels_range = |base_mip_level: u32, level_count: u32| {
+        vk::ImageSubresourceRange::default()
+            .aspect_mask(vk::ImageAspectFlags::COLOR)
+            .base_mip_level(base_mip_level)
+            .level_count(level_count)
+            .base_array_layer(0)
+            .layer_count(decoded_texture.array_layers)
+    };
+
+    command_pool.execute_one_time_commands(|command_buffer| {
+        command_buffer.transition_image_layout(
+            image,
+            get_mip_levels_range(0, mip_levels),
+            vk::ImageLayout::UNDEFINED,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            (
+                vk::PipelineStageFlags::TOP_OF_PIPE,
+                vk::AccessFlags::empty(),
+            ),
+            (
+                vk::PipelineStageFlags::TRANSFER,
+                vk::AccessFlags::TRANSFER_WRITE,
+            ),
+        );
+
+        command_buffer.copy_buffer_to_image(staging_buffer.get_buffer(), image, &all_copy_regions);
+
+        //Each mip level is blitted from the previous one, which is then ready to be sampled
+        let first_unsampled_mip_level = if generate_mip_levels {
+            for mip_level in 1..mip_levels {
+                command_buffer.transition_image_layout(
+                    image,
+                    get_mip_levels_range(mip_level - 1, 1),
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    (
+                        vk::PipelineStageFlags::TRANSFER,
+                        vk::AccessFlags::TRANSFER_WRITE,
+                    ),
+                    (
+                        vk::PipelineStageFlags::TRANSFER,
+                        vk::AccessFlags::TRANSFER_READ,
+                    ),
+                );
+
+                command_buffer.blit_image(
+                    image,
+                    image,
+                    get_mip_level_blit(decoded_texture, mip_level),
+                    vk::Filter::LINEAR,
+                );
+
+                command_buffer.transition_image_layout(
+                    image,
+                    get_mip_levels_range(mip_level - 1, 1),
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                    (
+                        vk::PipelineStageFlags::TRANSFER,
+                        vk::AccessFlags::TRANSFER_READ,
+                    ),
+                    (
+                        vk::PipelineStageFlags::FRAGMENT_SHADER,
+                        vk::AccessFlags::SHADER_READ,
+                    ),
+                );
+            }
+
+            mip_levels - 1
+        } else {
+            0
+        };
+
+        command_buffer.transition_image_layout(
+            image,
+            get_mip_levels_range(
+                first_unsampled_mip_level,
+                mip_levels - first_unsampled_mip_level,
+            ),
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            (
+                vk::PipelineStageFlags::TRANSFER,
+                vk::AccessFlags::TRANSFER_WRITE,
+            ),
+            (
+                vk::PipelineStageFlags::FRAGMENT_SHADER,
+                vk::AccessFlags::SHADER_READ,
+            ),
+        );
+    });
+
+    Ok(vulkan_image)
+}
+
+///Return the number of mip levels of a full mip chain, from `extent` to 1x1
+fn get_full_mip_levels(extent: vk::Extent2D) -> u32 {
+    extent.width.max(extent.height).max(1).ilog2() + 1
+}
+
+///Return the size of `mip_level` of an image whose mip level 0 is `extent`
+fn get_mip_level_extent(extent: vk::Extent2D, mip_level: u32) -> vk::Extent2D {
+    vk::Extent2D {
+        width: (extent.width >> mip_level).max(1),
+        height: (extent.height >> mip_level).max(1),
+    }
+}
+
+fn get_color_subresource_layers(mip_level: u32, array_layers: u32) -> vk::ImageSubresourceLayers {
+    vk::ImageSubresourceLayers::default()
+        .aspect_mask(vk::ImageAspectFlags::COLOR)
+        .mip_level(mip_level)
+        .base_array_layer(0)
+        .layer_count(array_layers)
+}
+
+///Return the blit scaling the whole `mip_level - 1` of `decoded_texture` to the whole `mip_level`
+fn get_mip_level_blit(decoded_texture: &DecodedTexture, mip_level: u32) -> vk::ImageBlit {
+    let get_max_offset = |mip_level: u32| {
+        let mip_level_extent = get_mip_level_extent(decoded_texture.extent, mip_level);
+        vk::Offset3D {
+            x: mip_level_extent.width as i32,
+            y: mip_level_extent.height as i32,
+            z: 1,
+        }
+    };
+
+    vk::ImageBlit::default()
+        .src_subresource(get_color_subresource_layers(
+            mip_level - 1,
+            decoded_texture.array_layers,
+        ))
+        .src_offsets([vk::Offset3D::default(), get_max_offset(mip_level - 1)])
+        .dst_subresource(get_color_subresource_layers(
+            mip_level,
+            decoded_texture.array_layers,
+        ))
+        .dst_offsets([vk::Offset3D::default(), get_max_offset(mip_level)])
+}
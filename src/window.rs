@@ -0,0 +1,299 @@
+use std::cmp::Reverse;
+
+use glfw::{Glfw, GlfwReceiver, Monitor, PWindow, VidMode, Window, WindowEvent, WindowHint};
+
+///How the window is displayed
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+
+    ///Fullscreen in the current video mode of the monitor, without changing it
+    BorderlessFullscreen,
+
+    ///Fullscreen in the video mode of the monitor closest to the size of the window
+    ExclusiveFullscreen,
+}
+
+///Monitor on which the window is fullscreen
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub enum MonitorSelection {
+    #[default]
+    Primary,
+
+    ///The monitor at this index in the connected monitors, the primary monitor being the first one
+    Index(usize),
+
+    ///The monitor with this human-readable name
+    Name(String),
+}
+
+impl MonitorSelection {
+    ///Return the selected monitor of `all_monitors`
+    ///
+    /// # Returns
+    ///
+    /// The primary monitor if the selected one isn't connected, the reason is printed
+    ///
+    /// None if no monitor is connected
+    fn select<'monitor>(
+        &self,
+        all_monitors: &'monitor [&'monitor mut Monitor],
+    ) -> Option<&'monitor Monitor> {
+        let monitor_opt = match self {
+            MonitorSelection::Primary => all_monitors.first(),
+            MonitorSelection::Index(index) => all_monitors.get(*index),
+            MonitorSelection::Name(name) => all_monitors
+                .iter()
+                .find(|monitor| monitor.get_name().as_deref() == Some(name.as_str())),
+        };
+
+        if monitor_opt.is_none() && *self != MonitorSelection::Primary {
+            eprintln!("monitor {self:?} isn't connected, the primary monitor is used");
+        }
+
+        monitor_opt
+            .or_else(|| all_monitors.first())
+            .map(|monitor| &**monitor)
+    }
+}
+
+///Describe the window created by the application
+#[derive(Clone, Debug)]
+pub struct WindowConfig {
+    pub title: String,
+
+    ///The width (in screen coordinates) of the windowed window, and the desired resolution in exclusive fullscreen
+    pub width: u32,
+
+    ///The height (in screen coordinates) of the windowed window, and the desired resolution in exclusive fullscreen
+    pub height: u32,
+
+    pub display_mode: DisplayMode,
+
+    ///The monitor on which the window is fullscreen
+    pub monitor: MonitorSelection,
+
+    ///If the windowed window can be resized by the user
+    pub is_resizable: bool,
+
+    ///If the windowed window has a border and a title bar
+    pub is_decorated: bool,
+
+    ///If the swapchain presents with the vertical synchronization
+    pub is_vsync_enabled: bool,
+
+    ///If the cursor is centered on the window when it's created fullscreen
+    pub is_cursor_centered: bool,
+
+    ///If the fullscreen window is iconified and its video mode restored when it loses the focus
+    pub is_auto_iconified: bool,
+}
+
+impl WindowConfig {
+    ///Create the config of a resizable and decorated 1280x720 window, titled `title`
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_owned(),
+            width: 1280,
+            height: 720,
+            display_mode: DisplayMode::Windowed,
+            monitor: MonitorSelection::Primary,
+            is_resizable: true,
+            is_decorated: true,
+            is_vsync_enabled: false,
+            is_cursor_centered: false,
+            is_auto_iconified: false,
+        }
+    }
+
+    ///Return the display mode used when the window is switched to fullscreen
+    ///
+    /// # Returns
+    ///
+    /// BorderlessFullscreen if `&self` is windowed
+    pub fn get_fullscreen_display_mode(&self) -> DisplayMode {
+        match self.display_mode {
+            DisplayMode::Windowed => DisplayMode::BorderlessFullscreen,
+            display_mode => display_mode,
+        }
+    }
+}
+
+///Position and size (in screen coordinates) of a windowed window
+#[derive(Clone, Copy, Debug)]
+pub struct WindowPlacement {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowPlacement {
+    ///Return the current placement of `glfw_window`
+    pub fn from_window(glfw_window: &Window) -> Self {
+        let (x, y) = glfw_window.get_pos();
+        let (width, height) = glfw_window.get_size();
+
+        Self {
+            x,
+            y,
+            width: width.max(0) as u32,
+            height: height.max(0) as u32,
+        }
+    }
+}
+
+///Create a window without client API (for Vulkan) as described by `window_config`
+///
+///If the window is fullscreen but no monitor is connected, it's created windowed
+pub fn create_window(
+    glfw: &mut Glfw,
+    window_config: &WindowConfig,
+) -> (PWindow, GlfwReceiver<(f64, WindowEvent)>) {
+    glfw.window_hint(WindowHint::ClientApi(glfw::ClientApiHint::NoApi));
+    glfw.window_hint(WindowHint::Resizable(window_config.is_resizable));
+    glfw.window_hint(WindowHint::Decorated(window_config.is_decorated));
+    glfw.window_hint(WindowHint::CenterCursor(window_config.is_cursor_centered));
+    glfw.window_hint(WindowHint::AutoIconify(window_config.is_auto_iconified));
+
+    glfw.with_connected_monitors(|glfw, all_monitors| {
+        let fullscreen_opt = window_config
+            .monitor
+            .select(all_monitors)
+            .and_then(|monitor| {
+                select_video_mode(monitor, window_config.display_mode, window_config)
+                    .map(|video_mode| (monitor, video_mode))
+            });
+
+        match fullscreen_opt {
+            Some((monitor, video_mode)) => {
+                //A fullscreen window matching the current video mode of the monitor is borderless
+                glfw.window_hint(WindowHint::RedBits(Some(video_mode.red_bits)));
+                glfw.window_hint(WindowHint::GreenBits(Some(video_mode.green_bits)));
+                glfw.window_hint(WindowHint::BlueBits(Some(video_mode.blue_bits)));
+                glfw.window_hint(WindowHint::RefreshRate(Some(video_mode.refresh_rate)));
+
+                glfw.create_window(
+                    video_mode.width,
+                    video_mode.height,
+                    &window_config.title,
+                    glfw::WindowMode::FullScreen(monitor),
+                )
+            }
+            None => glfw.create_window(
+                window_config.width,
+                window_config.height,
+                &window_config.title,
+                glfw::WindowMode::Windowed,
+            ),
+        }
+    })
+    .expect("failed to create window")
+}
+
+///Return if `glfw_window` is fullscreen on a monitor
+pub fn is_fullscreen(glfw_window: &Window) -> bool {
+    glfw_window
+        .with_window_mode(|window_mode| matches!(window_mode, glfw::WindowMode::FullScreen(_)))
+}
+
+///Make `glfw_window` fullscreen on the monitor of `window_config`, in its fullscreen display mode
+///
+/// # Returns
+///
+/// The placement of the windowed `glfw_window`, to restore it with `set_windowed`
+///
+/// None if `glfw_window` is already fullscreen or no monitor is connected
+pub fn set_fullscreen(
+    glfw: &mut Glfw,
+    glfw_window: &mut Window,
+    window_config: &WindowConfig,
+) -> Option<WindowPlacement> {
+    if is_fullscreen(glfw_window) {
+        return None;
+    }
+    let window_placement = WindowPlacement::from_window(glfw_window);
+
+    glfw.with_connected_monitors(|_, all_monitors| {
+        let monitor = window_config.monitor.select(all_monitors)?;
+        let video_mode = select_video_mode(
+            monitor,
+            window_config.get_fullscreen_display_mode(),
+            window_config,
+        )?;
+
+        glfw_window.set_monitor(
+            glfw::WindowMode::FullScreen(monitor),
+            0,
+            0,
+            video_mode.width,
+            video_mode.height,
+            Some(video_mode.refresh_rate),
+        );
+
+        Some(window_placement)
+    })
+}
+
+///Make `glfw_window` windowed at `window_placement`
+///
+///If `window_placement` is None, the window has the size of `window_config`, centered on its monitor
+pub fn set_windowed(
+    glfw: &mut Glfw,
+    glfw_window: &mut Window,
+    window_config: &WindowConfig,
+    window_placement: Option<WindowPlacement>,
+) {
+    let window_placement = window_placement.unwrap_or_else(|| {
+        glfw.with_window_monitor(glfw_window, |_, monitor_opt| {
+            let (monitor_x, monitor_y, monitor_width, monitor_height) =
+                monitor_opt.map_or((0, 0, 0, 0), |monitor| monitor.get_workarea());
+
+            WindowPlacement {
+                x: monitor_x + (monitor_width - window_config.width as i32).max(0) / 2,
+                y: monitor_y + (monitor_height - window_config.height as i32).max(0) / 2,
+                width: window_config.width,
+                height: window_config.height,
+            }
+        })
+    });
+
+    glfw_window.set_monitor(
+        glfw::WindowMode::Windowed,
+        window_placement.x,
+        window_placement.y,
+        window_placement.width,
+        window_placement.height,
+        None,
+    );
+}
+
+///Return the video mode of `monitor` used in `display_mode`
+///
+/// # Returns
+///
+/// In exclusive fullscreen, the video mode closest to the size of `window_config`, with the highest refresh rate
+///
+/// None if `display_mode` is windowed or the video mode of `monitor` cannot be queried
+fn select_video_mode(
+    monitor: &Monitor,
+    display_mode: DisplayMode,
+    window_config: &WindowConfig,
+) -> Option<VidMode> {
+    match display_mode {
+        DisplayMode::Windowed => None,
+        DisplayMode::BorderlessFullscreen => monitor.get_video_mode(),
+        DisplayMode::ExclusiveFullscreen => monitor
+            .get_video_modes()
+            .into_iter()
+            .min_by_key(|video_mode| {
+                (
+                    video_mode.width.abs_diff(window_config.width)
+                        + video_mode.height.abs_diff(window_config.height),
+                    Reverse(video_mode.refresh_rate),
+                )
+            })
+            .or_else(|| monitor.get_video_mode()),
+    }
+}
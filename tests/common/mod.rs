@@ -0,0 +1,27 @@
+//!Helpers shared by the integration tests, each test file includes them with `mod common;`
+
+//Each test file only uses some of the helpers
+#![allow(dead_code)]
+
+use u3d_engine::math::vector::Vec3;
+
+///The maximum difference allowed between two floats computed differently
+pub const EPSILON: f32 = 1e-5;
+
+///Assert that the difference between `actual` and `expected` is less than `EPSILON`
+#[track_caller]
+pub fn assert_near(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < EPSILON,
+        "{actual} isn't {expected}"
+    );
+}
+
+///Assert that the distance between `actual` and `expected` is less than `EPSILON`
+#[track_caller]
+pub fn assert_vec3_near(actual: Vec3, expected: Vec3) {
+    assert!(
+        (actual - expected).length() < EPSILON,
+        "{actual:?} isn't {expected:?}"
+    );
+}
@@ -0,0 +1,135 @@
+//!Check the decoding of the environment images and the sizes of the image-based lighting
+
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, Rgb32FImage, RgbImage};
+use u3d_engine::{
+    environment::{
+        EnvironmentConfig, EnvironmentImage, EnvironmentImageError,
+        load_environment_image_from_bytes,
+    },
+    math::vector::Vec3,
+};
+
+///An equirectangular image of 8x4 texels, brighter than white in its top row
+fn high_dynamic_range_image() -> Rgb32FImage {
+    Rgb32FImage::from_fn(8, 4, |x, y| {
+        let brightness = if y == 0 { 16.0 } else { 0.25 };
+        image::Rgb([brightness, x as f32 / 8.0, 0.5])
+    })
+}
+
+fn encode(image: DynamicImage, format: ImageFormat) -> Vec<u8> {
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, format)
+        .expect("failed to encode image");
+    bytes.into_inner()
+}
+
+fn assert_texels_near(environment_image: &EnvironmentImage, expected_image: &Rgb32FImage) {
+    assert_eq!(environment_image.width, expected_image.width());
+    assert_eq!(environment_image.height, expected_image.height());
+    for (texel, expected_texel) in environment_image
+        .all_texels
+        .iter()
+        .zip(expected_image.pixels())
+    {
+        for (component, expected_component) in texel.iter().zip(expected_texel.0) {
+            //RGBE keeps 8 bits of mantissa by texel
+            assert!(
+                (component - expected_component).abs() <= expected_component.abs() / 64.0 + 1e-3,
+                "{texel:?} != {expected_texel:?}"
+            );
+        }
+        assert_eq!(texel[3], 1.0);
+    }
+}
+
+#[test]
+fn radiance_hdr_keeps_colors_brighter_than_white() {
+    let expected_image = high_dynamic_range_image();
+    let bytes = encode(
+        DynamicImage::ImageRgb32F(expected_image.clone()),
+        ImageFormat::Hdr,
+    );
+
+    let environment_image =
+        load_environment_image_from_bytes(&bytes).expect("failed to load Radiance HDR image");
+
+    assert_texels_near(&environment_image, &expected_image);
+    assert!(environment_image.all_texels[0][0] > 1.0);
+}
+
+#[test]
+fn openexr_keeps_colors_brighter_than_white() {
+    let expected_image = high_dynamic_range_image();
+    let bytes = encode(
+        DynamicImage::ImageRgb32F(expected_image.clone()),
+        ImageFormat::OpenExr,
+    );
+
+    let environment_image =
+        load_environment_image_from_bytes(&bytes).expect("failed to load OpenEXR image");
+
+    assert_texels_near(&environment_image, &expected_image);
+}
+
+#[test]
+fn low_dynamic_range_image_is_refused() {
+    let bytes = encode(
+        DynamicImage::ImageRgb8(RgbImage::new(8, 4)),
+        ImageFormat::Png,
+    );
+
+    assert!(matches!(
+        load_environment_image_from_bytes(&bytes),
+        Err(EnvironmentImageError::NotHighDynamicRange)
+    ));
+}
+
+#[test]
+fn solid_color_is_a_single_opaque_texel() {
+    let environment_image = EnvironmentImage::new_solid_color(Vec3::new(0.1, 0.2, 0.3));
+
+    assert_eq!((environment_image.width, environment_image.height), (1, 1));
+    assert_eq!(environment_image.all_texels, vec![[0.1, 0.2, 0.3, 1.0]]);
+}
+
+#[test]
+fn cube_size_is_a_quarter_of_the_width_as_a_power_of_two() {
+    let environment_config = EnvironmentConfig::default();
+    let get_cube_size = |width| {
+        environment_config.get_cube_size(&EnvironmentImage {
+            width,
+            height: width / 2,
+            all_texels: Vec::new(),
+        })
+    };
+
+    assert_eq!(get_cube_size(2048), 512);
+    assert_eq!(get_cube_size(1000), 256);
+    assert_eq!(get_cube_size(1), 1);
+    assert_eq!(get_cube_size(16384), environment_config.max_cube_size);
+}
+
+#[test]
+fn prefiltered_mip_levels_span_all_roughnesses() {
+    let environment_config = EnvironmentConfig::default();
+    let last_mip_level = environment_config.get_prefiltered_mip_levels() - 1;
+
+    assert_eq!(environment_config.get_prefiltered_roughness(0), 0.0);
+    assert_eq!(
+        environment_config.get_prefiltered_roughness(last_mip_level),
+        1.0
+    );
+
+    //The mip levels are limited to the mip chain of the prefiltered cube map
+    let small_environment_config = EnvironmentConfig {
+        prefiltered_size: 4,
+        prefiltered_mip_levels: 10,
+        ..EnvironmentConfig::default()
+    };
+    assert_eq!(small_environment_config.get_prefiltered_mip_levels(), 3);
+    assert_eq!(small_environment_config.get_prefiltered_roughness(1), 0.5);
+}
@@ -0,0 +1,271 @@
+//!Parse glTF and GLB documents and check the loaded nodes, meshes, materials and animations
+
+mod common;
+
+use std::path::Path;
+
+use ash::vk;
+use common::assert_vec3_near;
+use u3d_engine::{
+    gltf::{GltfError, GltfModel, parse_gltf},
+    json::{JsonValue, parse_json},
+    material::{AlphaMode, TextureImage},
+    math::vector::Vec3,
+};
+
+///Buffer with a triangle of 3 VEC3 positions at 0, its 3 UNSIGNED_SHORT indices at 36,
+///2 keyframe times at 44 and 2 VEC3 translations at 52
+const TRIANGLE_BUFFER_BASE64: &str = "AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAAEAAAIBAAADAQA==";
+
+///Return a glTF document drawing the triangle of TRIANGLE_BUFFER_BASE64 with a child node,
+///whose translation is animated, and `extra_members` added to its root object
+fn get_triangle_document(buffer_uri_opt: Option<&str>, extra_members: &str) -> String {
+    let buffer_uri = buffer_uri_opt
+        .map(|uri| format!(r#""uri": "{uri}", "#))
+        .unwrap_or_default();
+
+    format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [
+                {{ "name": "parent", "translation": [1, 2, 3], "children": [1] }},
+                {{ "name": "triangle", "mesh": 0, "scale": [2, 2, 2] }},
+                {{ "name": "hidden" }}
+            ],
+            "meshes": [{{ "name": "triangle", "primitives": [{{
+                "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0
+            }}] }}],
+            "materials": [{{
+                "name": "red",
+                "pbrMetallicRoughness": {{ "baseColorFactor": [1, 0, 0, 1], "roughnessFactor": 0.25 }},
+                "alphaMode": "MASK",
+                "doubleSided": true
+            }}],
+            "animations": [{{
+                "channels": [{{ "sampler": 0, "target": {{ "node": 1, "path": "translation" }} }}],
+                "samplers": [{{ "input": 2, "output": 3 }}]
+            }}],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }},
+                {{ "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }},
+                {{ "bufferView": 2, "componentType": 5126, "count": 2, "type": "SCALAR" }},
+                {{ "bufferView": 3, "componentType": 5126, "count": 2, "type": "VEC3" }}
+            ],
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }},
+                {{ "buffer": 0, "byteOffset": 44, "byteLength": 8 }},
+                {{ "buffer": 0, "byteOffset": 52, "byteLength": 24 }}
+            ],
+            "buffers": [{{ {buffer_uri}"byteLength": 76 }}]
+            {extra_members}
+        }}"#
+    )
+}
+
+///Return the model of the embedded triangle document
+fn load_triangle_model(extra_members: &str) -> Result<GltfModel, GltfError> {
+    let document = get_triangle_document(
+        Some(&format!(
+            "data:application/octet-stream;base64,{TRIANGLE_BUFFER_BASE64}"
+        )),
+        extra_members,
+    );
+    parse_gltf(document.as_bytes(), Path::new(""))
+}
+
+///Return a GLB file made of `json` and `bin` chunks
+fn create_glb(json: &str, bin: &[u8]) -> Vec<u8> {
+    //The chunks are padded to 4 bytes, the JSON with spaces and the BIN with zeros
+    let mut json_chunk = json.as_bytes().to_vec();
+    json_chunk.resize(json_chunk.len().next_multiple_of(4), b' ');
+    let mut bin_chunk = bin.to_vec();
+    bin_chunk.resize(bin_chunk.len().next_multiple_of(4), 0);
+
+    let length = 12 + 8 + json_chunk.len() + 8 + bin_chunk.len();
+    let mut glb = Vec::with_capacity(length);
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&(length as u32).to_le_bytes());
+    for (chunk, chunk_type) in [(json_chunk, b"JSON"), (bin_chunk, b"BIN\0")] {
+        glb.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+        glb.extend_from_slice(chunk_type);
+        glb.extend_from_slice(&chunk);
+    }
+    glb
+}
+
+#[test]
+fn embedded_triangle_is_loaded_with_its_hierarchy() {
+    let mut model = load_triangle_model("").expect("failed to load triangle");
+
+    let mesh = &model.all_meshes[0].mesh;
+    assert_eq!(mesh.get_triangle_count(), 1);
+    assert_eq!(mesh.all_submeshes[0].material_index, Some(0));
+    //Without normals, the flat normal of the counterclockwise triangle is generated
+    for vertex in &mesh.all_vertices {
+        assert_vec3_near(vertex.normal, Vec3::Z);
+    }
+    assert!(model.all_meshes[0].all_vertex_joints.is_empty());
+
+    let parent_id = model
+        .scene
+        .find_node_by_name("parent")
+        .expect("failed to find parent");
+    let triangle_id = model
+        .scene
+        .find_node_by_name("triangle")
+        .expect("failed to find triangle");
+    let hidden_id = model
+        .scene
+        .find_node_by_name("hidden")
+        .expect("failed to find hidden");
+    let scene = &mut model.scene;
+    assert_eq!(
+        scene.get_node(triangle_id).unwrap().get_parent(),
+        Some(parent_id)
+    );
+    //The nodes missing from the default scene are loaded, but not visible
+    assert!(!scene.get_node(hidden_id).unwrap().is_visible);
+
+    scene.update_world_transforms();
+    let triangle_origin = scene
+        .get_node(triangle_id)
+        .unwrap()
+        .get_world_matrix()
+        .transform_point3(Vec3::X);
+    assert_vec3_near(triangle_origin, Vec3::new(3.0, 2.0, 3.0));
+}
+
+#[test]
+fn material_parameters_have_gltf_defaults() {
+    let model = load_triangle_model("").expect("failed to load triangle");
+
+    let material = &model.all_materials[0];
+    assert_eq!(material.name, "red");
+    assert_eq!(material.base_color_factor.x, 1.0);
+    assert_eq!(material.roughness_factor, 0.25);
+    //The metallic factor is 1 when missing, unlike the default Material
+    assert_eq!(material.metallic_factor, 1.0);
+    assert_eq!(material.alpha_mode, AlphaMode::Mask { cutoff: 0.5 });
+    assert!(material.is_double_sided);
+    assert_eq!(material.base_color_texture, None);
+}
+
+#[test]
+fn animation_interpolates_translation() {
+    let mut model = load_triangle_model("").expect("failed to load triangle");
+
+    let animation = &model.all_animations[0];
+    assert_eq!(animation.get_duration(), 1.0);
+    animation.apply(&mut model.scene, 0.25);
+
+    let triangle_id = model
+        .scene
+        .find_node_by_name("triangle")
+        .expect("failed to find triangle");
+    let translation = model
+        .scene
+        .get_node(triangle_id)
+        .unwrap()
+        .get_transform()
+        .translation;
+    assert_vec3_near(translation, Vec3::new(0.5, 1.0, 1.5));
+}
+
+#[test]
+fn glb_buffer_is_read_from_binary_chunk() {
+    let bin: Vec<u8> = [0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+        .iter()
+        .flat_map(|component| component.to_le_bytes())
+        .chain([0u16, 1, 2].iter().flat_map(|index| index.to_le_bytes()))
+        .chain([0u8; 2])
+        .chain(
+            [0f32, 1.0, 0.0, 0.0, 0.0, 2.0, 4.0, 6.0]
+                .iter()
+                .flat_map(|value| value.to_le_bytes()),
+        )
+        .collect();
+    let glb = create_glb(&get_triangle_document(None, ""), &bin);
+
+    let model = parse_gltf(&glb, Path::new("")).expect("failed to load GLB");
+    assert_eq!(model.all_meshes[0].mesh.all_indices, [0, 1, 2]);
+    assert_eq!(
+        model.all_meshes[0].mesh.all_vertices[1].position,
+        Vec3::new(1.0, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn textures_reference_their_images_and_samplers() {
+    let document = r#"{
+        "asset": { "version": "2.1" },
+        "materials": [{ "pbrMetallicRoughness": { "baseColorTexture": { "index": 0 } } }],
+        "textures": [{ "source": 0, "sampler": 0 }],
+        "samplers": [{ "magFilter": 9728, "minFilter": 9987, "wrapS": 33071 }],
+        "images": [{ "uri": "textures/base%20color.png" }]
+    }"#;
+
+    let model =
+        parse_gltf(document.as_bytes(), Path::new("models")).expect("failed to load material");
+    let texture = model.all_materials[0]
+        .base_color_texture
+        .as_ref()
+        .expect("failed to find base color texture");
+    assert_eq!(
+        texture.image,
+        TextureImage::File(Path::new("models").join("textures/base color.png"))
+    );
+
+    let sampler_parameters = texture.sampler_parameters;
+    assert_eq!(sampler_parameters.mag_filter, vk::Filter::NEAREST);
+    assert_eq!(sampler_parameters.min_filter, vk::Filter::LINEAR);
+    assert_eq!(
+        sampler_parameters.mipmap_mode,
+        vk::SamplerMipmapMode::LINEAR
+    );
+    assert_eq!(
+        sampler_parameters.address_modes[..2],
+        [
+            vk::SamplerAddressMode::CLAMP_TO_EDGE,
+            vk::SamplerAddressMode::REPEAT
+        ]
+    );
+}
+
+#[test]
+fn unsupported_required_extension_is_reported() {
+    let error =
+        match load_triangle_model(r#", "extensionsRequired": ["KHR_draco_mesh_compression"]"#) {
+            Ok(_) => panic!("document requiring Draco loaded"),
+            Err(error) => error,
+        };
+
+    assert!(matches!(
+        error,
+        GltfError::UnsupportedExtension(extension) if extension == "KHR_draco_mesh_compression"
+    ));
+}
+
+#[test]
+fn json_escapes_are_decoded() {
+    let value =
+        parse_json(r#"{ "name": "a\"b\u00e9\ud83d\ude00", "list": [1, -2.5e1, true, null] }"#)
+            .expect("failed to parse JSON");
+
+    assert_eq!(
+        value.get("name").and_then(JsonValue::as_str),
+        Some("a\"bé😀")
+    );
+    let all_values = value
+        .get("list")
+        .and_then(JsonValue::as_array)
+        .expect("failed to find list");
+    assert_eq!(all_values[1].as_f64(), Some(-25.0));
+    assert_eq!(all_values[3], JsonValue::Null);
+
+    assert!(parse_json("[1, 2").is_err());
+    assert!(parse_json(&"[".repeat(1000)).is_err());
+}
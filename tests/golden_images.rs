@@ -0,0 +1,575 @@
+//!Render scenes offscreen on a software Vulkan device (for example lavapipe) and compare them to golden PNG images
+//!
+//!The tests are ignored by default since they require a software Vulkan device, run them with `cargo test --test golden_images -- --ignored`.
+//!They fail if no Vulkan instance or CPU device is available, or if a golden image is missing from `tests/golden`.
+//!The golden images are only written from the rendered images if the `U3D_UPDATE_GOLDENS` environment variable is set.
+//!On a mismatch, the rendered image and the difference are written in `golden-diffs` of the cargo target temporary directory
+
+use std::{io::Cursor, path::PathBuf};
+
+use ash::vk;
+use bytemuck::{Pod, Zeroable};
+
+use u3d_engine::{
+    math::{matrix::Mat4, vector::Vec3},
+    mesh::{MeshData, Submesh, Vertex},
+    vulkan::{
+        capture::VulkanImageCapture,
+        command_buffer::{VulkanCommandBuffer, VulkanCommandPool},
+        descriptor::{VulkanDescriptorPool, VulkanDescriptorSetLayout},
+        device::VulkanDevice,
+        entry::VulkanEntry,
+        framebuffer::VulkanFramebuffer,
+        graphics_pipeline::{GraphicsPipelineConfig, VulkanGraphicsPipeline, push_constant_range},
+        image::{SamplerParameters, VulkanImage, VulkanImageView, VulkanSampler},
+        instance::VulkanInstance,
+        mesh::{VERTEX_ATTRIBUTE_DESCRIPTIONS, VERTEX_BINDING_DESCRIPTIONS, VulkanMesh},
+        shaders::{DEFAULT_SHADER_ENTRYPOINT, VulkanShaderModule, VulkanShaderStage},
+        swapchain::OutputColorSpace,
+        texture::{TextureColorSpace, load_texture_from_bytes},
+    },
+};
+
+///The size of the rendered images
+const RENDER_EXTENT: vk::Extent2D = vk::Extent2D {
+    width: 256,
+    height: 256,
+};
+
+///The format of the rendered images, read back without conversion
+const RENDER_FORMAT: vk::Format = vk::Format::R8G8B8A8_UNORM;
+
+const CLEAR_COLOR: [f32; 4] = [0.1, 0.1, 0.1, 1.0];
+
+///The maximum difference allowed between a channel of a rendered texel and of its golden texel
+const CHANNEL_TOLERANCE: u8 = 2;
+
+///Environment variable requesting the write of all the golden images
+const UPDATE_GOLDENS_VARIABLE: &str = "U3D_UPDATE_GOLDENS";
+
+///Push constants of the base shaders
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct BasePushConstants {
+    model_view_projection: Mat4,
+    color_tint: [f32; 4],
+    output_color_space: u32,
+    _padding: [u32; 3],
+}
+
+///Push constants of the depth shaders, drawing a triangle of `color` moved by `offset` at `depth`
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct DepthPushConstants {
+    color: [f32; 4],
+    offset: [f32; 2],
+    depth: f32,
+    _padding: f32,
+}
+
+#[test]
+#[ignore = "requires a software Vulkan device"]
+fn golden_triangle() {
+    with_software_device(|vulkan_device, command_pool| {
+        let all_shader_modules = [
+            load_shader_module(
+                vulkan_device,
+                "shaders/spir-v/base.vert.spv",
+                vk::ShaderStageFlags::VERTEX,
+            ),
+            load_shader_module(
+                vulkan_device,
+                "shaders/spir-v/base.frag.spv",
+                vk::ShaderStageFlags::FRAGMENT,
+            ),
+        ];
+        let graphics_pipeline = VulkanGraphicsPipeline::new(
+            vulkan_device,
+            RENDER_EXTENT,
+            RENDER_FORMAT,
+            &create_all_shader_stages(&all_shader_modules),
+            &[push_constant_range::<BasePushConstants>(
+                vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
+                0,
+            )],
+            &[],
+            &GraphicsPipelineConfig {
+                all_vertex_binding_descriptions: &VERTEX_BINDING_DESCRIPTIONS,
+                all_vertex_attribute_descriptions: &VERTEX_ATTRIBUTE_DESCRIPTIONS,
+                ..GraphicsPipelineConfig::default()
+            },
+        );
+
+        //A counterclockwise triangle, whose normals toward +Z are generated
+        let mut mesh_data = MeshData {
+            all_vertices: [[0.0, 0.5], [-0.5, -0.5], [0.5, -0.5]]
+                .map(|[x, y]| Vertex {
+                    position: Vec3::new(x, y, 0.0),
+                    ..Vertex::default()
+                })
+                .to_vec(),
+            all_indices: vec![0, 1, 2],
+            all_submeshes: vec![Submesh {
+                first_index: 0,
+                index_count: 3,
+                material_index: None,
+            }],
+        };
+        mesh_data.generate_normals();
+        mesh_data.generate_tangents();
+        let mesh = VulkanMesh::new(vulkan_device, command_pool, &mesh_data);
+
+        let rgba8_texels = render(
+            vulkan_device,
+            command_pool,
+            &graphics_pipeline,
+            |command_buffer| {
+                mesh.bind(command_buffer);
+                command_buffer.push_constants(
+                    &graphics_pipeline,
+                    vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
+                    0,
+                    &BasePushConstants {
+                        //The Y axis is flipped up, as by the projections of Mat4
+                        model_view_projection: Mat4::from_scale(Vec3::new(1.0, -1.0, 1.0)),
+                        color_tint: [1.0; 4],
+                        output_color_space: OutputColorSpace::Srgb as u32,
+                        _padding: [0; 3],
+                    },
+                );
+                mesh.draw_submesh(command_buffer, &mesh.get_all_submeshes()[0]);
+            },
+        );
+
+        compare_to_golden("triangle", &rgba8_texels);
+    });
+}
+
+#[test]
+#[ignore = "requires a software Vulkan device"]
+fn golden_textured_quad() {
+    with_software_device(|vulkan_device, command_pool| {
+        let texture = load_texture_from_bytes(
+            vulkan_device,
+            command_pool,
+            &create_checker_png(),
+            TextureColorSpace::Linear,
+        )
+        .expect("failed to load checker texture");
+        let texture_view = VulkanImageView::new_color(&texture);
+        let sampler = VulkanSampler::new(
+            vulkan_device,
+            &SamplerParameters {
+                mag_filter: vk::Filter::NEAREST,
+                min_filter: vk::Filter::NEAREST,
+                mipmap_mode: vk::SamplerMipmapMode::NEAREST,
+                address_modes: [vk::SamplerAddressMode::CLAMP_TO_EDGE; 3],
+                max_anisotropy: None,
+            },
+        );
+
+        let descriptor_set_layout = VulkanDescriptorSetLayout::new(
+            vulkan_device,
+            &[
+                vk::DescriptorSetLayoutBinding::default()
+                    .binding(0)
+                    .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+                    .descriptor_count(1)
+                    .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+                vk::DescriptorSetLayoutBinding::default()
+                    .binding(1)
+                    .descriptor_type(vk::DescriptorType::SAMPLER)
+                    .descriptor_count(1)
+                    .stage_flags(vk::ShaderStageFlags::FRAGMENT),
+            ],
+        );
+        let descriptor_pool = VulkanDescriptorPool::new(
+            vulkan_device,
+            1,
+            &[
+                vk::DescriptorPoolSize::default()
+                    .ty(vk::DescriptorType::SAMPLED_IMAGE)
+                    .descriptor_count(1),
+                vk::DescriptorPoolSize::default()
+                    .ty(vk::DescriptorType::SAMPLER)
+                    .descriptor_count(1),
+            ],
+        );
+        let all_descriptor_sets = descriptor_pool
+            .allocate_descriptor_sets(&[descriptor_set_layout.get_descriptor_set_layout()]);
+        all_descriptor_sets[0].write_sampled_image(0, &texture_view);
+        all_descriptor_sets[0].write_sampler(1, &sampler);
+
+        let all_shader_modules = [
+            load_shader_module(
+                vulkan_device,
+                "tests/shaders/spir-v/textured.vert.spv",
+                vk::ShaderStageFlags::VERTEX,
+            ),
+            load_shader_module(
+                vulkan_device,
+                "tests/shaders/spir-v/textured.frag.spv",
+                vk::ShaderStageFlags::FRAGMENT,
+            ),
+        ];
+        let graphics_pipeline = VulkanGraphicsPipeline::new(
+            vulkan_device,
+            RENDER_EXTENT,
+            RENDER_FORMAT,
+            &create_all_shader_stages(&all_shader_modules),
+            &[],
+            &[descriptor_set_layout.get_descriptor_set_layout()],
+            &GraphicsPipelineConfig::default(),
+        );
+
+        let rgba8_texels = render(
+            vulkan_device,
+            command_pool,
+            &graphics_pipeline,
+            |command_buffer| {
+                command_buffer.bind_descriptor_sets(
+                    &graphics_pipeline,
+                    0,
+                    &[all_descriptor_sets[0].get_descriptor_set()],
+                );
+                command_buffer.draw(6, 1, 0, 0);
+            },
+        );
+
+        compare_to_golden("textured_quad", &rgba8_texels);
+    });
+}
+
+#[test]
+#[ignore = "requires a software Vulkan device"]
+fn golden_depth_test() {
+    with_software_device(|vulkan_device, command_pool| {
+        let depth_format = vulkan_device
+            .get_supported_depth_format()
+            .expect("none depth format supported");
+
+        let all_shader_modules = [
+            load_shader_module(
+                vulkan_device,
+                "tests/shaders/spir-v/depth.vert.spv",
+                vk::ShaderStageFlags::VERTEX,
+            ),
+            load_shader_module(
+                vulkan_device,
+                "tests/shaders/spir-v/depth.frag.spv",
+                vk::ShaderStageFlags::FRAGMENT,
+            ),
+        ];
+        let push_constants_stage_flags =
+            vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT;
+        let graphics_pipeline = VulkanGraphicsPipeline::new(
+            vulkan_device,
+            RENDER_EXTENT,
+            RENDER_FORMAT,
+            &create_all_shader_stages(&all_shader_modules),
+            &[push_constant_range::<DepthPushConstants>(
+                push_constants_stage_flags,
+                0,
+            )],
+            &[],
+            &GraphicsPipelineConfig::with_depth(depth_format),
+        );
+
+        //The far green triangle is drawn after the near red one, and must stay hidden behind it where they overlap
+        let rgba8_texels = render(
+            vulkan_device,
+            command_pool,
+            &graphics_pipeline,
+            |command_buffer| {
+                for (color, offset, depth) in [
+                    ([1.0, 0.0, 0.0, 1.0], [-0.2, 0.0], 0.25),
+                    ([0.0, 1.0, 0.0, 1.0], [0.2, 0.1], 0.75),
+                ] {
+                    command_buffer.push_constants(
+                        &graphics_pipeline,
+                        push_constants_stage_flags,
+                        0,
+                        &DepthPushConstants {
+                            color,
+                            offset,
+                            depth,
+                            _padding: 0.0,
+                        },
+                    );
+                    command_buffer.draw(3, 1, 0, 0);
+                }
+            },
+        );
+
+        compare_to_golden("depth_test", &rgba8_texels);
+    });
+}
+
+///Run `test` with a software device and a command pool of its graphics queue
+///
+/// # Panics
+///
+/// If no Vulkan instance or software device is available
+fn with_software_device(test: impl FnOnce(&VulkanDevice, &VulkanCommandPool)) {
+    let vulkan_entry = VulkanEntry::init();
+    let vulkan_instance = VulkanInstance::new_headless(&vulkan_entry)
+        .unwrap_or_else(|error| panic!("failed to create Vulkan instance: {error}"));
+
+    let vulkan_device =
+        VulkanDevice::select_headless_device(&vulkan_instance, Some(vk::PhysicalDeviceType::CPU))
+            .expect("none software Vulkan device available");
+
+    let command_pool = VulkanCommandPool::new_for_graphics_queue(&vulkan_device);
+
+    test(&vulkan_device, &command_pool);
+
+    vulkan_device.wait_idle();
+}
+
+///Load a SPIR-V shader module from `file_path`, relative to the root of the crate
+fn load_shader_module<'vulkan_device>(
+    vulkan_device: &'vulkan_device VulkanDevice,
+    file_path: &str,
+    shader_stage: vk::ShaderStageFlags,
+) -> VulkanShaderModule<'vulkan_device> {
+    let file_path = format!("{}/{file_path}", env!("CARGO_MANIFEST_DIR"));
+
+    VulkanShaderModule::from_file(vulkan_device, &file_path, shader_stage)
+        .unwrap_or_else(|error| panic!("failed to load shader {file_path}: {error}"))
+}
+
+fn create_all_shader_stages<'shader_module, 'vulkan_device>(
+    all_shader_modules: &'shader_module [VulkanShaderModule<'vulkan_device>],
+) -> Box<[VulkanShaderStage<'shader_module, 'vulkan_device>]> {
+    all_shader_modules
+        .iter()
+        .map(|shader_module| {
+            VulkanShaderStage::new(shader_module, DEFAULT_SHADER_ENTRYPOINT)
+                .expect("failed to create shader stage")
+        })
+        .collect()
+}
+
+///Encode in PNG a 8x8 checker of white and blue texels
+fn create_checker_png() -> Vec<u8> {
+    let checker_image = image::RgbaImage::from_fn(8, 8, |x, y| {
+        if (x + y) % 2 == 0 {
+            image::Rgba([255, 255, 255, 255])
+        } else {
+            image::Rgba([32, 64, 255, 255])
+        }
+    });
+
+    let mut png_bytes = Vec::new();
+    checker_image
+        .write_to(&mut Cursor::new(&mut png_bytes), image::ImageFormat::Png)
+        .expect("failed to encode checker texture");
+
+    png_bytes
+}
+
+///Render with `graphics_pipeline` the draws recorded by `record_draws` in a new offscreen image, and return its texels in RGBA8
+///
+///The image is cleared with CLEAR_COLOR, and a depth image is created if `graphics_pipeline` tests the depth
+fn render(
+    vulkan_device: &VulkanDevice,
+    command_pool: &VulkanCommandPool,
+    graphics_pipeline: &VulkanGraphicsPipeline,
+    record_draws: impl FnOnce(&VulkanCommandBuffer),
+) -> Vec<u8> {
+    let color_image = VulkanImage::new_2d(
+        vulkan_device,
+        RENDER_EXTENT,
+        RENDER_FORMAT,
+        1,
+        1,
+        false,
+        vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC,
+    );
+    let color_image_view = VulkanImageView::new_color(&color_image);
+
+    let depth_image = graphics_pipeline.get_depth_format().map(|depth_format| {
+        VulkanImage::new_2d(
+            vulkan_device,
+            RENDER_EXTENT,
+            depth_format,
+            1,
+            1,
+            false,
+            vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+        )
+    });
+    let depth_image_view = depth_image.as_ref().map(VulkanImageView::new_depth);
+    let depth_image_view_handle = depth_image_view
+        .as_ref()
+        .map(VulkanImageView::get_image_view);
+
+    let framebuffer = graphics_pipeline.get_render_pass().map(|_| {
+        VulkanFramebuffer::new_for_image_views(
+            vulkan_device,
+            graphics_pipeline,
+            color_image_view.get_image_view(),
+            depth_image_view_handle,
+            RENDER_EXTENT,
+        )
+    });
+
+    let image_capture = VulkanImageCapture::new(vulkan_device, RENDER_FORMAT, RENDER_EXTENT)
+        .expect("failed to create capture of rendered image");
+
+    command_pool.execute_one_time_commands(|command_buffer| {
+        //The render pass leaves the color image ready to be presented
+        let rendered_layout = match &framebuffer {
+            Some(framebuffer) => {
+                command_buffer.begin_render_pass(
+                    graphics_pipeline,
+                    framebuffer.get_framebuffer(),
+                    RENDER_EXTENT,
+                    CLEAR_COLOR,
+                );
+
+                vk::ImageLayout::PRESENT_SRC_KHR
+            }
+            None => {
+                command_buffer.transition_color_image_layout(
+                    color_image.get_image(),
+                    vk::ImageLayout::UNDEFINED,
+                    vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                    (
+                        vk::PipelineStageFlags::TOP_OF_PIPE,
+                        vk::AccessFlags::empty(),
+                    ),
+                    (
+                        vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                        vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+                    ),
+                );
+                if let Some(depth_image) = &depth_image {
+                    command_buffer.transition_image_layout(
+                        depth_image.get_image(),
+                        vk::ImageSubresourceRange::default()
+                            .aspect_mask(vk::ImageAspectFlags::DEPTH)
+                            .base_mip_level(0)
+                            .level_count(1)
+                            .base_array_layer(0)
+                            .layer_count(1),
+                        vk::ImageLayout::UNDEFINED,
+                        vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                        (
+                            vk::PipelineStageFlags::TOP_OF_PIPE,
+                            vk::AccessFlags::empty(),
+                        ),
+                        (
+                            vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS,
+                            vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                        ),
+                    );
+                }
+                command_buffer.begin_rendering(
+                    color_image_view.get_image_view(),
+                    depth_image_view_handle,
+                    RENDER_EXTENT,
+                    CLEAR_COLOR,
+                );
+
+                vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+            }
+        };
+
+        command_buffer.bind_graphics_pipeline(graphics_pipeline);
+        record_draws(command_buffer);
+
+        if framebuffer.is_some() {
+            command_buffer.end_render_pass();
+        } else {
+            command_buffer.end_rendering();
+        }
+
+        image_capture.record_copy_from_image(
+            command_buffer,
+            color_image.get_image(),
+            rendered_layout,
+        );
+    });
+
+    image_capture.read_rgba8()
+}
+
+///Compare `rgba8_texels` to the golden image `name`, each channel being allowed to differ by CHANNEL_TOLERANCE
+///
+/// # Panics
+///
+/// If the golden image is missing,
+/// or if the images differ, after writing the rendered image and the difference in `golden-diffs`
+fn compare_to_golden(name: &str, rgba8_texels: &[u8]) {
+    let golden_file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.png"));
+
+    if std::env::var_os(UPDATE_GOLDENS_VARIABLE).is_some() {
+        std::fs::create_dir_all(golden_file_path.parent().unwrap())
+            .expect("failed to create golden directory");
+        save_rgba8_png(&golden_file_path, rgba8_texels);
+        eprintln!("golden image written: {}", golden_file_path.display());
+        return;
+    }
+
+    assert!(
+        golden_file_path.exists(),
+        "golden image {} is missing, write it with {UPDATE_GOLDENS_VARIABLE}=1",
+        golden_file_path.display()
+    );
+
+    let golden_image = image::open(&golden_file_path)
+        .unwrap_or_else(|error| panic!("failed to open {}: {error}", golden_file_path.display()))
+        .into_rgba8();
+    assert_eq!(
+        (golden_image.width(), golden_image.height()),
+        (RENDER_EXTENT.width, RENDER_EXTENT.height),
+        "golden image {name} doesn't have the size of the rendered image"
+    );
+
+    //The difference is opaque, so it can be seen in any image viewer
+    let diff_texels: Vec<u8> = rgba8_texels
+        .chunks_exact(4)
+        .zip(golden_image.as_raw().chunks_exact(4))
+        .flat_map(|(texel, golden_texel)| {
+            let diff = |channel: usize| texel[channel].abs_diff(golden_texel[channel]);
+            [diff(0), diff(1), diff(2), 255]
+        })
+        .collect();
+    let mismatch_count = rgba8_texels
+        .iter()
+        .zip(golden_image.as_raw())
+        .filter(|(channel, golden_channel)| channel.abs_diff(**golden_channel) > CHANNEL_TOLERANCE)
+        .count();
+
+    if mismatch_count > 0 {
+        let diff_directory_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden-diffs");
+        std::fs::create_dir_all(&diff_directory_path)
+            .expect("failed to create golden-diffs directory");
+
+        let actual_file_path = diff_directory_path.join(format!("{name}.actual.png"));
+        let diff_file_path = diff_directory_path.join(format!("{name}.diff.png"));
+        save_rgba8_png(&actual_file_path, rgba8_texels);
+        save_rgba8_png(&diff_file_path, &diff_texels);
+
+        panic!(
+            "{mismatch_count} channels of {name} differ from the golden image by more than {CHANNEL_TOLERANCE}, see {} and {}",
+            actual_file_path.display(),
+            diff_file_path.display()
+        );
+    }
+}
+
+fn save_rgba8_png(file_path: &std::path::Path, rgba8_texels: &[u8]) {
+    image::save_buffer_with_format(
+        file_path,
+        rgba8_texels,
+        RENDER_EXTENT.width,
+        RENDER_EXTENT.height,
+        image::ExtendedColorType::Rgba8,
+        image::ImageFormat::Png,
+    )
+    .unwrap_or_else(|error| panic!("failed to write {}: {error}", file_path.display()));
+}
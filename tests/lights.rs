@@ -0,0 +1,69 @@
+//!Check the lights converted to the layout read by the shaders
+
+mod common;
+
+use std::f32::consts::FRAC_PI_2;
+
+use common::{assert_near, assert_vec3_near};
+use u3d_engine::{
+    math::{matrix::Mat4, quaternion::Quat, vector::Vec3},
+    scene::{Light, Transform},
+    vulkan::light::GpuLight,
+};
+
+#[test]
+fn gpu_light_matches_std430_layout() {
+    //The lights of the runtime array of the shaders are aligned to 16 bytes
+    assert_eq!(size_of::<GpuLight>(), 64);
+}
+
+#[test]
+fn directional_light_points_toward_negative_z_of_its_transform() {
+    let light = Light::Directional {
+        color: Vec3::new(1.0, 0.5, 0.25),
+        intensity: 2.0,
+    };
+    let world_matrix = Transform::from_rotation(Quat::from_rotation_x(-FRAC_PI_2)).to_matrix();
+
+    let gpu_light = GpuLight::new(&light, &world_matrix);
+    assert_vec3_near(Vec3::from_array(gpu_light.direction), -Vec3::Y);
+    //The intensity is premultiplied with the color
+    assert_vec3_near(Vec3::from_array(gpu_light.color), Vec3::new(2.0, 1.0, 0.5));
+    assert_eq!(gpu_light.light_type, 0);
+}
+
+#[test]
+fn point_light_is_placed_by_its_transform() {
+    let light = Light::Point {
+        color: Vec3::ONE,
+        intensity: 1.0,
+        range: None,
+    };
+    let world_matrix = Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0));
+
+    let gpu_light = GpuLight::new(&light, &world_matrix);
+    assert_vec3_near(
+        Vec3::from_array(gpu_light.position),
+        Vec3::new(1.0, 2.0, 3.0),
+    );
+    //An infinite range is 0
+    assert_eq!(gpu_light.range, 0.0);
+    assert_eq!(gpu_light.light_type, 1);
+}
+
+#[test]
+fn spot_light_cones_are_cosines() {
+    let light = Light::Spot {
+        color: Vec3::ONE,
+        intensity: 1.0,
+        range: Some(10.0),
+        inner_cone_angle: 0.0,
+        outer_cone_angle: FRAC_PI_2,
+    };
+
+    let gpu_light = GpuLight::new(&light, &Mat4::IDENTITY);
+    assert_eq!(gpu_light.range, 10.0);
+    assert_near(gpu_light.inner_cone_cos, 1.0);
+    assert_near(gpu_light.outer_cone_cos, 0.0);
+    assert_eq!(gpu_light.light_type, 2);
+}
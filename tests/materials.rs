@@ -0,0 +1,98 @@
+//!Check the pipeline keys and uniforms of the materials, and the order of their draws
+
+use u3d_engine::{
+    material::{AlphaMode, Material, MaterialDraw, MaterialPipelineKey, sort_material_draws},
+    math::{matrix::Mat4, vector::Vec3},
+    scene::MeshId,
+    vulkan::material::MaterialUniforms,
+};
+
+const OPAQUE_KEY: MaterialPipelineKey = MaterialPipelineKey {
+    is_alpha_blended: false,
+    is_double_sided: false,
+};
+
+const DOUBLE_SIDED_KEY: MaterialPipelineKey = MaterialPipelineKey {
+    is_alpha_blended: false,
+    is_double_sided: true,
+};
+
+const BLENDED_KEY: MaterialPipelineKey = MaterialPipelineKey {
+    is_alpha_blended: true,
+    is_double_sided: false,
+};
+
+///Return the draw of the first submesh of `mesh` with the material `material_index`, at `camera_distance`
+fn create_draw(
+    pipeline_key: MaterialPipelineKey,
+    material_index: usize,
+    mesh: usize,
+    camera_distance: f32,
+) -> MaterialDraw {
+    MaterialDraw {
+        pipeline_key,
+        material_index,
+        mesh: MeshId(mesh),
+        submesh_index: 0,
+        world_matrix: Mat4::IDENTITY,
+        camera_distance,
+    }
+}
+
+#[test]
+fn pipeline_key_follows_alpha_mode_and_sides() {
+    let mut material = Material {
+        alpha_mode: AlphaMode::Mask { cutoff: 0.5 },
+        ..Material::default()
+    };
+    //The masked materials are drawn with the opaque pipelines, their fragments are discarded
+    assert_eq!(material.get_pipeline_key(), OPAQUE_KEY);
+
+    material.is_double_sided = true;
+    assert_eq!(material.get_pipeline_key(), DOUBLE_SIDED_KEY);
+
+    material.alpha_mode = AlphaMode::Blend;
+    material.is_double_sided = false;
+    assert_eq!(material.get_pipeline_key(), BLENDED_KEY);
+}
+
+#[test]
+fn opaque_draws_are_grouped_before_blended_draws_back_to_front() {
+    let mut all_draws = [
+        create_draw(BLENDED_KEY, 3, 0, 1.0),
+        create_draw(DOUBLE_SIDED_KEY, 1, 2, 5.0),
+        create_draw(OPAQUE_KEY, 2, 1, 2.0),
+        create_draw(BLENDED_KEY, 4, 1, 7.0),
+        create_draw(OPAQUE_KEY, 0, 3, 9.0),
+        create_draw(OPAQUE_KEY, 2, 0, 3.0),
+    ];
+
+    sort_material_draws(&mut all_draws);
+
+    let all_orders: Vec<(usize, usize)> = all_draws
+        .iter()
+        .map(|draw| (draw.material_index, draw.mesh.0))
+        .collect();
+    assert_eq!(all_orders, [(0, 3), (2, 0), (2, 1), (1, 2), (4, 1), (3, 0)]);
+}
+
+#[test]
+fn material_uniforms_match_shader_layout() {
+    assert_eq!(size_of::<MaterialUniforms>(), 64);
+
+    let material = Material {
+        emissive_factor: Vec3::new(1.0, 0.5, 0.25),
+        alpha_mode: AlphaMode::Mask { cutoff: 0.3 },
+        ..Material::default()
+    };
+    let uniforms = MaterialUniforms::from(&material);
+    assert_eq!(uniforms.emissive_factor, [1.0, 0.5, 0.25, 0.0]);
+    assert_eq!(uniforms.alpha_cutoff, 0.3);
+    assert_eq!(uniforms.alpha_mode, 1);
+
+    let uniforms = MaterialUniforms::from(&Material {
+        alpha_mode: AlphaMode::Blend,
+        ..Material::default()
+    });
+    assert_eq!(uniforms.alpha_mode, 2);
+}
@@ -0,0 +1,210 @@
+//!Check the core identities of the math module on random vectors, rotations and transforms
+
+mod common;
+
+use std::f32::consts::PI;
+
+use proptest::prelude::*;
+
+use u3d_engine::math::{
+    matrix::{Mat3, Mat4},
+    quaternion::Quat,
+    vector::{Vec3, Vec4},
+};
+
+///The maximum difference allowed by the properties, larger than `common::EPSILON`
+///because the random values go through several operations and can have large magnitudes
+const PROPERTY_EPSILON: f32 = common::EPSILON * 100f32;
+
+fn vec3(range: f32) -> impl Strategy<Value = Vec3> {
+    (-range..range, -range..range, -range..range).prop_map(|(x, y, z)| Vec3::new(x, y, z))
+}
+
+///Vector of length 1
+fn unit_vec3() -> impl Strategy<Value = Vec3> {
+    vec3(1f32).prop_filter_map("vector too short to be normalized", |vector| {
+        (vector.length() > 0.1f32).then(|| vector.normalize())
+    })
+}
+
+///Rotation by an angle in [-π, π] around a random axis
+fn rotation() -> impl Strategy<Value = Quat> {
+    (unit_vec3(), -PI..PI).prop_map(|(axis, angle)| Quat::from_axis_angle(axis, angle))
+}
+
+///Scale whose components are far enough from 0 to be decomposed
+fn scale() -> impl Strategy<Value = Vec3> {
+    (0.1f32..10f32, 0.1f32..10f32, 0.1f32..10f32).prop_map(|(x, y, z)| Vec3::new(x, y, z))
+}
+
+fn transform() -> impl Strategy<Value = Mat4> {
+    (scale(), rotation(), vec3(100f32)).prop_map(|(scale, rotation, translation)| {
+        Mat4::from_scale_rotation_translation(scale, rotation, translation)
+    })
+}
+
+proptest! {
+    #[test]
+    fn rotation_preserves_length(rotation in rotation(), vector in vec3(100f32)) {
+        prop_assert!(((rotation * vector).length() - vector.length()).abs() < PROPERTY_EPSILON * vector.length().max(1f32));
+    }
+
+    #[test]
+    fn rotation_matrix_rotates_as_quaternion(rotation in rotation(), vector in vec3(100f32)) {
+        let rotated_vector = rotation * vector;
+
+        prop_assert!((rotation.to_rotation_matrix() * vector).abs_diff_eq(rotated_vector, PROPERTY_EPSILON * 100f32));
+        prop_assert!(Mat4::from_quat(rotation).transform_vector3(vector).abs_diff_eq(rotated_vector, PROPERTY_EPSILON * 100f32));
+    }
+
+    #[test]
+    fn rotation_matrix_round_trips(rotation in rotation()) {
+        let round_tripped = Quat::from_rotation_matrix(&rotation.to_rotation_matrix());
+
+        prop_assert!(round_tripped.abs_diff_eq_rotation(rotation, PROPERTY_EPSILON));
+    }
+
+    #[test]
+    fn axis_angle_round_trips(rotation in rotation()) {
+        let (axis, angle) = rotation.to_axis_angle();
+
+        prop_assert!(Quat::from_axis_angle(axis, angle).abs_diff_eq_rotation(rotation, PROPERTY_EPSILON));
+    }
+
+    #[test]
+    fn quaternion_product_composes_rotations(first in rotation(), second in rotation(), vector in vec3(100f32)) {
+        prop_assert!(((second * first) * vector).abs_diff_eq(second * (first * vector), PROPERTY_EPSILON * 100f32));
+        prop_assert!(Mat4::from_quat(second * first).abs_diff_eq(&(Mat4::from_quat(second) * Mat4::from_quat(first)), PROPERTY_EPSILON));
+    }
+
+    #[test]
+    fn quaternion_times_inverse_is_identity(rotation in rotation()) {
+        prop_assert!((rotation * rotation.inverse()).abs_diff_eq_rotation(Quat::IDENTITY, PROPERTY_EPSILON));
+        prop_assert!((rotation * rotation.conjugate()).abs_diff_eq_rotation(Quat::IDENTITY, PROPERTY_EPSILON));
+    }
+
+    #[test]
+    fn slerp_interpolates_between_rotations(first in rotation(), second in rotation()) {
+        prop_assert!(first.slerp(second, 0f32).abs_diff_eq_rotation(first, PROPERTY_EPSILON));
+        prop_assert!(first.slerp(second, 1f32).abs_diff_eq_rotation(second, PROPERTY_EPSILON));
+        prop_assert!((first.slerp(second, 0.5f32).length() - 1f32).abs() < PROPERTY_EPSILON);
+    }
+
+    #[test]
+    fn mat4_times_inverse_is_identity(transform in transform()) {
+        let inverse = transform.inverse().expect("transform not invertible");
+
+        prop_assert!((transform * inverse).abs_diff_eq(&Mat4::IDENTITY, PROPERTY_EPSILON));
+        prop_assert!((inverse * transform).abs_diff_eq(&Mat4::IDENTITY, PROPERTY_EPSILON));
+    }
+
+    #[test]
+    fn mat3_times_inverse_is_identity(transform in transform()) {
+        let matrix = Mat3::from_mat4(&transform);
+        let inverse = matrix.inverse().expect("matrix not invertible");
+
+        prop_assert!((matrix * inverse).abs_diff_eq(&Mat3::IDENTITY, PROPERTY_EPSILON));
+    }
+
+    #[test]
+    fn determinant_is_multiplicative(first in transform(), second in transform()) {
+        let product_determinant = (first * second).determinant();
+        let expected_determinant = first.determinant() * second.determinant();
+
+        prop_assert!((product_determinant - expected_determinant).abs() <= PROPERTY_EPSILON * expected_determinant.abs().max(1f32));
+        prop_assert!((first.determinant() - Mat3::from_mat4(&first).determinant()).abs() <= PROPERTY_EPSILON * first.determinant().abs().max(1f32));
+    }
+
+    #[test]
+    fn transpose_is_involutive(transform in transform()) {
+        prop_assert_eq!(transform.transpose().transpose(), transform);
+    }
+
+    #[test]
+    fn scale_rotation_translation_round_trips(scale in scale(), rotation in rotation(), translation in vec3(100f32), is_mirrored: bool) {
+        //A mirrored transform is decomposed with a negative X scale
+        let scale = if is_mirrored { Vec3::new(-scale.x, scale.y, scale.z) } else { scale };
+        let transform = Mat4::from_scale_rotation_translation(scale, rotation, translation);
+
+        let (decomposed_scale, decomposed_rotation, decomposed_translation) = transform
+            .to_scale_rotation_translation()
+            .expect("transform not decomposable");
+
+        prop_assert!(decomposed_scale.abs_diff_eq(scale, PROPERTY_EPSILON * 10f32));
+        prop_assert!(decomposed_rotation.abs_diff_eq_rotation(rotation, PROPERTY_EPSILON));
+        prop_assert!(decomposed_translation.abs_diff_eq(translation, PROPERTY_EPSILON));
+    }
+
+    #[test]
+    fn normal_matrix_keeps_normals_perpendicular(transform in transform(), normal in unit_vec3(), tangent in unit_vec3()) {
+        //Make the tangent perpendicular to the normal
+        let tangent = tangent - normal * tangent.dot(normal);
+        prop_assume!(tangent.length() > 0.1f32);
+
+        let normal_matrix = transform.to_normal_matrix().expect("transform not invertible");
+        let transformed_normal = (normal_matrix * normal).normalize();
+        let transformed_tangent = transform.transform_vector3(tangent).normalize();
+
+        prop_assert!(transformed_normal.dot(transformed_tangent).abs() < PROPERTY_EPSILON);
+    }
+
+    #[test]
+    fn look_at_moves_target_in_front_of_eye(eye in vec3(100f32), target in vec3(100f32)) {
+        let direction = target - eye;
+        prop_assume!(direction.length() > 0.1f32);
+        prop_assume!(direction.normalize().cross(Vec3::Y).length() > 0.1f32);
+
+        let view = Mat4::look_at(eye, target, Vec3::Y);
+
+        prop_assert!(view.transform_point3(eye).abs_diff_eq(Vec3::ZERO, PROPERTY_EPSILON));
+        prop_assert!(view.transform_point3(target).abs_diff_eq(Vec3::new(0f32, 0f32, -direction.length()), PROPERTY_EPSILON * 10f32));
+        //The view matrix is a rigid transform
+        prop_assert!((view.determinant() - 1f32).abs() < PROPERTY_EPSILON);
+    }
+
+    #[test]
+    fn perspective_maps_near_and_far_to_depth_range(fov_y in 0.1f32..3f32, aspect_ratio in 0.2f32..5f32, z_near in 0.01f32..10f32, depth_range in 1f32..1000f32) {
+        let z_far = z_near + depth_range;
+
+        let projection = Mat4::perspective(fov_y, aspect_ratio, z_near, z_far);
+        prop_assert!(projection.transform_point3(Vec3::new(0f32, 0f32, -z_near)).z.abs() < PROPERTY_EPSILON);
+        prop_assert!((projection.transform_point3(Vec3::new(0f32, 0f32, -z_far)).z - 1f32).abs() < PROPERTY_EPSILON);
+
+        let reverse_z_projection = Mat4::perspective_reverse_z(fov_y, aspect_ratio, z_near, z_far);
+        prop_assert!((reverse_z_projection.transform_point3(Vec3::new(0f32, 0f32, -z_near)).z - 1f32).abs() < PROPERTY_EPSILON);
+        prop_assert!(reverse_z_projection.transform_point3(Vec3::new(0f32, 0f32, -z_far)).z.abs() < PROPERTY_EPSILON);
+
+        let infinite_projection = Mat4::perspective_infinite_reverse_z(fov_y, aspect_ratio, z_near);
+        prop_assert!((infinite_projection.transform_point3(Vec3::new(0f32, 0f32, -z_near)).z - 1f32).abs() < PROPERTY_EPSILON);
+        prop_assert!(infinite_projection.transform_point3(Vec3::new(0f32, 0f32, -z_far)).z < 1f32);
+
+        //The top of the field of view is at the top of the clip space, where Y is -1
+        let top = Vec3::new(0f32, (fov_y * 0.5f32).tan() * z_near, -z_near);
+        prop_assert!((projection.transform_point3(top).y + 1f32).abs() < PROPERTY_EPSILON);
+    }
+
+    #[test]
+    fn orthographic_maps_box_to_clip_space(left in -100f32..0f32, width in 1f32..100f32, bottom in -100f32..0f32, height in 1f32..100f32, z_near in -10f32..10f32, depth_range in 1f32..1000f32) {
+        let (right, top, z_far) = (left + width, bottom + height, z_near + depth_range);
+
+        let projection = Mat4::orthographic(left, right, bottom, top, z_near, z_far);
+        prop_assert!(projection.transform_point3(Vec3::new(left, top, -z_near)).abs_diff_eq(Vec3::new(-1f32, -1f32, 0f32), PROPERTY_EPSILON));
+        prop_assert!(projection.transform_point3(Vec3::new(right, bottom, -z_far)).abs_diff_eq(Vec3::new(1f32, 1f32, 1f32), PROPERTY_EPSILON));
+
+        let reverse_z_projection = Mat4::orthographic_reverse_z(left, right, bottom, top, z_near, z_far);
+        prop_assert!(reverse_z_projection.transform_point3(Vec3::new(left, top, -z_near)).abs_diff_eq(Vec3::new(-1f32, -1f32, 1f32), PROPERTY_EPSILON));
+        prop_assert!(reverse_z_projection.transform_point3(Vec3::new(right, bottom, -z_far)).abs_diff_eq(Vec3::new(1f32, 1f32, 0f32), PROPERTY_EPSILON));
+    }
+}
+
+#[test]
+fn matrices_are_laid_out_by_column() {
+    let matrix = Mat4::from_translation(Vec3::new(1f32, 2f32, 3f32));
+    let floats: [f32; 16] = bytemuck::cast(matrix);
+
+    assert_eq!(&floats[12..16], &[1f32, 2f32, 3f32, 1f32]);
+    assert_eq!(size_of::<Mat4>(), 64);
+    assert_eq!(size_of::<Mat3>(), 36);
+    assert_eq!(size_of::<Quat>(), 16);
+    assert_eq!(size_of::<Vec4>(), 16);
+}
@@ -0,0 +1,123 @@
+//!Parse Wavefront OBJ and MTL sources and check the triangulated, deduplicated meshes and their materials
+
+mod common;
+
+use std::path::Path;
+
+use common::{assert_near, assert_vec3_near};
+use u3d_engine::{
+    material::{AlphaMode, MaterialTexture},
+    math::vector::{Vec2, Vec3},
+    obj::{ObjError, load_obj_from_file, parse_mtl, parse_obj},
+};
+
+#[test]
+fn quad_is_triangulated_with_shared_vertices() {
+    let model = parse_obj(
+        "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n",
+        Path::new(""),
+    )
+    .expect("failed to parse quad");
+
+    assert_eq!(model.mesh.all_vertices.len(), 4);
+    assert_eq!(model.mesh.all_indices, [0, 1, 2, 0, 2, 3]);
+    assert_eq!(model.mesh.all_submeshes.len(), 1);
+    assert_eq!(model.mesh.all_submeshes[0].index_count, 6);
+}
+
+#[test]
+fn missing_normals_and_tangents_are_generated() {
+    let model = parse_obj(
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0 1\nf -3/-3 -2/-2 -1/-1\n",
+        Path::new(""),
+    )
+    .expect("failed to parse triangle");
+
+    for vertex in &model.mesh.all_vertices {
+        assert_vec3_near(vertex.normal, Vec3::Z);
+        assert_vec3_near(vertex.tangent.truncate(), Vec3::X);
+        //V is flipped, so the bitangent toward +V points toward -Y
+        assert_eq!(vertex.tangent.w, -1.0);
+    }
+    assert_eq!(model.mesh.all_vertices[2].uv, Vec2::new(0.0, 0.0));
+    assert_eq!(model.mesh.all_vertices[0].uv, Vec2::new(0.0, 1.0));
+}
+
+#[test]
+fn submeshes_are_split_by_material() {
+    let model = parse_obj(
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nusemtl red\nf 1 3 2\nusemtl empty\nusemtl red\nf 3 2 1\n",
+        Path::new(""),
+    )
+    .expect("failed to parse triangles");
+
+    //The empty submesh is removed, and the unknown materials are replaced by the default material
+    let all_first_indices: Vec<u32> = model
+        .mesh
+        .all_submeshes
+        .iter()
+        .map(|submesh| submesh.first_index)
+        .collect();
+    assert_eq!(all_first_indices, [0, 3, 6]);
+    assert!(
+        model
+            .mesh
+            .all_submeshes
+            .iter()
+            .all(|submesh| submesh.index_count == 3 && submesh.material_index.is_none())
+    );
+}
+
+#[test]
+fn invalid_face_is_reported_with_its_line() {
+    let error = parse_obj("v 0 0 0\n\nf 1 2 3\n", Path::new(""))
+        .expect_err("face with missing vertices parsed");
+
+    assert!(matches!(error, ObjError::Parse { line_number: 3, .. }));
+}
+
+#[test]
+fn mtl_parameters_are_mapped_to_material() {
+    let all_materials = parse_mtl(
+        "newmtl glass\nKd 0.2 0.4 0.6\nd 0.5\nNs 0\nmap_Kd textures/glass.png\nmap_Bump -bm 0.5 glass_normal.png\n",
+        Path::new("models"),
+    )
+    .expect("failed to parse material");
+
+    let material = &all_materials[0];
+    assert_eq!(material.name, "glass");
+    assert_eq!(material.base_color_factor.w, 0.5);
+    assert_eq!(material.alpha_mode, AlphaMode::Blend);
+    assert_near(material.roughness_factor, 1.0);
+    assert_eq!(
+        material.base_color_texture,
+        Some(MaterialTexture::from_file("models/textures/glass.png"))
+    );
+    assert_eq!(material.normal_scale, 0.5);
+}
+
+#[test]
+fn cube_asset_is_loaded() {
+    let model = load_obj_from_file(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/assets/models/cube.obj"
+    ))
+    .expect("failed to load cube");
+
+    //Each corner of the cube is shared by 3 faces with different normals
+    assert_eq!(model.mesh.all_vertices.len(), 24);
+    assert_eq!(model.mesh.get_triangle_count(), 12);
+    assert_eq!(model.all_materials.len(), 1);
+    assert_eq!(model.mesh.all_submeshes[0].material_index, Some(0));
+
+    //The front faces are counterclockwise, so their normals point outward
+    for triangle in model.mesh.all_indices.chunks_exact(3) {
+        let [vertex_0, vertex_1, vertex_2] =
+            [0, 1, 2].map(|corner| model.mesh.all_vertices[triangle[corner] as usize]);
+        let face_normal = (vertex_1.position - vertex_0.position)
+            .cross(vertex_2.position - vertex_0.position)
+            .normalize();
+        assert_vec3_near(face_normal, vertex_0.normal);
+        assert!(face_normal.dot(vertex_0.position) > 0.0);
+    }
+}
@@ -0,0 +1,192 @@
+//!Check the hierarchy of a scene, the propagation of its dirty transforms and the search of its nodes
+
+mod common;
+
+use common::assert_vec3_near;
+use u3d_engine::{
+    math::vector::Vec3,
+    scene::{NodeId, Scene, Transform},
+};
+
+fn get_world_position(scene: &Scene, node_id: NodeId) -> Vec3 {
+    scene
+        .get_node(node_id)
+        .expect("failed to find the node")
+        .get_world_matrix()
+        .transform_point3(Vec3::ZERO)
+}
+
+///A root at X = 1 with two children at Y = 1 and Y = 2, the first one with a child at Z = 1
+struct Hierarchy {
+    scene: Scene,
+    root_id: NodeId,
+    child_id: NodeId,
+    sibling_id: NodeId,
+    grandchild_id: NodeId,
+}
+
+fn create_hierarchy() -> Hierarchy {
+    let mut scene = Scene::new();
+    let root_id = scene.add_node(
+        "root",
+        Transform::from_translation(Vec3::new(1.0, 0.0, 0.0)),
+        None,
+    );
+    let child_id = scene.add_node(
+        "child",
+        Transform::from_translation(Vec3::new(0.0, 1.0, 0.0)),
+        Some(root_id),
+    );
+    let sibling_id = scene.add_node(
+        "sibling",
+        Transform::from_translation(Vec3::new(0.0, 2.0, 0.0)),
+        Some(root_id),
+    );
+    let grandchild_id = scene.add_node(
+        "grandchild",
+        Transform::from_translation(Vec3::new(0.0, 0.0, 1.0)),
+        Some(child_id),
+    );
+    scene.update_world_transforms();
+
+    Hierarchy {
+        scene,
+        root_id,
+        child_id,
+        sibling_id,
+        grandchild_id,
+    }
+}
+
+#[test]
+fn world_transforms_compose_the_ancestors() {
+    let Hierarchy {
+        scene,
+        grandchild_id,
+        ..
+    } = create_hierarchy();
+
+    assert_vec3_near(
+        get_world_position(&scene, grandchild_id),
+        Vec3::new(1.0, 1.0, 1.0),
+    );
+    assert!(
+        scene
+            .get_all_node_ids()
+            .all(|node_id| !scene.get_node(node_id).unwrap().is_dirty())
+    );
+}
+
+#[test]
+fn dirty_parent_updates_its_descendants() {
+    let Hierarchy {
+        mut scene,
+        root_id,
+        child_id,
+        sibling_id,
+        grandchild_id,
+    } = create_hierarchy();
+
+    scene
+        .get_node_mut(root_id)
+        .unwrap()
+        .get_transform_mut()
+        .translation = Vec3::new(5.0, 0.0, 0.0);
+    assert!(scene.get_node(root_id).unwrap().is_dirty());
+    scene.update_world_transforms();
+
+    assert_vec3_near(
+        get_world_position(&scene, child_id),
+        Vec3::new(5.0, 1.0, 0.0),
+    );
+    assert_vec3_near(
+        get_world_position(&scene, grandchild_id),
+        Vec3::new(5.0, 1.0, 1.0),
+    );
+    assert_vec3_near(
+        get_world_position(&scene, sibling_id),
+        Vec3::new(5.0, 2.0, 0.0),
+    );
+    assert!(!scene.get_node(root_id).unwrap().is_dirty());
+}
+
+#[test]
+fn dirty_child_leaves_its_clean_parent_and_siblings_untouched() {
+    let Hierarchy {
+        mut scene,
+        root_id,
+        child_id,
+        sibling_id,
+        grandchild_id,
+    } = create_hierarchy();
+    let get_world_matrix =
+        |scene: &Scene, node_id| *scene.get_node(node_id).unwrap().get_world_matrix();
+    let root_matrix = get_world_matrix(&scene, root_id);
+    let sibling_matrix = get_world_matrix(&scene, sibling_id);
+
+    scene
+        .get_node_mut(child_id)
+        .unwrap()
+        .set_transform(Transform::from_translation(Vec3::new(0.0, 3.0, 0.0)));
+    assert!(!scene.get_node(sibling_id).unwrap().is_dirty());
+    scene.update_world_transforms();
+
+    assert_vec3_near(
+        get_world_position(&scene, grandchild_id),
+        Vec3::new(1.0, 3.0, 1.0),
+    );
+    assert_eq!(get_world_matrix(&scene, root_id), root_matrix);
+    assert_eq!(get_world_matrix(&scene, sibling_id), sibling_matrix);
+}
+
+#[test]
+fn reparented_node_moves_in_the_world() {
+    let Hierarchy {
+        mut scene,
+        child_id,
+        sibling_id,
+        grandchild_id,
+        ..
+    } = create_hierarchy();
+
+    scene.set_parent(grandchild_id, Some(sibling_id));
+    scene.update_world_transforms();
+
+    assert_vec3_near(
+        get_world_position(&scene, grandchild_id),
+        Vec3::new(1.0, 2.0, 1.0),
+    );
+    assert!(
+        scene
+            .get_node(child_id)
+            .unwrap()
+            .get_all_children()
+            .is_empty()
+    );
+}
+
+#[test]
+fn nodes_are_found_by_name() {
+    let Hierarchy {
+        mut scene,
+        root_id,
+        child_id,
+        sibling_id,
+        grandchild_id,
+    } = create_hierarchy();
+    let duplicate_id = scene.add_node("child", Transform::IDENTITY, None);
+
+    //The first node in the order of the IDs is returned
+    assert_eq!(scene.find_node_by_name("child"), Some(child_id));
+    assert_eq!(scene.find_node_by_name("grandchild"), Some(grandchild_id));
+    assert_eq!(scene.find_node_by_name("missing"), None);
+
+    //The removed nodes and their descendants aren't found anymore
+    assert!(scene.remove_node(child_id));
+    assert_eq!(scene.find_node_by_name("child"), Some(duplicate_id));
+    assert_eq!(scene.find_node_by_name("grandchild"), None);
+    assert_eq!(
+        scene.get_node(root_id).unwrap().get_all_children(),
+        [sibling_id]
+    );
+}
@@ -0,0 +1,13 @@
+#version 450
+
+layout(location = 0) out vec4 outColor;
+
+layout(push_constant) uniform PushConstants {
+    vec4 color;
+    vec2 offset;
+    float depth;
+} pushConstants;
+
+void main() {
+    outColor = pushConstants.color;
+}
\ No newline at end of file
@@ -0,0 +1,17 @@
+#version 450
+
+layout(push_constant) uniform PushConstants {
+    vec4 color;
+    vec2 offset;
+    float depth;
+} pushConstants;
+
+vec2 positions[3] = vec2[](
+    vec2(0.0, -0.5),
+    vec2(-0.5, 0.5),
+    vec2(0.5, 0.5)
+);
+
+void main() {
+    gl_Position = vec4(positions[gl_VertexIndex] + pushConstants.offset, pushConstants.depth, 1.0);
+}
\ No newline at end of file
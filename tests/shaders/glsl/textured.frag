@@ -0,0 +1,12 @@
+#version 450
+
+layout(location = 0) in vec2 fragTexCoord;
+
+layout(location = 0) out vec4 outColor;
+
+layout(set = 0, binding = 0) uniform texture2D colorTexture;
+layout(set = 0, binding = 1) uniform sampler colorSampler;
+
+void main() {
+    outColor = texture(sampler2D(colorTexture, colorSampler), fragTexCoord);
+}
\ No newline at end of file
@@ -0,0 +1,17 @@
+#version 450
+
+layout(location = 0) out vec2 fragTexCoord;
+
+vec2 positions[6] = vec2[](
+    vec2(-0.75, -0.75),
+    vec2(0.75, 0.75),
+    vec2(0.75, -0.75),
+    vec2(-0.75, -0.75),
+    vec2(-0.75, 0.75),
+    vec2(0.75, 0.75)
+);
+
+void main() {
+    gl_Position = vec4(positions[gl_VertexIndex], 0.0, 1.0);
+    fragTexCoord = positions[gl_VertexIndex] / 1.5 + 0.5;
+}
\ No newline at end of file
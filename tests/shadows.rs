@@ -0,0 +1,155 @@
+//!Check the tiles of the shadow atlas and the shadow views of the lights
+
+mod common;
+
+use common::EPSILON;
+use u3d_engine::{
+    camera::Camera,
+    math::{
+        matrix::Mat4,
+        vector::{Vec3, Vec4},
+    },
+    scene::Light,
+    shadow::{
+        ShadowConfig, get_cascade_view_projection, get_light_shadow_views,
+        get_spot_light_view_projection,
+    },
+    vulkan::shadow::{GpuShadow, get_gpu_lights_and_shadows},
+};
+
+fn directional_light() -> Light {
+    Light::Directional {
+        color: Vec3::ONE,
+        intensity: 1.0,
+    }
+}
+
+fn assert_in_clip_volume(point: Vec3) {
+    assert!(
+        point.x.abs() <= 1.0 + EPSILON
+            && point.y.abs() <= 1.0 + EPSILON
+            && (-EPSILON..=1.0 + EPSILON).contains(&point.z),
+        "{point:?} is outside the clip volume"
+    );
+}
+
+#[test]
+fn gpu_shadow_matches_std430_layout() {
+    //The shadows of the runtime array of the shaders are aligned to 16 bytes
+    assert_eq!(size_of::<GpuShadow>(), 96);
+}
+
+#[test]
+fn atlas_tiles_are_ordered_by_row() {
+    let shadow_config = ShadowConfig {
+        atlas_size: 2048,
+        tile_size: 512,
+        ..ShadowConfig::default()
+    };
+
+    assert_eq!(shadow_config.get_tile_count(), 16);
+    assert_eq!(shadow_config.get_tile_offset(0), [0, 0]);
+    assert_eq!(shadow_config.get_tile_offset(5), [512, 512]);
+    assert_eq!(shadow_config.get_tile_offset(15), [1536, 1536]);
+    assert_eq!(
+        shadow_config.get_tile_uv_rect(6),
+        Vec4::new(0.5, 0.25, 0.25, 0.25)
+    );
+}
+
+#[test]
+fn spot_light_view_centers_its_direction() {
+    let view_projection =
+        get_spot_light_view_projection(Vec3::new(0.0, 5.0, 0.0), -Vec3::Y, 0.5, Some(10.0));
+
+    let point = view_projection.transform_point3(Vec3::new(0.0, 0.0, 0.0));
+    assert!(point.x.abs() < EPSILON && point.y.abs() < EPSILON);
+    assert_in_clip_volume(point);
+}
+
+#[test]
+fn cascade_view_contains_its_part_of_the_camera_view() {
+    let mut camera = Camera::new_perspective(1.0, 0.1, 200.0);
+    camera.set_aspect_ratio(16.0 / 9.0);
+    camera.position = Vec3::new(3.0, 2.0, 1.0);
+    let direction = Vec3::new(0.3, -1.0, 0.2).normalize();
+
+    let view_projection = get_cascade_view_projection(&camera, direction, (5.0, 20.0), 1024);
+    let inverse_view_projection = (camera.get_projection_matrix() * camera.get_view_matrix())
+        .inverse()
+        .expect("the camera view projection isn't invertible");
+    for distance in [5.0, 20.0] {
+        let depth = camera
+            .get_projection_matrix()
+            .transform_point3(Vec3::new(0.0, 0.0, -distance))
+            .z;
+        for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+            let corner = inverse_view_projection.transform_point3(Vec3::new(x, y, depth));
+            assert_in_clip_volume(view_projection.transform_point3(corner));
+        }
+    }
+}
+
+#[test]
+fn directional_light_has_a_view_by_cascade() {
+    let camera = Camera::new_perspective(1.0, 0.1, 200.0);
+    let shadow_config = ShadowConfig::default();
+
+    let all_shadow_views = get_light_shadow_views(
+        &directional_light(),
+        &Mat4::IDENTITY,
+        &camera,
+        &shadow_config,
+    );
+    let all_split_distances: Vec<f32> = all_shadow_views
+        .iter()
+        .map(|shadow_view| shadow_view.split_distance)
+        .collect();
+    assert_eq!(all_split_distances, shadow_config.cascade_split_distances);
+}
+
+#[test]
+fn lights_get_shadows_while_tiles_remain() {
+    let camera = Camera::new_perspective(1.0, 0.1, 200.0);
+    //4 tiles, used by the 3 cascades of the first directional light
+    let shadow_config = ShadowConfig {
+        atlas_size: 1024,
+        tile_size: 512,
+        cascade_split_distances: vec![5.0, 20.0, 50.0],
+        ..ShadowConfig::default()
+    };
+    let point_light = Light::Point {
+        color: Vec3::ONE,
+        intensity: 1.0,
+        range: None,
+    };
+    let spot_light = Light::Spot {
+        color: Vec3::ONE,
+        intensity: 1.0,
+        range: Some(10.0),
+        inner_cone_angle: 0.2,
+        outer_cone_angle: 0.4,
+    };
+    let all_lights = [
+        (directional_light(), Mat4::IDENTITY),
+        (point_light, Mat4::IDENTITY),
+        (directional_light(), Mat4::IDENTITY),
+        (spot_light, Mat4::IDENTITY),
+    ];
+
+    let (all_gpu_lights, all_shadows) =
+        get_gpu_lights_and_shadows(&all_lights, 16, Some(&camera), &shadow_config);
+    assert_eq!(all_gpu_lights.len(), 4);
+    assert_eq!(all_shadows.len(), 4);
+    let all_shadow_ranges: Vec<(u32, u32)> = all_gpu_lights
+        .iter()
+        .map(|gpu_light| (gpu_light.first_shadow_index, gpu_light.shadow_count))
+        .collect();
+    //The point light has no shadow, and the cascades of the second directional light don't fit
+    assert_eq!(all_shadow_ranges, [(0, 3), (0, 0), (0, 0), (3, 1)]);
+    assert_eq!(all_shadows[3].atlas_rect, [0.5, 0.5, 0.5, 0.5]);
+
+    //Without camera, no light has shadows
+    let (_, all_shadows) = get_gpu_lights_and_shadows(&all_lights, 16, None, &shadow_config);
+    assert!(all_shadows.is_empty());
+}